@@ -0,0 +1,140 @@
+//! Cached command results, keyed by repository HEAD
+
+use crate::runner::CommandOutput;
+use anyhow::Result;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Location of the result cache, relative to the config file's directory
+pub const CACHE_DIR: &str = ".rrepos/cache";
+
+/// Result cache kept next to the config file in `config_dir`
+pub fn cache_dir(config_dir: Option<&Path>) -> PathBuf {
+    config_dir.unwrap_or(Path::new("")).join(CACHE_DIR)
+}
+
+/// A cached result of a command run at a specific commit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedResult {
+    pub command: String,
+    pub sha: String,
+    pub created_at: String,
+    pub output: CommandOutput,
+}
+
+/// On-disk store of command results, one directory per repository
+pub struct ResultCache {
+    dir: PathBuf,
+}
+
+impl ResultCache {
+    /// Use a cache stored in a specific directory
+    pub fn at(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Look up the result of `command` for a repository at commit `sha`
+    pub fn get(&self, repo_name: &str, sha: &str, command: &str) -> Option<CommandOutput> {
+        let content = std::fs::read_to_string(self.entry_path(repo_name, sha, command)).ok()?;
+        let cached: CachedResult = serde_json::from_str(&content).ok()?;
+        (cached.command == command && cached.sha == sha).then_some(cached.output)
+    }
+
+    /// Store the result of `command` for a repository at commit `sha`
+    pub fn put(
+        &self,
+        repo_name: &str,
+        sha: &str,
+        command: &str,
+        output: &CommandOutput,
+    ) -> Result<()> {
+        let path = self.entry_path(repo_name, sha, command);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let cached = CachedResult {
+            command: command.to_string(),
+            sha: sha.to_string(),
+            created_at: Utc::now().to_rfc3339(),
+            output: output.clone(),
+        };
+        std::fs::write(path, serde_json::to_string(&cached)?)?;
+        Ok(())
+    }
+
+    /// Remove cached results for the given repositories, or for all
+    /// repositories when `repo_names` is empty. Returns the number of
+    /// entries removed.
+    pub fn clear(&self, repo_names: &[String]) -> Result<usize> {
+        if !self.dir.exists() {
+            return Ok(0);
+        }
+
+        let mut removed = 0;
+        for entry in std::fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !repo_names.is_empty() && !repo_names.contains(&name) {
+                continue;
+            }
+            if entry.path().is_dir() {
+                removed += std::fs::read_dir(entry.path())?.count();
+                std::fs::remove_dir_all(entry.path())?;
+            }
+        }
+
+        Ok(removed)
+    }
+
+    fn entry_path(&self, repo_name: &str, sha: &str, command: &str) -> PathBuf {
+        self.dir
+            .join(repo_name)
+            .join(format!("{sha}-{:016x}.json", fingerprint(command)))
+    }
+}
+
+/// Stable 64-bit FNV-1a hash, so cache keys survive toolchain upgrades
+pub fn fingerprint(value: &str) -> u64 {
+    value.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::OutputLine;
+
+    #[test]
+    fn test_cache_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("rrepos-cache-test-{}", std::process::id()));
+        let cache = ResultCache::at(&dir);
+        let output = CommandOutput {
+            lines: vec![OutputLine {
+                stderr: false,
+                text: "ok".to_string(),
+            }],
+            exit_code: 0,
+        };
+
+        cache.put("repo", "abc123", "make lint", &output).unwrap();
+        let cached = cache.get("repo", "abc123", "make lint").unwrap();
+        assert_eq!(cached.lines[0].text, "ok");
+        assert!(cache.get("repo", "def456", "make lint").is_none());
+        assert!(cache.get("repo", "abc123", "make test").is_none());
+
+        assert_eq!(cache.clear(&["other".to_string()]).unwrap(), 0);
+        assert_eq!(cache.clear(&[]).unwrap(), 1);
+        assert!(cache.get("repo", "abc123", "make lint").is_none());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_fingerprint_is_stable() {
+        assert_eq!(fingerprint(""), 0xcbf29ce484222325);
+        assert_eq!(fingerprint("a"), 0xaf63dc4c8601ec8c);
+    }
+}
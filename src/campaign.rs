@@ -0,0 +1,289 @@
+//! Campaign definitions: declarative multi-step changes rolled out as pull
+//! requests, with their progress persisted between invocations
+
+use crate::config::Repository;
+use crate::github::WaitFor;
+use crate::state::RepoStatus;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Directory holding campaign states, relative to the working directory
+pub const CAMPAIGN_STATE_DIR: &str = ".rrepos/campaigns";
+
+/// A change to make in each repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CampaignStep {
+    /// Run a shell command
+    Run(String),
+    /// Find and replace a regex pattern
+    Replace {
+        pattern: String,
+        replacement: String,
+        #[serde(default)]
+        glob: Option<String>,
+    },
+    /// Sync the files of a sync map, relative to the campaign file
+    SyncFiles(String),
+}
+
+/// Pull request options of a campaign
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CampaignPr {
+    pub title: String,
+    #[serde(default)]
+    pub body: String,
+    /// Branch template, defaulting to the configured one or `campaign/<name>`
+    #[serde(default)]
+    pub branch: Option<String>,
+    #[serde(default)]
+    pub base: Option<String>,
+    #[serde(default)]
+    pub draft: bool,
+}
+
+/// A campaign file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Campaign {
+    pub name: String,
+    /// Only repositories with this tag
+    #[serde(default)]
+    pub tag: Option<String>,
+    /// Only these repositories
+    #[serde(default)]
+    pub repos: Vec<String>,
+    /// Written as `- run: ...` rather than with YAML tags
+    #[serde(with = "serde_yaml::with::singleton_map_recursive")]
+    pub steps: Vec<CampaignStep>,
+    pub commit_message: String,
+    /// Without pull request options, changes are only committed on a branch
+    #[serde(default)]
+    pub pr: Option<CampaignPr>,
+    /// Repositories to process per invocation; all at once when unset
+    #[serde(default)]
+    pub batch_size: Option<usize>,
+    /// Keep going batch after batch once the previous batch's pull requests
+    /// are merged or green, instead of stopping after each batch
+    #[serde(default)]
+    pub wait_for: Option<WaitFor>,
+    /// Minutes to wait for a batch before stopping
+    #[serde(default = "default_wait_timeout")]
+    pub wait_timeout: u64,
+    #[serde(skip)]
+    pub base_dir: PathBuf,
+}
+
+fn default_wait_timeout() -> u64 {
+    120
+}
+
+impl Campaign {
+    /// Load and validate a campaign file
+    pub fn load(path: &str) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read campaign file '{path}'"))?;
+        let mut campaign: Campaign = serde_yaml::from_str(&content)
+            .with_context(|| format!("Invalid campaign file '{path}'"))?;
+        campaign.base_dir = Path::new(path)
+            .parent()
+            .map(|p| p.to_path_buf())
+            .unwrap_or_default();
+        campaign.validate()?;
+        Ok(campaign)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.name.is_empty() || self.name.contains(['/', '\\']) {
+            anyhow::bail!("Invalid campaign name: '{}'", self.name);
+        }
+        if self.steps.is_empty() {
+            anyhow::bail!("Campaign '{}' has no steps", self.name);
+        }
+        if self.batch_size == Some(0) {
+            anyhow::bail!("Campaign batch size must be at least 1");
+        }
+        if self.wait_for.is_some() && self.pr.is_none() {
+            anyhow::bail!(
+                "Campaign '{}' waits for pull requests but has no `pr` section",
+                self.name
+            );
+        }
+        for step in &self.steps {
+            if let CampaignStep::Replace { pattern, .. } = step {
+                Regex::new(pattern)
+                    .with_context(|| format!("Invalid replace pattern '{pattern}'"))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Where the campaign's progress is stored
+    pub fn state_path(&self) -> PathBuf {
+        Path::new(CAMPAIGN_STATE_DIR).join(format!("{}.json", self.name))
+    }
+}
+
+/// Progress of a campaign in one repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampaignRepoState {
+    pub status: RepoStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pull_request: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Persisted progress of a campaign
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampaignState {
+    pub name: String,
+    pub started_at: String,
+    pub updated_at: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub aborted: bool,
+    pub repositories: BTreeMap<String, CampaignRepoState>,
+}
+
+impl CampaignState {
+    /// Create a new state with all repositories pending
+    pub fn new(name: &str, repos: &[Repository]) -> Self {
+        let now = Utc::now().to_rfc3339();
+        Self {
+            name: name.to_string(),
+            started_at: now.clone(),
+            updated_at: now,
+            aborted: false,
+            repositories: repos
+                .iter()
+                .map(|r| {
+                    (
+                        r.name.clone(),
+                        CampaignRepoState {
+                            status: RepoStatus::Pending,
+                            pull_request: None,
+                            error: None,
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Load a campaign state
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read campaign state '{}'", path.display()))?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Write the state, creating its directory if needed
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Number of repositories with the given status
+    pub fn count(&self, status: RepoStatus) -> usize {
+        self.repositories
+            .values()
+            .filter(|repo| repo.status == status)
+            .count()
+    }
+
+    /// Whether every repository succeeded
+    pub fn is_complete(&self) -> bool {
+        self.count(RepoStatus::Succeeded) == self.repositories.len()
+    }
+
+    /// The next repositories to process: those of the campaign that haven't
+    /// succeeded yet, in config order, up to `batch_size`
+    pub fn next_batch(&self, repos: Vec<Repository>, batch_size: Option<usize>) -> Vec<Repository> {
+        repos
+            .into_iter()
+            .filter(|repo| {
+                self.repositories
+                    .get(&repo.name)
+                    .is_some_and(|state| state.status != RepoStatus::Succeeded)
+            })
+            .take(batch_size.unwrap_or(usize::MAX))
+            .collect()
+    }
+
+    /// Record the outcome of a repository
+    pub fn record(&mut self, name: &str, result: &Result<Option<String>>) {
+        let state = match result {
+            Ok(pull_request) => CampaignRepoState {
+                status: RepoStatus::Succeeded,
+                pull_request: pull_request.clone(),
+                error: None,
+            },
+            Err(e) => CampaignRepoState {
+                status: RepoStatus::Failed,
+                pull_request: None,
+                error: Some(e.to_string()),
+            },
+        };
+        self.repositories.insert(name.to_string(), state);
+        self.updated_at = Utc::now().to_rfc3339();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_campaign() {
+        let campaign: Campaign = serde_yaml::from_str(
+            r#"
+name: bump-serde
+tag: rust
+steps:
+  - run: cargo update -p serde
+  - replace:
+      pattern: 'serde = "1\.0\.\d+"'
+      replacement: 'serde = "1.0.200"'
+      glob: Cargo.toml
+  - sync-files: sync.yaml
+commit_message: Bump serde
+pr:
+  title: Bump serde
+batch_size: 5
+wait_for: ci-green
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(campaign.steps.len(), 3);
+        assert!(matches!(&campaign.steps[2], CampaignStep::SyncFiles(map) if map == "sync.yaml"));
+        assert_eq!(campaign.wait_for, Some(WaitFor::CiGreen));
+        assert_eq!(campaign.wait_timeout, 120);
+        assert!(campaign.validate().is_ok());
+    }
+
+    #[test]
+    fn test_next_batch() {
+        let repos: Vec<Repository> = ["a", "b", "c", "d"]
+            .iter()
+            .map(|n| Repository::new(n.to_string(), format!("git@github.com:owner/{n}.git")))
+            .collect();
+        let mut state = CampaignState::new("test", &repos[..3]);
+        state.record("a", &Ok(None));
+        state.record("b", &Err(anyhow::anyhow!("failed")));
+
+        let names =
+            |batch: Vec<Repository>| -> Vec<String> { batch.into_iter().map(|r| r.name).collect() };
+        assert_eq!(names(state.next_batch(repos.clone(), None)), vec!["b", "c"]);
+        assert_eq!(names(state.next_batch(repos, Some(1))), vec!["b"]);
+        assert!(!state.is_complete());
+    }
+}
@@ -0,0 +1,327 @@
+//! Actions command implementation
+
+use super::{Command, CommandContext};
+use crate::config::{Config, Repository};
+use crate::git::{self, Logger};
+use crate::github::{self, GitHubClient, PrOptions};
+use crate::workflows::{self, WORKFLOWS_DIR};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use colored::*;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Operations on the GitHub Actions used by workflows
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum ActionsAction {
+    /// Rewrite `uses:` references to full commit SHAs, resolving tags and
+    /// branches through the GitHub API
+    Pin {
+        /// Specific repository names to update (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Open a pull request for every repository that changes
+        #[arg(long)]
+        pr: bool,
+
+        /// Only list the references that would be pinned
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Title for the pull requests opened with --pr
+        #[arg(long, default_value = "Pin GitHub Actions to commit SHAs")]
+        title: String,
+
+        /// Body text for the pull requests opened with --pr
+        #[arg(
+            long,
+            default_value = "This PR pins the GitHub Actions used by the workflows to full commit SHAs"
+        )]
+        body: String,
+
+        /// Branch name to create
+        #[arg(long)]
+        branch: Option<String>,
+
+        /// Base branch for the PR
+        #[arg(long)]
+        base: Option<String>,
+
+        /// Create PR as draft
+        #[arg(long)]
+        draft: bool,
+
+        /// GitHub token (falls back to GITHUB_TOKEN)
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+}
+
+/// Pin command rewriting action references to commit SHAs across
+/// repositories
+pub struct ActionsPinCommand {
+    pub dry_run: bool,
+    /// Token for resolving references; unauthenticated requests are rate
+    /// limited much sooner
+    pub token: Option<String>,
+    /// Options for the pull requests; `None` to only change the clones
+    pub pr: Option<PrOptions>,
+}
+
+#[async_trait]
+impl Command for ActionsPinCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            format!("Pinning actions in {} repositories...", repositories.len()).green()
+        );
+
+        let logger = Logger;
+        let client = if self.dry_run {
+            None
+        } else {
+            Some(GitHubClient::with_settings(
+                self.token.clone(),
+                &context.config.github_settings(),
+            )?)
+        };
+
+        // References resolve the same in every repository, so each is looked
+        // up once
+        let mut shas = HashMap::new();
+        let mut pinned = 0;
+        let mut failed = 0;
+        for repo in &repositories {
+            let repo_dir = repo.get_target_dir();
+            if !Path::new(&repo_dir).exists() {
+                logger.warn(repo, "Repository directory does not exist, skipping");
+                continue;
+            }
+
+            let result = match &client {
+                None => {
+                    list_unpinned(repo);
+                    Ok(())
+                }
+                Some(client) => {
+                    match pin_repository(&context.config, client, repo, &mut shas, self.pr.as_ref())
+                        .await
+                    {
+                        Ok(changed) => {
+                            pinned += usize::from(changed);
+                            Ok(())
+                        }
+                        Err(e) => Err(e),
+                    }
+                }
+            };
+            if let Err(e) = result {
+                failed += 1;
+                eprintln!(
+                    "{} | {}",
+                    repo.name.cyan().bold(),
+                    format!("Error: {e}").red()
+                );
+            }
+        }
+
+        if failed > 0 {
+            anyhow::bail!("{} repositories failed", failed);
+        }
+        if self.dry_run {
+            println!("{}", "Dry run, nothing changed".yellow());
+        } else {
+            println!(
+                "{}",
+                format!("Pinned actions in {pinned} repositories").green()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Pin the actions of one repository and, with `pr_options`, open a pull
+/// request when its workflows changed. Returns whether they did.
+pub(crate) async fn pin_repository(
+    config: &Config,
+    client: &GitHubClient,
+    repo: &Repository,
+    shas: &mut HashMap<String, String>,
+    pr_options: Option<&PrOptions>,
+) -> Result<bool> {
+    // Only the pinned workflows may end up in the pull request's commit
+    if pr_options.is_some() && git::has_changes(&repo.get_target_dir())? {
+        anyhow::bail!("Working tree has uncommitted changes");
+    }
+
+    if pin_actions(client, repo, shas).await? == 0 {
+        return Ok(false);
+    }
+    if let Some(pr_options) = pr_options {
+        github::create_pull_request(config, repo, pr_options).await?;
+    }
+    Ok(true)
+}
+
+/// Log the unpinned references in a repository's workflows
+fn list_unpinned(repo: &Repository) {
+    let logger = Logger;
+    for path in workflows::workflow_files(Path::new(&repo.get_target_dir())) {
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let file = path.file_name().unwrap_or_default().to_string_lossy();
+        for action in workflows::action_refs(&content) {
+            if !action.is_pinned() {
+                logger.info(
+                    repo,
+                    &format!(
+                        "Would pin {}@{} ({WORKFLOWS_DIR}/{file}:{})",
+                        action.name, action.reference, action.line
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// Pin the unpinned actions in a repository's workflows to commit SHAs,
+/// resolving them through the API; `shas` caches resolved references across
+/// repositories. Returns the number of files changed.
+pub async fn pin_actions(
+    client: &GitHubClient,
+    repo: &Repository,
+    shas: &mut HashMap<String, String>,
+) -> Result<usize> {
+    let logger = Logger;
+    let mut changed = 0;
+    for path in workflows::workflow_files(Path::new(&repo.get_target_dir())) {
+        let content = std::fs::read_to_string(&path)?;
+        for action in workflows::action_refs(&content) {
+            let key = format!("{}@{}", action.name, action.reference);
+            if action.is_pinned() || shas.contains_key(&key) {
+                continue;
+            }
+            let Some((owner, name)) = action.repository() else {
+                continue;
+            };
+            let sha = client
+                .commit_sha(owner, name, &action.reference)
+                .await
+                .with_context(|| format!("Failed to resolve {key}"))?;
+            shas.insert(key, sha);
+        }
+
+        let pinned = workflows::pin(&content, shas);
+        if pinned != content {
+            std::fs::write(&path, pinned)?;
+            changed += 1;
+            logger.success(
+                repo,
+                &format!(
+                    "Pinned actions in {}",
+                    path.file_name().unwrap_or_default().to_string_lossy()
+                ),
+            );
+        }
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::git;
+
+    #[tokio::test]
+    async fn test_pin_actions() {
+        let dir = std::env::temp_dir().join(format!("rrepos-actions-test-{}", std::process::id()));
+        let workflows_dir = dir.join(WORKFLOWS_DIR);
+        std::fs::create_dir_all(&workflows_dir).unwrap();
+        let sha = "b4ffde65f46336ab88eb53be808477a3936bae11";
+        std::fs::write(
+            workflows_dir.join("ci.yml"),
+            "steps:\n  - uses: actions/checkout@v4\n  - uses: ./.github/actions/build\n",
+        )
+        .unwrap();
+        let lint = format!("steps:\n  - uses: actions/setup-go@{sha} # v5\n");
+        std::fs::write(workflows_dir.join("lint.yml"), &lint).unwrap();
+
+        let mut repo = Repository::new("api".to_string(), "git@github.com:org/api.git".to_string());
+        repo.path = Some(dir.to_string_lossy().to_string());
+        // Resolved references are reused, so the client is never asked
+        let client = GitHubClient::new(None);
+        let mut shas = HashMap::from([("actions/checkout@v4".to_string(), sha.to_string())]);
+
+        let changed = pin_actions(&client, &repo, &mut shas).await.unwrap();
+        let again = pin_actions(&client, &repo, &mut shas).await.unwrap();
+        let ci = std::fs::read_to_string(workflows_dir.join("ci.yml")).unwrap();
+        let lint_after = std::fs::read_to_string(workflows_dir.join("lint.yml")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!((changed, again), (1, 0));
+        assert_eq!(
+            ci,
+            format!(
+                "steps:\n  - uses: actions/checkout@{sha} # v4\n  - uses: ./.github/actions/build\n"
+            )
+        );
+        assert_eq!(lint_after, lint);
+    }
+
+    #[tokio::test]
+    async fn test_pin_with_pr_refuses_dirty_tree() {
+        let dir =
+            std::env::temp_dir().join(format!("rrepos-actions-dirty-test-{}", std::process::id()));
+        let workflows_dir = dir.join(WORKFLOWS_DIR);
+        std::fs::create_dir_all(&workflows_dir).unwrap();
+        let workflow = "steps:\n  - uses: actions/checkout@v4\n";
+        std::fs::write(workflows_dir.join("ci.yml"), workflow).unwrap();
+        git(&dir, &["init", "--quiet"]);
+        git(&dir, &["add", "."]);
+        git(&dir, &["commit", "--quiet", "-m", "first"]);
+        std::fs::write(dir.join("notes.txt"), "unrelated edit\n").unwrap();
+
+        let mut repo = Repository::new("api".to_string(), "git@github.com:org/api.git".to_string());
+        repo.path = Some(dir.to_string_lossy().to_string());
+        let pr_options = PrOptions::new("Pin".to_string(), String::new(), String::new());
+        let mut shas = HashMap::from([(
+            "actions/checkout@v4".to_string(),
+            "b4ffde65f46336ab88eb53be808477a3936bae11".to_string(),
+        )]);
+
+        let client = GitHubClient::new(None);
+        let error = pin_repository(&Config::new(), &client, &repo, &mut shas, Some(&pr_options))
+            .await
+            .unwrap_err();
+        let after = std::fs::read_to_string(workflows_dir.join("ci.yml")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(error.to_string(), "Working tree has uncommitted changes");
+        assert_eq!(after, workflow);
+    }
+}
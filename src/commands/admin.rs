@@ -0,0 +1,311 @@
+//! Admin command implementation
+
+use super::{Command, CommandContext};
+use crate::config::{Config, Repository, hosts};
+use crate::git::{self, Logger};
+use crate::github::GitHubClient;
+use crate::util::approve;
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+use glob::Pattern;
+use std::path::Path;
+
+/// Administrative repository operations
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum AdminAction {
+    /// Archive repositories on GitHub and mark them archived in the config
+    Archive {
+        /// Repository names or glob patterns such as 'old-*'
+        #[arg(required_unless_present = "tag")]
+        repos: Vec<String>,
+
+        /// Only list the repositories that would be archived
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Don't ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+
+        /// GitHub token (falls back to GITHUB_TOKEN)
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Transfer repositories to another owner and update their URLs in the config
+    Transfer {
+        /// Repository names or glob patterns such as 'old-*'
+        #[arg(required_unless_present = "tag")]
+        repos: Vec<String>,
+
+        /// User or organization to transfer to
+        #[arg(long)]
+        to: String,
+
+        /// Only list the repositories that would be transferred
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Don't ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+
+        /// GitHub token (falls back to GITHUB_TOKEN)
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+}
+
+/// The change an admin command makes to each repository
+pub enum AdminOperation {
+    Archive,
+    Transfer { to: String },
+}
+
+/// Admin command for archiving or transferring repositories through the GitHub API
+pub struct AdminCommand {
+    pub operation: AdminOperation,
+    pub dry_run: bool,
+    /// Skip the confirmation prompt
+    pub yes: bool,
+    pub token: Option<String>,
+    /// Config file to update
+    pub config_path: String,
+}
+
+#[async_trait]
+impl Command for AdminCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        let action = match &self.operation {
+            AdminOperation::Archive => "archived".to_string(),
+            AdminOperation::Transfer { to } => format!("transferred to {to}"),
+        };
+        println!(
+            "{}",
+            format!("The following repositories will be {action}:").bold()
+        );
+        for repo in &repositories {
+            println!("  {} ({})", repo.name.cyan(), repo.url);
+        }
+
+        if self.dry_run {
+            println!("{}", "Dry run, nothing changed".yellow());
+            return Ok(());
+        }
+
+        if !self.yes
+            && !approve(
+                &context.config.policy,
+                &format!("Continue with {} repositories?", repositories.len()),
+                repositories.len(),
+                false,
+                true,
+            )?
+        {
+            println!("{}", "Aborted".yellow());
+            return Ok(());
+        }
+
+        let client =
+            GitHubClient::with_settings(self.token.clone(), &context.config.github_settings())?;
+
+        let mut config = context.config.clone();
+        let mut failed = 0;
+        for repo in &repositories {
+            if let Err(e) = self.apply(&client, repo, &mut config).await {
+                failed += 1;
+                eprintln!(
+                    "{} | {}",
+                    repo.name.cyan().bold(),
+                    format!("Error: {e}").red()
+                );
+            }
+        }
+
+        // Record whatever succeeded, even if some repositories failed
+        config.save(&self.config_path)?;
+        println!(
+            "{}",
+            format!(
+                "Updated {} repositories in {}",
+                repositories.len() - failed,
+                self.config_path
+            )
+            .green()
+        );
+
+        if failed > 0 {
+            anyhow::bail!("{} repositories failed", failed);
+        }
+        Ok(())
+    }
+}
+
+impl AdminCommand {
+    async fn apply(
+        &self,
+        client: &GitHubClient,
+        repo: &Repository,
+        config: &mut Config,
+    ) -> Result<()> {
+        let logger = Logger;
+        let (owner, name) = client.parse_github_url(&repo.url)?;
+
+        match &self.operation {
+            AdminOperation::Archive => {
+                client.archive_repository(&owner, &name).await?;
+                if let Some(entry) = config.get_repository_mut(&repo.name) {
+                    entry.archived = true;
+                }
+                logger.success(repo, "Archived");
+            }
+            AdminOperation::Transfer { to } => {
+                client.transfer_repository(&owner, &name, to).await?;
+                let url = match config.get_repository_mut(&repo.name) {
+                    Some(entry) => {
+                        record_transfer(entry, &owner, to);
+                        entry.url.clone()
+                    }
+                    None => transferred_url(&repo.url, &owner, to),
+                };
+
+                // Keep an existing clone pointing at the new location
+                let repo_dir = repo.get_target_dir();
+                if Path::new(&repo_dir).join(".git").exists() {
+                    git::set_remote_url(&repo_dir, "origin", &url)?;
+                }
+                logger.success(repo, &format!("Transferred to {url}"));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Expand glob patterns among `names` into the matching repository names
+/// from the config; plain names are kept as they are
+pub fn expand_patterns(config: &Config, names: &[String]) -> Result<Vec<String>> {
+    let mut expanded = Vec::new();
+    for name in names {
+        if !name.contains(['*', '?', '[']) {
+            expanded.push(name.clone());
+            continue;
+        }
+        let pattern = Pattern::new(name)?;
+        expanded.extend(
+            config
+                .repositories
+                .iter()
+                .filter(|repo| pattern.matches(&repo.name))
+                .map(|repo| repo.name.clone()),
+        );
+    }
+    Ok(expanded)
+}
+
+/// Point `repo` at `new_owner` after a transfer from `owner`. The `owner`
+/// field is only kept when the URL could not be rewritten, as filtering by
+/// `--owner` prefers it over the owner in the URL.
+fn record_transfer(repo: &mut Repository, owner: &str, new_owner: &str) {
+    repo.url = transferred_url(&repo.url, owner, new_owner);
+    repo.owner = (!hosts::url_owner(&repo.url).is_some_and(|o| o.eq_ignore_ascii_case(new_owner)))
+        .then(|| new_owner.to_string());
+}
+
+/// URL of a repository after moving it from `owner` to `new_owner`
+fn transferred_url(url: &str, owner: &str, new_owner: &str) -> String {
+    for separator in [':', '/'] {
+        let from = format!("{separator}{owner}/");
+        if let Some(idx) = url.rfind(&from) {
+            return format!(
+                "{}{separator}{new_owner}/{}",
+                &url[..idx],
+                &url[idx + from.len()..]
+            );
+        }
+    }
+    url.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transferred_url() {
+        assert_eq!(
+            transferred_url("git@github.com:old-org/api.git", "old-org", "new-org"),
+            "git@github.com:new-org/api.git"
+        );
+        assert_eq!(
+            transferred_url("https://github.com/old-org/api", "old-org", "new-org"),
+            "https://github.com/new-org/api"
+        );
+    }
+
+    #[test]
+    fn test_record_transfer() {
+        let mut repo = Repository::new(
+            "api".to_string(),
+            "git@github.com:old-org/api.git".to_string(),
+        );
+        // Left over from an earlier refresh-metadata
+        repo.owner = Some("stale-org".to_string());
+
+        record_transfer(&mut repo, "old-org", "new-org");
+        assert_eq!(repo.url, "git@github.com:new-org/api.git");
+        assert_eq!(repo.owner, None);
+        assert_eq!(repo.owner(), Some("new-org"));
+
+        let mut repo = Repository::new("web".to_string(), "/srv/git/web".to_string());
+        record_transfer(&mut repo, "old-org", "new-org");
+        assert_eq!(repo.owner.as_deref(), Some("new-org"));
+    }
+
+    #[test]
+    fn test_expand_patterns() {
+        let mut config = Config::new();
+        for name in ["old-api", "old-web", "billing"] {
+            config.repositories.push(Repository::new(
+                name.to_string(),
+                format!("git@github.com:org/{name}.git"),
+            ));
+        }
+
+        let names = expand_patterns(&config, &["old-*".to_string(), "billing".to_string()]);
+        assert_eq!(names.unwrap(), vec!["old-api", "old-web", "billing"]);
+    }
+}
@@ -1,7 +1,17 @@
 //! Base types and traits for the command pattern
 
-use crate::config::Config;
+use crate::config::{Config, Repository, Visibility};
 use anyhow::Result;
+use std::path::Path;
+
+/// Selection of repositories by whether their clone is on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Presence {
+    /// Only repositories that are cloned
+    Cloned,
+    /// Only repositories that are not cloned yet
+    Missing,
+}
 
 /// Context passed to all commands containing shared configuration and options
 #[derive(Clone)]
@@ -14,6 +24,49 @@ pub struct CommandContext {
     pub parallel: bool,
     /// Optional list of specific repository names to operate on
     pub repos: Option<Vec<String>>,
+    /// Whether to include repositories marked as archived
+    pub include_archived: bool,
+    /// Optional filter on whether repositories are cloned
+    pub presence: Option<Presence>,
+    /// Optional filter on the recorded visibility
+    pub visibility: Option<Visibility>,
+    /// Optional filter on the repository owner (organization or user)
+    pub owner: Option<String>,
+}
+
+impl CommandContext {
+    /// Repositories selected by the tag, name, presence, visibility and owner
+    /// filters, without archived repositories unless `include_archived` is set
+    pub fn repositories(&self) -> Vec<Repository> {
+        self.select(self.include_archived)
+    }
+
+    /// Repositories selected by the same filters as `repositories`, always
+    /// including archived ones, for commands that also manage those
+    pub fn repositories_with_archived(&self) -> Vec<Repository> {
+        self.select(true)
+    }
+
+    fn select(&self, include_archived: bool) -> Vec<Repository> {
+        self.config
+            .filter_repositories(self.tag.as_deref(), self.repos.as_deref())
+            .into_iter()
+            .filter(|repo| include_archived || !repo.archived)
+            .filter(|repo| match self.presence {
+                Some(presence) => {
+                    let cloned = Path::new(&repo.get_target_dir()).exists();
+                    cloned == (presence == Presence::Cloned)
+                }
+                None => true,
+            })
+            .filter(|repo| self.visibility.is_none_or(|v| repo.visibility == Some(v)))
+            .filter(|repo| match &self.owner {
+                // Owners are case-insensitive on GitHub
+                Some(owner) => repo.owner().is_some_and(|o| o.eq_ignore_ascii_case(owner)),
+                None => true,
+            })
+            .collect()
+    }
 }
 
 /// Trait that all commands must implement
@@ -22,3 +75,126 @@ pub trait Command {
     /// Execute the command with the given context
     async fn execute(&self, context: &CommandContext) -> Result<()>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repositories_skip_archived() {
+        let mut config = Config::new();
+        let mut archived = Repository::new(
+            "old".to_string(),
+            "git@github.com:owner/old.git".to_string(),
+        );
+        archived.archived = true;
+        config.repositories = vec![
+            archived,
+            Repository::new(
+                "new".to_string(),
+                "git@github.com:owner/new.git".to_string(),
+            ),
+        ];
+
+        let mut context = CommandContext {
+            config,
+            tag: None,
+            parallel: false,
+            repos: None,
+            include_archived: false,
+            presence: None,
+            visibility: None,
+            owner: None,
+        };
+        let names: Vec<String> = context.repositories().into_iter().map(|r| r.name).collect();
+        assert_eq!(names, vec!["new"]);
+
+        context.include_archived = true;
+        assert_eq!(context.repositories().len(), 2);
+    }
+
+    #[test]
+    fn test_repositories_by_presence() {
+        let dir = std::env::temp_dir().join(format!("rrepos-base-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut cloned = Repository::new(
+            "cloned".to_string(),
+            "git@github.com:owner/cloned.git".to_string(),
+        );
+        cloned.path = Some(dir.to_string_lossy().to_string());
+        let mut missing = Repository::new(
+            "missing".to_string(),
+            "git@github.com:owner/missing.git".to_string(),
+        );
+        missing.path = Some(dir.join("missing").to_string_lossy().to_string());
+        let mut config = Config::new();
+        config.repositories = vec![cloned, missing];
+
+        let mut context = CommandContext {
+            config,
+            tag: None,
+            parallel: false,
+            repos: None,
+            include_archived: false,
+            presence: Some(Presence::Cloned),
+            visibility: None,
+            owner: None,
+        };
+        let names: Vec<String> = context.repositories().into_iter().map(|r| r.name).collect();
+        assert_eq!(names, vec!["cloned"]);
+
+        context.presence = Some(Presence::Missing);
+        let names: Vec<String> = context.repositories().into_iter().map(|r| r.name).collect();
+        assert_eq!(names, vec!["missing"]);
+
+        // Commands that keep archived repositories still apply the filter
+        context.config.repositories[1].archived = true;
+        let names: Vec<String> = context
+            .repositories_with_archived()
+            .into_iter()
+            .map(|r| r.name)
+            .collect();
+        assert_eq!(names, vec!["missing"]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_repositories_by_visibility_and_owner() {
+        let mut public = Repository::new(
+            "public".to_string(),
+            "git@github.com:Acme/public.git".to_string(),
+        );
+        public.visibility = Some(Visibility::Public);
+        let mut moved = Repository::new(
+            "moved".to_string(),
+            "git@github.com:acme/moved.git".to_string(),
+        );
+        moved.visibility = Some(Visibility::Private);
+        moved.owner = Some("other".to_string());
+        let unknown = Repository::new(
+            "unknown".to_string(),
+            "git@github.com:acme/unknown.git".to_string(),
+        );
+        let mut config = Config::new();
+        config.repositories = vec![public, moved, unknown];
+
+        let mut context = CommandContext {
+            config,
+            tag: None,
+            parallel: false,
+            repos: None,
+            include_archived: false,
+            presence: None,
+            visibility: Some(Visibility::Public),
+            owner: None,
+        };
+        let names: Vec<String> = context.repositories().into_iter().map(|r| r.name).collect();
+        assert_eq!(names, vec!["public"]);
+
+        context.visibility = None;
+        context.owner = Some("acme".to_string());
+        let names: Vec<String> = context.repositories().into_iter().map(|r| r.name).collect();
+        assert_eq!(names, vec!["public", "unknown"]);
+    }
+}
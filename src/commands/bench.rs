@@ -0,0 +1,279 @@
+//! Bench command implementation
+
+use super::{Command, CommandContext};
+use crate::config::Repository;
+use crate::git::Logger;
+use crate::runner::Shell;
+use crate::template;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Instant;
+
+/// Timing statistics of a command in one repository, in seconds
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Timing {
+    pub min: f64,
+    pub mean: f64,
+    pub max: f64,
+}
+
+impl Timing {
+    fn from_samples(samples: &[f64]) -> Self {
+        Self {
+            min: samples.iter().copied().fold(f64::INFINITY, f64::min),
+            mean: samples.iter().sum::<f64>() / samples.len() as f64,
+            max: samples.iter().copied().fold(0.0, f64::max),
+        }
+    }
+}
+
+/// Saved timings to compare later runs against
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Baseline {
+    pub command: String,
+    pub timings: BTreeMap<String, Timing>,
+}
+
+impl Baseline {
+    pub fn load(path: &str) -> Result<Option<Self>> {
+        if !Path::new(path).exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(path)?;
+        let baseline = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid baseline file '{path}'"))?;
+        Ok(Some(baseline))
+    }
+}
+
+/// Bench command for timing a command across repositories
+pub struct BenchCommand {
+    pub command: String,
+    pub runs: usize,
+    pub warmup: usize,
+    /// Baseline file to compare against
+    pub baseline: Option<String>,
+    /// Write the timings to the baseline file instead of comparing
+    pub save_baseline: bool,
+    /// Allowed slowdown of the mean, in percent, before it counts as a regression
+    pub tolerance: f64,
+}
+
+#[async_trait]
+impl Command for BenchCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        if self.runs == 0 {
+            anyhow::bail!("--runs must be at least 1");
+        }
+
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        let baseline = match &self.baseline {
+            Some(path) if !self.save_baseline => Baseline::load(path)?,
+            _ => None,
+        };
+        if let Some(baseline) = &baseline
+            && baseline.command != self.command
+        {
+            eprintln!(
+                "{}",
+                format!(
+                    "Warning: baseline was recorded for '{}', not '{}'",
+                    baseline.command, self.command
+                )
+                .yellow()
+            );
+        }
+
+        println!(
+            "{}",
+            format!(
+                "Timing '{}' in {} repositories ({} runs, {} warmup)...",
+                self.command,
+                repositories.len(),
+                self.runs,
+                self.warmup
+            )
+            .green()
+        );
+
+        let logger = Logger;
+        let mut timings = BTreeMap::new();
+        let mut failed = 0;
+
+        for repo in &repositories {
+            match self.time_repository(repo) {
+                Ok(timing) => {
+                    logger.info(repo, &format!("mean {:.2}s", timing.mean));
+                    timings.insert(repo.name.clone(), timing);
+                }
+                Err(e) => {
+                    failed += 1;
+                    eprintln!(
+                        "{} | {}",
+                        repo.name.cyan().bold(),
+                        format!("Error: {e}").red()
+                    );
+                }
+            }
+        }
+
+        let regressions = self.print_table(&timings, baseline.as_ref());
+
+        if self.save_baseline
+            && let Some(path) = &self.baseline
+        {
+            // Keep the timings of repositories not benchmarked this time
+            let mut saved = Baseline::load(path)?
+                .filter(|baseline| baseline.command == self.command)
+                .unwrap_or_default();
+            saved.command = self.command.clone();
+            saved.timings.extend(timings);
+            std::fs::write(path, serde_json::to_string_pretty(&saved)?)?;
+            println!("{}", format!("Baseline written to '{path}'").green());
+        }
+
+        if failed > 0 {
+            anyhow::bail!("{} repositories failed", failed);
+        }
+        if regressions > 0 {
+            anyhow::bail!(
+                "{} repositories are more than {}% slower than the baseline",
+                regressions,
+                self.tolerance
+            );
+        }
+        Ok(())
+    }
+}
+
+impl BenchCommand {
+    /// Run the command `warmup + runs` times and time the measured runs
+    fn time_repository(&self, repo: &Repository) -> Result<Timing> {
+        let repo_dir = repo.get_target_dir();
+        if !Path::new(&repo_dir).exists() {
+            anyhow::bail!("Repository directory does not exist: {}", repo_dir);
+        }
+
+        let command = template::render(&self.command, &template::repository_variables(repo));
+        let mut samples = Vec::with_capacity(self.runs);
+
+        for run in 0..self.warmup + self.runs {
+            let started = Instant::now();
+            let status = Shell::default()
+                .command(&command)?
+                .current_dir(&repo_dir)
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .with_context(|| format!("Failed to run '{command}'"))?;
+            let elapsed = started.elapsed().as_secs_f64();
+
+            if !status.success() {
+                anyhow::bail!(
+                    "Command failed with exit code: {}",
+                    status.code().unwrap_or(-1)
+                );
+            }
+            if run >= self.warmup {
+                samples.push(elapsed);
+            }
+        }
+
+        Ok(Timing::from_samples(&samples))
+    }
+
+    /// Print the timing table and return how many repositories regressed
+    /// against the baseline
+    fn print_table(
+        &self,
+        timings: &BTreeMap<String, Timing>,
+        baseline: Option<&Baseline>,
+    ) -> usize {
+        let name_width = timings.keys().map(String::len).max().unwrap_or(0).max(10);
+
+        print!(
+            "\n{:<name_width$}  {:>8}  {:>8}  {:>8}",
+            "Repository", "Min", "Mean", "Max"
+        );
+        if baseline.is_some() {
+            print!("  {:>8}  {:>8}", "Baseline", "Change");
+        }
+        println!();
+
+        let mut regressions = 0;
+        for (name, timing) in timings {
+            print!(
+                "{:<name_width$}  {:>7.2}s  {:>7.2}s  {:>7.2}s",
+                name, timing.min, timing.mean, timing.max
+            );
+            if let Some(baseline) = baseline {
+                match baseline.timings.get(name) {
+                    Some(previous) => {
+                        let change = percent_change(previous.mean, timing.mean);
+                        let cell = format!("{change:>+7.1}%");
+                        let cell = if change > self.tolerance {
+                            regressions += 1;
+                            cell.red()
+                        } else if change < -self.tolerance {
+                            cell.green()
+                        } else {
+                            cell.normal()
+                        };
+                        print!("  {:>7.2}s  {cell}", previous.mean);
+                    }
+                    None => print!("  {:>8}  {:>8}", "-", "new"),
+                }
+            }
+            println!();
+        }
+        regressions
+    }
+}
+
+/// Relative change from `before` to `after`, in percent
+fn percent_change(before: f64, after: f64) -> f64 {
+    if before <= 0.0 {
+        return 0.0;
+    }
+    (after - before) / before * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timing_statistics() {
+        let timing = Timing::from_samples(&[2.0, 1.0, 3.0]);
+        assert_eq!(
+            timing,
+            Timing {
+                min: 1.0,
+                mean: 2.0,
+                max: 3.0
+            }
+        );
+        assert_eq!(percent_change(2.0, 2.5), 25.0);
+        assert_eq!(percent_change(0.0, 1.0), 0.0);
+    }
+}
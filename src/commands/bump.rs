@@ -0,0 +1,327 @@
+//! Version bump command implementation
+
+use super::{Command, CommandContext};
+use crate::config::{Config, Repository};
+use crate::git::{self, Logger};
+use crate::github::{self, PrOptions};
+use crate::manifest::{self, BumpLevel, Manifest};
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+use std::path::Path;
+
+/// Version bump command for coordinated releases
+pub struct BumpCommand {
+    pub level: BumpLevel,
+    /// Also update version pins on sibling repositories being bumped
+    pub update_dependents: bool,
+    pub dry_run: bool,
+    /// When set, bumped repositories go through the pull request flow
+    pub pr_options: Option<PrOptions>,
+    /// Branch to commit on without pull requests, created when missing
+    pub branch: Option<String>,
+    /// Commit message without pull requests, defaults to "Bump version to <version>"
+    pub commit_msg: Option<String>,
+}
+
+/// A planned version change for a single repository
+struct PlannedBump {
+    repo: Repository,
+    manifest: Manifest,
+    package: Option<String>,
+    old_version: String,
+    new_version: String,
+}
+
+#[async_trait]
+impl Command for BumpCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            format!("Bumping versions in {} repositories...", repositories.len()).green()
+        );
+
+        let logger = Logger;
+        let mut planned = Vec::new();
+        for repo in repositories {
+            if let Err(e) = self.switch_branch(&repo) {
+                eprintln!(
+                    "{} | {}",
+                    repo.name.cyan().bold(),
+                    format!("Error: {e}").red()
+                );
+                continue;
+            }
+            match plan_bump(&repo, self.level) {
+                Ok(Some(bump)) => planned.push(bump),
+                Ok(None) => logger.warn(&repo, "No versioned manifest found, skipping"),
+                Err(e) => eprintln!(
+                    "{} | {}",
+                    repo.name.cyan().bold(),
+                    format!("Error: {e}").red()
+                ),
+            }
+        }
+
+        let releases: Vec<(String, String)> = planned
+            .iter()
+            .filter_map(|b| b.package.clone().map(|p| (p, b.new_version.clone())))
+            .collect();
+
+        for mut bump in planned {
+            if let Err(e) = self.apply_bump(&context.config, &mut bump, &releases).await {
+                eprintln!(
+                    "{} | {}",
+                    bump.repo.name.cyan().bold(),
+                    format!("Error: {e}").red()
+                );
+            }
+        }
+
+        println!("{}", "Done bumping versions".green());
+        Ok(())
+    }
+}
+
+impl BumpCommand {
+    /// Check out the branch bumps are committed on, before reading the
+    /// manifest so the new version follows from that branch
+    fn switch_branch(&self, repo: &Repository) -> Result<()> {
+        let Some(branch) = &self.branch else {
+            return Ok(());
+        };
+        if self.dry_run || self.pr_options.is_some() {
+            return Ok(());
+        }
+
+        let repo_dir = repo.get_target_dir();
+        if git::current_branch(&repo_dir)?.as_ref() == Some(branch) {
+            return Ok(());
+        }
+        if git::ref_exists(&repo_dir, &format!("refs/heads/{branch}")) {
+            git::checkout(&repo_dir, branch)
+        } else {
+            git::create_and_checkout_branch(&repo_dir, branch)
+        }
+    }
+
+    async fn apply_bump(
+        &self,
+        config: &Config,
+        bump: &mut PlannedBump,
+        releases: &[(String, String)],
+    ) -> Result<()> {
+        let logger = Logger;
+        let repo_dir = bump.repo.get_target_dir();
+
+        // Only the bump may end up in the pull request's commit
+        if self.pr_options.is_some() && !self.dry_run && git::has_changes(&repo_dir)? {
+            anyhow::bail!("Working tree has uncommitted changes");
+        }
+
+        logger.info(
+            &bump.repo,
+            &format!(
+                "{} {} -> {}",
+                bump.manifest.kind.file_name(),
+                bump.old_version,
+                bump.new_version
+            ),
+        );
+
+        bump.manifest.set_version(&bump.new_version)?;
+
+        if self.update_dependents {
+            for (package, version) in releases {
+                if bump.package.as_ref() == Some(package) {
+                    continue;
+                }
+                if bump.manifest.set_dependency_version(package, version)? {
+                    logger.info(
+                        &bump.repo,
+                        &format!("Updated dependency {package} -> {version}"),
+                    );
+                }
+            }
+        }
+
+        if self.dry_run {
+            return Ok(());
+        }
+
+        bump.manifest.save()?;
+
+        // A lockfile that can't be regenerated is left for the next build
+        let mut paths = vec![bump.manifest.path.clone()];
+        match bump.manifest.update_lockfile() {
+            Ok(Some(lockfile)) => {
+                let file_name = lockfile.file_name().unwrap_or_default().to_string_lossy();
+                if git::is_tracked(&repo_dir, &file_name)? {
+                    paths.push(lockfile);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => logger.warn(
+                &bump.repo,
+                &format!("Lockfile not updated, it is out of date: {e}"),
+            ),
+        }
+
+        let default_msg = format!("Bump version to {}", bump.new_version);
+        match &self.pr_options {
+            Some(pr_options) => {
+                let mut options = pr_options.clone();
+                if options.commit_msg.is_none() {
+                    options.commit_msg = Some(default_msg);
+                }
+                github::create_pull_request(config, &bump.repo, &options).await?;
+            }
+            None => {
+                let message = self.commit_msg.as_deref().unwrap_or(&default_msg);
+                let paths: Vec<&Path> = paths.iter().map(|p| p.as_path()).collect();
+                git::commit_paths(&repo_dir, &paths, message)?;
+                logger.success(&bump.repo, &format!("Committed '{message}'"));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn plan_bump(repo: &Repository, level: BumpLevel) -> Result<Option<PlannedBump>> {
+    let repo_dir = repo.get_target_dir();
+    if !Path::new(&repo_dir).exists() {
+        anyhow::bail!("Repository directory does not exist: {}", repo_dir);
+    }
+
+    let Some(manifest) = Manifest::detect(Path::new(&repo_dir))? else {
+        return Ok(None);
+    };
+    let Some(old_version) = manifest.version() else {
+        return Ok(None);
+    };
+
+    let new_version = manifest::bump_version(&old_version, level)?;
+    Ok(Some(PlannedBump {
+        repo: repo.clone(),
+        package: manifest.package_name(),
+        manifest,
+        old_version,
+        new_version,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::git;
+
+    #[tokio::test]
+    async fn test_bump_commits_without_pr() {
+        let dir = std::env::temp_dir().join(format!("rrepos-bump-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        git(&dir, &["init", "--quiet", "--initial-branch=main"]);
+        git(&dir, &["config", "user.name", "Test"]);
+        git(&dir, &["config", "user.email", "test@example.com"]);
+        std::fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\nname = \"api\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/lib.rs"), "").unwrap();
+        std::fs::write(
+            dir.join("Cargo.lock"),
+            "version = 4\n\n[[package]]\nname = \"api\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("notes.txt"), "draft\n").unwrap();
+        git(&dir, &["add", "."]);
+        git(&dir, &["commit", "--quiet", "-m", "first"]);
+        std::fs::write(dir.join("notes.txt"), "unrelated edit\n").unwrap();
+
+        let mut repo = Repository::new("api".to_string(), "git@github.com:org/api.git".to_string());
+        repo.path = Some(dir.to_string_lossy().to_string());
+        let command = BumpCommand {
+            level: BumpLevel::Minor,
+            update_dependents: false,
+            dry_run: false,
+            pr_options: None,
+            branch: Some("release".to_string()),
+            commit_msg: None,
+        };
+
+        command.switch_branch(&repo).unwrap();
+        let mut bump = plan_bump(&repo, command.level).unwrap().unwrap();
+        command
+            .apply_bump(&Config::new(), &mut bump, &[])
+            .await
+            .unwrap();
+
+        let branch = git(&dir, &["branch", "--show-current"]);
+        let subject = git(&dir, &["log", "-1", "--format=%s"]);
+        let committed = git(&dir, &["show", "--name-only", "--format=", "HEAD"]);
+        let dirty = git(&dir, &["status", "--porcelain"]);
+        let lockfile = std::fs::read_to_string(dir.join("Cargo.lock")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(branch, "release");
+        assert_eq!(subject, "Bump version to 0.2.0");
+        assert_eq!(committed, "Cargo.lock\nCargo.toml");
+        assert_eq!(dirty, "M notes.txt");
+        assert!(lockfile.contains("name = \"api\"\nversion = \"0.2.0\""));
+    }
+
+    #[tokio::test]
+    async fn test_bump_pr_refuses_dirty_tree() {
+        let dir =
+            std::env::temp_dir().join(format!("rrepos-bump-dirty-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        git(&dir, &["init", "--quiet"]);
+        let manifest = "{\n  \"name\": \"web\",\n  \"version\": \"1.0.0\"\n}\n";
+        std::fs::write(dir.join("package.json"), manifest).unwrap();
+        git(&dir, &["add", "."]);
+        git(&dir, &["commit", "--quiet", "-m", "first"]);
+        std::fs::write(dir.join("notes.txt"), "unrelated edit\n").unwrap();
+
+        let mut repo = Repository::new("web".to_string(), "git@github.com:org/web.git".to_string());
+        repo.path = Some(dir.to_string_lossy().to_string());
+        let mut pr_options = PrOptions::new("Bump".to_string(), String::new(), String::new());
+        pr_options.create_only = true;
+        let command = BumpCommand {
+            level: BumpLevel::Patch,
+            update_dependents: false,
+            dry_run: false,
+            pr_options: Some(pr_options),
+            branch: None,
+            commit_msg: None,
+        };
+
+        let mut bump = plan_bump(&repo, command.level).unwrap().unwrap();
+        let error = command
+            .apply_bump(&Config::new(), &mut bump, &[])
+            .await
+            .unwrap_err();
+        let after = std::fs::read_to_string(dir.join("package.json")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(error.to_string(), "Working tree has uncommitted changes");
+        assert_eq!(after, manifest);
+    }
+}
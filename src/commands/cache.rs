@@ -0,0 +1,41 @@
+//! Cache command implementation
+
+use super::{Command, CommandContext};
+use crate::cache::{self, ResultCache};
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+use std::path::Path;
+
+/// Operations on the result cache
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum CacheAction {
+    /// Remove cached results
+    Clear {
+        /// Only clear results for these repositories (if not provided, clears all)
+        repos: Vec<String>,
+
+        /// Configuration file path, the cache is kept next to it
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+    },
+}
+
+/// Cache command for managing cached `run --cache` results
+pub struct CacheCommand {
+    pub action: CacheAction,
+}
+
+#[async_trait]
+impl Command for CacheCommand {
+    async fn execute(&self, _context: &CommandContext) -> Result<()> {
+        match &self.action {
+            CacheAction::Clear { repos, config } => {
+                let dir = cache::cache_dir(Path::new(config).parent());
+                let removed = ResultCache::at(dir).clear(repos)?;
+                println!("{}", format!("Removed {removed} cached results").green());
+            }
+        }
+        Ok(())
+    }
+}
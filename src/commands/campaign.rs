@@ -0,0 +1,385 @@
+//! Campaign command implementation
+
+use super::replace::replace_in_tree;
+use super::sync_files::SyncMap;
+use super::{Command, CommandContext};
+use crate::campaign::{Campaign, CampaignState, CampaignStep};
+use crate::config::{Config, Repository};
+use crate::git::{self, Logger};
+use crate::github::{self, GitHubClient, PrOptions, rollout};
+use crate::runner::CommandRunner;
+use crate::state::RepoStatus;
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+use glob::Pattern;
+use regex::Regex;
+use std::path::Path;
+use std::time::Duration;
+
+/// Campaign operations
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum CampaignAction {
+    /// Start a campaign, processing its first batch of repositories
+    Run {
+        /// Campaign file
+        #[arg(default_value = "campaign.yaml")]
+        file: String,
+
+        /// GitHub token (falls back to GITHUB_TOKEN)
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+    },
+
+    /// Process the next batch, retrying repositories that failed
+    Resume {
+        /// Campaign file
+        #[arg(default_value = "campaign.yaml")]
+        file: String,
+
+        /// GitHub token (falls back to GITHUB_TOKEN)
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+    },
+
+    /// Show the progress of a campaign
+    Status {
+        /// Campaign file
+        #[arg(default_value = "campaign.yaml")]
+        file: String,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+    },
+
+    /// Stop a campaign so it can't be resumed
+    Abort {
+        /// Campaign file
+        #[arg(default_value = "campaign.yaml")]
+        file: String,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+    },
+}
+
+/// What to do with a campaign
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CampaignOperation {
+    Run,
+    Resume,
+    Status,
+    Abort,
+}
+
+/// Campaign command for rolling out a campaign file's changes
+pub struct CampaignCommand {
+    pub campaign: Campaign,
+    pub operation: CampaignOperation,
+    pub token: Option<String>,
+}
+
+#[async_trait]
+impl Command for CampaignCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        match self.operation {
+            CampaignOperation::Status => self.status(),
+            CampaignOperation::Abort => self.abort(),
+            CampaignOperation::Run | CampaignOperation::Resume => self.rollout(context).await,
+        }
+    }
+}
+
+impl CampaignCommand {
+    async fn rollout(&self, context: &CommandContext) -> Result<()> {
+        let path = self.campaign.state_path();
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        let mut state = if self.operation == CampaignOperation::Run {
+            if let Ok(existing) = CampaignState::load(&path)
+                && !existing.aborted
+                && !existing.is_complete()
+            {
+                anyhow::bail!(
+                    "Campaign '{}' is in progress; continue it with `campaign resume` or stop it with `campaign abort`",
+                    self.campaign.name
+                );
+            }
+            CampaignState::new(&self.campaign.name, &repositories)
+        } else {
+            let state = CampaignState::load(&path)?;
+            if state.aborted {
+                anyhow::bail!(
+                    "Campaign '{}' was aborted; start it over with `campaign run`",
+                    self.campaign.name
+                );
+            }
+            state
+        };
+        state.save(&path)?;
+
+        let mut batch = state.next_batch(repositories.clone(), self.campaign.batch_size);
+        if batch.is_empty() {
+            println!(
+                "{}",
+                format!("Campaign '{}' is complete", self.campaign.name).green()
+            );
+            return Ok(());
+        }
+
+        let pr_options = self.pr_options()?;
+
+        loop {
+            println!(
+                "{}",
+                format!(
+                    "Running campaign '{}' in {} repositories...",
+                    self.campaign.name,
+                    batch.len()
+                )
+                .green()
+            );
+
+            let mut failed = 0;
+            let mut urls = Vec::new();
+            for repo in &batch {
+                let result = self.apply(&context.config, repo, &pr_options).await;
+                match &result {
+                    Ok(url) => urls.extend(url.clone()),
+                    Err(e) => {
+                        failed += 1;
+                        eprintln!(
+                            "{} | {}",
+                            repo.name.cyan().bold(),
+                            format!("Error: {e}").red()
+                        );
+                    }
+                }
+                state.record(&repo.name, &result);
+                state.save(&path)?;
+            }
+
+            if failed > 0 {
+                anyhow::bail!("{} repositories failed", failed);
+            }
+
+            let remaining = state.repositories.len() - state.count(RepoStatus::Succeeded);
+            if remaining == 0 {
+                println!(
+                    "{}",
+                    format!("Campaign '{}' is complete", self.campaign.name).green()
+                );
+                return Ok(());
+            }
+
+            // Without a wait condition, each invocation processes one batch
+            let Some(condition) = self.campaign.wait_for else {
+                println!(
+                    "{}",
+                    format!(
+                        "{remaining} repositories left; continue with `rrepos campaign resume`"
+                    )
+                    .yellow()
+                );
+                return Ok(());
+            };
+
+            if !urls.is_empty() {
+                let client = GitHubClient::with_settings(
+                    Some(pr_options.token.clone()),
+                    &context.config.github_settings(),
+                )?;
+                let timeout = Duration::from_secs(self.campaign.wait_timeout * 60);
+                if let Err(e) = rollout::wait_for_batch(&client, &urls, condition, timeout).await {
+                    anyhow::bail!(
+                        "Stopping campaign '{}': {e}; continue with `rrepos campaign resume` once it is resolved",
+                        self.campaign.name
+                    );
+                }
+            }
+            batch = state.next_batch(repositories.clone(), self.campaign.batch_size);
+        }
+    }
+
+    /// Pull request options for the campaign; without a `pr` section the
+    /// changes are only committed
+    fn pr_options(&self) -> Result<PrOptions> {
+        let commit_msg = Some(self.campaign.commit_message.clone());
+        let branch_template = self.campaign.pr.as_ref().and_then(|pr| pr.branch.clone());
+        let campaign = Some(self.campaign.name.clone());
+
+        let Some(pr) = &self.campaign.pr else {
+            return Ok(PrOptions {
+                branch_template,
+                commit_msg,
+                campaign,
+                create_only: true,
+                ..PrOptions::new(
+                    self.campaign.commit_message.clone(),
+                    String::new(),
+                    String::new(),
+                )
+            });
+        };
+
+        let token = self
+            .token
+            .clone()
+            .or_else(|| std::env::var("GITHUB_TOKEN").ok())
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "GitHub token not provided. Use --token flag or set GITHUB_TOKEN environment variable."
+                )
+            })?;
+        Ok(PrOptions {
+            branch_template,
+            campaign,
+            base_branch: pr.base.clone(),
+            commit_msg,
+            draft: pr.draft,
+            ..PrOptions::new(pr.title.clone(), pr.body.clone(), token)
+        })
+    }
+
+    /// Run the campaign's steps in a repository and open its pull request,
+    /// returning the pull request URL
+    async fn apply(
+        &self,
+        config: &Config,
+        repo: &Repository,
+        pr_options: &PrOptions,
+    ) -> Result<Option<String>> {
+        let logger = Logger;
+        let repo_dir = repo.get_target_dir();
+
+        if !Path::new(&repo_dir).exists() {
+            anyhow::bail!("Repository directory does not exist: {}", repo_dir);
+        }
+        // Only the campaign's own changes may end up in the commit
+        if git::has_changes(&repo_dir)? {
+            anyhow::bail!("Working tree has uncommitted changes");
+        }
+
+        let runner = CommandRunner::new();
+        for step in &self.campaign.steps {
+            match step {
+                CampaignStep::Run(command) => {
+                    runner.run_command(repo, command, None).await?;
+                }
+                CampaignStep::Replace {
+                    pattern,
+                    replacement,
+                    glob,
+                } => {
+                    let regex = Regex::new(pattern)?;
+                    let glob = glob.as_deref().map(Pattern::new).transpose()?;
+                    let changed = replace_in_tree(
+                        Path::new(&repo_dir),
+                        &regex,
+                        replacement,
+                        glob.as_ref(),
+                        false,
+                    )?;
+                    logger.info(
+                        repo,
+                        &format!("Replaced '{pattern}' in {} files", changed.len()),
+                    );
+                }
+                CampaignStep::SyncFiles(map) => {
+                    let map_path = self.campaign.base_dir.join(map);
+                    let map = SyncMap::load(&map_path.to_string_lossy())?;
+                    for (file, content) in map.outdated_files(repo)? {
+                        let target = Path::new(&repo_dir).join(&file.target);
+                        if let Some(parent) = target.parent() {
+                            std::fs::create_dir_all(parent)?;
+                        }
+                        std::fs::write(&target, content)?;
+                        logger.info(repo, &format!("Updated {}", file.target));
+                    }
+                }
+            }
+        }
+
+        github::open_pull_request(config, repo, pr_options).await
+    }
+
+    fn status(&self) -> Result<()> {
+        let state = CampaignState::load(&self.campaign.state_path())?;
+
+        let mut heading = format!("Campaign '{}' started {}", state.name, state.started_at);
+        if state.aborted {
+            heading.push_str(" (aborted)");
+        }
+        println!("{}", heading.bold());
+
+        for (name, repo) in &state.repositories {
+            let (status, detail) = match repo.status {
+                RepoStatus::Succeeded => (
+                    "succeeded".green(),
+                    repo.pull_request.clone().unwrap_or_default(),
+                ),
+                RepoStatus::Failed => ("failed".red(), repo.error.clone().unwrap_or_default()),
+                RepoStatus::Pending => ("pending".yellow(), String::new()),
+            };
+            println!("  {:<30} {status:<10} {detail}", name.cyan());
+        }
+
+        println!(
+            "{} succeeded, {} failed, {} pending",
+            state.count(RepoStatus::Succeeded),
+            state.count(RepoStatus::Failed),
+            state.count(RepoStatus::Pending)
+        );
+        Ok(())
+    }
+
+    fn abort(&self) -> Result<()> {
+        let path = self.campaign.state_path();
+        let mut state = CampaignState::load(&path)?;
+        state.aborted = true;
+        state.save(&path)?;
+
+        println!(
+            "{}",
+            format!("Campaign '{}' aborted", self.campaign.name).yellow()
+        );
+
+        // Pull requests that were already opened stay open
+        let opened: Vec<&String> = state
+            .repositories
+            .values()
+            .filter_map(|repo| repo.pull_request.as_ref())
+            .collect();
+        if !opened.is_empty() {
+            println!("Pull requests already opened:");
+            for url in opened {
+                println!("  {url}");
+            }
+        }
+        Ok(())
+    }
+}
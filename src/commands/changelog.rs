@@ -0,0 +1,186 @@
+//! Changelog command implementation
+
+use super::{Command, CommandContext};
+use crate::conventional::{self, ConventionalCommit};
+use crate::git::{self, CommitInfo, Logger};
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// Output format for the generated changelog
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ChangelogFormat {
+    Markdown,
+    Json,
+}
+
+/// Changelog command for collecting commits across repositories
+pub struct ChangelogCommand {
+    pub since: String,
+    pub until: String,
+    pub format: ChangelogFormat,
+    /// Group commits by Conventional Commits type
+    pub conventional: bool,
+    pub output: Option<String>,
+}
+
+/// Changes collected for a single repository
+#[derive(Debug, Serialize)]
+struct RepositoryChanges {
+    repository: String,
+    range: String,
+    commits: Vec<ChangelogEntry>,
+}
+
+#[derive(Debug, Serialize)]
+struct ChangelogEntry {
+    #[serde(flatten)]
+    commit: CommitInfo,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    commit_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scope: Option<String>,
+    breaking: bool,
+}
+
+#[async_trait]
+impl Command for ChangelogCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            eprintln!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        let logger = Logger;
+        let range = format!("{}..{}", self.since, self.until);
+        let mut changes = Vec::new();
+
+        for repo in repositories {
+            let repo_dir = repo.get_target_dir();
+            if !Path::new(&repo_dir).exists() {
+                logger.warn(&repo, "Repository directory does not exist, skipping");
+                continue;
+            }
+            if !git::ref_exists(&repo_dir, &self.since) {
+                logger.warn(
+                    &repo,
+                    &format!("Reference '{}' not found, skipping", self.since),
+                );
+                continue;
+            }
+
+            match git::log(&repo_dir, &["--no-merges", &range]) {
+                Ok(commits) if !commits.is_empty() => changes.push(RepositoryChanges {
+                    repository: repo.name.clone(),
+                    range: range.clone(),
+                    commits: commits.into_iter().map(ChangelogEntry::from).collect(),
+                }),
+                Ok(_) => {}
+                Err(e) => eprintln!(
+                    "{} | {}",
+                    repo.name.cyan().bold(),
+                    format!("Error: {e}").red()
+                ),
+            }
+        }
+
+        let document = match self.format {
+            ChangelogFormat::Markdown => self.render_markdown(&changes),
+            ChangelogFormat::Json => serde_json::to_string_pretty(&changes)?,
+        };
+
+        match &self.output {
+            Some(path) => {
+                std::fs::write(path, document)?;
+                eprintln!("{}", format!("Changelog written to '{path}'").green());
+            }
+            None => println!("{document}"),
+        }
+
+        Ok(())
+    }
+}
+
+impl ChangelogCommand {
+    fn render_markdown(&self, changes: &[RepositoryChanges]) -> String {
+        let mut out = String::from("# Changelog\n");
+
+        for repo in changes {
+            let _ = writeln!(out, "\n## {} ({})\n", repo.repository, repo.range);
+
+            if !self.conventional {
+                for entry in &repo.commits {
+                    let _ = writeln!(
+                        out,
+                        "- {} ({})",
+                        entry.commit.subject,
+                        short(&entry.commit.sha)
+                    );
+                }
+                continue;
+            }
+
+            let mut sections: Vec<&str> = Vec::new();
+            for entry in &repo.commits {
+                let section = entry.section();
+                if !sections.contains(&section) {
+                    sections.push(section);
+                }
+            }
+
+            for (index, section) in sections.into_iter().enumerate() {
+                if index > 0 {
+                    out.push('\n');
+                }
+                let _ = writeln!(out, "### {section}\n");
+                for entry in repo.commits.iter().filter(|e| e.section() == section) {
+                    let marker = if entry.breaking { "**BREAKING** " } else { "" };
+                    let _ = writeln!(
+                        out,
+                        "- {marker}{} ({})",
+                        entry.commit.subject,
+                        short(&entry.commit.sha)
+                    );
+                }
+            }
+        }
+
+        out
+    }
+}
+
+impl From<CommitInfo> for ChangelogEntry {
+    fn from(commit: CommitInfo) -> Self {
+        let parsed = ConventionalCommit::parse(&commit.subject);
+        Self {
+            commit_type: parsed.as_ref().map(|c| c.commit_type.clone()),
+            scope: parsed.as_ref().and_then(|c| c.scope.clone()),
+            breaking: parsed.is_some_and(|c| c.breaking),
+            commit,
+        }
+    }
+}
+
+impl ChangelogEntry {
+    fn section(&self) -> &'static str {
+        conventional::section_for(self.commit_type.as_deref().unwrap_or(""))
+    }
+}
+
+fn short(sha: &str) -> &str {
+    &sha[..sha.len().min(7)]
+}
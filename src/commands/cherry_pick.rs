@@ -0,0 +1,180 @@
+//! Cherry-pick command implementation
+
+use super::{Command, CommandContext};
+use crate::config::{Config, Repository};
+use crate::git::{self, Logger};
+use crate::github::{self, PrOptions};
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+use std::path::Path;
+
+/// Result of applying the patch in one repository
+enum PickOutcome {
+    Applied,
+    Conflicts,
+}
+
+/// Cherry-pick command for applying a commit from one repository to the others
+pub struct CherryPickCommand {
+    pub commit: String,
+    /// Name of the repository the commit is taken from
+    pub from: String,
+    /// When set, the picked change goes through the pull request flow instead
+    /// of being committed on the current branch. An empty title or commit
+    /// message defaults to the picked commit's subject.
+    pub pr_options: Option<PrOptions>,
+}
+
+#[async_trait]
+impl Command for CherryPickCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let Some(source) = context
+            .config
+            .repositories
+            .iter()
+            .find(|repo| repo.name == self.from)
+        else {
+            anyhow::bail!("Repository '{}' not found in config", self.from);
+        };
+        let source_dir = source.get_target_dir();
+        if !Path::new(&source_dir).exists() {
+            anyhow::bail!("Repository directory does not exist: {}", source_dir);
+        }
+
+        let patch = git::format_patch(&source_dir, &self.commit)?;
+        let subject = git::log(&source_dir, &["-1", &self.commit])?
+            .into_iter()
+            .next()
+            .map(|commit| commit.subject)
+            .unwrap_or_else(|| self.commit.clone());
+
+        let repositories: Vec<Repository> = context
+            .repositories()
+            .into_iter()
+            .filter(|repo| repo.name != self.from)
+            .collect();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        // git am reads the patch from a file; keep it outside the repositories
+        let patch_file =
+            std::env::temp_dir().join(format!("rrepos-cherry-pick-{}.patch", std::process::id()));
+        std::fs::write(&patch_file, patch)?;
+        let patch_file = patch_file.to_string_lossy().to_string();
+
+        let pr_options = self.pr_options.clone().map(|mut options| {
+            if options.title.is_empty() {
+                options.title = subject.clone();
+            }
+            options.commit_msg.get_or_insert_with(|| subject.clone());
+            options
+        });
+
+        println!(
+            "{}",
+            format!(
+                "Cherry-picking '{}' from {} into {} repositories...",
+                subject,
+                self.from,
+                repositories.len()
+            )
+            .green()
+        );
+
+        let logger = Logger;
+        let mut applied = 0;
+        let mut conflicting = 0;
+        let mut failed = 0;
+
+        for repo in &repositories {
+            match self
+                .pick(&context.config, repo, &patch_file, pr_options.as_ref())
+                .await
+            {
+                Ok(PickOutcome::Applied) => {
+                    applied += 1;
+                    logger.success(repo, "Applied");
+                }
+                Ok(PickOutcome::Conflicts) => {
+                    conflicting += 1;
+                    eprintln!(
+                        "{} | {}",
+                        repo.name.cyan().bold(),
+                        "Conflicts, patch not applied".red()
+                    );
+                }
+                Err(e) => {
+                    failed += 1;
+                    eprintln!(
+                        "{} | {}",
+                        repo.name.cyan().bold(),
+                        format!("Error: {e}").red()
+                    );
+                }
+            }
+        }
+
+        std::fs::remove_file(&patch_file).ok();
+
+        println!(
+            "{}",
+            format!(
+                "Applied in {applied} of {} repositories, {conflicting} with conflicts",
+                repositories.len()
+            )
+            .green()
+        );
+
+        if failed > 0 {
+            anyhow::bail!("{} repositories failed", failed);
+        }
+        if conflicting > 0 {
+            anyhow::bail!("{} repositories have conflicts", conflicting);
+        }
+        Ok(())
+    }
+}
+
+impl CherryPickCommand {
+    async fn pick(
+        &self,
+        config: &Config,
+        repo: &Repository,
+        patch_file: &str,
+        pr_options: Option<&PrOptions>,
+    ) -> Result<PickOutcome> {
+        let repo_dir = repo.get_target_dir();
+        if !Path::new(&repo_dir).exists() {
+            anyhow::bail!("Repository directory does not exist: {}", repo_dir);
+        }
+        if git::has_changes(&repo_dir)? {
+            anyhow::bail!("Repository has uncommitted changes");
+        }
+
+        if !git::apply_patch(&repo_dir, patch_file)? {
+            return Ok(PickOutcome::Conflicts);
+        }
+
+        if let Some(pr_options) = pr_options {
+            // Hand the picked change to the pull request flow as staged
+            // changes so it lands on its own branch
+            git::reset_soft(&repo_dir, "HEAD~1")?;
+            github::create_pull_request(config, repo, pr_options).await?;
+        }
+
+        Ok(PickOutcome::Applied)
+    }
+}
@@ -0,0 +1,348 @@
+//! CI command implementation
+
+use super::actions::pin_repository;
+use super::{Command, CommandContext};
+use crate::config::Repository;
+use crate::git::Logger;
+use crate::github::{GitHubClient, PrOptions};
+use crate::workflows::{self, WORKFLOWS_DIR};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Operations on GitHub Actions workflows
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum CiAction {
+    /// Check workflows against a policy of required jobs, pinned actions and
+    /// allowed runners
+    Audit {
+        /// Specific repository names to audit (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Policy file listing required jobs, allowed runners and whether
+        /// actions must be pinned
+        #[arg(long, default_value = "ci-policy.yaml")]
+        policy: String,
+
+        /// Write the non-compliance report as JSON to this file
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Open pull requests pinning unpinned actions to commit SHAs
+        #[arg(long)]
+        fix: bool,
+
+        /// Title for the pull requests opened with --fix
+        #[arg(long, default_value = "Pin GitHub Actions to commit SHAs")]
+        title: String,
+
+        /// Body text for the pull requests opened with --fix
+        #[arg(
+            long,
+            default_value = "This PR pins the GitHub Actions used by the workflows to full commit SHAs"
+        )]
+        body: String,
+
+        /// Branch name to create
+        #[arg(long)]
+        branch: Option<String>,
+
+        /// Base branch for the PR
+        #[arg(long)]
+        base: Option<String>,
+
+        /// Create PR as draft
+        #[arg(long)]
+        draft: bool,
+
+        /// GitHub token
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+}
+
+/// Rules workflows are audited against
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct CiPolicy {
+    /// Jobs every repository must define, matched by job ID or name
+    pub required_jobs: Vec<String>,
+    /// Require actions to be referenced by full commit SHA
+    pub pin_actions: bool,
+    /// Runner labels jobs may use in `runs-on`; any label when empty
+    pub allowed_runners: Vec<String>,
+}
+
+impl CiPolicy {
+    pub fn load(path: &str) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read CI policy '{path}'"))?;
+        serde_yaml::from_str(&content).with_context(|| format!("Invalid CI policy '{path}'"))
+    }
+}
+
+/// Kind of policy rule a violation breaks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Rule {
+    RequiredJob,
+    PinnedAction,
+    AllowedRunner,
+}
+
+/// A place where a repository's workflows don't follow the policy
+#[derive(Debug, Clone, Serialize)]
+pub struct Violation {
+    pub repository: String,
+    pub rule: Rule,
+    /// Workflow file, relative to the repository; empty for missing jobs
+    pub file: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+/// CI audit command checking workflows against a policy and optionally
+/// opening pull requests that pin actions
+pub struct CiAuditCommand {
+    pub policy: CiPolicy,
+    pub output: Option<String>,
+    /// Options for the pinning pull requests; `None` to only report
+    pub fix: Option<PrOptions>,
+}
+
+#[async_trait]
+impl Command for CiAuditCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            format!(
+                "Auditing workflows in {} repositories...",
+                repositories.len()
+            )
+            .green()
+        );
+
+        let logger = Logger;
+        let mut violations = Vec::new();
+        let mut failed = 0;
+        for repo in &repositories {
+            let repo_dir = repo.get_target_dir();
+            if !Path::new(&repo_dir).exists() {
+                logger.warn(repo, "Repository directory does not exist, skipping");
+                continue;
+            }
+
+            match audit(repo, Path::new(&repo_dir), &self.policy) {
+                Ok(found) if found.is_empty() => logger.success(repo, "Compliant"),
+                Ok(found) => {
+                    for violation in &found {
+                        let location = match violation.line {
+                            Some(line) => format!("{}:{line}: ", violation.file),
+                            None if !violation.file.is_empty() => {
+                                format!("{}: ", violation.file)
+                            }
+                            None => String::new(),
+                        };
+                        println!(
+                            "{} | {}",
+                            repo.name.cyan().bold(),
+                            format!("{location}{}", violation.message).red()
+                        );
+                    }
+                    violations.extend(found);
+                }
+                Err(e) => {
+                    failed += 1;
+                    eprintln!(
+                        "{} | {}",
+                        repo.name.cyan().bold(),
+                        format!("Error: {e}").red()
+                    );
+                }
+            }
+        }
+
+        if let Some(path) = &self.output {
+            std::fs::write(path, serde_json::to_string_pretty(&violations)?)?;
+            println!(
+                "{}",
+                format!("{} violations written to '{path}'", violations.len()).green()
+            );
+        }
+
+        if let Some(pr_options) = &self.fix {
+            let client = GitHubClient::with_settings(
+                Some(pr_options.token.clone()),
+                &context.config.github_settings(),
+            )?;
+            let mut shas = HashMap::new();
+            for repo in &repositories {
+                let unpinned = violations
+                    .iter()
+                    .any(|v| v.repository == repo.name && v.rule == Rule::PinnedAction);
+                if !unpinned {
+                    continue;
+                }
+                if let Err(e) =
+                    pin_repository(&context.config, &client, repo, &mut shas, Some(pr_options))
+                        .await
+                {
+                    failed += 1;
+                    eprintln!(
+                        "{} | {}",
+                        repo.name.cyan().bold(),
+                        format!("Error: {e}").red()
+                    );
+                }
+            }
+        }
+
+        if failed > 0 {
+            anyhow::bail!("{} repositories failed", failed);
+        }
+        if !violations.is_empty() && self.fix.is_none() {
+            anyhow::bail!("{} CI policy violations", violations.len());
+        }
+
+        println!("{}", "Done auditing workflows".green());
+        Ok(())
+    }
+}
+
+/// Check a repository's workflows against `policy`
+fn audit(repo: &Repository, repo_dir: &Path, policy: &CiPolicy) -> Result<Vec<Violation>> {
+    let mut violations = Vec::new();
+    let violation = |rule, file: &str, line, message: String| Violation {
+        repository: repo.name.clone(),
+        rule,
+        file: file.to_string(),
+        line,
+        message,
+    };
+
+    let mut job_names = Vec::new();
+    for path in workflows::workflow_files(repo_dir) {
+        let content = std::fs::read_to_string(&path)?;
+        let file = format!(
+            "{WORKFLOWS_DIR}/{}",
+            path.file_name().unwrap_or_default().to_string_lossy()
+        );
+        let jobs =
+            workflows::jobs(&content).with_context(|| format!("Failed to parse '{file}'"))?;
+
+        for job in jobs {
+            for label in &job.runs_on {
+                // Labels computed from a matrix or inputs can't be checked statically
+                if !policy.allowed_runners.is_empty()
+                    && !label.contains("${{")
+                    && !policy.allowed_runners.contains(label)
+                {
+                    violations.push(violation(
+                        Rule::AllowedRunner,
+                        &file,
+                        None,
+                        format!("Job '{}' runs on disallowed runner '{label}'", job.id),
+                    ));
+                }
+            }
+            job_names.push(job.id);
+            job_names.extend(job.name);
+        }
+
+        if policy.pin_actions {
+            for action in workflows::action_refs(&content) {
+                if !action.is_pinned() {
+                    violations.push(violation(
+                        Rule::PinnedAction,
+                        &file,
+                        Some(action.line),
+                        format!(
+                            "{}@{} is not pinned to a commit SHA",
+                            action.name, action.reference
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    for required in &policy.required_jobs {
+        if !job_names.contains(required) {
+            violations.push(violation(
+                Rule::RequiredJob,
+                "",
+                None,
+                format!("Required job '{required}' is missing"),
+            ));
+        }
+    }
+
+    Ok(violations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit() {
+        let dir = std::env::temp_dir().join(format!("rrepos-ci-test-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join(WORKFLOWS_DIR)).unwrap();
+        std::fs::write(
+            dir.join(WORKFLOWS_DIR).join("ci.yml"),
+            "jobs:\n  test:\n    runs-on: ${{ matrix.os }}\n  lint:\n    runs-on: windows-latest\n    steps:\n      - uses: actions/checkout@v4\n",
+        )
+        .unwrap();
+
+        let repo = Repository::new("api".to_string(), "git@github.com:org/api.git".to_string());
+        let policy = CiPolicy {
+            required_jobs: vec!["test".to_string(), "build".to_string()],
+            pin_actions: true,
+            allowed_runners: vec!["ubuntu-latest".to_string()],
+        };
+        let violations = audit(&repo, &dir, &policy).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let found: Vec<(Rule, &str, Option<usize>)> = violations
+            .iter()
+            .map(|v| (v.rule, v.file.as_str(), v.line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (Rule::AllowedRunner, ".github/workflows/ci.yml", None),
+                (Rule::PinnedAction, ".github/workflows/ci.yml", Some(7)),
+                (Rule::RequiredJob, "", None),
+            ]
+        );
+        assert_eq!(violations[2].message, "Required job 'build' is missing");
+    }
+}
@@ -1,20 +1,35 @@
 //! Clone command implementation
 
+use super::remove::unsaved_work;
+use super::sync::{check_origin, sync_repository};
 use super::{Command, CommandContext};
-use crate::git;
+use crate::config::{HostSettings, IfExists, Repository};
+use crate::git::{self, Logger};
+use crate::preflight;
+use crate::scheduler::{self, HostLimiter};
+use crate::state::{self, ResumeMode, StateTracker};
 use anyhow::Result;
 use async_trait::async_trait;
 use colored::*;
+use std::path::Path;
 
 /// Clone command for cloning repositories
-pub struct CloneCommand;
+#[derive(Default)]
+pub struct CloneCommand {
+    /// Which repositories to process based on the previous clone's state
+    pub resume: ResumeMode,
+    /// Check that remotes are reachable before cloning
+    pub preflight: bool,
+    /// What to do with existing directories, overriding the config
+    pub if_exists: Option<IfExists>,
+    /// Point `origin` of existing clones at the configured URL when it differs
+    pub fix_remote: bool,
+}
 
 #[async_trait]
 impl Command for CloneCommand {
     async fn execute(&self, context: &CommandContext) -> Result<()> {
-        let repositories = context
-            .config
-            .filter_repositories(context.tag.as_deref(), context.repos.as_deref());
+        let repositories = context.repositories();
 
         if repositories.is_empty() {
             let filter_desc = match (&context.tag, &context.repos) {
@@ -30,34 +45,73 @@ impl Command for CloneCommand {
             return Ok(());
         }
 
+        let (state, repositories) = StateTracker::start(
+            state::state_file(context.config.config_dir.as_deref()),
+            "clone",
+            "",
+            repositories,
+            self.resume,
+        )?;
+        if repositories.is_empty() {
+            println!(
+                "{}",
+                "Nothing left to clone from the previous state".yellow()
+            );
+            return Ok(());
+        }
+
+        let repositories = if self.preflight {
+            preflight::check_remotes(&context.config, repositories).await?
+        } else {
+            repositories
+        };
+
         println!(
             "{}",
             format!("Cloning {} repositories...", repositories.len()).green()
         );
 
         if context.parallel {
+            let limiter = HostLimiter::new(&context.config, &repositories);
             let tasks: Vec<_> = repositories
                 .into_iter()
                 .map(|repo| {
-                    tokio::spawn(async move {
-                        tokio::task::spawn_blocking(move || git::clone_repository(&repo)).await?
-                    })
+                    let name = repo.name.clone();
+                    let limiter = limiter.clone();
+                    let settings = repo.host_settings(&context.config);
+                    let if_exists = self.if_exists(context, &repo);
+                    let fix_remote = self.fix_remote;
+                    let task = tokio::spawn(async move {
+                        let _permit = limiter.acquire(&repo).await;
+                        let _slot = scheduler::job_slot().await;
+                        tokio::task::spawn_blocking(move || {
+                            clone_or_update(&repo, &settings, if_exists, fix_remote)
+                        })
+                        .await?
+                    });
+                    (name, task)
                 })
                 .collect();
 
-            for task in tasks {
-                if let Err(e) = task.await? {
+            for (name, task) in tasks {
+                let result = task.await?;
+                state.record(&name, result.is_ok());
+                if let Err(e) = result {
                     eprintln!("{}", format!("Error: {e}").red());
                 }
             }
         } else {
             for repo in repositories {
-                if let Err(e) = tokio::task::spawn_blocking({
+                let result = tokio::task::spawn_blocking({
                     let repo = repo.clone();
-                    move || git::clone_repository(&repo)
+                    let settings = repo.host_settings(&context.config);
+                    let if_exists = self.if_exists(context, &repo);
+                    let fix_remote = self.fix_remote;
+                    move || clone_or_update(&repo, &settings, if_exists, fix_remote)
                 })
-                .await?
-                {
+                .await?;
+                state.record(&repo.name, result.is_ok());
+                if let Err(e) = result {
                     eprintln!("{}", format!("Error: {e}").red());
                 }
             }
@@ -67,3 +121,93 @@ impl Command for CloneCommand {
         Ok(())
     }
 }
+
+impl CloneCommand {
+    /// What to do with an existing directory: `--if-exists`, else the
+    /// repository's or the config's setting
+    fn if_exists(&self, context: &CommandContext, repo: &Repository) -> IfExists {
+        self.if_exists
+            .unwrap_or_else(|| repo.if_exists(&context.config))
+    }
+}
+
+/// Clone a repository, or handle its existing directory as `if_exists` asks
+fn clone_or_update(
+    repo: &Repository,
+    settings: &HostSettings,
+    if_exists: IfExists,
+    fix_remote: bool,
+) -> Result<()> {
+    let repo_dir = repo.get_target_dir();
+    if !Path::new(&repo_dir).exists() {
+        return git::clone_repository(repo, settings);
+    }
+
+    match if_exists {
+        IfExists::Skip => {
+            // Surface config drift such as a renamed repository
+            if let Err(e) = check_origin(repo, fix_remote) {
+                Logger.warn(repo, &e.to_string());
+            }
+            Logger.warn(repo, "Repository directory already exists, skipping");
+            Ok(())
+        }
+        IfExists::Pull => sync_repository(repo, settings, fix_remote),
+        IfExists::Recreate => {
+            if let Some(reason) = unsaved_work(&repo_dir)? {
+                anyhow::bail!("{}, not recreating {}", reason, repo_dir);
+            }
+            git::remove_repository(repo)?;
+            git::clone_repository(repo, settings)
+        }
+        IfExists::Fail => anyhow::bail!("Repository directory already exists: {}", repo_dir),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::git;
+
+    #[test]
+    fn test_clone_if_exists() {
+        let dir = std::env::temp_dir().join(format!("rrepos-clone-test-{}", std::process::id()));
+        let origin = dir.join("origin");
+        std::fs::create_dir_all(&origin).unwrap();
+        git(&origin, &["init", "--quiet"]);
+        git(
+            &origin,
+            &["commit", "--quiet", "--allow-empty", "-m", "first"],
+        );
+
+        let mut repo = Repository::new("api".to_string(), origin.to_string_lossy().to_string());
+        let clone_dir = dir.join("api");
+        repo.path = Some(clone_dir.to_string_lossy().to_string());
+        let marker = clone_dir.join(".git").join("marker");
+        let untracked = clone_dir.join("notes.txt");
+
+        let settings = HostSettings::default();
+        clone_or_update(&repo, &settings, IfExists::Skip, false).unwrap();
+        std::fs::write(&marker, "").unwrap();
+
+        // Skip leaves the clone alone, Fail reports it
+        clone_or_update(&repo, &settings, IfExists::Skip, false).unwrap();
+        let skipped = marker.exists();
+        let failed = clone_or_update(&repo, &settings, IfExists::Fail, false).is_err();
+
+        // Recreate refuses to throw away unsaved work, then clones afresh
+        std::fs::write(&untracked, "draft\n").unwrap();
+        let refused = clone_or_update(&repo, &settings, IfExists::Recreate, false).is_err();
+        let kept = untracked.exists() && marker.exists();
+        std::fs::remove_file(&untracked).unwrap();
+        clone_or_update(&repo, &settings, IfExists::Recreate, false).unwrap();
+        let recreated = clone_dir.join(".git").exists() && !marker.exists();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(skipped);
+        assert!(failed);
+        assert!(refused);
+        assert!(kept);
+        assert!(recreated);
+    }
+}
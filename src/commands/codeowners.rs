@@ -0,0 +1,463 @@
+//! CODEOWNERS command implementation
+
+use super::{Command, CommandContext};
+use crate::config::{Config, Repository};
+use crate::git::{self, Logger};
+use crate::github::{self, GitHubClient, PrOptions};
+use crate::template;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use colored::*;
+use glob::{MatchOptions, Pattern};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Locations GitHub reads CODEOWNERS from, in order of precedence
+pub const CODEOWNERS_PATHS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// Operations on CODEOWNERS files
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum CodeownersAction {
+    /// Check that each repository has a valid CODEOWNERS file
+    Audit {
+        /// Specific repository names to audit (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Path that must have an owner (can be repeated)
+        #[arg(long = "require")]
+        required: Vec<String>,
+
+        /// GitHub token (falls back to GITHUB_TOKEN; team owners need the read:org scope)
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Write CODEOWNERS from a template and open pull requests
+    Apply {
+        /// Specific repository names to update (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// CODEOWNERS template; `{placeholder}` variables are rendered per repository
+        #[arg(long)]
+        template: String,
+
+        /// Show which repositories would change without writing
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Title for the pull request
+        #[arg(long, default_value = "Update CODEOWNERS")]
+        title: String,
+
+        /// Body text for the pull request
+        #[arg(
+            long,
+            default_value = "This PR updates the CODEOWNERS file to match the shared template"
+        )]
+        body: String,
+
+        /// Branch name to create
+        #[arg(long)]
+        branch: Option<String>,
+
+        /// Base branch for the PR
+        #[arg(long)]
+        base: Option<String>,
+
+        /// Create PR as draft
+        #[arg(long)]
+        draft: bool,
+
+        /// GitHub token
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+}
+
+/// A single CODEOWNERS rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnerRule {
+    pub pattern: String,
+    pub owners: Vec<String>,
+}
+
+/// Parse CODEOWNERS content into rules, skipping comments and blank lines
+pub fn parse_codeowners(content: &str) -> Vec<OwnerRule> {
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let mut parts = line.split_whitespace();
+            OwnerRule {
+                pattern: parts.next().unwrap_or_default().to_string(),
+                owners: parts.map(str::to_string).collect(),
+            }
+        })
+        .collect()
+}
+
+/// Owners of a path; the last matching rule wins, as on GitHub
+pub fn owners_for<'a>(rules: &'a [OwnerRule], path: &str) -> &'a [String] {
+    rules
+        .iter()
+        .rev()
+        .find(|rule| pattern_matches(&rule.pattern, path))
+        .map(|rule| rule.owners.as_slice())
+        .unwrap_or_default()
+}
+
+/// Match a path against a CODEOWNERS (gitignore-style) pattern
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    if pattern == "*" {
+        return true;
+    }
+
+    let directory_only = pattern.ends_with('/');
+    let trimmed = pattern.trim_end_matches('/');
+    // Patterns with a slash other than a trailing one are relative to the root
+    let anchored = trimmed.contains('/');
+    let glob = if anchored {
+        trimmed.trim_start_matches('/').to_string()
+    } else {
+        format!("**/{trimmed}")
+    };
+    let Ok(glob) = Pattern::new(&glob) else {
+        return false;
+    };
+    let options = MatchOptions {
+        require_literal_separator: true,
+        ..MatchOptions::new()
+    };
+
+    // A pattern matching a directory owns everything below it
+    let path = path.trim_start_matches('/');
+    let components: Vec<&str> = path.split('/').collect();
+    (1..=components.len()).any(|n| {
+        let is_file = n == components.len();
+        !(directory_only && is_file) && glob.matches_with(&components[..n].join("/"), options)
+    })
+}
+
+/// Find the CODEOWNERS file GitHub would use for a repository
+pub fn find_codeowners(repo_dir: &Path) -> Option<PathBuf> {
+    CODEOWNERS_PATHS
+        .iter()
+        .map(|path| repo_dir.join(path))
+        .find(|path| path.is_file())
+}
+
+/// Audit command for checking CODEOWNERS files across repositories
+pub struct CodeownersAuditCommand {
+    /// Paths that must have an owner
+    pub required: Vec<String>,
+    pub token: Option<String>,
+}
+
+#[async_trait]
+impl Command for CodeownersAuditCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            format!(
+                "Auditing CODEOWNERS in {} repositories...",
+                repositories.len()
+            )
+            .green()
+        );
+
+        let client =
+            GitHubClient::with_settings(self.token.clone(), &context.config.github_settings())?;
+        // Owners are usually shared across repositories, so look each up once
+        let mut known_owners: HashMap<String, bool> = HashMap::new();
+        let mut failed = 0;
+
+        for repo in &repositories {
+            match self
+                .audit_repository(repo, &client, &mut known_owners)
+                .await
+            {
+                Ok(problems) if problems.is_empty() => {
+                    println!("{} | {}", repo.name.cyan().bold(), "OK".green())
+                }
+                Ok(problems) => {
+                    failed += 1;
+                    for problem in problems {
+                        eprintln!("{} | {}", repo.name.cyan().bold(), problem.red());
+                    }
+                }
+                Err(e) => {
+                    failed += 1;
+                    eprintln!(
+                        "{} | {}",
+                        repo.name.cyan().bold(),
+                        format!("Error: {e}").red()
+                    );
+                }
+            }
+        }
+
+        if failed > 0 {
+            anyhow::bail!("{} repositories failed the CODEOWNERS audit", failed);
+        }
+
+        println!("{}", "All CODEOWNERS files passed the audit".green());
+        Ok(())
+    }
+}
+
+impl CodeownersAuditCommand {
+    async fn audit_repository(
+        &self,
+        repo: &Repository,
+        client: &GitHubClient,
+        known_owners: &mut HashMap<String, bool>,
+    ) -> Result<Vec<String>> {
+        let repo_dir = repo.get_target_dir();
+        if !Path::new(&repo_dir).exists() {
+            anyhow::bail!("Repository directory does not exist: {}", repo_dir);
+        }
+
+        let Some(path) = find_codeowners(Path::new(&repo_dir)) else {
+            return Ok(vec!["No CODEOWNERS file".to_string()]);
+        };
+        let rules = parse_codeowners(&std::fs::read_to_string(&path)?);
+
+        let mut problems = Vec::new();
+
+        for owner in rules.iter().flat_map(|rule| &rule.owners) {
+            let exists = match known_owners.get(owner) {
+                Some(exists) => *exists,
+                None => {
+                    let exists = owner_exists(client, owner).await?;
+                    known_owners.insert(owner.clone(), exists);
+                    exists
+                }
+            };
+            let problem = format!("Unknown owner {owner}");
+            if !exists && !problems.contains(&problem) {
+                problems.push(problem);
+            }
+        }
+
+        for required in &self.required {
+            if owners_for(&rules, required).is_empty() {
+                problems.push(format!("No owner for {required}"));
+            }
+        }
+
+        Ok(problems)
+    }
+}
+
+/// Check that a CODEOWNERS owner (`@user`, `@org/team` or an email) exists
+async fn owner_exists(client: &GitHubClient, owner: &str) -> Result<bool> {
+    let Some(name) = owner.strip_prefix('@') else {
+        // Email owners can't be checked through the API
+        return Ok(owner.contains('@'));
+    };
+
+    match name.split_once('/') {
+        Some((org, team)) => client.team_exists(org, team).await,
+        None => client.user_exists(name).await,
+    }
+}
+
+/// Apply command for writing CODEOWNERS from a template
+pub struct CodeownersApplyCommand {
+    pub template: String,
+    pub dry_run: bool,
+    /// Options for the pull requests opened for changed repositories
+    pub pr_options: PrOptions,
+}
+
+#[async_trait]
+impl Command for CodeownersApplyCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let template = std::fs::read_to_string(&self.template)
+            .with_context(|| format!("Failed to read template '{}'", self.template))?;
+
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            format!(
+                "Applying CODEOWNERS to {} repositories...",
+                repositories.len()
+            )
+            .green()
+        );
+
+        for repo in &repositories {
+            if let Err(e) = self
+                .apply_repository(&context.config, repo, &template)
+                .await
+            {
+                eprintln!(
+                    "{} | {}",
+                    repo.name.cyan().bold(),
+                    format!("Error: {e}").red()
+                );
+            }
+        }
+
+        println!("{}", "Done applying CODEOWNERS".green());
+        Ok(())
+    }
+}
+
+impl CodeownersApplyCommand {
+    async fn apply_repository(
+        &self,
+        config: &Config,
+        repo: &Repository,
+        template: &str,
+    ) -> Result<()> {
+        let logger = Logger;
+        let repo_dir = repo.get_target_dir();
+        if !Path::new(&repo_dir).exists() {
+            anyhow::bail!("Repository directory does not exist: {}", repo_dir);
+        }
+
+        let content = template::render(template, &template::repository_variables(repo));
+        // Update the file GitHub reads, or create it in .github/
+        let target = find_codeowners(Path::new(&repo_dir))
+            .unwrap_or_else(|| Path::new(&repo_dir).join(CODEOWNERS_PATHS[0]));
+        let relative = target.strip_prefix(&repo_dir).unwrap_or(&target).display();
+
+        if std::fs::read_to_string(&target).ok().as_deref() == Some(content.as_str()) {
+            logger.info(repo, "CODEOWNERS is up to date");
+            return Ok(());
+        }
+
+        if self.dry_run {
+            logger.info(repo, &format!("Would update {relative}"));
+            return Ok(());
+        }
+
+        // Only CODEOWNERS may end up in the pull request's commit
+        if git::has_changes(&repo_dir)? {
+            anyhow::bail!("Working tree has uncommitted changes");
+        }
+
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&target, content)?;
+        logger.success(repo, &format!("Updated {relative}"));
+
+        github::create_pull_request(config, repo, &self.pr_options).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::git;
+
+    #[test]
+    fn test_parse_codeowners() {
+        let rules = parse_codeowners("# Owners\n*  @my-org/core\n/docs/ @alice # docs\n\n");
+        assert_eq!(
+            rules,
+            vec![
+                OwnerRule {
+                    pattern: "*".to_string(),
+                    owners: vec!["@my-org/core".to_string()],
+                },
+                OwnerRule {
+                    pattern: "/docs/".to_string(),
+                    owners: vec!["@alice".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_owners_for_last_match_wins() {
+        let rules = parse_codeowners("*.rs @rust\n/docs/ @docs\napps/ @apps\n/build/logs/ \n");
+
+        assert_eq!(owners_for(&rules, "src/main.rs"), ["@rust"]);
+        assert_eq!(owners_for(&rules, "docs/guide/intro.md"), ["@docs"]);
+        assert_eq!(owners_for(&rules, "nested/apps/web/index.js"), ["@apps"]);
+        assert!(owners_for(&rules, "build/logs/out.rs").is_empty());
+        assert!(owners_for(&rules, "README.md").is_empty());
+        assert!(owners_for(&rules, "other/docs/page.md").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_apply_refuses_dirty_tree() {
+        let dir = std::env::temp_dir().join(format!(
+            "rrepos-codeowners-dirty-test-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        git(&dir, &["init", "--quiet"]);
+        git(&dir, &["commit", "--quiet", "--allow-empty", "-m", "first"]);
+        std::fs::write(dir.join("notes.txt"), "unrelated edit\n").unwrap();
+
+        let mut repo = Repository::new("api".to_string(), "git@github.com:org/api.git".to_string());
+        repo.path = Some(dir.to_string_lossy().to_string());
+        let command = CodeownersApplyCommand {
+            template: String::new(),
+            dry_run: false,
+            pr_options: PrOptions::new("CODEOWNERS".to_string(), String::new(), String::new()),
+        };
+
+        let error = command
+            .apply_repository(&Config::new(), &repo, "* @org/{name}-maintainers\n")
+            .await
+            .unwrap_err();
+        let written = find_codeowners(&dir).is_some();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(error.to_string(), "Working tree has uncommitted changes");
+        assert!(!written);
+    }
+}
@@ -0,0 +1,100 @@
+//! Completions command implementation
+
+use super::{Command, CommandContext};
+use crate::config::{Config, discovery};
+use anyhow::Result;
+use async_trait::async_trait;
+use clap_complete::Shell;
+use clap_complete::engine::{ArgValueCompleter, CompletionCandidate};
+use std::collections::BTreeSet;
+use std::ffi::OsStr;
+
+/// Completions command for printing a static shell completion script
+pub struct CompletionsCommand {
+    pub shell: Shell,
+    /// The command-line definition completions are generated for
+    pub cli: clap::Command,
+}
+
+#[async_trait]
+impl Command for CompletionsCommand {
+    async fn execute(&self, _context: &CommandContext) -> Result<()> {
+        let mut cli = self.cli.clone();
+        clap_complete::generate(self.shell, &mut cli, "rrepos", &mut std::io::stdout());
+        Ok(())
+    }
+}
+
+/// Attach config-aware completers to every `--tag` and `repos` argument,
+/// recursing into subcommands
+pub fn with_dynamic_completers(cli: clap::Command) -> clap::Command {
+    let subcommands: Vec<String> = cli
+        .get_subcommands()
+        .map(|sub| sub.get_name().to_string())
+        .collect();
+
+    let cli = cli.mut_args(|arg| match arg.get_id().as_str() {
+        "tag" => arg.add(ArgValueCompleter::new(complete_tags)),
+        "repos" => arg.add(ArgValueCompleter::new(complete_repos)),
+        _ => arg,
+    });
+
+    subcommands.iter().fold(cli, |cli, name| {
+        cli.mut_subcommand(name, with_dynamic_completers)
+    })
+}
+
+fn complete_tags(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(config) = completion_config() else {
+        return Vec::new();
+    };
+    let tags: BTreeSet<String> = config
+        .repositories
+        .iter()
+        .flat_map(|repo| repo.tags.iter().cloned())
+        .collect();
+    candidates(tags, current)
+}
+
+fn complete_repos(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(config) = completion_config() else {
+        return Vec::new();
+    };
+    let names: BTreeSet<String> = config
+        .repositories
+        .into_iter()
+        .map(|repo| repo.name)
+        .collect();
+    candidates(names, current)
+}
+
+/// Parse the discovered config without decrypting secrets, so completion
+/// never shells out to age or sops
+fn completion_config() -> Option<Config> {
+    let content = std::fs::read_to_string(discovery::discover()).ok()?;
+    serde_yaml::from_str(&content).ok()
+}
+
+fn candidates(values: BTreeSet<String>, current: &OsStr) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+    values
+        .into_iter()
+        .filter(|value| value.starts_with(current.as_ref()))
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidates_filter_by_prefix() {
+        let values: BTreeSet<String> = ["api", "app", "web"].map(String::from).into();
+        let completed: Vec<_> = candidates(values, OsStr::new("ap"))
+            .iter()
+            .map(|candidate| candidate.get_value().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(completed, vec!["api", "app"]);
+    }
+}
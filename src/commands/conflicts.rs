@@ -0,0 +1,141 @@
+//! Conflicts command implementation
+
+use super::{Command, CommandContext};
+use crate::config::{Config, Repository};
+use crate::git::{self, Logger};
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+use std::path::Path;
+
+/// Base branch used when neither `--against` nor the repository's recorded
+/// default branch is available
+const DEFAULT_BASE: &str = "main";
+
+/// Result of test-merging a branch in one repository
+enum MergeOutcome {
+    Clean,
+    Conflicts(Vec<String>),
+    MissingBranch,
+}
+
+/// Conflicts command for reporting which repositories a merge would conflict in
+pub struct ConflictsCommand {
+    pub branch: String,
+    /// Base to merge into; the repository's default branch when unset
+    pub against: Option<String>,
+    /// Fetch from origin before test-merging
+    pub fetch: bool,
+}
+
+#[async_trait]
+impl Command for ConflictsCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            format!(
+                "Test-merging '{}' in {} repositories...",
+                self.branch,
+                repositories.len()
+            )
+            .green()
+        );
+
+        let logger = Logger;
+        let mut conflicting = 0;
+        let mut tested = 0;
+        let mut failed = 0;
+
+        for repo in &repositories {
+            match self.test_merge(&context.config, repo) {
+                Ok(MergeOutcome::Clean) => {
+                    tested += 1;
+                    println!("{} | {}", repo.name.cyan().bold(), "merges cleanly".green());
+                }
+                Ok(MergeOutcome::Conflicts(files)) => {
+                    tested += 1;
+                    conflicting += 1;
+                    println!(
+                        "{} | {}",
+                        repo.name.cyan().bold(),
+                        format!("{} conflicting files", files.len()).red()
+                    );
+                    for file in files {
+                        println!("{} |   {file}", repo.name.cyan().bold());
+                    }
+                }
+                Ok(MergeOutcome::MissingBranch) => {
+                    logger.info(repo, &format!("No branch '{}', skipping", self.branch));
+                }
+                Err(e) => {
+                    failed += 1;
+                    eprintln!(
+                        "{} | {}",
+                        repo.name.cyan().bold(),
+                        format!("Error: {e}").red()
+                    );
+                }
+            }
+        }
+
+        let summary = format!("{conflicting} of {tested} repositories would conflict");
+        if conflicting > 0 {
+            println!("{}", summary.yellow());
+        } else {
+            println!("{}", summary.green());
+        }
+
+        if failed > 0 {
+            anyhow::bail!("{} repositories failed", failed);
+        }
+        Ok(())
+    }
+}
+
+impl ConflictsCommand {
+    fn test_merge(&self, config: &Config, repo: &Repository) -> Result<MergeOutcome> {
+        let repo_dir = repo.get_target_dir();
+        if !Path::new(&repo_dir).exists() {
+            anyhow::bail!("Repository directory does not exist: {}", repo_dir);
+        }
+
+        if self.fetch {
+            git::fetch(repo, &repo.host_settings(config), false)?;
+        }
+
+        let Some(branch) = git::resolve_ref(&repo_dir, &self.branch) else {
+            return Ok(MergeOutcome::MissingBranch);
+        };
+        let base = self
+            .against
+            .as_deref()
+            .or(repo.default_branch.as_deref())
+            .unwrap_or(DEFAULT_BASE);
+        let Some(base) = git::resolve_ref(&repo_dir, base) else {
+            anyhow::bail!("Base branch '{}' not found", base);
+        };
+
+        let files = git::merge_conflicts(&repo_dir, &base, &branch)?;
+        Ok(if files.is_empty() {
+            MergeOutcome::Clean
+        } else {
+            MergeOutcome::Conflicts(files)
+        })
+    }
+}
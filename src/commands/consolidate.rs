@@ -0,0 +1,169 @@
+//! Consolidate command implementation
+
+use super::{Command, CommandContext};
+use crate::config::Repository;
+use crate::git::{self, Logger};
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+use std::path::Path;
+
+/// How the directory of each repository inside the monorepo is named
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum PrefixBy {
+    /// `<name>`
+    Name,
+    /// `<first tag>/<name>`
+    Tag,
+}
+
+/// Consolidate command combining repositories into a single monorepo while
+/// keeping their histories
+pub struct ConsolidateCommand {
+    /// Target repository, created when missing
+    pub into: String,
+    pub prefix_by: PrefixBy,
+    /// Rewrite each history with `git filter-repo` so past commits show the
+    /// files under their prefix too
+    pub rewrite_history: bool,
+    /// Only show where each repository would be placed
+    pub dry_run: bool,
+}
+
+#[async_trait]
+impl Command for ConsolidateCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        if self.dry_run {
+            for repo in &repositories {
+                println!(
+                    "{} | {} -> {}/{}/",
+                    repo.name.cyan().bold(),
+                    repo.get_target_dir(),
+                    self.into,
+                    self.prefix(repo)
+                );
+            }
+            println!("{}", "Dry run, nothing merged".yellow());
+            return Ok(());
+        }
+
+        self.prepare_target()?;
+        println!(
+            "{}",
+            format!(
+                "Consolidating {} repositories into {}...",
+                repositories.len(),
+                self.into
+            )
+            .green()
+        );
+
+        let logger = Logger;
+        let mut merged = 0;
+        for repo in &repositories {
+            let source = repo.get_target_dir();
+            let prefix = self.prefix(repo);
+            if !Path::new(&source).exists() {
+                logger.warn(repo, "Repository directory does not exist, skipping");
+                continue;
+            }
+            if Path::new(&self.into).join(&prefix).exists() {
+                logger.warn(repo, &format!("'{prefix}/' already exists, skipping"));
+                continue;
+            }
+
+            // Git resolves the source relative to the target
+            let source = std::fs::canonicalize(&source)?
+                .to_string_lossy()
+                .to_string();
+            match self.merge(repo, &source, &prefix) {
+                Ok(()) => {
+                    merged += 1;
+                    logger.success(repo, &format!("Merged into {prefix}/"));
+                }
+                Err(e) => eprintln!(
+                    "{} | {}",
+                    repo.name.cyan().bold(),
+                    format!("Error: {e}").red()
+                ),
+            }
+        }
+
+        println!(
+            "{}",
+            format!("Consolidated {merged} repositories into {}", self.into).green()
+        );
+        Ok(())
+    }
+}
+
+impl ConsolidateCommand {
+    fn prefix(&self, repo: &Repository) -> String {
+        match self.prefix_by {
+            PrefixBy::Name => repo.name.clone(),
+            PrefixBy::Tag => {
+                let group = repo.tags.first().map(String::as_str).unwrap_or("untagged");
+                format!("{group}/{}", repo.name)
+            }
+        }
+    }
+
+    /// Create the target repository, or check that an existing one can take
+    /// merges
+    fn prepare_target(&self) -> Result<()> {
+        if !Path::new(&self.into).exists() {
+            std::fs::create_dir_all(&self.into)?;
+            git::init(&self.into, "main")?;
+            // Merges need a commit to start from
+            return git::commit_empty(&self.into, "Initialize monorepo");
+        }
+
+        if !Path::new(&self.into).join(".git").exists() {
+            anyhow::bail!("'{}' exists but is not a git repository", self.into);
+        }
+        if git::has_changes(&self.into)? {
+            anyhow::bail!("'{}' has uncommitted changes", self.into);
+        }
+        if git::ref_exists(&self.into, "HEAD") {
+            Ok(())
+        } else {
+            git::commit_empty(&self.into, "Initialize monorepo")
+        }
+    }
+
+    fn merge(&self, repo: &Repository, source: &str, prefix: &str) -> Result<()> {
+        let message = format!("Add {} under {prefix}/", repo.name);
+        if !self.rewrite_history {
+            return git::merge_repository(&self.into, source, Some(prefix), &message);
+        }
+
+        // Rewrite a copy so the clone itself stays untouched
+        let copy = std::env::temp_dir().join(format!(
+            "rrepos-consolidate-{}-{}",
+            std::process::id(),
+            repo.name
+        ));
+        let copy = copy.to_string_lossy().to_string();
+        let result = git::clone_local(source, &copy)
+            .and_then(|_| git::filter_repo(&copy, &["--to-subdirectory-filter", prefix]))
+            .and_then(|_| git::merge_repository(&self.into, &copy, None, &message));
+        let _ = std::fs::remove_dir_all(&copy);
+        result
+    }
+}
@@ -0,0 +1,212 @@
+//! Contributors command implementation
+
+use super::{Command, CommandContext};
+use crate::config::Repository;
+use crate::git::{self, Logger};
+use crate::util;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use colored::*;
+use serde::Serialize;
+use std::path::Path;
+
+/// Output format for the contributor report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ContributorsFormat {
+    Table,
+    Json,
+}
+
+/// Contributors command reporting author statistics and bus factors
+pub struct ContributorsCommand {
+    /// Only count commits newer than this age or date, e.g. `1y`
+    pub since: Option<String>,
+    /// Number of top committers listed per repository
+    pub top: usize,
+    pub format: ContributorsFormat,
+}
+
+/// An author's share of a repository's commits
+#[derive(Debug, Serialize)]
+struct Author {
+    author: String,
+    commits: usize,
+    /// Percentage of the repository's commits
+    share: f64,
+}
+
+/// Author statistics of a repository
+#[derive(Debug, Serialize)]
+struct RepoContributors {
+    repository: String,
+    commits: usize,
+    contributors: usize,
+    /// Fewest authors who together made more than half of the commits
+    bus_factor: usize,
+    top_authors: Vec<Author>,
+}
+
+#[async_trait]
+impl Command for ContributorsCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            eprintln!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        let since = self
+            .since
+            .as_deref()
+            .map(|since| util::parse_time(since, Utc::now()))
+            .transpose()
+            .map_err(anyhow::Error::msg)?
+            .map(|time| time.format("%Y-%m-%d %H:%M:%S +0000").to_string());
+
+        let logger = Logger;
+        let mut report = Vec::new();
+        for repo in &repositories {
+            if !Path::new(&repo.get_target_dir()).exists() {
+                logger.warn(repo, "Repository directory does not exist, skipping");
+                continue;
+            }
+            match self.analyze(repo, since.as_deref()) {
+                Ok(contributors) => report.push(contributors),
+                Err(e) => eprintln!(
+                    "{} | {}",
+                    repo.name.cyan().bold(),
+                    format!("Error: {e}").red()
+                ),
+            }
+        }
+
+        match self.format {
+            ContributorsFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+            ContributorsFormat::Table => print_table(&report),
+        }
+        Ok(())
+    }
+}
+
+impl ContributorsCommand {
+    fn analyze(&self, repo: &Repository, since: Option<&str>) -> Result<RepoContributors> {
+        let counts = git::commit_counts(&repo.get_target_dir(), since)?;
+        let commits: usize = counts.iter().map(|(_, count)| count).sum();
+
+        Ok(RepoContributors {
+            repository: repo.name.clone(),
+            commits,
+            contributors: counts.len(),
+            bus_factor: bus_factor(&counts),
+            top_authors: counts
+                .iter()
+                .take(self.top)
+                .map(|(author, count)| Author {
+                    author: author.clone(),
+                    commits: *count,
+                    share: *count as f64 * 100.0 / commits as f64,
+                })
+                .collect(),
+        })
+    }
+}
+
+/// Fewest authors who together made more than half of the commits; `counts`
+/// is sorted by commits, most first
+fn bus_factor(counts: &[(String, usize)]) -> usize {
+    let total: usize = counts.iter().map(|(_, count)| count).sum();
+    let mut covered = 0;
+    for (index, (_, count)) in counts.iter().enumerate() {
+        covered += count;
+        if covered * 2 > total {
+            return index + 1;
+        }
+    }
+    counts.len()
+}
+
+fn print_table(report: &[RepoContributors]) {
+    let name_width = report
+        .iter()
+        .map(|repo| repo.repository.len())
+        .max()
+        .unwrap_or(0)
+        .max(10);
+
+    println!(
+        "{:<name_width$}  {:>7}  {:>7}  {:>10}  Top committers",
+        "Repository", "Commits", "Authors", "Bus factor"
+    );
+    for repo in report {
+        let bus_factor = if repo.commits == 0 {
+            format!("{:>10}", "-").normal()
+        } else if repo.bus_factor == 1 {
+            format!("{:>10}", repo.bus_factor).red().bold()
+        } else {
+            format!("{:>10}", repo.bus_factor).normal()
+        };
+        let top: Vec<String> = repo
+            .top_authors
+            .iter()
+            .map(|author| {
+                // Leave out the email address
+                let name = author
+                    .author
+                    .split_once(" <")
+                    .map_or(author.author.as_str(), |(name, _)| name);
+                format!("{name} ({:.0}%)", author.share)
+            })
+            .collect();
+        println!(
+            "{:<name_width$}  {:>7}  {:>7}  {bus_factor}  {}",
+            repo.repository,
+            repo.commits,
+            repo.contributors,
+            top.join(", ")
+        );
+    }
+
+    let active = report.iter().filter(|repo| repo.commits > 0).count();
+    let at_risk = report
+        .iter()
+        .filter(|repo| repo.commits > 0 && repo.bus_factor == 1)
+        .count();
+    let summary = format!("\n{at_risk} of {active} active repositories have a bus factor of 1");
+    if at_risk > 0 {
+        println!("{}", summary.yellow());
+    } else {
+        println!("{}", summary.green());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(commits: &[usize]) -> Vec<(String, usize)> {
+        commits
+            .iter()
+            .enumerate()
+            .map(|(index, count)| (format!("author{index}"), *count))
+            .collect()
+    }
+
+    #[test]
+    fn test_bus_factor() {
+        assert_eq!(bus_factor(&counts(&[60, 30, 10])), 1);
+        assert_eq!(bus_factor(&counts(&[50, 30, 20])), 2);
+        assert_eq!(bus_factor(&counts(&[25, 25, 25, 25])), 3);
+        assert_eq!(bus_factor(&counts(&[])), 0);
+    }
+}
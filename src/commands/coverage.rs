@@ -0,0 +1,246 @@
+//! Coverage command implementation
+
+use super::{Command, CommandContext};
+use crate::git::Logger;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use colored::*;
+use std::path::Path;
+
+/// Where coverage tools commonly write LCOV reports, relative to the repository
+const LCOV_PATHS: [&str; 5] = [
+    "lcov.info",
+    "coverage/lcov.info",
+    "target/lcov.info",
+    "target/coverage/lcov.info",
+    "coverage.lcov",
+];
+
+/// Coverage report formats that can be merged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum CoverageFormat {
+    Lcov,
+}
+
+/// Line coverage of a repository
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LineCoverage {
+    pub found: u64,
+    pub hit: u64,
+}
+
+impl LineCoverage {
+    pub fn percent(&self) -> f64 {
+        if self.found == 0 {
+            100.0
+        } else {
+            self.hit as f64 * 100.0 / self.found as f64
+        }
+    }
+}
+
+/// Coverage command for merging per-repository coverage reports
+pub struct CoverageCommand {
+    pub format: CoverageFormat,
+    /// Report path relative to each repository; well-known paths are tried when unset
+    pub report: Option<String>,
+    /// Fail when a repository's line coverage is below this percentage
+    pub threshold: Option<f64>,
+    pub output: String,
+}
+
+#[async_trait]
+impl Command for CoverageCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        let logger = Logger;
+        let mut merged = String::new();
+        let mut rows = Vec::new();
+
+        for repo in &repositories {
+            let repo_dir = repo.get_target_dir();
+            if !Path::new(&repo_dir).exists() {
+                logger.warn(repo, "Repository directory does not exist, skipping");
+                continue;
+            }
+
+            let candidates: Vec<&str> = match &self.report {
+                Some(report) => vec![report.as_str()],
+                None => LCOV_PATHS.to_vec(),
+            };
+            let Some(path) = candidates
+                .into_iter()
+                .map(|path| Path::new(&repo_dir).join(path))
+                .find(|path| path.is_file())
+            else {
+                logger.warn(repo, "No coverage report found, skipping");
+                continue;
+            };
+
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let (report, coverage) = match self.format {
+                CoverageFormat::Lcov => {
+                    let root = std::fs::canonicalize(&repo_dir)?;
+                    prefix_lcov(&content, &repo.name, &root)
+                }
+            };
+            merged.push_str(&report);
+            rows.push((repo.name.clone(), coverage));
+        }
+
+        if rows.is_empty() {
+            println!("{}", "No coverage reports found".yellow());
+            return Ok(());
+        }
+
+        std::fs::write(&self.output, &merged)?;
+        let below = print_table(&rows, self.threshold);
+        println!(
+            "{}",
+            format!(
+                "Merged coverage of {} repositories written to '{}'",
+                rows.len(),
+                self.output
+            )
+            .green()
+        );
+
+        if below > 0 {
+            anyhow::bail!(
+                "{} repositories are below the {}% coverage threshold",
+                below,
+                self.threshold.unwrap_or_default()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Print the per-repository coverage table and return how many repositories
+/// are below `threshold`
+fn print_table(rows: &[(String, LineCoverage)], threshold: Option<f64>) -> usize {
+    let name_width = rows
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0)
+        .max(10);
+
+    println!(
+        "\n{:<name_width$}  {:>8}  {:>8}  {:>8}",
+        "Repository", "Lines", "Covered", "Coverage"
+    );
+
+    let mut total = LineCoverage::default();
+    let mut below = 0;
+    for (name, coverage) in rows {
+        let percent = format!("{:>7.1}%", coverage.percent());
+        let percent = match threshold {
+            Some(threshold) if coverage.percent() < threshold => {
+                below += 1;
+                percent.red()
+            }
+            Some(_) => percent.green(),
+            None => percent.normal(),
+        };
+        println!(
+            "{:<name_width$}  {:>8}  {:>8}  {percent}",
+            name, coverage.found, coverage.hit
+        );
+        total.found += coverage.found;
+        total.hit += coverage.hit;
+    }
+
+    println!(
+        "{}",
+        format!(
+            "{:<name_width$}  {:>8}  {:>8}  {:>7.1}%",
+            "Total",
+            total.found,
+            total.hit,
+            total.percent()
+        )
+        .bold()
+    );
+    below
+}
+
+/// Prefix the source files of an LCOV report with the repository name and
+/// total its line coverage. Absolute paths inside the repository are made
+/// relative first.
+fn prefix_lcov(content: &str, repository: &str, repo_dir: &Path) -> (String, LineCoverage) {
+    let mut report = String::with_capacity(content.len());
+    let mut coverage = LineCoverage::default();
+    let mut record = LineCoverage::default();
+    let mut totals: Option<LineCoverage> = None;
+
+    for line in content.lines() {
+        if let Some(source) = line.strip_prefix("SF:") {
+            let relative = Path::new(source)
+                .strip_prefix(repo_dir)
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_else(|_| source.trim_start_matches("./").to_string());
+            report.push_str(&format!("SF:{repository}/{relative}\n"));
+            continue;
+        }
+
+        if let Some(data) = line.strip_prefix("DA:") {
+            record.found += 1;
+            if data
+                .split(',')
+                .nth(1)
+                .and_then(|hits| hits.parse::<u64>().ok())
+                .is_some_and(|hits| hits > 0)
+            {
+                record.hit += 1;
+            }
+        } else if let Some(found) = line.strip_prefix("LF:") {
+            totals.get_or_insert_default().found = found.trim().parse().unwrap_or(0);
+        } else if let Some(hit) = line.strip_prefix("LH:") {
+            totals.get_or_insert_default().hit = hit.trim().parse().unwrap_or(0);
+        } else if line == "end_of_record" {
+            // Prefer the tool's own totals over counting DA lines
+            let counted = totals.take().unwrap_or(record);
+            coverage.found += counted.found;
+            coverage.hit += counted.hit;
+            record = LineCoverage::default();
+        }
+
+        report.push_str(line);
+        report.push('\n');
+    }
+
+    (report, coverage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_lcov() {
+        let content = "TN:\nSF:/work/api/src/lib.rs\nDA:1,1\nDA:2,0\nLF:2\nLH:1\nend_of_record\nSF:src/main.rs\nDA:1,3\nDA:2,0\nDA:3,1\nend_of_record\n";
+        let (report, coverage) = prefix_lcov(content, "api", Path::new("/work/api"));
+
+        assert!(report.contains("SF:api/src/lib.rs\n"));
+        assert!(report.contains("SF:api/src/main.rs\n"));
+        assert_eq!(coverage, LineCoverage { found: 5, hit: 3 });
+        assert_eq!(coverage.percent(), 60.0);
+    }
+}
@@ -0,0 +1,294 @@
+//! Dependabot command implementation
+
+use super::{Command, CommandContext};
+use crate::config::{Config, Repository};
+use crate::git::{self, Logger};
+use crate::github::{self, PrOptions};
+use crate::template;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use colored::*;
+use std::path::Path;
+
+/// Where GitHub reads the Dependabot configuration from
+pub const DEPENDABOT_PATH: &str = ".github/dependabot.yml";
+
+/// Dependabot package ecosystems and the root files that indicate them
+const ECOSYSTEMS: [(&str, &[&str]); 10] = [
+    ("bundler", &["Gemfile"]),
+    ("cargo", &["Cargo.toml"]),
+    ("composer", &["composer.json"]),
+    ("docker", &["Dockerfile"]),
+    ("github-actions", &[".github/workflows"]),
+    ("gomod", &["go.mod"]),
+    ("gradle", &["build.gradle", "build.gradle.kts"]),
+    ("maven", &["pom.xml"]),
+    ("npm", &["package.json"]),
+    (
+        "pip",
+        &["requirements.txt", "pyproject.toml", "setup.py", "Pipfile"],
+    ),
+];
+
+/// Operations on Dependabot/Renovate configuration
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum DependabotAction {
+    /// Render a configuration for each repository's ecosystems and open pull requests
+    Apply {
+        /// Specific repository names to update (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Configuration template; `{updates}` expands to an update entry per
+        /// detected ecosystem and `{ecosystems}` to their names
+        #[arg(long)]
+        template: String,
+
+        /// File to write, relative to the repository root (e.g. renovate.json)
+        #[arg(long, default_value = DEPENDABOT_PATH)]
+        path: String,
+
+        /// Schedule interval used in the generated update entries
+        #[arg(long, default_value = "weekly")]
+        interval: String,
+
+        /// Show which repositories would change without writing
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Title for the pull request
+        #[arg(long, default_value = "Update dependency update configuration")]
+        title: String,
+
+        /// Body text for the pull request
+        #[arg(
+            long,
+            default_value = "This PR updates the dependency update configuration to match the shared template"
+        )]
+        body: String,
+
+        /// Branch name to create
+        #[arg(long)]
+        branch: Option<String>,
+
+        /// Base branch for the PR
+        #[arg(long)]
+        base: Option<String>,
+
+        /// Create PR as draft
+        #[arg(long)]
+        draft: bool,
+
+        /// GitHub token
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+}
+
+/// Dependabot package ecosystems used by a repository
+pub fn detect_ecosystems(repo_dir: &Path) -> Vec<&'static str> {
+    ECOSYSTEMS
+        .iter()
+        .filter(|(_, files)| files.iter().any(|file| repo_dir.join(file).exists()))
+        .map(|(ecosystem, _)| *ecosystem)
+        .collect()
+}
+
+/// Dependabot `updates` entries for `ecosystems`, indented to sit under
+/// `updates:`
+fn render_updates(ecosystems: &[&str], interval: &str) -> String {
+    ecosystems
+        .iter()
+        .map(|ecosystem| {
+            format!(
+                "  - package-ecosystem: \"{ecosystem}\"\n    directory: \"/\"\n    schedule:\n      interval: \"{interval}\""
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Apply command for rolling out Dependabot/Renovate configuration
+pub struct DependabotApplyCommand {
+    pub template: String,
+    /// Target file, relative to the repository root
+    pub path: String,
+    pub interval: String,
+    pub dry_run: bool,
+    /// Options for the pull requests opened for changed repositories
+    pub pr_options: PrOptions,
+}
+
+#[async_trait]
+impl Command for DependabotApplyCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let template = std::fs::read_to_string(&self.template)
+            .with_context(|| format!("Failed to read template '{}'", self.template))?;
+
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            format!(
+                "Applying {} to {} repositories...",
+                self.path,
+                repositories.len()
+            )
+            .green()
+        );
+
+        for repo in &repositories {
+            if let Err(e) = self
+                .apply_repository(&context.config, repo, &template)
+                .await
+            {
+                eprintln!(
+                    "{} | {}",
+                    repo.name.cyan().bold(),
+                    format!("Error: {e}").red()
+                );
+            }
+        }
+
+        println!("{}", format!("Done applying {}", self.path).green());
+        Ok(())
+    }
+}
+
+impl DependabotApplyCommand {
+    async fn apply_repository(
+        &self,
+        config: &Config,
+        repo: &Repository,
+        template: &str,
+    ) -> Result<()> {
+        let logger = Logger;
+        let repo_dir = repo.get_target_dir();
+        if !Path::new(&repo_dir).exists() {
+            anyhow::bail!("Repository directory does not exist: {}", repo_dir);
+        }
+
+        let ecosystems = detect_ecosystems(Path::new(&repo_dir));
+        if ecosystems.is_empty() {
+            logger.info(repo, "No supported package ecosystems, skipping");
+            return Ok(());
+        }
+
+        let mut vars = template::repository_variables(repo);
+        vars.insert("ecosystems".to_string(), ecosystems.join(","));
+        vars.insert(
+            "updates".to_string(),
+            render_updates(&ecosystems, &self.interval),
+        );
+        let content = template::render(template, &vars);
+        let target = Path::new(&repo_dir).join(&self.path);
+
+        if std::fs::read_to_string(&target).ok().as_deref() == Some(content.as_str()) {
+            logger.info(repo, &format!("{} is up to date", self.path));
+            return Ok(());
+        }
+
+        if self.dry_run {
+            logger.info(
+                repo,
+                &format!("Would update {} ({})", self.path, ecosystems.join(", ")),
+            );
+            return Ok(());
+        }
+
+        // Only the Dependabot config may end up in the pull request's commit
+        if git::has_changes(&repo_dir)? {
+            anyhow::bail!("Working tree has uncommitted changes");
+        }
+
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&target, content)?;
+        logger.success(
+            repo,
+            &format!("Updated {} ({})", self.path, ecosystems.join(", ")),
+        );
+
+        github::create_pull_request(config, repo, &self.pr_options).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::git;
+
+    #[test]
+    fn test_detect_ecosystems() {
+        let dir =
+            std::env::temp_dir().join(format!("rrepos-dependabot-test-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join(".github/workflows")).unwrap();
+        std::fs::write(dir.join("Cargo.toml"), "").unwrap();
+        std::fs::write(dir.join("Dockerfile"), "").unwrap();
+
+        let ecosystems = detect_ecosystems(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(ecosystems, ["cargo", "docker", "github-actions"]);
+
+        assert_eq!(
+            render_updates(&["cargo"], "daily"),
+            "  - package-ecosystem: \"cargo\"\n    directory: \"/\"\n    schedule:\n      interval: \"daily\""
+        );
+    }
+
+    #[tokio::test]
+    async fn test_apply_refuses_dirty_tree() {
+        let dir = std::env::temp_dir().join(format!(
+            "rrepos-dependabot-dirty-test-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        git(&dir, &["init", "--quiet"]);
+        std::fs::write(dir.join("Cargo.toml"), "").unwrap();
+        git(&dir, &["add", "."]);
+        git(&dir, &["commit", "--quiet", "-m", "first"]);
+        std::fs::write(dir.join("notes.txt"), "unrelated edit\n").unwrap();
+
+        let mut repo = Repository::new("api".to_string(), "git@github.com:org/api.git".to_string());
+        repo.path = Some(dir.to_string_lossy().to_string());
+        let command = DependabotApplyCommand {
+            template: String::new(),
+            path: DEPENDABOT_PATH.to_string(),
+            interval: "weekly".to_string(),
+            dry_run: false,
+            pr_options: PrOptions::new("Dependabot".to_string(), String::new(), String::new()),
+        };
+
+        let error = command
+            .apply_repository(&Config::new(), &repo, "updates: {updates}\n")
+            .await
+            .unwrap_err();
+        let written = dir.join(DEPENDABOT_PATH).exists();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(error.to_string(), "Working tree has uncommitted changes");
+        assert!(!written);
+    }
+}
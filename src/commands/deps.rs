@@ -0,0 +1,188 @@
+//! Deps command implementation
+
+use super::{Command, CommandContext};
+use crate::git::Logger;
+use crate::manifest::{self, Dependency};
+use crate::registry::RegistryClient;
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// Registry lookups made at once when checking for outdated packages
+const REGISTRY_CONCURRENCY: usize = 8;
+
+/// Versions of one package in use, mapped to the repositories using them
+type Usage = BTreeMap<String, Vec<String>>;
+
+/// Deps command for reporting dependency versions across repositories
+pub struct DepsCommand {
+    /// Only report this package
+    pub package: Option<String>,
+    /// Only report packages behind the latest published version
+    pub outdated: bool,
+}
+
+#[async_trait]
+impl Command for DepsCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        let logger = Logger;
+        let mut packages: BTreeMap<(&'static str, String), Usage> = BTreeMap::new();
+
+        for repo in &repositories {
+            let repo_dir = repo.get_target_dir();
+            if !Path::new(&repo_dir).exists() {
+                logger.warn(repo, "Repository directory does not exist, skipping");
+                continue;
+            }
+
+            let dependencies = match manifest::repository_dependencies(Path::new(&repo_dir)) {
+                Ok(dependencies) => dependencies,
+                Err(e) => {
+                    eprintln!(
+                        "{} | {}",
+                        repo.name.cyan().bold(),
+                        format!("Error: {e}").red()
+                    );
+                    continue;
+                }
+            };
+
+            for Dependency {
+                ecosystem,
+                name,
+                version,
+            } in dependencies
+            {
+                if self
+                    .package
+                    .as_deref()
+                    .is_some_and(|package| !matches_package(&name, package))
+                {
+                    continue;
+                }
+                let repos = packages
+                    .entry((ecosystem, name))
+                    .or_default()
+                    .entry(version)
+                    .or_default();
+                if !repos.contains(&repo.name) {
+                    repos.push(repo.name.clone());
+                }
+            }
+        }
+
+        if packages.is_empty() {
+            let what = match &self.package {
+                Some(package) => format!("No repositories depend on '{package}'"),
+                None => "No dependencies found".to_string(),
+            };
+            println!("{}", what.yellow());
+            return Ok(());
+        }
+
+        let latest = if self.outdated {
+            latest_versions(packages.keys().cloned().collect()).await?
+        } else {
+            HashMap::new()
+        };
+
+        let mut reported = 0;
+        for ((ecosystem, name), usage) in &packages {
+            let latest = latest.get(&(*ecosystem, name.clone())).cloned().flatten();
+            if self.outdated && !usage.keys().any(|v| is_behind(v, latest.as_deref())) {
+                continue;
+            }
+            reported += 1;
+
+            let header = match &latest {
+                Some(latest) => format!("{} ({ecosystem}), latest {latest}", name.bold()),
+                None => format!("{} ({ecosystem})", name.bold()),
+            };
+            println!("{header}");
+            for (version, repos) in usage {
+                let version_text = if is_behind(version, latest.as_deref()) {
+                    version.yellow()
+                } else {
+                    version.normal()
+                };
+                println!("  {:<16} {}", version_text, repos.join(", ").cyan());
+            }
+        }
+
+        let summary = if self.outdated {
+            format!("{reported} of {} packages are outdated", packages.len())
+        } else {
+            format!("{} packages found", packages.len())
+        };
+        println!("{}", summary.green());
+        Ok(())
+    }
+}
+
+/// Whether a dependency name refers to `package`; Maven artifacts also match
+/// by artifact id alone
+fn matches_package(name: &str, package: &str) -> bool {
+    name == package || name.rsplit_once(':').is_some_and(|(_, a)| a == package)
+}
+
+/// Whether `version` is older than `latest`
+fn is_behind(version: &str, latest: Option<&str>) -> bool {
+    latest
+        .and_then(|latest| manifest::compare_versions(version, latest))
+        .is_some_and(|ordering| ordering == Ordering::Less)
+}
+
+/// Latest published version of each package, looked up a batch at a time
+async fn latest_versions(
+    packages: Vec<(&'static str, String)>,
+) -> Result<HashMap<(&'static str, String), Option<String>>> {
+    let client = RegistryClient::new()?;
+    let mut latest = HashMap::new();
+
+    for batch in packages.chunks(REGISTRY_CONCURRENCY) {
+        let tasks = batch
+            .iter()
+            .map(|(ecosystem, name)| client.latest_version(ecosystem, name));
+        for (package, result) in batch.iter().zip(futures::future::join_all(tasks).await) {
+            let version = result.unwrap_or_else(|e| {
+                eprintln!("{}", format!("Error: {e}").red());
+                None
+            });
+            latest.insert(package.clone(), version);
+        }
+    }
+    Ok(latest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outdated_detection() {
+        assert!(is_behind("^4.17.15", Some("4.17.21")));
+        assert!(!is_behind("1.0", Some("1.0.0")));
+        assert!(!is_behind("path", Some("1.0.0")));
+        assert!(!is_behind("1.0", None));
+        assert!(matches_package("org.junit:junit", "junit"));
+    }
+}
@@ -0,0 +1,339 @@
+//! Devfiles command implementation
+
+use super::{Command, CommandContext};
+use crate::config::Repository;
+use crate::git::{self, Logger};
+use crate::template;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use colored::*;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Component, Path};
+
+/// Operations on per-clone developer configuration files
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum DevfilesAction {
+    /// Render a registry configuration file into each clone, kept out of git
+    Apply {
+        /// Specific repository names to update (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Kind of file to render
+        #[arg(long, value_enum)]
+        kind: DevfileKind,
+
+        /// Jinja template, e.g. using `{{ env.NPM_TOKEN }}` and `{{ repo.name }}`
+        #[arg(long)]
+        template: String,
+
+        /// File to write, relative to the repository root, instead of the
+        /// kind's usual location
+        #[arg(long)]
+        path: Option<String>,
+
+        /// Show which repositories would change without writing
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+}
+
+/// Registry configuration files that commonly carry credentials
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DevfileKind {
+    /// npm's `.npmrc`
+    #[value(name = "npmrc")]
+    Npmrc,
+    /// Maven's `settings.xml`, used with `mvn -s .mvn/settings.xml`
+    #[value(name = "settings.xml")]
+    SettingsXml,
+    /// Gradle's `gradle.properties`
+    #[value(name = "gradle.properties")]
+    GradleProperties,
+}
+
+impl DevfileKind {
+    /// Where the file is written, relative to the repository root
+    pub fn path(&self) -> &'static str {
+        match self {
+            DevfileKind::Npmrc => ".npmrc",
+            DevfileKind::SettingsXml => ".mvn/settings.xml",
+            DevfileKind::GradleProperties => "gradle.properties",
+        }
+    }
+
+    /// Package manager the file configures
+    fn tool(&self) -> &'static str {
+        match self {
+            DevfileKind::Npmrc => "npm",
+            DevfileKind::SettingsXml => "Maven",
+            DevfileKind::GradleProperties => "Gradle",
+        }
+    }
+
+    /// Whether a repository uses the package manager the file is for
+    fn applies_to(&self, repo_dir: &Path) -> bool {
+        let markers: &[&str] = match self {
+            DevfileKind::Npmrc => &["package.json"],
+            DevfileKind::SettingsXml => &["pom.xml"],
+            DevfileKind::GradleProperties => &[
+                "build.gradle",
+                "build.gradle.kts",
+                "settings.gradle",
+                "settings.gradle.kts",
+            ],
+        };
+        markers.iter().any(|marker| repo_dir.join(marker).exists())
+    }
+}
+
+/// Apply command rendering registry configuration into each clone. Files
+/// are excluded from git in the clone, and tracked files are never
+/// overwritten, so credentials can't be committed.
+pub struct DevfilesApplyCommand {
+    pub kind: DevfileKind,
+    pub template: String,
+    /// Target file, relative to the repository root
+    pub path: String,
+    pub dry_run: bool,
+}
+
+#[async_trait]
+impl Command for DevfilesApplyCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        check_path(&self.path)?;
+        let template = std::fs::read_to_string(&self.template)
+            .with_context(|| format!("Failed to read template '{}'", self.template))?;
+
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            format!(
+                "Applying {} to {} repositories...",
+                self.path,
+                repositories.len()
+            )
+            .green()
+        );
+
+        let mut failed = 0;
+        for repo in &repositories {
+            if let Err(e) = self.apply_repository(repo, &template) {
+                failed += 1;
+                eprintln!(
+                    "{} | {}",
+                    repo.name.cyan().bold(),
+                    format!("Error: {e}").red()
+                );
+            }
+        }
+
+        if failed > 0 {
+            anyhow::bail!("{} repositories failed", failed);
+        }
+        println!("{}", format!("Done applying {}", self.path).green());
+        Ok(())
+    }
+}
+
+impl DevfilesApplyCommand {
+    fn apply_repository(&self, repo: &Repository, template: &str) -> Result<()> {
+        let logger = Logger;
+        let repo_dir = repo.get_target_dir();
+        if !Path::new(&repo_dir).exists() {
+            logger.warn(repo, "Repository directory does not exist, skipping");
+            return Ok(());
+        }
+        if !self.kind.applies_to(Path::new(&repo_dir)) {
+            logger.info(
+                repo,
+                &format!("No {} project found, skipping", self.kind.tool()),
+            );
+            return Ok(());
+        }
+
+        if git::is_tracked(&repo_dir, &self.path)? {
+            anyhow::bail!(
+                "{} is tracked by git; refusing to write credentials into it",
+                self.path
+            );
+        }
+
+        let mut content = template::render_jinja(template, template_context(repo))?;
+        // Jinja drops the template's final newline
+        if template.ends_with('\n') && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        let target = Path::new(&repo_dir).join(&self.path);
+        let unchanged = std::fs::read_to_string(&target).ok().as_deref() == Some(content.as_str());
+
+        if self.dry_run {
+            if unchanged {
+                logger.info(repo, &format!("{} is up to date", self.path));
+            } else {
+                logger.info(repo, &format!("Would write {}", self.path));
+            }
+            return Ok(());
+        }
+
+        // Exclude the file before writing it, so it never shows up as a change
+        git::exclude_locally(&repo_dir, &self.path)?;
+        if unchanged {
+            logger.info(repo, &format!("{} is up to date", self.path));
+            return Ok(());
+        }
+
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        write_private(&target, &content)?;
+        logger.success(repo, &format!("Wrote {} (excluded from git)", self.path));
+        Ok(())
+    }
+}
+
+/// Refuse target paths that could point outside the clone: absolute ones and
+/// ones with `..` components
+fn check_path(path: &str) -> Result<()> {
+    let components: Vec<Component> = Path::new(path).components().collect();
+    let inside = components
+        .iter()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+        && components.iter().any(|c| matches!(c, Component::Normal(_)));
+    if !inside {
+        anyhow::bail!(
+            "Path '{}' must be relative to the repository root, without '..'",
+            path
+        );
+    }
+    Ok(())
+}
+
+/// Write `content` to a file only its owner can read. New files are created
+/// that way; existing ones are restricted before the content is written.
+fn write_private(path: &Path, content: &str) -> Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(content.as_bytes())?;
+    Ok(())
+}
+
+/// Template context: the repository and the environment, where a
+/// repository's configured `env` overrides inherited variables
+fn template_context(repo: &Repository) -> Value {
+    let mut env: BTreeMap<String, String> = std::env::vars().collect();
+    env.extend(repo.env.iter().map(|(k, v)| (k.clone(), v.clone())));
+    serde_json::json!({
+        "repo": {
+            "name": repo.name,
+            "url": repo.url,
+            "path": repo.get_target_dir(),
+            "branch": repo.branch,
+            "tags": repo.tags,
+        },
+        "env": env,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_context() {
+        let mut repo = Repository::new("web".to_string(), "git@github.com:org/web.git".to_string());
+        repo.env
+            .insert("NPM_REGISTRY".to_string(), "npm.example.com".to_string());
+
+        let rendered = template::render_jinja(
+            "//{{ env.NPM_REGISTRY }}/:_authToken=${NPM_TOKEN}\n# {{ repo.name }}",
+            template_context(&repo),
+        )
+        .unwrap();
+        assert_eq!(
+            rendered,
+            "//npm.example.com/:_authToken=${NPM_TOKEN}\n# web"
+        );
+    }
+
+    #[test]
+    fn test_applies_to() {
+        let dir = std::env::temp_dir().join(format!("rrepos-devfiles-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("pom.xml"), "").unwrap();
+
+        let applies = [
+            DevfileKind::Npmrc.applies_to(&dir),
+            DevfileKind::SettingsXml.applies_to(&dir),
+            DevfileKind::GradleProperties.applies_to(&dir),
+        ];
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(applies, [false, true, false]);
+    }
+
+    #[test]
+    fn test_check_path() {
+        assert!(check_path(".npmrc").is_ok());
+        assert!(check_path("./.mvn/settings.xml").is_ok());
+        assert!(check_path("/etc/npmrc").is_err());
+        assert!(check_path("../other/.npmrc").is_err());
+        assert!(check_path("config/../../.npmrc").is_err());
+        assert!(check_path(".").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir =
+            std::env::temp_dir().join(format!("rrepos-devfiles-write-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(".npmrc");
+        std::fs::write(&path, "old\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        write_private(&path, "token\n").unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(mode, 0o600);
+        assert_eq!(content, "token\n");
+    }
+}
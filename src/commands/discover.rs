@@ -0,0 +1,214 @@
+//! Discover command implementation
+
+use super::{Command, CommandContext};
+use crate::config::{Repository, RepositoryBuilder};
+use crate::github::{GitHubClient, GitHubRepo};
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+
+/// Discover command for comparing a GitHub organization with the config
+pub struct DiscoverCommand {
+    pub org: String,
+    /// Update the config instead of only reporting differences
+    pub apply: bool,
+    /// Use HTTPS clone URLs for new repositories instead of SSH
+    pub https: bool,
+    pub token: Option<String>,
+    /// Config file to update
+    pub config_path: String,
+}
+
+/// Differences between an organization and the configured repositories
+#[derive(Debug, Default)]
+struct Discovery {
+    /// Repositories in the organization that are not configured
+    added: Vec<GitHubRepo>,
+    /// Configured repositories of the organization that no longer exist
+    /// under their configured name
+    missing: Vec<Repository>,
+}
+
+#[async_trait]
+impl Command for DiscoverCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        println!(
+            "{}",
+            format!(
+                "Listing repositories of GitHub organization '{}'...",
+                self.org
+            )
+            .green()
+        );
+
+        let client =
+            GitHubClient::with_settings(self.token.clone(), &context.config.github_settings())?;
+        let remote = client.list_org_repositories(&self.org).await?;
+        let discovery = discover(&client, &context.config.repositories, &self.org, remote);
+
+        let mut config = context.config.clone();
+        let mut changes = 0;
+        let mut renamed = Vec::new();
+
+        for repo in &discovery.missing {
+            let (owner, name) = client.parse_github_url(&repo.url)?;
+            // Renamed or transferred repositories still resolve under the old name
+            match client.get_repository(&owner, &name).await {
+                Ok(moved) if moved.full_name != format!("{owner}/{name}") => {
+                    println!(
+                        "{} {} -> {}",
+                        "~".yellow(),
+                        repo.name.cyan().bold(),
+                        moved.full_name
+                    );
+                    if let Some(entry) = config.get_repository_mut(&repo.name) {
+                        entry.name = moved.name.clone();
+                        entry.url = self.clone_url(&moved);
+                    }
+                    renamed.push(moved.full_name.to_lowercase());
+                }
+                _ => {
+                    println!(
+                        "{} {} (no longer in organization)",
+                        "-".red(),
+                        repo.name.cyan().bold()
+                    );
+                    config.remove_repository(&repo.name);
+                }
+            }
+            changes += 1;
+        }
+
+        for repo in &discovery.added {
+            if renamed.contains(&repo.full_name.to_lowercase()) {
+                continue;
+            }
+            let archived = if repo.archived { " (archived)" } else { "" };
+            println!("{} {}{}", "+".green(), repo.name.cyan().bold(), archived);
+            let mut new_repo =
+                RepositoryBuilder::new(repo.name.clone(), self.clone_url(repo)).build();
+            new_repo.archived = repo.archived;
+            new_repo.default_branch = Some(repo.default_branch.clone());
+            config.add_repository(new_repo)?;
+            changes += 1;
+        }
+
+        if changes == 0 {
+            println!("{}", "Config is in sync with the organization".green());
+            return Ok(());
+        }
+
+        if self.apply {
+            config.save(&self.config_path)?;
+            println!(
+                "{}",
+                format!("Applied {changes} changes to {}", self.config_path).green()
+            );
+        } else {
+            println!(
+                "{}",
+                format!("{changes} differences found, use --apply to update the config").yellow()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl DiscoverCommand {
+    fn clone_url(&self, repo: &GitHubRepo) -> String {
+        if self.https || repo.ssh_url.is_empty() {
+            repo.clone_url.clone()
+        } else {
+            repo.ssh_url.clone()
+        }
+    }
+}
+
+/// Compare organization repositories with configured ones, matching by
+/// owner and repository name from the configured URL
+fn discover(
+    client: &GitHubClient,
+    configured: &[Repository],
+    org: &str,
+    remote: Vec<GitHubRepo>,
+) -> Discovery {
+    let configured_names: Vec<(String, &Repository)> = configured
+        .iter()
+        .filter_map(|repo| {
+            let (owner, name) = client.parse_github_url(&repo.url).ok()?;
+            owner
+                .eq_ignore_ascii_case(org)
+                .then(|| (name.to_lowercase(), repo))
+        })
+        .collect();
+
+    let missing = configured_names
+        .iter()
+        .filter(|(name, _)| !remote.iter().any(|r| r.name.to_lowercase() == *name))
+        .map(|(_, repo)| (*repo).clone())
+        .collect();
+
+    let added = remote
+        .into_iter()
+        .filter(|r| {
+            !configured_names
+                .iter()
+                .any(|(name, _)| *name == r.name.to_lowercase())
+        })
+        .collect();
+
+    Discovery { added, missing }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remote(name: &str) -> GitHubRepo {
+        GitHubRepo {
+            id: 1,
+            name: name.to_string(),
+            full_name: format!("org/{name}"),
+            html_url: format!("https://github.com/org/{name}"),
+            clone_url: format!("https://github.com/org/{name}.git"),
+            ssh_url: format!("git@github.com:org/{name}.git"),
+            default_branch: "main".to_string(),
+            archived: false,
+            disabled: false,
+            topics: Vec::new(),
+            language: None,
+            private: false,
+            visibility: None,
+            pushed_at: None,
+        }
+    }
+
+    #[test]
+    fn test_discover() {
+        let client = GitHubClient::new(None);
+        let configured = vec![
+            Repository::new("api".to_string(), "git@github.com:org/api.git".to_string()),
+            Repository::new(
+                "gone".to_string(),
+                "git@github.com:org/gone.git".to_string(),
+            ),
+            Repository::new(
+                "other".to_string(),
+                "git@github.com:elsewhere/x.git".to_string(),
+            ),
+        ];
+
+        let discovery = discover(
+            &client,
+            &configured,
+            "org",
+            vec![remote("API"), remote("web")],
+        );
+
+        let added: Vec<&str> = discovery.added.iter().map(|r| r.name.as_str()).collect();
+        let missing: Vec<&str> = discovery.missing.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(added, vec!["web"]);
+        assert_eq!(missing, vec!["gone"]);
+    }
+}
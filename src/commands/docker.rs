@@ -0,0 +1,174 @@
+//! Docker command implementation
+
+use super::{Command, CommandContext};
+use crate::config::{Repository, dependency_levels};
+use crate::git::{self, Logger};
+use crate::runner::{CommandRunner, Shell};
+use crate::scheduler;
+use crate::template;
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Docker image operations
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum DockerAction {
+    /// Build each repository's Dockerfile in dependency order
+    Build {
+        /// Specific repository names to build (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Image tag; `{git_sha}`, `{short_sha}` and the usual `{name}` variables are rendered
+        #[arg(long, default_value = "{name}:{short_sha}")]
+        tag_template: String,
+
+        /// Dockerfile path, relative to the repository root
+        #[arg(short, long, default_value = "Dockerfile")]
+        file: String,
+
+        /// Push each image after it is built
+        #[arg(long)]
+        push: bool,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+
+        /// Build the repositories of each dependency level in parallel
+        #[arg(short, long)]
+        parallel: bool,
+    },
+}
+
+/// Build command for Docker images across repositories
+pub struct DockerBuildCommand {
+    pub tag_template: String,
+    /// Dockerfile path, relative to the repository root
+    pub file: String,
+    pub push: bool,
+}
+
+#[async_trait]
+impl Command for DockerBuildCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        // Base images must exist before the images built on them
+        let levels = dependency_levels(&repositories)?;
+        let logger = Logger;
+        let runner = CommandRunner::new();
+        let mut failed: HashSet<String> = HashSet::new();
+        let mut built = 0;
+
+        println!(
+            "{}",
+            format!("Building images for {} repositories...", repositories.len()).green()
+        );
+
+        for level in levels {
+            let mut buildable = Vec::new();
+            for repo in level {
+                if let Some(dependency) = repo.depends_on.iter().find(|d| failed.contains(*d)) {
+                    logger.warn(&repo, &format!("Skipping, {dependency} failed to build"));
+                    failed.insert(repo.name.clone());
+                } else if !Path::new(&repo.get_target_dir()).join(&self.file).is_file() {
+                    logger.info(&repo, &format!("No {}, skipping", self.file));
+                } else {
+                    buildable.push(repo);
+                }
+            }
+
+            let results = if context.parallel {
+                let tasks = buildable
+                    .iter()
+                    .map(|repo| scheduler::limited(self.build_repository(&runner, repo)));
+                futures::future::join_all(tasks).await
+            } else {
+                let mut results = Vec::new();
+                for repo in &buildable {
+                    results.push(self.build_repository(&runner, repo).await);
+                }
+                results
+            };
+
+            for (repo, result) in buildable.iter().zip(results) {
+                match result {
+                    Ok(image) => {
+                        built += 1;
+                        logger.success(repo, &format!("Built {image}"));
+                    }
+                    Err(e) => {
+                        failed.insert(repo.name.clone());
+                        eprintln!(
+                            "{} | {}",
+                            repo.name.cyan().bold(),
+                            format!("Error: {e}").red()
+                        );
+                    }
+                }
+            }
+        }
+
+        if !failed.is_empty() {
+            anyhow::bail!("{} repositories failed to build", failed.len());
+        }
+
+        println!("{}", format!("Built {built} images").green());
+        Ok(())
+    }
+}
+
+impl DockerBuildCommand {
+    /// Build (and push) a repository's image, returning its tag
+    async fn build_repository(&self, runner: &CommandRunner, repo: &Repository) -> Result<String> {
+        let image = self.image_tag(repo)?;
+        let shell = Shell::default();
+
+        runner
+            .run_command(
+                repo,
+                &format!(
+                    "docker build -t {} -f {} .",
+                    shell.quote(&image),
+                    shell.quote(&self.file)
+                ),
+                None,
+            )
+            .await?;
+
+        if self.push {
+            runner
+                .run_command(repo, &format!("docker push {}", shell.quote(&image)), None)
+                .await?;
+        }
+        Ok(image)
+    }
+
+    fn image_tag(&self, repo: &Repository) -> Result<String> {
+        let sha = git::head_sha(&repo.get_target_dir())?;
+        let mut vars = template::repository_variables(repo);
+        vars.insert("short_sha".to_string(), sha.chars().take(7).collect());
+        vars.insert("git_sha".to_string(), sha);
+        Ok(template::render(&self.tag_template, &vars))
+    }
+}
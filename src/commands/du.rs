@@ -0,0 +1,148 @@
+//! Disk usage command implementation
+
+use super::{Command, CommandContext};
+use crate::config::Repository;
+use crate::scheduler;
+use crate::util::{dir_size, format_size};
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+use std::path::Path;
+
+/// Disk usage command reporting the size of each clone
+pub struct DuCommand {
+    /// List the largest clones first
+    pub sort: bool,
+}
+
+/// Sizes of a clone in bytes
+#[derive(Debug, Clone, Copy, Default)]
+struct Usage {
+    worktree: u64,
+    git: u64,
+}
+
+impl Usage {
+    fn total(&self) -> u64 {
+        self.worktree + self.git
+    }
+}
+
+#[async_trait]
+impl Command for DuCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        let mut results = Vec::new();
+        if context.parallel {
+            let tasks: Vec<_> = repositories
+                .into_iter()
+                .map(|repo| {
+                    scheduler::spawn_blocking(move || {
+                        let usage = usage(&repo);
+                        (repo, usage)
+                    })
+                })
+                .collect();
+            for task in tasks {
+                results.push(task.await?);
+            }
+        } else {
+            for repo in repositories {
+                let usage = usage(&repo);
+                results.push((repo, usage));
+            }
+        }
+
+        let mut cloned: Vec<(Repository, Usage)> = results
+            .into_iter()
+            .filter_map(|(repo, usage)| usage.map(|usage| (repo, usage)))
+            .collect();
+        if self.sort {
+            cloned.sort_by_key(|(_, usage)| std::cmp::Reverse(usage.total()));
+        }
+
+        let name_width = cloned
+            .iter()
+            .map(|(repo, _)| repo.name.len())
+            .max()
+            .unwrap_or(0)
+            .max(10);
+        println!(
+            "{:<name_width$}  {:>12}  {:>12}  {:>12}",
+            "Repository", "Working tree", ".git", "Total"
+        );
+
+        let mut total = Usage::default();
+        let mut over_budget = 0;
+        for (repo, usage) in &cloned {
+            total.worktree += usage.worktree;
+            total.git += usage.git;
+
+            let budget = match repo.max_size() {
+                Some(max_size) if usage.total() > max_size => {
+                    over_budget += 1;
+                    format!("  over max_size of {}", format_size(max_size))
+                        .red()
+                        .to_string()
+                }
+                _ => String::new(),
+            };
+            println!(
+                "{:<name_width$}  {:>12}  {:>12}  {:>12}{budget}",
+                repo.name,
+                format_size(usage.worktree),
+                format_size(usage.git),
+                format_size(usage.total())
+            );
+        }
+        println!(
+            "{}",
+            format!(
+                "{:<name_width$}  {:>12}  {:>12}  {:>12}",
+                "Total",
+                format_size(total.worktree),
+                format_size(total.git),
+                format_size(total.total())
+            )
+            .bold()
+        );
+
+        if over_budget > 0 {
+            println!(
+                "{}",
+                format!("\n{over_budget} repositories exceed their max_size").yellow()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Sizes of a repository's clone, or `None` when it isn't cloned
+fn usage(repo: &Repository) -> Option<Usage> {
+    let repo_dir = repo.get_target_dir();
+    let repo_dir = Path::new(&repo_dir);
+    if !repo_dir.exists() {
+        return None;
+    }
+    let total = dir_size(repo_dir);
+    let git = dir_size(&repo_dir.join(".git"));
+    Some(Usage {
+        worktree: total.saturating_sub(git),
+        git,
+    })
+}
@@ -0,0 +1,69 @@
+//! Env command implementation
+
+use super::{Command, CommandContext};
+use crate::environment;
+use crate::util::shell_quote;
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// Output formats for repository variables
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum EnvFormat {
+    /// `export KEY=VALUE` lines for `eval` or direnv
+    Sh,
+    /// A JSON object
+    Json,
+}
+
+/// Env command for printing the variables of a repository
+pub struct EnvCommand {
+    pub repo: String,
+    pub format: EnvFormat,
+}
+
+#[async_trait]
+impl Command for EnvCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repo = context
+            .config
+            .get_repository(&self.repo)
+            .ok_or_else(|| anyhow::anyhow!("Repository '{}' not found", self.repo))?;
+
+        let vars = environment::repository_vars(repo);
+        print!("{}", render(&vars, self.format)?);
+        Ok(())
+    }
+}
+
+fn render(vars: &[(String, String)], format: EnvFormat) -> Result<String> {
+    Ok(match format {
+        EnvFormat::Sh => vars
+            .iter()
+            .map(|(key, value)| format!("export {key}={}\n", shell_quote(value)))
+            .collect(),
+        EnvFormat::Json => {
+            let object: serde_json::Map<_, _> = vars
+                .iter()
+                .map(|(key, value)| (key.clone(), serde_json::Value::from(value.as_str())))
+                .collect();
+            format!("{}\n", serde_json::to_string_pretty(&object)?)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_sh() {
+        let vars = vec![
+            ("RREPOS_REPO".to_string(), "api".to_string()),
+            ("GREETING".to_string(), "it's here".to_string()),
+        ];
+        assert_eq!(
+            render(&vars, EnvFormat::Sh).unwrap(),
+            "export RREPOS_REPO=api\nexport GREETING='it'\\''s here'\n"
+        );
+    }
+}
@@ -0,0 +1,146 @@
+//! Extract command implementation
+
+use super::{Command, CommandContext};
+use crate::config::{Repository, hosts};
+use crate::git::{self, Logger};
+use crate::github::GitHubClient;
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+use std::path::Path;
+
+/// Extract command splitting a subdirectory, with its history, out of a
+/// repository into a new one
+pub struct ExtractCommand {
+    /// Configured repository to extract from
+    pub repo: String,
+    /// Subdirectory to extract, relative to the repository root
+    pub path: String,
+    /// URL of the new repository
+    pub to: String,
+    /// Name of the new repository; defaults to the last part of its URL
+    pub name: Option<String>,
+    /// Tags of the new repository; defaults to those of the source
+    pub tags: Vec<String>,
+    /// Create the GitHub repository as private when it doesn't exist
+    pub private: bool,
+    pub token: Option<String>,
+    pub config_path: String,
+}
+
+#[async_trait]
+impl Command for ExtractCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let logger = Logger;
+        let mut config = context.config.clone();
+
+        let source = config
+            .get_repository(&self.repo)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Repository '{}' not found in config", self.repo))?;
+        let source_dir = source.get_target_dir();
+        if !Path::new(&source_dir).exists() {
+            anyhow::bail!("Repository '{}' is not cloned", source.name);
+        }
+        let subdirectory = self.path.trim_matches('/');
+        if !Path::new(&source_dir).join(subdirectory).is_dir() {
+            anyhow::bail!("'{}' is not a directory in '{}'", self.path, source.name);
+        }
+
+        let name = match &self.name {
+            Some(name) => name.clone(),
+            None => name_from_url(&self.to)
+                .ok_or_else(|| anyhow::anyhow!("No repository name in URL '{}'", self.to))?,
+        };
+        let mut repo = Repository::new(name, self.to.clone());
+        repo.tags = if self.tags.is_empty() {
+            source.tags.clone()
+        } else {
+            self.tags.clone()
+        };
+        repo.set_workspace(
+            Path::new(&self.config_path).parent().map(Path::to_path_buf),
+            config.layout,
+        );
+        let settings = repo.host_settings(&config);
+
+        // Fail before touching anything if the repository can't be recorded
+        config.add_repository(repo.clone())?;
+
+        let repo_dir = repo.get_target_dir();
+        if Path::new(&repo_dir).exists() {
+            anyhow::bail!("Directory already exists: {}", repo_dir);
+        }
+
+        // Copy the clone, then keep only the subdirectory's history
+        let result = git::clone_local(&source_dir, &repo_dir)
+            .and_then(|_| git::filter_repo(&repo_dir, &["--subdirectory-filter", subdirectory]));
+        if let Err(e) = result {
+            let _ = std::fs::remove_dir_all(&repo_dir);
+            return Err(e);
+        }
+        logger.info(
+            &repo,
+            &format!("Extracted {subdirectory} from {} with history", source.name),
+        );
+
+        // filter-repo drops origin so the rewritten history isn't pushed back
+        // by accident
+        if git::remote_url(&repo_dir, "origin").is_ok() {
+            git::set_remote_url(&repo_dir, "origin", &repo.url)?;
+        } else {
+            git::add_remote(&repo_dir, "origin", &repo.url)?;
+        }
+
+        if hosts::url_host(&repo.url) == Some(hosts::GITHUB_HOST) {
+            let client = GitHubClient::with_settings(self.token.clone(), &settings)?;
+            let (owner, name) = client.parse_github_url(&repo.url)?;
+            if !client.repository_exists(&owner, &name).await? {
+                client
+                    .create_repository(&owner, &name, self.private)
+                    .await?;
+                logger.info(&repo, &format!("Created {owner}/{name}"));
+            }
+        }
+
+        let branch = git::current_branch(&repo_dir)?
+            .ok_or_else(|| anyhow::anyhow!("No branch checked out in {}", repo_dir))?;
+        git::push_branch(&repo, &settings, &branch)?;
+        logger.info(&repo, &format!("Pushed {branch}"));
+
+        config.save(&self.config_path)?;
+        println!(
+            "{}",
+            format!("Added {} to {}", repo.name, self.config_path).green()
+        );
+        Ok(())
+    }
+}
+
+/// Repository name at the end of a git URL, e.g. `payments` for
+/// `git@github.com:org/payments.git`
+fn name_from_url(url: &str) -> Option<String> {
+    let url = url.trim_end_matches('/');
+    let url = url.strip_suffix(".git").unwrap_or(url);
+    let name = url.rsplit(['/', ':']).next()?;
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_name_from_url() {
+        assert_eq!(
+            name_from_url("git@github.com:org/payments.git"),
+            Some("payments".to_string())
+        );
+        assert_eq!(
+            name_from_url("https://github.com/org/payments/"),
+            Some("payments".to_string())
+        );
+        assert_eq!(name_from_url("payments.git"), Some("payments".to_string()));
+        assert_eq!(name_from_url("git@github.com:"), None);
+    }
+}
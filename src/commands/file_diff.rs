@@ -0,0 +1,149 @@
+//! File-diff command implementation
+
+use super::{Command, CommandContext};
+use crate::git::{self, Logger};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use colored::*;
+use std::path::{Path, PathBuf};
+
+/// File-diff command for reporting drift of a shared file across repositories
+pub struct FileDiffCommand {
+    /// Path of the file, relative to each repository root
+    pub path: String,
+    /// File every repository is compared against; without it, repositories
+    /// are compared against the most common version
+    pub baseline: Option<String>,
+}
+
+#[async_trait]
+impl Command for FileDiffCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        let logger = Logger;
+        let mut files = Vec::new();
+        for repo in &repositories {
+            let file = Path::new(&repo.get_target_dir()).join(&self.path);
+            match std::fs::read_to_string(&file) {
+                Ok(content) => files.push((repo.name.clone(), file, content)),
+                Err(_) => logger.warn(repo, &format!("No {}", self.path)),
+            }
+        }
+
+        let (reference_path, reference, label) = match &self.baseline {
+            Some(baseline) => {
+                let content = std::fs::read_to_string(baseline)
+                    .with_context(|| format!("Failed to read baseline '{baseline}'"))?;
+                (PathBuf::from(baseline), content, baseline.clone())
+            }
+            None => {
+                let groups = group_by_content(
+                    files
+                        .iter()
+                        .map(|(name, _, content)| (name.clone(), content.clone()))
+                        .collect(),
+                );
+                let Some((content, names)) = groups.into_iter().next() else {
+                    println!(
+                        "{}",
+                        format!("No repository contains {}", self.path).yellow()
+                    );
+                    return Ok(());
+                };
+                let path = files
+                    .iter()
+                    .find(|(name, _, _)| *name == names[0])
+                    .map(|(_, path, _)| path.clone())
+                    .unwrap_or_default();
+                let label = format!("the version in {}", names.join(", "));
+                (path, content, label)
+            }
+        };
+
+        println!(
+            "{}",
+            format!(
+                "Comparing {} in {} repositories against {}...",
+                self.path,
+                files.len(),
+                label
+            )
+            .green()
+        );
+
+        let mut differing = 0;
+        for (name, path, content) in &files {
+            if *content == reference {
+                println!("{} | {}", name.cyan().bold(), "identical".green());
+                continue;
+            }
+
+            differing += 1;
+            println!("{} | {}", name.cyan().bold(), "differs".red());
+            for line in git::diff_files(&reference_path, path)?.lines() {
+                println!("{} | {line}", name.cyan());
+            }
+        }
+
+        let missing = repositories.len() - files.len();
+        let summary = format!(
+            "{differing} of {} repositories differ, {missing} missing the file",
+            repositories.len()
+        );
+        if differing > 0 || missing > 0 {
+            println!("{}", summary.yellow());
+        } else {
+            println!("{}", summary.green());
+        }
+        Ok(())
+    }
+}
+
+/// Group repositories by file content, largest group first; equally large
+/// groups keep the order in which their content was first seen
+fn group_by_content(files: Vec<(String, String)>) -> Vec<(String, Vec<String>)> {
+    let mut groups: Vec<(String, Vec<String>)> = Vec::new();
+    for (name, content) in files {
+        match groups.iter_mut().find(|(c, _)| *c == content) {
+            Some((_, names)) => names.push(name),
+            None => groups.push((content, vec![name])),
+        }
+    }
+    groups.sort_by_key(|(_, names)| std::cmp::Reverse(names.len()));
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_by_content() {
+        let files = vec![
+            ("a".to_string(), "v1".to_string()),
+            ("b".to_string(), "v2".to_string()),
+            ("c".to_string(), "v2".to_string()),
+            ("d".to_string(), "v3".to_string()),
+        ];
+
+        let groups = group_by_content(files);
+        assert_eq!(groups[0], ("v2".to_string(), vec!["b".into(), "c".into()]));
+        assert_eq!(groups[1].1, vec!["a".to_string()]);
+        assert_eq!(groups[2].1, vec!["d".to_string()]);
+    }
+}
@@ -0,0 +1,138 @@
+//! Gerrit command implementation
+
+use super::{Command, CommandContext};
+use crate::config::Provider;
+use crate::gerrit::{self, ChangeInfo, GerritClient};
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+
+/// Operations on Gerrit changes
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum GerritAction {
+    /// Show the state of changes in Gerrit-backed repositories
+    Status {
+        /// Specific repository names to query (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Only show changes with this topic, such as the branch name `pr` used;
+        /// otherwise open changes are shown
+        #[arg(long)]
+        topic: Option<String>,
+
+        /// Gerrit username for authenticated queries (falls back to GERRIT_USER);
+        /// the HTTP password is the host's token
+        #[arg(long)]
+        user: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+}
+
+/// Gerrit status command listing changes through the REST API
+pub struct GerritStatusCommand {
+    pub topic: Option<String>,
+    pub user: Option<String>,
+}
+
+#[async_trait]
+impl Command for GerritStatusCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories: Vec<_> = context
+            .repositories()
+            .into_iter()
+            .filter(|repo| repo.provider == Provider::Gerrit)
+            .collect();
+
+        if repositories.is_empty() {
+            println!(
+                "{}",
+                "No repositories with 'provider: gerrit' found".yellow()
+            );
+            return Ok(());
+        }
+
+        let tasks: Vec<_> = repositories
+            .iter()
+            .map(|repo| async move {
+                let result = async {
+                    let project = gerrit::project(&repo.url).ok_or_else(|| {
+                        anyhow::anyhow!("No Gerrit project in URL '{}'", repo.url)
+                    })?;
+                    let client = GerritClient::for_repository(
+                        repo,
+                        &repo.host_settings(&context.config),
+                        self.user.as_deref(),
+                    )?;
+                    let changes = client.changes(&project, self.topic.as_deref()).await?;
+                    let urls: Vec<String> = changes
+                        .iter()
+                        .map(|change| client.change_url(&project, change.number))
+                        .collect();
+                    Ok::<_, anyhow::Error>(changes.into_iter().zip(urls).collect::<Vec<_>>())
+                }
+                .await;
+                (repo, result)
+            })
+            .collect();
+
+        let mut total = 0;
+        for (repo, result) in futures::future::join_all(tasks).await {
+            match result {
+                Ok(changes) if changes.is_empty() => {
+                    println!("{} | {}", repo.name.cyan().bold(), "No changes".dimmed());
+                }
+                Ok(changes) => {
+                    total += changes.len();
+                    for (change, url) in changes {
+                        println!(
+                            "{} | #{} {} {}{}\n      {}",
+                            repo.name.cyan().bold(),
+                            change.number,
+                            colored_status(&change.status),
+                            change.subject,
+                            votes(&change),
+                            url
+                        );
+                    }
+                }
+                Err(e) => eprintln!(
+                    "{} | {}",
+                    repo.name.cyan().bold(),
+                    format!("Error: {e}").red()
+                ),
+            }
+        }
+
+        println!("{}", format!("{total} changes").green());
+        Ok(())
+    }
+}
+
+fn colored_status(status: &str) -> ColoredString {
+    match status {
+        "MERGED" => status.green(),
+        "ABANDONED" => status.red(),
+        _ => status.yellow(),
+    }
+}
+
+/// Votes on a change's labels, e.g. ` [Code-Review +2, Verified +1]`
+fn votes(change: &ChangeInfo) -> String {
+    let votes: Vec<String> = change
+        .labels
+        .iter()
+        .filter_map(|(label, info)| Some(format!("{label} {}", info.vote()?)))
+        .collect();
+    if votes.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", votes.join(", ")).dimmed().to_string()
+    }
+}
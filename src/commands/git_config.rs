@@ -0,0 +1,176 @@
+//! Git-config command implementation
+
+use super::{Command, CommandContext};
+use crate::config::Repository;
+use crate::git::{self, Logger};
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Operations on the local git config of each clone
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum GitConfigAction {
+    /// Apply the `git_config` settings from the config file and `--set` values
+    Apply {
+        /// Specific repository names to configure (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Setting to apply as KEY=VALUE, overriding the config file (can be repeated)
+        #[arg(long = "set")]
+        settings: Vec<String>,
+
+        /// Only show the settings that would change
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+}
+
+/// Git-config apply command for enforcing local git settings in every clone
+pub struct GitConfigApplyCommand {
+    /// `KEY=VALUE` settings given on the command line
+    pub settings: Vec<String>,
+    pub dry_run: bool,
+}
+
+#[async_trait]
+impl Command for GitConfigApplyCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let settings = self.merged_settings(&context.config.git_config)?;
+        if settings.is_empty() {
+            println!(
+                "{}",
+                "No git settings given; use --set or git_config in the config file".yellow()
+            );
+            return Ok(());
+        }
+
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            format!(
+                "Applying {} git settings to {} repositories...",
+                settings.len(),
+                repositories.len()
+            )
+            .green()
+        );
+
+        for repo in &repositories {
+            if let Err(e) = self.apply_repository(repo, &settings) {
+                eprintln!(
+                    "{} | {}",
+                    repo.name.cyan().bold(),
+                    format!("Error: {e}").red()
+                );
+            }
+        }
+
+        println!("{}", "Done applying git settings".green());
+        Ok(())
+    }
+}
+
+impl GitConfigApplyCommand {
+    /// Settings from the config file, overridden by `--set` values
+    fn merged_settings(
+        &self,
+        configured: &BTreeMap<String, String>,
+    ) -> Result<BTreeMap<String, String>> {
+        let mut settings = configured.clone();
+        for setting in &self.settings {
+            let (key, value) = setting.split_once('=').ok_or_else(|| {
+                anyhow::anyhow!("Invalid git setting (expected KEY=VALUE): {}", setting)
+            })?;
+            if key.trim().is_empty() {
+                anyhow::bail!("Invalid git setting (expected KEY=VALUE): {}", setting);
+            }
+            settings.insert(key.trim().to_string(), value.to_string());
+        }
+        Ok(settings)
+    }
+
+    fn apply_repository(
+        &self,
+        repo: &Repository,
+        settings: &BTreeMap<String, String>,
+    ) -> Result<()> {
+        let logger = Logger;
+        let repo_dir = repo.get_target_dir();
+        if !Path::new(&repo_dir).exists() {
+            anyhow::bail!("Repository directory does not exist: {}", repo_dir);
+        }
+
+        let mut changed = 0;
+        for (key, value) in settings {
+            let current = git::get_config(&repo_dir, key)?;
+            if current.as_deref() == Some(value.as_str()) {
+                continue;
+            }
+            changed += 1;
+
+            let from = current.unwrap_or_else(|| "unset".to_string());
+            if self.dry_run {
+                logger.info(repo, &format!("Would set {key}={value} (currently {from})"));
+            } else {
+                git::set_config(&repo_dir, key, value)?;
+                logger.success(repo, &format!("Set {key}={value} (was {from})"));
+            }
+        }
+
+        if changed == 0 {
+            logger.info(repo, "Git settings are up to date");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merged_settings_override_config() {
+        let command = GitConfigApplyCommand {
+            settings: vec!["user.email=bot@example.com".to_string()],
+            dry_run: false,
+        };
+        let mut configured = BTreeMap::new();
+        configured.insert("user.email".to_string(), "dev@example.com".to_string());
+        configured.insert("commit.gpgsign".to_string(), "true".to_string());
+
+        let settings = command.merged_settings(&configured).unwrap();
+        assert_eq!(settings["user.email"], "bot@example.com");
+        assert_eq!(settings["commit.gpgsign"], "true");
+
+        let invalid = GitConfigApplyCommand {
+            settings: vec!["user.email".to_string()],
+            dry_run: false,
+        };
+        assert!(invalid.merged_settings(&configured).is_err());
+    }
+}
@@ -0,0 +1,224 @@
+//! Graph command implementation
+
+use super::{Command, CommandContext};
+use crate::config::{Repository, graph};
+use crate::manifest::Manifest;
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Output formats for the dependency graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum GraphFormat {
+    /// Graphviz DOT
+    Dot,
+    /// Mermaid flowchart
+    Mermaid,
+    /// Build order, one level of parallelizable repositories per line
+    Order,
+}
+
+/// A dependency between two repositories
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Edge {
+    from: String,
+    to: String,
+    /// Found in a package manifest rather than declared with `depends_on`
+    detected: bool,
+}
+
+/// Graph command for rendering the dependencies between repositories
+pub struct GraphCommand {
+    pub format: GraphFormat,
+    /// Also add dependencies found in the cloned repositories' manifests
+    pub detect: bool,
+}
+
+#[async_trait]
+impl Command for GraphCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let mut repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        let mut edges = declared_edges(&repositories);
+        if self.detect {
+            edges.extend(detected_edges(&repositories, &edges)?);
+        }
+
+        // Validate the combined graph; detected edges take part in the order
+        for edge in edges.iter().filter(|edge| edge.detected) {
+            if let Some(repo) = repositories.iter_mut().find(|r| r.name == edge.from) {
+                repo.depends_on.push(edge.to.clone());
+            }
+        }
+        let levels = graph::dependency_levels(&repositories);
+
+        match self.format {
+            GraphFormat::Dot => print!("{}", render_dot(&repositories, &edges)),
+            GraphFormat::Mermaid => print!("{}", render_mermaid(&repositories, &edges)),
+            GraphFormat::Order => {
+                for (index, level) in levels?.iter().enumerate() {
+                    let names: Vec<&str> = level.iter().map(|r| r.name.as_str()).collect();
+                    println!("{}: {}", index + 1, names.join(", "));
+                }
+                return Ok(());
+            }
+        }
+
+        // Rendered graphs are still printed for a cycle so it can be inspected
+        levels?;
+        Ok(())
+    }
+}
+
+/// Edges declared with `depends_on` between the given repositories
+fn declared_edges(repositories: &[Repository]) -> Vec<Edge> {
+    repositories
+        .iter()
+        .flat_map(|repo| {
+            repo.depends_on
+                .iter()
+                .filter(|dep| repositories.iter().any(|r| &r.name == *dep))
+                .map(|dep| Edge {
+                    from: repo.name.clone(),
+                    to: dep.clone(),
+                    detected: false,
+                })
+        })
+        .collect()
+}
+
+/// Edges found by matching the package names of cloned repositories against
+/// the dependencies of every other repository's manifest
+fn detected_edges(repositories: &[Repository], declared: &[Edge]) -> Result<Vec<Edge>> {
+    let mut manifests = Vec::new();
+    for repo in repositories {
+        if let Some(manifest) = Manifest::detect(Path::new(&repo.get_target_dir()))? {
+            manifests.push((repo.name.clone(), manifest));
+        }
+    }
+
+    let packages: HashMap<String, String> = manifests
+        .iter()
+        .filter_map(|(name, manifest)| Some((manifest.package_name()?, name.clone())))
+        .collect();
+
+    let mut edges = Vec::new();
+    for (name, manifest) in &manifests {
+        for (package, owner) in &packages {
+            if owner == name
+                || !manifest.depends_on(package)
+                || declared.iter().any(|e| &e.from == name && &e.to == owner)
+            {
+                continue;
+            }
+            edges.push(Edge {
+                from: name.clone(),
+                to: owner.clone(),
+                detected: true,
+            });
+        }
+    }
+    edges.sort_by(|a, b| (&a.from, &a.to).cmp(&(&b.from, &b.to)));
+    Ok(edges)
+}
+
+/// Render the graph in Graphviz DOT; detected edges are dashed
+fn render_dot(repositories: &[Repository], edges: &[Edge]) -> String {
+    let mut out = String::from("digraph rrepos {\n    rankdir=LR;\n");
+    for repo in repositories {
+        out.push_str(&format!("    {};\n", dot_id(&repo.name)));
+    }
+    for edge in edges {
+        let style = if edge.detected { " [style=dashed]" } else { "" };
+        out.push_str(&format!(
+            "    {} -> {}{style};\n",
+            dot_id(&edge.from),
+            dot_id(&edge.to)
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// Render the graph as a Mermaid flowchart; detected edges are dotted
+fn render_mermaid(repositories: &[Repository], edges: &[Edge]) -> String {
+    // Mermaid node ids are restricted, so repositories are numbered and
+    // labelled with their name
+    let ids: HashMap<&str, String> = repositories
+        .iter()
+        .enumerate()
+        .map(|(index, repo)| (repo.name.as_str(), format!("r{index}")))
+        .collect();
+
+    let mut out = String::from("flowchart LR\n");
+    for repo in repositories {
+        out.push_str(&format!(
+            "    {}[\"{}\"]\n",
+            ids[repo.name.as_str()],
+            repo.name.replace('"', "#quot;")
+        ));
+    }
+    for edge in edges {
+        let arrow = if edge.detected { "-.->" } else { "-->" };
+        out.push_str(&format!(
+            "    {} {arrow} {}\n",
+            ids[edge.from.as_str()],
+            ids[edge.to.as_str()]
+        ));
+    }
+    out
+}
+
+fn dot_id(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo(name: &str, depends_on: &[&str]) -> Repository {
+        let mut repo =
+            Repository::new(name.to_string(), format!("git@github.com:owner/{name}.git"));
+        repo.depends_on = depends_on.iter().map(|d| d.to_string()).collect();
+        repo
+    }
+
+    #[test]
+    fn test_render_graph() {
+        let repos = vec![repo("core", &[]), repo("api", &["core", "external"])];
+        let mut edges = declared_edges(&repos);
+        assert_eq!(edges.len(), 1);
+
+        edges.push(Edge {
+            from: "core".to_string(),
+            to: "api".to_string(),
+            detected: true,
+        });
+
+        let dot = render_dot(&repos, &edges);
+        assert!(dot.contains("    \"api\" -> \"core\";\n"));
+        assert!(dot.contains("    \"core\" -> \"api\" [style=dashed];\n"));
+
+        let mermaid = render_mermaid(&repos, &edges);
+        assert!(mermaid.contains("    r1[\"api\"]\n"));
+        assert!(mermaid.contains("    r1 --> r0\n"));
+        assert!(mermaid.contains("    r0 -.-> r1\n"));
+    }
+}
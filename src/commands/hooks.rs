@@ -0,0 +1,248 @@
+//! Hooks command implementation
+
+use super::{Command, CommandContext};
+use crate::config::Repository;
+use crate::git::{self, Logger};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use colored::*;
+use std::path::{Path, PathBuf};
+
+/// Hook runner frameworks that can be installed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum HookFramework {
+    /// https://pre-commit.com
+    PreCommit,
+    /// https://github.com/evilmartians/lefthook
+    Lefthook,
+}
+
+impl HookFramework {
+    /// Configuration files the framework reads, relative to the repository root
+    fn config_files(self) -> &'static [&'static str] {
+        match self {
+            HookFramework::PreCommit => &[".pre-commit-config.yaml"],
+            HookFramework::Lefthook => &[
+                "lefthook.yml",
+                "lefthook.yaml",
+                ".lefthook.yml",
+                ".lefthook.yaml",
+            ],
+        }
+    }
+
+    fn program(self) -> &'static str {
+        match self {
+            HookFramework::PreCommit => "pre-commit",
+            HookFramework::Lefthook => "lefthook",
+        }
+    }
+
+    /// Detect the framework a repository is configured for
+    fn detect(repo_dir: &Path) -> Option<Self> {
+        [HookFramework::PreCommit, HookFramework::Lefthook]
+            .into_iter()
+            .find(|framework| framework.is_configured(repo_dir))
+    }
+
+    fn is_configured(self, repo_dir: &Path) -> bool {
+        self.config_files()
+            .iter()
+            .any(|file| repo_dir.join(file).is_file())
+    }
+}
+
+/// Operations on git hooks
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum HooksAction {
+    /// Install hook runners, or link shared hook scripts, in each repository
+    Install {
+        /// Specific repository names to install hooks in (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Hook framework to install (detected from each repository's config file when unset)
+        #[arg(long, value_enum)]
+        framework: Option<HookFramework>,
+
+        /// Directory of shared hook scripts to link into each repository instead
+        #[arg(long, conflicts_with = "framework")]
+        scripts: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+}
+
+/// Result of installing hooks in a single repository
+enum Installation {
+    Installed,
+    NotConfigured,
+}
+
+/// Hooks install command for setting up git hooks across repositories
+pub struct HooksInstallCommand {
+    pub framework: Option<HookFramework>,
+    /// Directory of shared hook scripts to link
+    pub scripts: Option<String>,
+}
+
+#[async_trait]
+impl Command for HooksInstallCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let scripts = self
+            .scripts
+            .as_deref()
+            .map(|dir| {
+                std::fs::canonicalize(dir)
+                    .with_context(|| format!("Hook scripts directory '{dir}' not found"))
+            })
+            .transpose()?;
+
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            format!("Installing hooks in {} repositories...", repositories.len()).green()
+        );
+
+        let mut unconfigured = Vec::new();
+        for repo in &repositories {
+            match self.install(repo, scripts.as_deref()) {
+                Ok(Installation::Installed) => {}
+                Ok(Installation::NotConfigured) => unconfigured.push(repo.name.clone()),
+                Err(e) => eprintln!(
+                    "{} | {}",
+                    repo.name.cyan().bold(),
+                    format!("Error: {e}").red()
+                ),
+            }
+        }
+
+        if !unconfigured.is_empty() {
+            println!(
+                "{}",
+                format!(
+                    "{} repositories have no hook configuration file:",
+                    unconfigured.len()
+                )
+                .yellow()
+            );
+            for name in &unconfigured {
+                println!("  {}", name.cyan());
+            }
+        }
+
+        println!("{}", "Done installing hooks".green());
+        Ok(())
+    }
+}
+
+impl HooksInstallCommand {
+    fn install(&self, repo: &Repository, scripts: Option<&Path>) -> Result<Installation> {
+        let logger = Logger;
+        let repo_dir = repo.get_target_dir();
+        if !Path::new(&repo_dir).exists() {
+            anyhow::bail!("Repository directory does not exist: {}", repo_dir);
+        }
+
+        if let Some(scripts) = scripts {
+            let linked = link_scripts(scripts, &git::hooks_dir(&repo_dir)?)?;
+            logger.success(repo, &format!("Linked {linked} hook scripts"));
+            return Ok(Installation::Installed);
+        }
+
+        let framework = match self.framework {
+            Some(framework) if framework.is_configured(Path::new(&repo_dir)) => framework,
+            Some(_) => return Ok(Installation::NotConfigured),
+            None => match HookFramework::detect(Path::new(&repo_dir)) {
+                Some(framework) => framework,
+                None => return Ok(Installation::NotConfigured),
+            },
+        };
+
+        let output = std::process::Command::new(framework.program())
+            .arg("install")
+            .current_dir(&repo_dir)
+            .output()
+            .with_context(|| format!("Failed to execute {} install", framework.program()))?;
+
+        if !output.status.success() {
+            anyhow::bail!(
+                "{} install failed: {}",
+                framework.program(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        logger.success(repo, &format!("Installed {} hooks", framework.program()));
+        Ok(Installation::Installed)
+    }
+}
+
+/// Symlink every file in `scripts` into `hooks_dir`, replacing existing
+/// hooks of the same name. Returns the number of linked scripts.
+fn link_scripts(scripts: &Path, hooks_dir: &Path) -> Result<usize> {
+    std::fs::create_dir_all(hooks_dir)?;
+
+    let mut linked = 0;
+    for entry in std::fs::read_dir(scripts)? {
+        let source: PathBuf = entry?.path();
+        if !source.is_file() {
+            continue;
+        }
+        let Some(name) = source.file_name() else {
+            continue;
+        };
+
+        let target = hooks_dir.join(name);
+        if target.symlink_metadata().is_ok() {
+            std::fs::remove_file(&target)?;
+        }
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&source, &target)?;
+        #[cfg(not(unix))]
+        std::fs::copy(&source, &target)?;
+        linked += 1;
+    }
+
+    Ok(linked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_framework() {
+        let dir = std::env::temp_dir().join(format!("rrepos-hooks-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        assert_eq!(HookFramework::detect(&dir), None);
+
+        std::fs::write(dir.join(".lefthook.yml"), "").unwrap();
+        assert_eq!(HookFramework::detect(&dir), Some(HookFramework::Lefthook));
+
+        std::fs::write(dir.join(".pre-commit-config.yaml"), "").unwrap();
+        assert_eq!(HookFramework::detect(&dir), Some(HookFramework::PreCommit));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
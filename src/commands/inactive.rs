@@ -0,0 +1,211 @@
+//! Inactive command implementation
+
+use super::{Command, CommandContext};
+use crate::config::{Provider, Repository};
+use crate::git;
+use crate::github::GitHubClient;
+use crate::offline;
+use crate::util;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use colored::*;
+use std::path::Path;
+
+/// Inactive command reporting repositories without recent commits, pull
+/// requests or releases, as candidates for archiving
+pub struct InactiveCommand {
+    /// Age such as `180d`, or a date, before which activity counts as stale
+    pub threshold: String,
+    pub token: Option<String>,
+}
+
+/// Latest activity found for a repository
+#[derive(Debug, Default)]
+struct Activity {
+    last_commit: Option<DateTime<Utc>>,
+    last_pull_request: Option<DateTime<Utc>>,
+    last_release: Option<DateTime<Utc>>,
+}
+
+impl Activity {
+    fn latest(&self) -> Option<DateTime<Utc>> {
+        [self.last_commit, self.last_pull_request, self.last_release]
+            .into_iter()
+            .flatten()
+            .max()
+    }
+
+    /// Whether nothing happened since `cutoff`; repositories without any
+    /// activity count as inactive
+    fn is_inactive(&self, cutoff: DateTime<Utc>) -> bool {
+        self.latest().is_none_or(|latest| latest < cutoff)
+    }
+}
+
+#[async_trait]
+impl Command for InactiveCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        let now = Utc::now();
+        let cutoff = util::parse_time(&self.threshold, now).map_err(anyhow::Error::msg)?;
+        // Offline, only the clones' history is available
+        let client = if offline::is_enabled() {
+            None
+        } else {
+            Some(GitHubClient::with_settings(
+                self.token.clone(),
+                &context.config.github_settings(),
+            )?)
+        };
+
+        let tasks: Vec<_> = repositories
+            .iter()
+            .map(|repo| {
+                let client = client.as_ref();
+                async move { (repo, activity(client, repo).await) }
+            })
+            .collect();
+
+        let mut inactive = Vec::new();
+        for (repo, result) in futures::future::join_all(tasks).await {
+            match result {
+                Ok(activity) if activity.is_inactive(cutoff) => inactive.push((repo, activity)),
+                Ok(_) => {}
+                Err(e) => eprintln!(
+                    "{} | {}",
+                    repo.name.cyan().bold(),
+                    format!("Error: {e}").red()
+                ),
+            }
+        }
+
+        // Longest idle first
+        inactive.sort_by_key(|(_, activity)| activity.latest());
+
+        if !inactive.is_empty() {
+            let name_width = inactive
+                .iter()
+                .map(|(repo, _)| repo.name.len())
+                .max()
+                .unwrap_or(0)
+                .max(10);
+            println!(
+                "{:<name_width$}  {:<11}  {:<11}  {:<12}  Idle",
+                "Repository", "Last commit", "Last PR", "Last release"
+            );
+            for (repo, activity) in &inactive {
+                let idle = activity.latest().map_or_else(
+                    || "never active".to_string(),
+                    |latest| format!("{} days", (now - latest).num_days()),
+                );
+                println!(
+                    "{:<name_width$}  {:<11}  {:<11}  {:<12}  {}",
+                    repo.name,
+                    date(activity.last_commit),
+                    date(activity.last_pull_request),
+                    date(activity.last_release),
+                    idle.yellow()
+                );
+            }
+            println!();
+        }
+
+        println!(
+            "{}",
+            format!(
+                "{} of {} repositories inactive since {}",
+                inactive.len(),
+                repositories.len(),
+                cutoff.format("%Y-%m-%d")
+            )
+            .green()
+        );
+        Ok(())
+    }
+}
+
+/// Gather a repository's latest commit from its clone, and its latest pull
+/// request and release from GitHub when there is a client. Uncloned
+/// repositories fall back to the last push GitHub recorded.
+async fn activity(client: Option<&GitHubClient>, repo: &Repository) -> Result<Activity> {
+    let repo_dir = repo.get_target_dir();
+    let cloned = Path::new(&repo_dir).exists();
+    let mut activity = Activity::default();
+    if cloned {
+        activity.last_commit = parse_date(git::last_commit_date(&repo_dir)?.as_deref());
+    }
+
+    let Some(client) = client.filter(|_| repo.provider == Provider::GitHub) else {
+        if !cloned {
+            anyhow::bail!("Repository is not cloned");
+        }
+        return Ok(activity);
+    };
+
+    let (owner, name) = client.parse_github_url(&repo.url)?;
+    if !cloned {
+        let remote = client.get_repository(&owner, &name).await?;
+        activity.last_commit = parse_date(remote.pushed_at.as_deref());
+    }
+    let pull_request = client.latest_pull_request(&owner, &name).await?;
+    activity.last_pull_request = parse_date(pull_request.as_ref().map(|pr| pr.created_at.as_str()));
+    let release = client.latest_release(&owner, &name).await?;
+    activity.last_release = parse_date(release.as_ref().map(|release| {
+        release
+            .published_at
+            .as_ref()
+            .unwrap_or(&release.created_at)
+            .as_str()
+    }));
+    Ok(activity)
+}
+
+fn parse_date(value: Option<&str>) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value?)
+        .ok()
+        .map(|date| date.with_timezone(&Utc))
+}
+
+fn date(value: Option<DateTime<Utc>>) -> String {
+    value.map_or_else(
+        || "-".to_string(),
+        |date| date.format("%Y-%m-%d").to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_inactive() {
+        let cutoff = parse_date(Some("2024-01-01T00:00:00Z")).unwrap();
+        let mut activity = Activity {
+            last_commit: parse_date(Some("2023-06-01T12:00:00+02:00")),
+            last_pull_request: None,
+            last_release: parse_date(Some("2023-12-31T23:00:00Z")),
+        };
+        assert!(activity.is_inactive(cutoff));
+
+        activity.last_pull_request = parse_date(Some("2024-02-01T00:00:00Z"));
+        assert!(!activity.is_inactive(cutoff));
+
+        assert!(Activity::default().is_inactive(cutoff));
+    }
+}
@@ -0,0 +1,126 @@
+//! Maintenance command implementation
+
+use super::{Command, CommandContext};
+use crate::config::Repository;
+use crate::git::{self, Logger};
+use crate::scheduler;
+use crate::util::{dir_size, format_size};
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+use std::path::Path;
+
+/// Maintenance command expiring reflogs and running `git gc` in each clone,
+/// reporting the space reclaimed
+pub struct MaintenanceCommand {
+    /// Recompute deltas and prune unreachable objects and reflogs right away
+    pub aggressive: bool,
+}
+
+#[async_trait]
+impl Command for MaintenanceCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            format!(
+                "Running maintenance on {} repositories...",
+                repositories.len()
+            )
+            .green()
+        );
+
+        let mut results = Vec::new();
+        if context.parallel {
+            let tasks: Vec<_> = repositories
+                .into_iter()
+                .map(|repo| {
+                    let aggressive = self.aggressive;
+                    scheduler::spawn_blocking(move || {
+                        let result = maintain(&repo, aggressive);
+                        (repo, result)
+                    })
+                })
+                .collect();
+            for task in tasks {
+                results.push(task.await?);
+            }
+        } else {
+            for repo in repositories {
+                let result = maintain(&repo, self.aggressive);
+                results.push((repo, result));
+            }
+        }
+
+        let mut reclaimed = 0;
+        let mut maintained = 0;
+        for (repo, result) in &results {
+            match result {
+                Ok(Some(bytes)) => {
+                    maintained += 1;
+                    reclaimed += bytes;
+                }
+                Ok(None) => {}
+                Err(e) => eprintln!(
+                    "{} | {}",
+                    repo.name.cyan().bold(),
+                    format!("Error: {e}").red()
+                ),
+            }
+        }
+
+        println!(
+            "{}",
+            format!(
+                "Maintained {maintained} repositories, reclaimed {}",
+                format_size(reclaimed)
+            )
+            .green()
+        );
+        Ok(())
+    }
+}
+
+/// Run maintenance in a clone and return the bytes reclaimed from its `.git`
+/// directory, or `None` when it isn't cloned
+fn maintain(repo: &Repository, aggressive: bool) -> Result<Option<u64>> {
+    let logger = Logger;
+    let repo_dir = repo.get_target_dir();
+    let git_dir = Path::new(&repo_dir).join(".git");
+    if !git_dir.exists() {
+        logger.warn(repo, "Repository directory does not exist, skipping");
+        return Ok(None);
+    }
+
+    let before = dir_size(&git_dir);
+    git::gc(&repo_dir, aggressive)?;
+    let after = dir_size(&git_dir);
+
+    // Repacking can grow a repository slightly, which isn't worth reporting
+    let reclaimed = before.saturating_sub(after);
+    logger.success(
+        repo,
+        &format!(
+            "{} -> {} (reclaimed {})",
+            format_size(before),
+            format_size(after),
+            format_size(reclaimed)
+        ),
+    );
+    Ok(Some(reclaimed))
+}
@@ -0,0 +1,170 @@
+//! Mirror command implementation
+
+use super::{Command, CommandContext};
+use crate::config::{Repository, hosts};
+use crate::git::{self, Logger};
+use crate::github::GitHubClient;
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+use std::path::Path;
+
+/// Mirror command for replicating repositories to another remote
+pub struct MirrorCommand {
+    /// Remote prefix the repository name is appended to, e.g.
+    /// `git@backup-host:backup-org/`
+    pub to: String,
+    /// Also push all tags
+    pub push_tags: bool,
+    /// Token used to create missing repositories on GitHub
+    pub token: Option<String>,
+}
+
+#[async_trait]
+impl Command for MirrorCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            format!(
+                "Mirroring {} repositories to {}...",
+                repositories.len(),
+                self.to
+            )
+            .green()
+        );
+
+        let client =
+            GitHubClient::with_settings(self.token.clone(), &context.config.github_settings())?;
+
+        let mut failed = 0;
+        for repo in &repositories {
+            if let Err(e) = self.mirror_repository(context, repo, &client).await {
+                failed += 1;
+                eprintln!(
+                    "{} | {}",
+                    repo.name.cyan().bold(),
+                    format!("Error: {e}").red()
+                );
+            }
+        }
+
+        if failed > 0 {
+            anyhow::bail!("{} repositories failed to mirror", failed);
+        }
+
+        println!("{}", "Done mirroring repositories".green());
+        Ok(())
+    }
+}
+
+impl MirrorCommand {
+    async fn mirror_repository(
+        &self,
+        context: &CommandContext,
+        repo: &Repository,
+        client: &GitHubClient,
+    ) -> Result<()> {
+        let logger = Logger;
+        let repo_dir = repo.get_target_dir();
+        if !Path::new(&repo_dir).exists() {
+            anyhow::bail!("Repository directory does not exist: {}", repo_dir);
+        }
+
+        let target = mirror_url(&self.to, &repo.name);
+
+        // Only GitHub targets can be created through the API; other hosts
+        // need the repository to exist already
+        if self.token.is_some() && hosts::url_host(&target) == Some(hosts::GITHUB_HOST) {
+            let (owner, name) = client.parse_github_url(&target)?;
+            if !client.repository_exists(&owner, &name).await? {
+                client.create_repository(&owner, &name, true).await?;
+                logger.info(repo, &format!("Created {owner}/{name}"));
+            }
+        }
+
+        git::fetch(repo, &repo.host_settings(&context.config), self.push_tags)?;
+        let mirror = mirror_remote(repo, &target);
+        let settings = mirror.host_settings(&context.config);
+        git::push_mirror(&repo_dir, &mirror, &settings, self.push_tags)?;
+        logger.success(repo, &format!("Mirrored to {target}"));
+        Ok(())
+    }
+}
+
+/// The mirror as a remote, authenticated through the settings of its own
+/// host rather than the repository's SSH key
+fn mirror_remote(repo: &Repository, url: &str) -> Repository {
+    Repository::new(repo.name.clone(), url.to_string())
+}
+
+/// URL of a repository's mirror under the `to` prefix
+fn mirror_url(to: &str, name: &str) -> String {
+    if to.ends_with('/') || to.ends_with(':') {
+        format!("{to}{name}.git")
+    } else {
+        format!("{to}/{name}.git")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, HostSettings};
+
+    #[test]
+    fn test_mirror_remote() {
+        let mut config = Config::new();
+        config.hosts.insert(
+            "backup.example.com".to_string(),
+            HostSettings {
+                ssh_key: Some("~/.ssh/backup".to_string()),
+                ..Default::default()
+            },
+        );
+        let mut repo = Repository::new("api".to_string(), "git@github.com:org/api.git".to_string());
+        repo.ssh_key = Some("~/.ssh/github".to_string());
+
+        let mirror = mirror_remote(&repo, "git@backup.example.com:backup/api.git");
+        let settings = mirror.host_settings(&config);
+        assert_eq!(mirror.url, "git@backup.example.com:backup/api.git");
+        assert!(
+            mirror
+                .ssh_command(&settings)
+                .unwrap()
+                .contains(".ssh/backup")
+        );
+
+        let mirror = mirror_remote(&repo, "git@other.example.com:backup/api.git");
+        let settings = mirror.host_settings(&config);
+        assert!(mirror.ssh_command(&settings).is_none());
+    }
+
+    #[test]
+    fn test_mirror_url() {
+        assert_eq!(
+            mirror_url("git@backup-host:backup-org/", "api"),
+            "git@backup-host:backup-org/api.git"
+        );
+        assert_eq!(
+            mirror_url("https://github.com/backup-org", "api"),
+            "https://github.com/backup-org/api.git"
+        );
+        assert_eq!(mirror_url("/srv/mirrors", "api"), "/srv/mirrors/api.git");
+    }
+}
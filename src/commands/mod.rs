@@ -1,16 +1,130 @@
 //! Command pattern implementation for CLI operations
 
+pub mod actions;
+pub mod admin;
 pub mod base;
+pub mod bench;
+pub mod bump;
+pub mod cache;
+pub mod campaign;
+pub mod changelog;
+pub mod cherry_pick;
+pub mod ci;
 pub mod clone;
+pub mod codeowners;
+pub mod completions;
+pub mod conflicts;
+pub mod consolidate;
+pub mod contributors;
+pub mod coverage;
+pub mod dependabot;
+pub mod deps;
+pub mod devfiles;
+pub mod discover;
+pub mod docker;
+pub mod du;
+pub mod env;
+pub mod extract;
+pub mod file_diff;
+pub mod gerrit;
+pub mod git_config;
+pub mod graph;
+pub mod hooks;
+pub mod inactive;
 pub mod init;
+pub mod maintenance;
+pub mod mirror;
+pub mod move_repos;
+pub mod new;
+pub mod owners;
+pub mod plan;
 pub mod pr;
+pub mod rebase;
+pub mod refresh_metadata;
+pub mod relayout;
 pub mod remove;
+pub mod rename;
+pub mod replace;
+pub mod reviews;
 pub mod run;
+pub mod runs;
+pub mod sbom;
+pub mod scan;
+pub mod search_remote;
+pub mod settings;
+pub mod shell;
+pub mod sync;
+pub mod sync_files;
+pub mod tag;
+pub mod task;
+pub mod test;
+pub mod tmux;
+pub mod todos;
+pub mod verify;
+pub mod verify_signatures;
+pub mod workspace;
 
 // Re-export the base types and all commands
-pub use base::{Command, CommandContext};
+pub use actions::{ActionsAction, ActionsPinCommand};
+pub use admin::{AdminAction, AdminCommand, AdminOperation};
+pub use base::{Command, CommandContext, Presence};
+pub use bench::BenchCommand;
+pub use bump::BumpCommand;
+pub use cache::{CacheAction, CacheCommand};
+pub use campaign::{CampaignAction, CampaignCommand, CampaignOperation};
+pub use changelog::{ChangelogCommand, ChangelogFormat};
+pub use cherry_pick::CherryPickCommand;
+pub use ci::{CiAction, CiAuditCommand, CiPolicy};
 pub use clone::CloneCommand;
+pub use codeowners::{CodeownersAction, CodeownersApplyCommand, CodeownersAuditCommand};
+pub use completions::CompletionsCommand;
+pub use conflicts::ConflictsCommand;
+pub use consolidate::{ConsolidateCommand, PrefixBy};
+pub use contributors::{ContributorsCommand, ContributorsFormat};
+pub use coverage::{CoverageCommand, CoverageFormat};
+pub use dependabot::{DependabotAction, DependabotApplyCommand};
+pub use deps::DepsCommand;
+pub use devfiles::{DevfileKind, DevfilesAction, DevfilesApplyCommand};
+pub use discover::DiscoverCommand;
+pub use docker::{DockerAction, DockerBuildCommand};
+pub use du::DuCommand;
+pub use env::{EnvCommand, EnvFormat};
+pub use extract::ExtractCommand;
+pub use file_diff::FileDiffCommand;
+pub use gerrit::{GerritAction, GerritStatusCommand};
+pub use git_config::{GitConfigAction, GitConfigApplyCommand};
+pub use graph::{GraphCommand, GraphFormat};
+pub use hooks::{HookFramework, HooksAction, HooksInstallCommand};
+pub use inactive::InactiveCommand;
 pub use init::InitCommand;
+pub use maintenance::MaintenanceCommand;
+pub use mirror::MirrorCommand;
+pub use move_repos::MoveCommand;
+pub use new::NewCommand;
+pub use owners::{OwnersCommand, OwnersFormat};
+pub use plan::{PlanAction, PlanCommand, PlanFormat, PlannedOperation};
 pub use pr::PrCommand;
+pub use rebase::RebaseCommand;
+pub use refresh_metadata::RefreshMetadataCommand;
+pub use relayout::RelayoutCommand;
 pub use remove::RemoveCommand;
-pub use run::RunCommand;
+pub use rename::RenameCommand;
+pub use replace::ReplaceCommand;
+pub use reviews::ReviewsCommand;
+pub use run::{ChangedSince, RunCommand, RunReport};
+pub use runs::{RunsAction, RunsCommand};
+pub use sbom::{SbomCommand, SbomFormat};
+pub use scan::{ScanCommand, ScanFormat, ScanTool, Severity};
+pub use search_remote::SearchRemoteCommand;
+pub use settings::{RepoSettings, SettingsAction, SettingsApplyCommand};
+pub use shell::ShellCommand;
+pub use sync::SyncCommand;
+pub use sync_files::SyncFilesCommand;
+pub use tag::{ImportTopicsCommand, TagAction};
+pub use task::TaskCommand;
+pub use test::{TestCommand, TestPreset};
+pub use tmux::TmuxCommand;
+pub use todos::{TodosCommand, TodosFormat};
+pub use verify::VerifyCommand;
+pub use verify_signatures::VerifySignaturesCommand;
+pub use workspace::{Editor, WorkspaceAction, WorkspaceCommand};
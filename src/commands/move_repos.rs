@@ -0,0 +1,171 @@
+//! Move command implementation
+
+use super::{Command, CommandContext};
+use crate::config::{Layout, Repository};
+use crate::git;
+use crate::util::move_dir;
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+use std::path::{Path, PathBuf};
+
+/// Move command for relocating clones and updating their configured paths
+pub struct MoveCommand {
+    /// Directory to move clones into
+    pub to: String,
+    /// Place each clone under a directory named after its first tag
+    pub by_tag: bool,
+    /// Only show the planned moves
+    pub dry_run: bool,
+    /// Config file to update with the new paths
+    pub config_path: String,
+}
+
+#[async_trait]
+impl Command for MoveCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.repositories_with_archived();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        let base_dir = std::env::current_dir()?.join(&self.to);
+        if !self.dry_run {
+            std::fs::create_dir_all(&base_dir)?;
+        }
+        let base_dir = std::fs::canonicalize(&base_dir).unwrap_or(base_dir);
+        let config_dir = std::fs::canonicalize(
+            Path::new(&self.config_path)
+                .parent()
+                .filter(|p| !p.as_os_str().is_empty())
+                .unwrap_or(Path::new(".")),
+        )?;
+
+        println!(
+            "{}",
+            format!(
+                "Moving {} repositories to {}...",
+                repositories.len(),
+                base_dir.display()
+            )
+            .green()
+        );
+
+        let mut config = context.config.clone();
+        let mut updated = 0;
+
+        for repo in &repositories {
+            let target = self.target_dir(&base_dir, repo);
+            let source = PathBuf::from(repo.get_target_dir());
+            let source = std::fs::canonicalize(&source).unwrap_or(source);
+
+            if source == target {
+                println!(
+                    "{} | Already at {}",
+                    repo.name.cyan().bold(),
+                    target.display()
+                );
+                continue;
+            }
+
+            if self.dry_run {
+                println!(
+                    "{} | {} -> {}",
+                    repo.name.cyan().bold(),
+                    source.display(),
+                    target.display()
+                );
+                continue;
+            }
+
+            if let Err(e) = move_repository(repo, &source, &target) {
+                eprintln!(
+                    "{} | {}",
+                    repo.name.cyan().bold(),
+                    format!("Error: {e}").red()
+                );
+                continue;
+            }
+
+            // Keep paths relative to the config file when possible
+            let path = target
+                .strip_prefix(&config_dir)
+                .unwrap_or(&target)
+                .to_string_lossy()
+                .to_string();
+            if let Some(entry) = config.get_repository_mut(&repo.name) {
+                entry.path = Some(path);
+                updated += 1;
+            }
+        }
+
+        if self.dry_run {
+            println!("{}", "Dry run, nothing moved".yellow());
+            return Ok(());
+        }
+
+        if updated > 0 {
+            config.save(&self.config_path)?;
+            println!(
+                "{}",
+                format!("Updated {updated} paths in {}", self.config_path).green()
+            );
+        }
+
+        println!("{}", "Done moving repositories".green());
+        Ok(())
+    }
+}
+
+impl MoveCommand {
+    fn target_dir(&self, base_dir: &Path, repo: &Repository) -> PathBuf {
+        let layout = if self.by_tag {
+            Layout::ByTag
+        } else {
+            Layout::Flat
+        };
+        base_dir.join(layout.relative_dir(&repo.name, &repo.tags))
+    }
+}
+
+/// Move a clone and check that git still works in its new location.
+/// Repositories that are not cloned only get their configured path updated.
+pub(crate) fn move_repository(repo: &Repository, source: &Path, target: &Path) -> Result<()> {
+    if !source.exists() {
+        println!(
+            "{} | Not cloned, updating configured path only",
+            repo.name.cyan().bold()
+        );
+        return Ok(());
+    }
+    if target.exists() {
+        anyhow::bail!("Target directory already exists: {}", target.display());
+    }
+
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    move_dir(source, target)?;
+
+    let target_dir = target.to_string_lossy();
+    git::has_changes(&target_dir)
+        .map_err(|e| anyhow::anyhow!("Git no longer works after the move: {e}"))?;
+
+    println!(
+        "{} | {}",
+        repo.name.cyan().bold(),
+        format!("Moved to {}", target.display()).green()
+    );
+    Ok(())
+}
@@ -0,0 +1,158 @@
+//! New command implementation
+
+use super::{Command, CommandContext};
+use crate::config::{Repository, hosts};
+use crate::git::{self, Logger};
+use crate::github::GitHubClient;
+use crate::template;
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// New command for creating a repository from a template
+pub struct NewCommand {
+    pub name: String,
+    /// Git URL of the template repository
+    pub template: String,
+    /// URL of the new repository; defaults to a sibling of the template
+    pub url: Option<String>,
+    pub path: Option<String>,
+    pub tags: Vec<String>,
+    /// Extra `KEY=VALUE` template variables
+    pub vars: Vec<String>,
+    /// Initial branch of the new repository
+    pub branch: String,
+    /// Create the repository on GitHub and push the initial commit
+    pub create_remote: bool,
+    pub private: bool,
+    pub token: Option<String>,
+    pub config_path: String,
+}
+
+#[async_trait]
+impl Command for NewCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let logger = Logger;
+        let mut config = context.config.clone();
+
+        let url = self
+            .url
+            .clone()
+            .unwrap_or_else(|| sibling_url(&self.template, &self.name));
+        let mut repo = Repository::new(self.name.clone(), url);
+        repo.path = self.path.clone();
+        repo.tags = self.tags.clone();
+        repo.set_workspace(
+            Path::new(&self.config_path).parent().map(Path::to_path_buf),
+            config.layout,
+        );
+        let settings = repo.host_settings(&config);
+
+        // Fail before touching anything if the repository can't be recorded
+        config.add_repository(repo.clone())?;
+
+        let repo_dir = repo.get_target_dir();
+        if Path::new(&repo_dir).exists() {
+            anyhow::bail!("Directory already exists: {}", repo_dir);
+        }
+
+        let mut vars = template::repository_variables(&repo);
+        for var in &self.vars {
+            let (key, value) = var.split_once('=').ok_or_else(|| {
+                anyhow::anyhow!("Invalid template variable (expected KEY=VALUE): {}", var)
+            })?;
+            vars.insert(key.trim().to_string(), value.to_string());
+        }
+
+        // Clone the template in place, then start over with fresh history
+        let mut source = repo.clone();
+        source.url = self.template.clone();
+        git::clone_repository(&source, &source.host_settings(&config))?;
+        std::fs::remove_dir_all(Path::new(&repo_dir).join(".git"))?;
+
+        let rendered = render_tree(Path::new(&repo_dir), &vars)?;
+        logger.info(&repo, &format!("Rendered variables in {rendered} files"));
+
+        git::init(&repo_dir, &self.branch)?;
+        git::add_all_changes(&repo_dir)?;
+        git::commit_changes(&repo_dir, &format!("Initial commit from {}", self.template))?;
+        git::add_remote(&repo_dir, "origin", &repo.url)?;
+
+        if self.create_remote {
+            if hosts::url_host(&repo.url) != Some(hosts::GITHUB_HOST) {
+                anyhow::bail!("Only GitHub repositories can be created: {}", repo.url);
+            }
+            let client = GitHubClient::with_settings(self.token.clone(), &settings)?;
+            let (owner, name) = client.parse_github_url(&repo.url)?;
+            client
+                .create_repository(&owner, &name, self.private)
+                .await?;
+            logger.info(&repo, &format!("Created {owner}/{name}"));
+
+            git::push_branch(&repo, &settings, &self.branch)?;
+            logger.info(&repo, &format!("Pushed {}", self.branch));
+        }
+
+        config.save(&self.config_path)?;
+        println!(
+            "{}",
+            format!("Added {} to {}", repo.name, self.config_path).green()
+        );
+        Ok(())
+    }
+}
+
+/// URL of a repository named `name` next to `template`, e.g.
+/// `git@github.com:org/service-template.git` -> `git@github.com:org/billing.git`
+fn sibling_url(template: &str, name: &str) -> String {
+    let base = template.trim_end_matches('/');
+    let base = base.strip_suffix(".git").unwrap_or(base);
+    match base.rfind(['/', ':']) {
+        Some(idx) => format!("{}{name}.git", &base[..=idx]),
+        None => format!("{name}.git"),
+    }
+}
+
+/// Render `{placeholder}` variables in every text file below `root`,
+/// returning how many files changed
+fn render_tree(root: &Path, vars: &std::collections::HashMap<String, String>) -> Result<usize> {
+    let mut rendered = 0;
+
+    for entry in WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| e.file_name() != ".git")
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+    {
+        // Binary files are copied as they are
+        let Ok(content) = std::fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let output = template::render(&content, vars);
+        if output != content {
+            std::fs::write(entry.path(), output)?;
+            rendered += 1;
+        }
+    }
+
+    Ok(rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sibling_url() {
+        assert_eq!(
+            sibling_url("git@github.com:org/service-template.git", "billing"),
+            "git@github.com:org/billing.git"
+        );
+        assert_eq!(
+            sibling_url("https://github.com/org/service-template", "billing"),
+            "https://github.com/org/billing.git"
+        );
+    }
+}
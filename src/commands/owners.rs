@@ -0,0 +1,271 @@
+//! Owners command implementation
+
+use super::codeowners::{find_codeowners, owners_for, parse_codeowners};
+use super::{Command, CommandContext};
+use crate::config::Repository;
+use crate::git::{self, Logger};
+use crate::util;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use colored::*;
+use glob::Pattern;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Output format for the ownership report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OwnersFormat {
+    Table,
+    Json,
+}
+
+/// Owners command reporting who last changed the lines under a path, from
+/// `git blame`, next to the path's CODEOWNERS entries
+pub struct OwnersCommand {
+    /// Directory, file or glob pattern, relative to each repository root
+    pub path: String,
+    /// Only count lines changed after this age or date, e.g. `1y`
+    pub since: Option<String>,
+    /// Number of authors listed per repository
+    pub top: usize,
+    pub format: OwnersFormat,
+}
+
+/// An author's share of the lines under the path
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct Owner {
+    author: String,
+    lines: usize,
+    /// Date of the author's most recent change, e.g. `2024-05-01`
+    last_changed: String,
+}
+
+/// Ownership of the path in one repository
+#[derive(Debug, Serialize)]
+struct RepoOwners {
+    repository: String,
+    files: usize,
+    /// CODEOWNERS owners of the matching files
+    codeowners: Vec<String>,
+    authors: Vec<Owner>,
+}
+
+#[async_trait]
+impl Command for OwnersCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            eprintln!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        let since = self
+            .since
+            .as_deref()
+            .map(|since| util::parse_time(since, Utc::now()))
+            .transpose()
+            .map_err(anyhow::Error::msg)?
+            .map(|time| time.format("%Y-%m-%d %H:%M:%S +0000").to_string());
+
+        let logger = Logger;
+        let mut report = Vec::new();
+        for repo in &repositories {
+            if !Path::new(&repo.get_target_dir()).exists() {
+                logger.warn(repo, "Repository directory does not exist, skipping");
+                continue;
+            }
+            match self.analyze(repo, since.as_deref()) {
+                Ok(Some(owners)) => report.push(owners),
+                Ok(None) => {}
+                Err(e) => eprintln!(
+                    "{} | {}",
+                    repo.name.cyan().bold(),
+                    format!("Error: {e}").red()
+                ),
+            }
+        }
+
+        match self.format {
+            OwnersFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+            OwnersFormat::Table => self.print_table(&report),
+        }
+        Ok(())
+    }
+}
+
+impl OwnersCommand {
+    /// Ownership of the path in a repository, or `None` when no tracked file
+    /// matches it
+    fn analyze(&self, repo: &Repository, since: Option<&str>) -> Result<Option<RepoOwners>> {
+        let repo_dir = repo.get_target_dir();
+        let files: Vec<String> = git::tracked_files(&repo_dir)?
+            .into_iter()
+            .filter(|file| path_matches(&self.path, file))
+            .collect();
+        if files.is_empty() {
+            return Ok(None);
+        }
+
+        let rules = find_codeowners(Path::new(&repo_dir))
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .map(|content| parse_codeowners(&content))
+            .unwrap_or_default();
+        let mut codeowners: Vec<String> = Vec::new();
+        let mut lines = Vec::new();
+        for file in &files {
+            for owner in owners_for(&rules, file) {
+                if !codeowners.contains(owner) {
+                    codeowners.push(owner.clone());
+                }
+            }
+            lines.extend(git::blame_lines(&repo_dir, file, since)?);
+        }
+
+        let mut authors = rank(lines);
+        authors.truncate(self.top);
+        Ok(Some(RepoOwners {
+            repository: repo.name.clone(),
+            files: files.len(),
+            codeowners,
+            authors,
+        }))
+    }
+
+    fn print_table(&self, report: &[RepoOwners]) {
+        if report.is_empty() {
+            println!(
+                "{}",
+                format!("No tracked files match '{}'", self.path).yellow()
+            );
+            return;
+        }
+
+        let mut teams: Vec<&String> = Vec::new();
+        let mut people: HashMap<&str, usize> = HashMap::new();
+        for repo in report {
+            println!("{} ({} files)", repo.repository.cyan().bold(), repo.files);
+            if !repo.codeowners.is_empty() {
+                println!("  CODEOWNERS: {}", repo.codeowners.join(" "));
+            }
+            if repo.authors.is_empty() {
+                println!("  {}", "No changes in the period".yellow());
+            }
+            for owner in &repo.authors {
+                println!(
+                    "  {:<40}  {:>6} lines  last changed {}",
+                    owner.author, owner.lines, owner.last_changed
+                );
+                *people.entry(&owner.author).or_default() += owner.lines;
+            }
+            teams.extend(&repo.codeowners);
+        }
+
+        // Who to loop in across all repositories
+        teams.sort();
+        teams.dedup();
+        let mut people: Vec<(&str, usize)> = people.into_iter().collect();
+        people.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        let names: Vec<&str> = people
+            .iter()
+            .take(self.top)
+            .map(|(author, _)| {
+                // Leave out the email address
+                author.split_once(" <").map_or(*author, |(name, _)| name)
+            })
+            .collect();
+        println!();
+        if !teams.is_empty() {
+            let teams: Vec<&str> = teams.iter().map(|team| team.as_str()).collect();
+            println!("{} {}", "CODEOWNERS:".bold(), teams.join(", "));
+        }
+        if !names.is_empty() {
+            println!("{} {}", "Top authors:".bold(), names.join(", "));
+        }
+    }
+}
+
+/// Whether a tracked file falls under `path`: the file itself, a directory
+/// containing it or a glob pattern matching it
+fn path_matches(path: &str, file: &str) -> bool {
+    if path.contains(['*', '?', '[']) {
+        return Pattern::new(path).is_ok_and(|pattern| pattern.matches(file));
+    }
+    let path = path.trim_start_matches("./").trim_end_matches('/');
+    path.is_empty() || path == "." || file == path || file.starts_with(&format!("{path}/"))
+}
+
+/// Lines per author from blamed `(author, time)` pairs, most lines first
+fn rank(lines: Vec<(String, i64)>) -> Vec<Owner> {
+    let mut authors: HashMap<String, (usize, i64)> = HashMap::new();
+    for (author, time) in lines {
+        let entry = authors.entry(author).or_default();
+        entry.0 += 1;
+        entry.1 = entry.1.max(time);
+    }
+
+    let mut ranked: Vec<(String, usize, i64)> = authors
+        .into_iter()
+        .map(|(author, (lines, time))| (author, lines, time))
+        .collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then(b.2.cmp(&a.2)));
+    ranked
+        .into_iter()
+        .map(|(author, lines, time)| Owner {
+            author,
+            lines,
+            last_changed: DateTime::from_timestamp(time, 0)
+                .map(|time| time.format("%Y-%m-%d").to_string())
+                .unwrap_or_default(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_matches() {
+        assert!(path_matches("src/auth/", "src/auth/login.rs"));
+        assert!(path_matches("./src/auth", "src/auth/login.rs"));
+        assert!(!path_matches("src/auth/", "src/authz/policy.rs"));
+        assert!(path_matches("src/auth/login.rs", "src/auth/login.rs"));
+        assert!(path_matches("src/**/auth*", "src/api/auth.rs"));
+    }
+
+    #[test]
+    fn test_rank() {
+        let lines = vec![
+            ("Bob <bob@example.com>".to_string(), 1_700_000_000),
+            ("Alice <alice@example.com>".to_string(), 1_600_000_000),
+            ("Alice <alice@example.com>".to_string(), 1_714_521_600),
+        ];
+        assert_eq!(
+            rank(lines),
+            vec![
+                Owner {
+                    author: "Alice <alice@example.com>".to_string(),
+                    lines: 2,
+                    last_changed: "2024-05-01".to_string(),
+                },
+                Owner {
+                    author: "Bob <bob@example.com>".to_string(),
+                    lines: 1,
+                    last_changed: "2023-11-14".to_string(),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,474 @@
+//! Plan command implementation
+
+use super::remove::{trash_dir, unsaved_work};
+use super::sync_files::SyncMap;
+use super::{Command, CommandContext};
+use crate::config::{Config, Repository};
+use crate::git;
+use crate::github::api::{DEFAULT_BASE_BRANCH, render_branch_name};
+use crate::github::{GitHubClient, PrOptions};
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// Operations that can be planned
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum PlanAction {
+    /// Plan creating pull requests
+    Pr {
+        /// Specific repository names to create PRs for (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Title for the pull request
+        #[arg(long, default_value = "Automated changes")]
+        title: String,
+
+        /// Body text for the pull request
+        #[arg(long, default_value = "This PR was created automatically")]
+        body: String,
+
+        /// Branch name to create
+        #[arg(long)]
+        branch: Option<String>,
+
+        /// Base branch for the PR
+        #[arg(long)]
+        base: Option<String>,
+
+        /// Commit message
+        #[arg(long)]
+        message: Option<String>,
+
+        /// Create PR as draft
+        #[arg(long)]
+        draft: bool,
+
+        /// Only create PR, don't commit changes
+        #[arg(long)]
+        create_only: bool,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "markdown")]
+        format: PlanFormat,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Plan cloning repositories
+    Clone {
+        /// Specific repository names to clone (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "markdown")]
+        format: PlanFormat,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Plan removing cloned repositories
+    Rm {
+        /// Specific repository names to remove (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Remove repositories even if they have uncommitted or unpushed work
+        #[arg(long)]
+        force: bool,
+
+        /// Move repositories to the trash directory instead of deleting them
+        #[arg(long)]
+        trash: bool,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "markdown")]
+        format: PlanFormat,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Plan syncing shared files
+    SyncFiles {
+        /// Specific repository names to sync files into (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// File map declaring source templates and target paths
+        #[arg(short, long, default_value = "sync.yaml")]
+        map: String,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "markdown")]
+        format: PlanFormat,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+}
+
+/// Output format for a plan
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum PlanFormat {
+    Markdown,
+    Json,
+}
+
+/// The operation a plan describes, with its options
+pub enum PlannedOperation {
+    Pr(PrOptions),
+    Clone,
+    Rm { force: bool, trash: bool },
+    SyncFiles { map: String },
+}
+
+impl PlannedOperation {
+    fn name(&self) -> &'static str {
+        match self {
+            PlannedOperation::Pr(_) => "pr",
+            PlannedOperation::Clone => "clone",
+            PlannedOperation::Rm { .. } => "rm",
+            PlannedOperation::SyncFiles { .. } => "sync-files",
+        }
+    }
+}
+
+/// A single change the operation would make
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "action", rename_all = "kebab-case")]
+pub enum Step {
+    Clone {
+        url: String,
+        path: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        branch: Option<String>,
+        #[serde(rename = "ref", skip_serializing_if = "Option::is_none")]
+        reference: Option<String>,
+    },
+    CreateBranch {
+        branch: String,
+    },
+    Commit {
+        message: String,
+    },
+    Push {
+        branch: String,
+    },
+    ApiCall {
+        method: String,
+        endpoint: String,
+        payload: Value,
+    },
+    Remove {
+        path: String,
+    },
+    MoveToTrash {
+        path: String,
+        trash_dir: String,
+    },
+    WriteFile {
+        path: String,
+    },
+}
+
+impl std::fmt::Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Step::Clone {
+                url,
+                path,
+                branch,
+                reference,
+            } => {
+                write!(f, "Clone `{url}` into `{path}`")?;
+                if let Some(branch) = branch {
+                    write!(f, " on branch `{branch}`")?;
+                }
+                if let Some(reference) = reference {
+                    write!(f, " at `{reference}` (detached)")?;
+                }
+                Ok(())
+            }
+            Step::CreateBranch { branch } => write!(f, "Create branch `{branch}`"),
+            Step::Commit { message } => write!(f, "Commit all changes: \"{message}\""),
+            Step::Push { branch } => write!(f, "Push `{branch}` to origin"),
+            Step::ApiCall {
+                method, endpoint, ..
+            } => write!(f, "`{method} {endpoint}`"),
+            Step::Remove { path } => write!(f, "Delete `{path}`"),
+            Step::MoveToTrash { path, trash_dir } => write!(f, "Move `{path}` to `{trash_dir}`"),
+            Step::WriteFile { path } => write!(f, "Write `{path}`"),
+        }
+    }
+}
+
+/// What the operation would do in one repository
+#[derive(Debug, Serialize)]
+pub struct RepositoryPlan {
+    pub name: String,
+    pub url: String,
+    pub path: String,
+    pub steps: Vec<Step>,
+    /// Why the repository would be left alone
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<String>,
+}
+
+/// Everything an operation would do, without doing it
+#[derive(Debug, Serialize)]
+pub struct Plan {
+    pub operation: String,
+    pub repositories: Vec<RepositoryPlan>,
+}
+
+/// Plan command for describing what a mutating operation would do
+pub struct PlanCommand {
+    pub operation: PlannedOperation,
+    pub format: PlanFormat,
+}
+
+#[async_trait]
+impl Command for PlanCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = match self.operation {
+            // `rm` doesn't skip archived repositories either
+            PlannedOperation::Rm { .. } => context.repositories_with_archived(),
+            _ => context.repositories(),
+        };
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            eprintln!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        let map = match &self.operation {
+            PlannedOperation::SyncFiles { map } => Some(SyncMap::load(map)?),
+            _ => None,
+        };
+
+        let mut plan = Plan {
+            operation: self.operation.name().to_string(),
+            repositories: Vec::new(),
+        };
+        let mut failed = 0;
+        for repo in &repositories {
+            match self.plan_repository(context, repo, map.as_ref()) {
+                Ok(steps) => plan.repositories.push(steps),
+                Err(e) => {
+                    failed += 1;
+                    eprintln!(
+                        "{} | {}",
+                        repo.name.cyan().bold(),
+                        format!("Error: {e}").red()
+                    );
+                }
+            }
+        }
+
+        match self.format {
+            PlanFormat::Markdown => print!("{}", render_markdown(&plan)),
+            PlanFormat::Json => println!("{}", serde_json::to_string_pretty(&plan)?),
+        }
+
+        if failed > 0 {
+            anyhow::bail!("{} repositories could not be planned", failed);
+        }
+        Ok(())
+    }
+}
+
+impl PlanCommand {
+    fn plan_repository(
+        &self,
+        context: &CommandContext,
+        repo: &Repository,
+        map: Option<&SyncMap>,
+    ) -> Result<RepositoryPlan> {
+        let path = repo.get_target_dir();
+        let mut plan = RepositoryPlan {
+            name: repo.name.clone(),
+            url: repo.url.clone(),
+            path: path.clone(),
+            steps: Vec::new(),
+            skipped: None,
+        };
+
+        let cloned = Path::new(&path).exists();
+        if !cloned && !matches!(self.operation, PlannedOperation::Clone) {
+            plan.skipped = Some("Repository directory does not exist".to_string());
+            return Ok(plan);
+        }
+
+        match &self.operation {
+            PlannedOperation::Pr(options) => {
+                if !git::has_changes(&path)? {
+                    plan.skipped = Some("No changes detected".to_string());
+                    return Ok(plan);
+                }
+                plan.steps = pr_steps(&context.config, repo, options)?;
+            }
+            PlannedOperation::Clone => {
+                if cloned {
+                    plan.skipped = Some("Repository directory already exists".to_string());
+                    return Ok(plan);
+                }
+                plan.steps.push(Step::Clone {
+                    url: repo.url.clone(),
+                    path,
+                    branch: repo.branch.clone(),
+                    reference: repo.reference.clone(),
+                });
+            }
+            PlannedOperation::Rm { force, trash } => {
+                if !force && let Some(reason) = unsaved_work(&path)? {
+                    plan.skipped = Some(reason);
+                    return Ok(plan);
+                }
+                plan.steps.push(if *trash {
+                    Step::MoveToTrash {
+                        path,
+                        trash_dir: trash_dir(context).display().to_string(),
+                    }
+                } else {
+                    Step::Remove { path }
+                });
+            }
+            PlannedOperation::SyncFiles { .. } => {
+                let map = map.expect("sync map is loaded for sync-files plans");
+                plan.steps = map
+                    .outdated_files(repo)?
+                    .into_iter()
+                    .map(|(file, _)| Step::WriteFile {
+                        path: file.target.clone(),
+                    })
+                    .collect();
+                if plan.steps.is_empty() {
+                    plan.skipped = Some("Up to date".to_string());
+                }
+            }
+        }
+
+        Ok(plan)
+    }
+}
+
+/// The steps `pr` takes in a repository with changes
+fn pr_steps(config: &Config, repo: &Repository, options: &PrOptions) -> Result<Vec<Step>> {
+    let branch = render_branch_name(config, repo, options, "<random>");
+    let message = options
+        .commit_msg
+        .clone()
+        .unwrap_or_else(|| options.title.clone());
+
+    let mut steps = vec![
+        Step::CreateBranch {
+            branch: branch.clone(),
+        },
+        Step::Commit { message },
+    ];
+    if options.create_only {
+        return Ok(steps);
+    }
+
+    let (owner, name) = GitHubClient::new(None).parse_github_url(&repo.url)?;
+    steps.push(Step::Push {
+        branch: branch.clone(),
+    });
+    steps.push(Step::ApiCall {
+        method: "POST".to_string(),
+        endpoint: format!("/repos/{owner}/{name}/pulls"),
+        payload: json!({
+            "title": options.title,
+            "body": options.body,
+            "head": branch,
+            "base": options.base_branch.as_deref().unwrap_or(DEFAULT_BASE_BRANCH),
+            "draft": options.draft,
+        }),
+    });
+    Ok(steps)
+}
+
+fn render_markdown(plan: &Plan) -> String {
+    let affected = plan
+        .repositories
+        .iter()
+        .filter(|repo| !repo.steps.is_empty())
+        .count();
+
+    let mut out = format!("# Plan: {}\n\n", plan.operation);
+    let _ = writeln!(
+        out,
+        "{affected} of {} repositories affected.",
+        plan.repositories.len()
+    );
+
+    for repo in &plan.repositories {
+        let _ = writeln!(out, "\n## {}\n", repo.name);
+        let _ = writeln!(out, "`{}` ({})\n", repo.path, repo.url);
+        if let Some(reason) = &repo.skipped {
+            let _ = writeln!(out, "Skipped: {reason}");
+        }
+        for (index, step) in repo.steps.iter().enumerate() {
+            let _ = writeln!(out, "{}. {step}", index + 1);
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pr_steps() {
+        let repo = Repository::new(
+            "api".to_string(),
+            "git@github.com:owner/api.git".to_string(),
+        );
+        let mut options =
+            PrOptions::new("Bump deps".to_string(), "Body".to_string(), String::new());
+        options.branch_name = Some("bump-deps".to_string());
+
+        let steps = pr_steps(&Config::new(), &repo, &options).unwrap();
+        assert_eq!(steps.len(), 4);
+        assert_eq!(steps[2].to_string(), "Push `bump-deps` to origin");
+        assert_eq!(steps[3].to_string(), "`POST /repos/owner/api/pulls`");
+
+        options.create_only = true;
+        assert_eq!(pr_steps(&Config::new(), &repo, &options).unwrap().len(), 2);
+    }
+}
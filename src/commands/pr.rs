@@ -1,29 +1,44 @@
 //! Pull request command implementation
 
 use super::{Command, CommandContext};
-use crate::github::{self, PrOptions};
+use crate::config::{Config, Repository};
+use crate::github::{self, GitHubClient, PrOptions, WaitFor, rollout};
+use crate::preflight;
+use crate::scheduler::HostLimiter;
+use crate::state::{self, ResumeMode, StateTracker};
 use anyhow::Result;
 use async_trait::async_trait;
 use colored::*;
+use std::time::Duration;
 
 /// Pull request command for creating PRs with changes
 pub struct PrCommand {
     pub title: String,
     pub body: String,
     pub branch_name: Option<String>,
+    /// Template for the branch name, overriding the repositories' templates
+    pub branch_template: Option<String>,
     pub base_branch: Option<String>,
     pub commit_msg: Option<String>,
     pub draft: bool,
     pub token: String,
     pub create_only: bool,
+    /// Which repositories to process based on the previous invocation's state
+    pub resume: ResumeMode,
+    /// Check that remotes are reachable before processing
+    pub preflight: bool,
+    /// Open pull requests this many repositories at a time
+    pub batch_size: Option<usize>,
+    /// What a batch's pull requests must reach before the next batch starts
+    pub wait_for: Option<WaitFor>,
+    /// How long to wait for a batch, in minutes
+    pub wait_timeout: u64,
 }
 
 #[async_trait]
 impl Command for PrCommand {
     async fn execute(&self, context: &CommandContext) -> Result<()> {
-        let repositories = context
-            .config
-            .filter_repositories(context.tag.as_deref(), context.repos.as_deref());
+        let repositories = context.repositories();
 
         if repositories.is_empty() {
             let filter_desc = match (&context.tag, &context.repos) {
@@ -39,6 +54,27 @@ impl Command for PrCommand {
             return Ok(());
         }
 
+        let (state, repositories) = StateTracker::start(
+            state::state_file(context.config.config_dir.as_deref()),
+            "pr",
+            &self.title,
+            repositories,
+            self.resume,
+        )?;
+        if repositories.is_empty() {
+            println!(
+                "{}",
+                "Nothing left to process from the previous state".yellow()
+            );
+            return Ok(());
+        }
+
+        let repositories = if self.preflight {
+            preflight::check_remotes(&context.config, repositories).await?
+        } else {
+            repositories
+        };
+
         println!(
             "{}",
             format!(
@@ -57,35 +93,112 @@ impl Command for PrCommand {
             draft: self.draft,
             token: self.token.clone(),
             create_only: self.create_only,
+            branch_template: self.branch_template.clone(),
+            campaign: None,
         };
 
-        if context.parallel {
+        let batch_size = self.batch_size.unwrap_or(repositories.len()).max(1);
+        let batches: Vec<Vec<Repository>> = repositories
+            .chunks(batch_size)
+            .map(<[Repository]>::to_vec)
+            .collect();
+        let total = batches.len();
+
+        for (index, batch) in batches.into_iter().enumerate() {
+            if total > 1 {
+                println!(
+                    "{}",
+                    format!("Batch {}/{total}: {} repositories", index + 1, batch.len()).green()
+                );
+            }
+            let urls = self
+                .process_batch(
+                    &context.config,
+                    batch,
+                    &pr_options,
+                    context.parallel,
+                    &state,
+                )
+                .await;
+
+            if index + 1 < total
+                && !urls.is_empty()
+                && let Some(condition) = self.wait_for
+            {
+                let client = GitHubClient::with_settings(
+                    Some(self.token.clone()),
+                    &context.config.github_settings(),
+                )?;
+                let timeout = Duration::from_secs(self.wait_timeout * 60);
+                if let Err(e) = rollout::wait_for_batch(&client, &urls, condition, timeout).await {
+                    anyhow::bail!(
+                        "Stopping the rollout: {e}; continue with --resume once it is resolved"
+                    );
+                }
+            }
+        }
+
+        println!("{}", "Done processing pull requests".green());
+        Ok(())
+    }
+}
+
+impl PrCommand {
+    /// Open pull requests in a batch of repositories, returning their URLs
+    async fn process_batch(
+        &self,
+        config: &Config,
+        repositories: Vec<Repository>,
+        pr_options: &PrOptions,
+        parallel: bool,
+        state: &StateTracker,
+    ) -> Vec<String> {
+        let mut urls = Vec::new();
+
+        if parallel {
+            let limiter = HostLimiter::new(config, &repositories);
             let tasks: Vec<_> = repositories
                 .into_iter()
                 .map(|repo| {
                     let pr_options = pr_options.clone();
-                    async move { github::create_pull_request(&repo, &pr_options).await }
+                    let limiter = &limiter;
+                    async move {
+                        let result = limiter
+                            .run(&repo, github::open_pull_request(config, &repo, &pr_options))
+                            .await;
+                        (repo.name, result)
+                    }
                 })
                 .collect();
 
-            for task in tasks {
-                if let Err(e) = task.await {
-                    eprintln!("{}", format!("Error: {e}").red());
+            for (name, result) in futures::future::join_all(tasks).await {
+                state.record(&name, result.is_ok());
+                match result {
+                    Ok(url) => urls.extend(url.filter(|url| is_pull_request(url))),
+                    Err(e) => eprintln!("{}", format!("Error: {e}").red()),
                 }
             }
         } else {
             for repo in repositories {
-                if let Err(e) = github::create_pull_request(&repo, &pr_options).await {
-                    eprintln!(
+                let result = github::open_pull_request(config, &repo, pr_options).await;
+                state.record(&repo.name, result.is_ok());
+                match result {
+                    Ok(url) => urls.extend(url.filter(|url| is_pull_request(url))),
+                    Err(e) => eprintln!(
                         "{} | {}",
                         repo.name.cyan().bold(),
                         format!("Error: {e}").red()
-                    );
+                    ),
                 }
             }
         }
 
-        println!("{}", "Done processing pull requests".green());
-        Ok(())
+        urls
     }
 }
+
+/// Whether a URL is a GitHub pull request a rollout can wait for, rather
+/// than e.g. a Gerrit change
+fn is_pull_request(url: &str) -> bool {
+    rollout::parse_pull_request_url(url).is_some()
+}
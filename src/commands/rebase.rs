@@ -0,0 +1,164 @@
+//! Rebase command implementation
+
+use super::{Command, CommandContext};
+use crate::config::{Config, Repository};
+use crate::git::{self, Logger};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use colored::*;
+use glob::Pattern;
+use std::path::Path;
+
+/// Rebase command for refreshing local branches onto an updated base
+pub struct RebaseCommand {
+    /// Base to rebase onto; resolved locally, then as `origin/<onto>`
+    pub onto: String,
+    /// Glob matching the local branches to rebase
+    pub branch: String,
+    /// Fetch from origin before rebasing
+    pub fetch: bool,
+}
+
+/// Outcome of rebasing the matching branches of one repository
+#[derive(Default)]
+struct RebaseSummary {
+    rebased: Vec<String>,
+    conflicted: Vec<String>,
+}
+
+#[async_trait]
+impl Command for RebaseCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let pattern = Pattern::new(&self.branch)
+            .with_context(|| format!("Invalid branch pattern '{}'", self.branch))?;
+
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            format!(
+                "Rebasing '{}' onto '{}' in {} repositories...",
+                self.branch,
+                self.onto,
+                repositories.len()
+            )
+            .green()
+        );
+
+        let logger = Logger;
+        let mut rebased = 0;
+        let mut conflicted = 0;
+        let mut failed = 0;
+
+        for repo in &repositories {
+            match self.rebase_repository(&context.config, repo, &pattern) {
+                Ok(summary) => {
+                    if summary.rebased.is_empty() && summary.conflicted.is_empty() {
+                        logger.info(repo, "No matching branches");
+                    }
+                    for branch in &summary.rebased {
+                        logger.success(repo, &format!("Rebased {branch}"));
+                    }
+                    for branch in &summary.conflicted {
+                        eprintln!(
+                            "{} | {}",
+                            repo.name.cyan().bold(),
+                            format!("Conflicts rebasing {branch}, aborted").red()
+                        );
+                    }
+                    rebased += summary.rebased.len();
+                    conflicted += summary.conflicted.len();
+                }
+                Err(e) => {
+                    failed += 1;
+                    eprintln!(
+                        "{} | {}",
+                        repo.name.cyan().bold(),
+                        format!("Error: {e}").red()
+                    );
+                }
+            }
+        }
+
+        println!(
+            "{}",
+            format!("{rebased} branches rebased, {conflicted} with conflicts").green()
+        );
+
+        if failed > 0 {
+            anyhow::bail!("{} repositories failed", failed);
+        }
+        if conflicted > 0 {
+            anyhow::bail!("{} branches could not be rebased cleanly", conflicted);
+        }
+        Ok(())
+    }
+}
+
+impl RebaseCommand {
+    fn rebase_repository(
+        &self,
+        config: &Config,
+        repo: &Repository,
+        pattern: &Pattern,
+    ) -> Result<RebaseSummary> {
+        let repo_dir = repo.get_target_dir();
+        if !Path::new(&repo_dir).exists() {
+            anyhow::bail!("Repository directory does not exist: {}", repo_dir);
+        }
+
+        let branches: Vec<String> = git::local_branches(&repo_dir)?
+            .into_iter()
+            .filter(|branch| pattern.matches(branch) && *branch != self.onto)
+            .collect();
+        if branches.is_empty() {
+            return Ok(RebaseSummary::default());
+        }
+
+        if git::has_changes(&repo_dir)? {
+            anyhow::bail!("Repository has uncommitted changes");
+        }
+        if self.fetch {
+            git::fetch(repo, &repo.host_settings(config), false)?;
+        }
+        let Some(onto) = git::resolve_ref(&repo_dir, &self.onto) else {
+            anyhow::bail!("Base '{}' not found", self.onto);
+        };
+
+        // Rebasing checks each branch out, so return to where we started
+        let original = match git::current_branch(&repo_dir)? {
+            Some(branch) => branch,
+            None => git::head_sha(&repo_dir)?,
+        };
+
+        let mut summary = RebaseSummary::default();
+        let mut result = Ok(());
+        for branch in branches {
+            match git::rebase(&repo_dir, &onto, &branch) {
+                Ok(true) => summary.rebased.push(branch),
+                Ok(false) => summary.conflicted.push(branch),
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            }
+        }
+
+        git::checkout(&repo_dir, &original)?;
+        result.map(|_| summary)
+    }
+}
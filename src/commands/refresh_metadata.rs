@@ -0,0 +1,140 @@
+//! Refresh metadata command implementation
+
+use super::{Command, CommandContext};
+use crate::config::{Repository, Visibility, hosts};
+use crate::github::{GitHubClient, GitHubRepo};
+use crate::scheduler;
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+
+/// Refresh metadata command for recording GitHub repository status in the config
+pub struct RefreshMetadataCommand {
+    pub token: Option<String>,
+    /// Config file to update
+    pub config_path: String,
+}
+
+#[async_trait]
+impl Command for RefreshMetadataCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        // Archived repositories are included so their status can be cleared
+        let repositories = context.repositories_with_archived();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            format!(
+                "Refreshing metadata for {} repositories...",
+                repositories.len()
+            )
+            .green()
+        );
+
+        let client =
+            GitHubClient::with_settings(self.token.clone(), &context.config.github_settings())?;
+        let tasks: Vec<_> = repositories
+            .iter()
+            .map(|repo| {
+                let client = &client;
+                scheduler::limited(async move { (repo, fetch_metadata(client, repo).await) })
+            })
+            .collect();
+
+        let results = if context.parallel {
+            futures::future::join_all(tasks).await
+        } else {
+            let mut results = Vec::new();
+            for task in tasks {
+                results.push(task.await);
+            }
+            results
+        };
+
+        let mut config = context.config.clone();
+        let mut updated = 0;
+        for (repo, result) in results {
+            let metadata = match result {
+                Ok(metadata) => metadata,
+                Err(e) => {
+                    eprintln!(
+                        "{} | {}",
+                        repo.name.cyan().bold(),
+                        format!("Error: {e}").red()
+                    );
+                    continue;
+                }
+            };
+
+            let status = if metadata.disabled {
+                "disabled".red()
+            } else if metadata.archived {
+                "archived".yellow()
+            } else {
+                "active".green()
+            };
+            println!(
+                "{} | {} (default branch: {})",
+                repo.name.cyan().bold(),
+                status,
+                metadata.default_branch
+            );
+
+            if let Some(entry) = config.get_repository_mut(&repo.name) {
+                entry.archived = metadata.archived || metadata.disabled;
+                entry.visibility = Some(visibility(&metadata));
+                // GitHub redirects transferred repositories, so the owner in
+                // the URL may be stale
+                let owner = metadata.full_name.split_once('/').map(|(owner, _)| owner);
+                entry.owner = owner
+                    .filter(|owner| {
+                        !hosts::url_owner(&entry.url).is_some_and(|o| o.eq_ignore_ascii_case(owner))
+                    })
+                    .map(str::to_string);
+                entry.default_branch = Some(metadata.default_branch);
+                updated += 1;
+            }
+        }
+
+        if updated == 0 {
+            println!("{}", "No metadata was updated".yellow());
+            return Ok(());
+        }
+
+        config.save(&self.config_path)?;
+        println!(
+            "{}",
+            format!("Updated metadata in {}", self.config_path).green()
+        );
+        Ok(())
+    }
+}
+
+/// Visibility of a repository; only GitHub Enterprise reports `internal`
+fn visibility(metadata: &GitHubRepo) -> Visibility {
+    match metadata.visibility.as_deref() {
+        Some("public") => Visibility::Public,
+        Some("internal") => Visibility::Internal,
+        Some(_) => Visibility::Private,
+        None if metadata.private => Visibility::Private,
+        None => Visibility::Public,
+    }
+}
+
+async fn fetch_metadata(client: &GitHubClient, repo: &Repository) -> Result<GitHubRepo> {
+    let (owner, name) = client.parse_github_url(&repo.url)?;
+    client.get_repository(&owner, &name).await
+}
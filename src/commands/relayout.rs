@@ -0,0 +1,164 @@
+//! Relayout command implementation
+
+use super::move_repos::move_repository;
+use super::{Command, CommandContext};
+use crate::config::{Layout, Repository};
+use crate::git;
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+use std::fs;
+use std::path::Path;
+
+/// Relayout command for moving clones to where the configured layout expects them
+pub struct RelayoutCommand {
+    /// Only show the planned moves
+    pub dry_run: bool,
+}
+
+#[async_trait]
+impl Command for RelayoutCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        // Whether a clone exists is what relayout works out for itself, by
+        // looking where other layouts would have put it
+        if context.presence.is_some() {
+            anyhow::bail!("--cloned-only and --missing-only can't be used with relayout");
+        }
+
+        // Repositories with an explicit path don't depend on the layout
+        let repositories: Vec<_> = context
+            .repositories_with_archived()
+            .into_iter()
+            .filter(|repo| repo.path.is_none())
+            .collect();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            format!(
+                "Arranging {} repositories in the {} layout...",
+                repositories.len(),
+                context.config.layout
+            )
+            .green()
+        );
+
+        let mut moved = 0;
+        let mut failed = 0;
+        for repo in &repositories {
+            let target = repo.get_target_dir();
+            if Path::new(&target).exists() {
+                continue;
+            }
+
+            let Some(source) = previous_dir(repo, context.config.layout) else {
+                continue;
+            };
+
+            if self.dry_run {
+                println!("{} | {source} -> {target}", repo.name.cyan().bold());
+                continue;
+            }
+
+            match move_repository(repo, Path::new(&source), Path::new(&target)) {
+                Ok(()) => moved += 1,
+                Err(e) => {
+                    failed += 1;
+                    eprintln!(
+                        "{} | {}",
+                        repo.name.cyan().bold(),
+                        format!("Error: {e}").red()
+                    );
+                }
+            }
+        }
+
+        if self.dry_run {
+            println!("{}", "Dry run, nothing moved".yellow());
+            return Ok(());
+        }
+
+        if failed > 0 {
+            anyhow::bail!("{} repositories failed to move", failed);
+        }
+
+        println!("{}", format!("Moved {moved} repositories").green());
+        Ok(())
+    }
+}
+
+/// Find a clone left where another layout would have put it, or, under
+/// `by-tag`, in the directory of a tag that used to be the primary one
+fn previous_dir(repo: &Repository, layout: Layout) -> Option<String> {
+    if let Some(dir) = Layout::ALL
+        .iter()
+        .filter(|other| **other != layout)
+        .map(|other| repo.default_dir(*other))
+        .find(|dir| Path::new(dir).exists())
+    {
+        return Some(dir);
+    }
+    if layout.is_flat() {
+        return None;
+    }
+
+    // Any directory of the same name could be another repository, so only
+    // take one whose origin is this repository
+    let flat = repo.default_dir(Layout::Flat);
+    let workspace = Path::new(&flat).parent()?;
+    fs::read_dir(workspace)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path().join(&repo.name))
+        .filter(|dir| dir.join(".git").exists())
+        .map(|dir| dir.to_string_lossy().to_string())
+        .find(|dir| git::remote_url(dir, "origin").is_ok_and(|url| url == repo.url))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::git;
+
+    #[test]
+    fn test_previous_dir_after_primary_tag_change() {
+        let workspace =
+            std::env::temp_dir().join(format!("rrepos-relayout-test-{}", std::process::id()));
+        let url = "https://github.com/owner/api.git";
+        for (dir, origin) in [
+            ("java/api", url),
+            ("other/api", "https://github.com/owner/fork.git"),
+        ] {
+            let dir = workspace.join(dir);
+            fs::create_dir_all(&dir).unwrap();
+            git(&dir, &["init", "-q"]);
+            git(&dir, &["remote", "add", "origin", origin]);
+        }
+
+        let mut repo = Repository::new("api".to_string(), url.to_string());
+        repo.tags = vec!["kotlin".to_string(), "java".to_string()];
+        repo.config_dir = Some(workspace.clone());
+
+        assert_eq!(
+            previous_dir(&repo, Layout::ByTag),
+            Some(workspace.join("java/api").to_string_lossy().to_string())
+        );
+        repo.url = "https://github.com/owner/unknown.git".to_string();
+        assert_eq!(previous_dir(&repo, Layout::ByTag), None);
+
+        fs::remove_dir_all(&workspace).unwrap();
+    }
+}
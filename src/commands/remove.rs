@@ -1,20 +1,44 @@
 //! Remove command implementation
 
 use super::{Command, CommandContext};
+use crate::config::Repository;
+use crate::git;
+use crate::scheduler;
+use crate::util::{approve, move_dir};
 use anyhow::Result;
 use async_trait::async_trait;
+use chrono::Local;
 use colored::*;
 use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Trash directory used when the config doesn't set `trash_dir`
+pub const DEFAULT_TRASH_DIR: &str = ".rrepos/trash";
 
 /// Remove command for deleting cloned repositories
-pub struct RemoveCommand;
+#[derive(Default)]
+pub struct RemoveCommand {
+    /// Remove repositories even with uncommitted or unpushed work
+    pub force: bool,
+    /// Only list what would be removed
+    pub dry_run: bool,
+    /// Skip the confirmation prompt
+    pub yes: bool,
+    /// Move repositories to the trash directory instead of deleting them
+    pub trash: bool,
+    /// Permanently delete everything in the trash directory
+    pub purge_trash: bool,
+}
 
 #[async_trait]
 impl Command for RemoveCommand {
     async fn execute(&self, context: &CommandContext) -> Result<()> {
-        let repositories = context
-            .config
-            .filter_repositories(context.tag.as_deref(), context.repos.as_deref());
+        if self.purge_trash {
+            return self.purge(context, &trash_dir(context));
+        }
+
+        // Clones of archived repositories take up space as well
+        let repositories = context.repositories_with_archived();
 
         if repositories.is_empty() {
             let filter_desc = match (&context.tag, &context.repos) {
@@ -30,27 +54,56 @@ impl Command for RemoveCommand {
             return Ok(());
         }
 
+        let repositories = self.removable(repositories);
+        if repositories.is_empty() {
+            println!("{}", "Nothing to remove".yellow());
+            return Ok(());
+        }
+
+        let action = if self.trash {
+            "moved to the trash"
+        } else {
+            "removed"
+        };
+        println!(
+            "{}",
+            format!("The following directories will be {action}:").bold()
+        );
+        for repo in &repositories {
+            println!("  {} ({})", repo.get_target_dir(), repo.name.cyan());
+        }
+
+        if self.dry_run {
+            println!("{}", "Dry run, nothing removed".yellow());
+            return Ok(());
+        }
+
+        if !self.yes
+            && !approve(
+                &context.config.policy,
+                &format!("Remove {} repositories?", repositories.len()),
+                repositories.len(),
+                self.force,
+                true,
+            )?
+        {
+            println!("{}", "Aborted".yellow());
+            return Ok(());
+        }
+
         println!(
             "{}",
             format!("Removing {} repositories...", repositories.len()).green()
         );
 
+        let trash_dir = self.trash.then(|| trash_dir(context));
+
         if context.parallel {
             let tasks: Vec<_> = repositories
                 .into_iter()
                 .map(|repo| {
-                    tokio::spawn(async move {
-                        let target_dir = repo.get_target_dir();
-                        tokio::task::spawn_blocking(move || {
-                            if std::path::Path::new(&target_dir).exists() {
-                                fs::remove_dir_all(&target_dir).map_err(anyhow::Error::from)
-                            } else {
-                                println!("{} | Directory does not exist", repo.name.cyan().bold());
-                                Ok(())
-                            }
-                        })
-                        .await?
-                    })
+                    let trash_dir = trash_dir.clone();
+                    scheduler::spawn_blocking(move || remove_directory(&repo, trash_dir.as_deref()))
                 })
                 .collect();
 
@@ -61,24 +114,321 @@ impl Command for RemoveCommand {
             }
         } else {
             for repo in repositories {
+                if let Err(e) = remove_directory(&repo, trash_dir.as_deref()) {
+                    eprintln!(
+                        "{} | {}",
+                        repo.name.cyan().bold(),
+                        format!("Error: {e}").red()
+                    );
+                }
+            }
+        }
+
+        println!("{}", "Done removing repositories".green());
+        Ok(())
+    }
+}
+
+impl RemoveCommand {
+    /// Permanently delete the contents of the trash directory
+    fn purge(&self, context: &CommandContext, trash_dir: &Path) -> Result<()> {
+        let entries: Vec<PathBuf> = match fs::read_dir(trash_dir) {
+            Ok(entries) => entries.filter_map(|e| e.ok()).map(|e| e.path()).collect(),
+            Err(_) => Vec::new(),
+        };
+        if entries.is_empty() {
+            println!("{}", "Trash is empty".yellow());
+            return Ok(());
+        }
+
+        println!("{}", "The following directories will be purged:".bold());
+        for entry in &entries {
+            println!("  {}", entry.display());
+        }
+
+        if self.dry_run {
+            println!("{}", "Dry run, nothing removed".yellow());
+            return Ok(());
+        }
+        // Purging can't be undone, so the policy treats it like --force
+        if !self.yes
+            && !approve(
+                &context.config.policy,
+                &format!("Permanently delete {} entries?", entries.len()),
+                entries.len(),
+                true,
+                true,
+            )?
+        {
+            println!("{}", "Aborted".yellow());
+            return Ok(());
+        }
+
+        for entry in &entries {
+            if entry.is_dir() {
+                fs::remove_dir_all(entry)?;
+            } else {
+                fs::remove_file(entry)?;
+            }
+        }
+
+        println!("{}", format!("Purged {} entries", entries.len()).green());
+        Ok(())
+    }
+
+    /// Keep repositories whose directory exists and that are safe to delete,
+    /// reporting the ones that are skipped
+    fn removable(&self, repositories: Vec<Repository>) -> Vec<Repository> {
+        repositories
+            .into_iter()
+            .filter(|repo| {
                 let target_dir = repo.get_target_dir();
-                if std::path::Path::new(&target_dir).exists() {
-                    if let Err(e) = fs::remove_dir_all(&target_dir) {
+                if !std::path::Path::new(&target_dir).exists() {
+                    println!("{} | Directory does not exist", repo.name.cyan().bold());
+                    return false;
+                }
+                if self.force {
+                    return true;
+                }
+
+                match unsaved_work(&target_dir) {
+                    Ok(None) => true,
+                    Ok(Some(reason)) => {
+                        eprintln!(
+                            "{} | {}",
+                            repo.name.cyan().bold(),
+                            format!("{reason}, skipping (use --force to remove anyway)").yellow()
+                        );
+                        false
+                    }
+                    Err(e) => {
                         eprintln!(
                             "{} | {}",
                             repo.name.cyan().bold(),
                             format!("Error: {e}").red()
                         );
-                    } else {
-                        println!("{} | {}", repo.name.cyan().bold(), "Removed".green());
+                        false
                     }
-                } else {
-                    println!("{} | Directory does not exist", repo.name.cyan().bold());
                 }
-            }
+            })
+            .collect()
+    }
+}
+
+/// Describe work in a repository that would be lost by deleting it
+pub(crate) fn unsaved_work(repo_dir: &str) -> Result<Option<String>> {
+    if git::has_changes(repo_dir)? {
+        return Ok(Some("Working tree has uncommitted changes".to_string()));
+    }
+
+    let unpushed = git::unpushed_commits(repo_dir)?;
+    if unpushed > 0 {
+        return Ok(Some(format!("{unpushed} commits not pushed to any remote")));
+    }
+
+    Ok(None)
+}
+
+/// Trash directory from the config, defaulting to `.rrepos/trash`; relative
+/// paths are resolved against the config file's directory, like clone paths
+pub(crate) fn trash_dir(context: &CommandContext) -> PathBuf {
+    let trash_dir = Path::new(
+        context
+            .config
+            .trash_dir
+            .as_deref()
+            .unwrap_or(DEFAULT_TRASH_DIR),
+    );
+    match &context.config.config_dir {
+        Some(config_dir) => config_dir.join(trash_dir),
+        None => trash_dir.to_path_buf(),
+    }
+}
+
+/// Delete a repository directory, or move it into `trash_dir` under a
+/// timestamped name when given
+fn remove_directory(repo: &Repository, trash_dir: Option<&Path>) -> Result<()> {
+    let target_dir = repo.get_target_dir();
+
+    let Some(trash_dir) = trash_dir else {
+        fs::remove_dir_all(&target_dir)?;
+        println!("{} | {}", repo.name.cyan().bold(), "Removed".green());
+        return Ok(());
+    };
+
+    fs::create_dir_all(trash_dir)?;
+    let destination = trash_dir.join(format!(
+        "{}-{}",
+        repo.name,
+        Local::now().format("%Y%m%d_%H%M%S")
+    ));
+    move_dir(Path::new(&target_dir), &destination)?;
+    println!(
+        "{} | {}",
+        repo.name.cyan().bold(),
+        format!("Moved to {}", destination.display()).green()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, Visibility};
+    use crate::test_util::git;
+
+    #[tokio::test]
+    async fn test_remove_honours_owner_and_visibility() {
+        let dir = std::env::temp_dir().join(format!("rrepos-remove-test-{}", std::process::id()));
+        let mut config = Config::new();
+        for (name, owner, visibility) in [
+            ("acme-public", "acme", Visibility::Public),
+            ("acme-private", "acme", Visibility::Private),
+            ("other-private", "other", Visibility::Private),
+        ] {
+            let mut repo = Repository::new(
+                name.to_string(),
+                format!("git@github.com:{owner}/{name}.git"),
+            );
+            repo.visibility = Some(visibility);
+            repo.path = Some(dir.join(name).to_string_lossy().to_string());
+            fs::create_dir_all(dir.join(name)).unwrap();
+            config.repositories.push(repo);
         }
 
-        println!("{}", "Done removing repositories".green());
-        Ok(())
+        let context = CommandContext {
+            config,
+            tag: None,
+            parallel: false,
+            repos: None,
+            include_archived: false,
+            presence: None,
+            visibility: Some(Visibility::Public),
+            owner: Some("acme".to_string()),
+        };
+        let command = RemoveCommand {
+            force: true,
+            yes: true,
+            ..Default::default()
+        };
+        command.execute(&context).await.unwrap();
+
+        let remaining = [
+            dir.join("acme-public").exists(),
+            dir.join("acme-private").exists(),
+            dir.join("other-private").exists(),
+        ];
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(remaining, [false, true, true]);
+    }
+
+    #[tokio::test]
+    async fn test_remove_keeps_unsaved_work() {
+        let dir =
+            std::env::temp_dir().join(format!("rrepos-remove-safety-test-{}", std::process::id()));
+        let origin = dir.join("origin");
+        fs::create_dir_all(&origin).unwrap();
+        git(&origin, &["init", "--quiet"]);
+        git(
+            &origin,
+            &["commit", "--quiet", "--allow-empty", "-m", "first"],
+        );
+
+        let mut config = Config::new();
+        for name in ["pushed", "dirty", "unpushed"] {
+            git(&dir, &["clone", "--quiet", "origin", name]);
+            let mut repo =
+                Repository::new(name.to_string(), "git@github.com:org/x.git".to_string());
+            repo.path = Some(dir.join(name).to_string_lossy().to_string());
+            config.repositories.push(repo);
+        }
+        fs::write(dir.join("dirty/notes.txt"), "draft\n").unwrap();
+        git(
+            &dir.join("unpushed"),
+            &["commit", "--quiet", "--allow-empty", "-m", "local"],
+        );
+
+        let unsaved = ["pushed", "dirty", "unpushed"]
+            .map(|name| unsaved_work(&dir.join(name).to_string_lossy()).unwrap());
+        let context = CommandContext {
+            config,
+            tag: None,
+            parallel: false,
+            repos: None,
+            include_archived: false,
+            presence: None,
+            visibility: None,
+            owner: None,
+        };
+        let remaining = |command: RemoveCommand| {
+            let context = &context;
+            let dir = &dir;
+            async move {
+                command.execute(context).await.unwrap();
+                ["pushed", "dirty", "unpushed"].map(|name| dir.join(name).exists())
+            }
+        };
+
+        let after_dry_run = remaining(RemoveCommand {
+            dry_run: true,
+            ..Default::default()
+        })
+        .await;
+        let after_remove = remaining(RemoveCommand {
+            yes: true,
+            ..Default::default()
+        })
+        .await;
+        let after_force = remaining(RemoveCommand {
+            yes: true,
+            force: true,
+            ..Default::default()
+        })
+        .await;
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            unsaved,
+            [
+                None,
+                Some("Working tree has uncommitted changes".to_string()),
+                Some("1 commits not pushed to any remote".to_string())
+            ]
+        );
+        assert_eq!(after_dry_run, [true, true, true]);
+        assert_eq!(after_remove, [false, true, true]);
+        assert_eq!(after_force, [false, false, false]);
+    }
+
+    #[test]
+    fn test_trash_dir_is_relative_to_config() {
+        let dir = std::env::temp_dir().join(format!("rrepos-trash-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.yaml");
+        fs::write(&path, "repositories: []\ntrash_dir: old-clones\n").unwrap();
+        let configured = Config::load(&path.to_string_lossy()).unwrap();
+        fs::write(&path, "repositories: []\n").unwrap();
+        let defaulted = Config::load(&path.to_string_lossy()).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let trash_dir = |config: Config| {
+            trash_dir(&CommandContext {
+                config,
+                tag: None,
+                parallel: false,
+                repos: None,
+                include_archived: false,
+                presence: None,
+                visibility: None,
+                owner: None,
+            })
+        };
+        let mut absolute = Config::new();
+        absolute.trash_dir = Some("/var/tmp/trash".to_string());
+        absolute.config_dir = Some(dir.clone());
+
+        assert_eq!(trash_dir(configured), dir.join("old-clones"));
+        assert_eq!(trash_dir(defaulted), dir.join(DEFAULT_TRASH_DIR));
+        assert_eq!(trash_dir(absolute), PathBuf::from("/var/tmp/trash"));
     }
 }
@@ -0,0 +1,144 @@
+//! Rename command implementation
+
+use super::move_repos::move_repository;
+use super::{Command, CommandContext};
+use crate::config::Provider;
+use crate::git::{self, Logger};
+use crate::github::GitHubClient;
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+use std::path::{Path, PathBuf};
+
+/// Rename command updating a repository's config entry, clone directory and
+/// remote URL together, and optionally the repository on GitHub
+pub struct RenameCommand {
+    pub old_name: String,
+    pub new_name: String,
+    /// Also rename the repository on GitHub
+    pub remote: bool,
+    pub token: Option<String>,
+    /// Config file to update
+    pub config_path: String,
+}
+
+#[async_trait]
+impl Command for RenameCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let logger = Logger;
+        let mut config = context.config.clone();
+
+        let repo = config
+            .get_repository(&self.old_name)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Repository '{}' not found", self.old_name))?;
+        if config.get_repository(&self.new_name).is_some() {
+            anyhow::bail!("Repository '{}' already exists", self.new_name);
+        }
+
+        let mut renamed = repo.clone();
+        renamed.name = self.new_name.clone();
+        // An explicit path named after the repository follows the rename
+        if let Some(path) = &repo.path
+            && Path::new(path).file_name() == Some(repo.name.as_ref())
+        {
+            renamed.path = Some(
+                Path::new(path)
+                    .with_file_name(&self.new_name)
+                    .to_string_lossy()
+                    .to_string(),
+            );
+        }
+        renamed.validate()?;
+
+        // Check the local move before touching the remote, so a clash
+        // doesn't leave the two out of step
+        let source = PathBuf::from(repo.get_target_dir());
+        let target = PathBuf::from(renamed.get_target_dir());
+        if source != target && source.exists() && target.exists() {
+            anyhow::bail!("Target directory already exists: {}", target.display());
+        }
+
+        if self.remote {
+            if repo.provider != Provider::GitHub {
+                anyhow::bail!("--remote is only supported for GitHub repositories");
+            }
+            let client =
+                GitHubClient::with_settings(self.token.clone(), &context.config.github_settings())?;
+            let (owner, name) = client.parse_github_url(&repo.url)?;
+            client
+                .rename_repository(&owner, &name, &self.new_name)
+                .await?;
+            renamed.url = renamed_url(&repo.url, &name, &self.new_name);
+            logger.success(
+                &repo,
+                &format!(
+                    "Renamed {owner}/{name} to {owner}/{} on GitHub",
+                    self.new_name
+                ),
+            );
+        }
+
+        if source != target {
+            move_repository(&renamed, &source, &target)?;
+        }
+        if renamed.url != repo.url && target.join(".git").exists() {
+            git::set_remote_url(&target.to_string_lossy(), "origin", &renamed.url)?;
+        }
+
+        for entry in &mut config.repositories {
+            for dependency in &mut entry.depends_on {
+                if *dependency == self.old_name {
+                    *dependency = self.new_name.clone();
+                }
+            }
+        }
+        if let Some(entry) = config.get_repository_mut(&self.old_name) {
+            *entry = renamed;
+        }
+        config.save(&self.config_path)?;
+
+        println!(
+            "{}",
+            format!(
+                "Renamed {} to {} in {}",
+                self.old_name, self.new_name, self.config_path
+            )
+            .green()
+        );
+        Ok(())
+    }
+}
+
+/// Point `url` at the repository's new name, keeping a `.git` suffix
+fn renamed_url(url: &str, name: &str, new_name: &str) -> String {
+    let (base, suffix) = match url.strip_suffix(".git") {
+        Some(base) => (base, ".git"),
+        None => (url, ""),
+    };
+    match base.strip_suffix(name) {
+        Some(prefix) if prefix.ends_with(['/', ':']) => format!("{prefix}{new_name}{suffix}"),
+        _ => url.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_renamed_url() {
+        assert_eq!(
+            renamed_url("git@github.com:org/api.git", "api", "api-gateway"),
+            "git@github.com:org/api-gateway.git"
+        );
+        assert_eq!(
+            renamed_url("https://github.com/org/api", "api", "api-gateway"),
+            "https://github.com/org/api-gateway"
+        );
+        assert_eq!(
+            renamed_url("https://github.com/org/my-api", "api", "web"),
+            "https://github.com/org/my-api"
+        );
+    }
+}
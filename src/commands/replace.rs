@@ -0,0 +1,239 @@
+//! Replace command implementation
+
+use super::{Command, CommandContext};
+use crate::config::{Config, Repository};
+use crate::git::{self, Logger};
+use crate::github::{self, PrOptions};
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+use glob::Pattern;
+use regex::Regex;
+use std::path::Path;
+
+/// Replace command for regex find-and-replace across repositories
+pub struct ReplaceCommand {
+    pub pattern: String,
+    pub replacement: String,
+    pub glob: Option<String>,
+    pub dry_run: bool,
+    /// When set, changed repositories go through the pull request flow
+    pub pr_options: Option<PrOptions>,
+}
+
+#[async_trait]
+impl Command for ReplaceCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let regex = Regex::new(&self.pattern)?;
+        let glob = self.glob.as_deref().map(Pattern::new).transpose()?;
+
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            format!(
+                "Replacing '{}' in {} repositories...",
+                self.pattern,
+                repositories.len()
+            )
+            .green()
+        );
+
+        for repo in repositories {
+            if let Err(e) = self
+                .process_repository(&context.config, &repo, &regex, glob.as_ref())
+                .await
+            {
+                eprintln!(
+                    "{} | {}",
+                    repo.name.cyan().bold(),
+                    format!("Error: {e}").red()
+                );
+            }
+        }
+
+        println!("{}", "Done replacing".green());
+        Ok(())
+    }
+}
+
+impl ReplaceCommand {
+    async fn process_repository(
+        &self,
+        config: &Config,
+        repo: &Repository,
+        regex: &Regex,
+        glob: Option<&Pattern>,
+    ) -> Result<()> {
+        let logger = Logger;
+        let repo_dir = repo.get_target_dir();
+
+        if !Path::new(&repo_dir).exists() {
+            anyhow::bail!("Repository directory does not exist: {}", repo_dir);
+        }
+
+        // Only the replacement may end up in the pull request's commit
+        if self.pr_options.is_some() && !self.dry_run && git::has_changes(&repo_dir)? {
+            anyhow::bail!("Working tree has uncommitted changes");
+        }
+
+        if self.dry_run {
+            let replacements =
+                find_replacements(Path::new(&repo_dir), regex, &self.replacement, glob)?;
+            if replacements.is_empty() {
+                logger.info(repo, "No matches");
+            }
+            for replaced in &replacements {
+                logger.info(repo, &format!("Would modify {}", replaced.path));
+                let path = Path::new(&repo_dir).join(&replaced.path);
+                for line in git::diff_content(&path, &replaced.content, &replaced.path)?.lines() {
+                    println!("{} | {line}", repo.name.cyan());
+                }
+            }
+            return Ok(());
+        }
+
+        let changed = replace_in_tree(Path::new(&repo_dir), regex, &self.replacement, glob, false)?;
+
+        if changed.is_empty() {
+            logger.info(repo, "No matches");
+            return Ok(());
+        }
+
+        for line in git::diff(&repo_dir, &changed)?.lines() {
+            println!("{} | {line}", repo.name.cyan());
+        }
+
+        if let Some(pr_options) = &self.pr_options {
+            github::create_pull_request(config, repo, pr_options).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A file whose content a replacement changes
+pub struct Replacement {
+    /// Path relative to the tree's root
+    pub path: String,
+    pub content: String,
+}
+
+/// Apply a regex replacement to all matching tracked files below `root`,
+/// returning the relative paths of files whose content changed
+pub fn replace_in_tree(
+    root: &Path,
+    regex: &Regex,
+    replacement: &str,
+    glob: Option<&Pattern>,
+    dry_run: bool,
+) -> Result<Vec<String>> {
+    let replacements = find_replacements(root, regex, replacement, glob)?;
+    if !dry_run {
+        for replaced in &replacements {
+            std::fs::write(root.join(&replaced.path), &replaced.content)?;
+        }
+    }
+    Ok(replacements.into_iter().map(|r| r.path).collect())
+}
+
+/// Tracked files below `root` a regex replacement would change, with their
+/// new content, sorted by path. Ignored and untracked files such as build
+/// output or vendored dependencies are left alone.
+pub fn find_replacements(
+    root: &Path,
+    regex: &Regex,
+    replacement: &str,
+    glob: Option<&Pattern>,
+) -> Result<Vec<Replacement>> {
+    let mut replacements = Vec::new();
+
+    for file in git::tracked_files(&root.to_string_lossy())? {
+        let relative = Path::new(&file);
+
+        if let Some(glob) = glob {
+            let matches_name = relative
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| glob.matches(name));
+            if !glob.matches_path(relative) && !matches_name {
+                continue;
+            }
+        }
+
+        // Skip binary, non-UTF-8 and deleted files
+        let Ok(content) = std::fs::read_to_string(root.join(relative)) else {
+            continue;
+        };
+
+        let replaced = regex.replace_all(&content, replacement);
+        if replaced == content {
+            continue;
+        }
+
+        replacements.push(Replacement {
+            path: file.clone(),
+            content: replaced.into_owned(),
+        });
+    }
+
+    replacements.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(replacements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::git;
+
+    #[test]
+    fn test_replace_in_tree() {
+        let dir = std::env::temp_dir().join(format!("rrepos-replace-test-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::create_dir_all(dir.join("target")).unwrap();
+        git(&dir, &["init", "--quiet"]);
+        std::fs::write(dir.join("README.md"), "uses lib-v1\n").unwrap();
+        std::fs::write(dir.join("src/app.txt"), "lib-v1 and lib-v1\n").unwrap();
+        std::fs::write(dir.join("src/other.txt"), "nothing here\n").unwrap();
+        std::fs::write(dir.join("logo.bin"), [0xff, 0xfe, b'l']).unwrap();
+        std::fs::write(dir.join(".gitignore"), "target/\n").unwrap();
+        git(&dir, &["add", "."]);
+        // Ignored build output and untracked files are never rewritten
+        std::fs::write(dir.join("target/out.txt"), "lib-v1\n").unwrap();
+        std::fs::write(dir.join("scratch.txt"), "lib-v1\n").unwrap();
+
+        let regex = Regex::new(r"lib-v(\d+)").unwrap();
+        let dry_run = replace_in_tree(&dir, &regex, "lib-v2", None, true).unwrap();
+        let untouched = std::fs::read_to_string(dir.join("README.md")).unwrap();
+
+        let glob = Pattern::new("*.txt").unwrap();
+        let globbed = replace_in_tree(&dir, &regex, "lib-v${1}0", Some(&glob), false).unwrap();
+        let app = std::fs::read_to_string(dir.join("src/app.txt")).unwrap();
+        let readme = std::fs::read_to_string(dir.join("README.md")).unwrap();
+        let build_output = std::fs::read_to_string(dir.join("target/out.txt")).unwrap();
+        let scratch = std::fs::read_to_string(dir.join("scratch.txt")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(dry_run, vec!["README.md", "src/app.txt"]);
+        assert_eq!(untouched, "uses lib-v1\n");
+        assert_eq!(globbed, vec!["src/app.txt"]);
+        assert_eq!(app, "lib-v10 and lib-v10\n");
+        assert_eq!(readme, "uses lib-v1\n");
+        assert_eq!(build_output, "lib-v1\n");
+        assert_eq!(scratch, "lib-v1\n");
+    }
+}
@@ -0,0 +1,173 @@
+//! Reviews command implementation
+
+use super::{Command, CommandContext};
+use crate::github::{GitHubClient, PullRequest};
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use colored::*;
+
+/// Reviews command for listing pull requests awaiting review across repositories
+pub struct ReviewsCommand {
+    /// User login or team slug to list reviews for (the token's user and
+    /// their teams when unset)
+    pub reviewer: Option<String>,
+    /// Also list draft pull requests
+    pub include_drafts: bool,
+    pub token: String,
+}
+
+#[async_trait]
+impl Command for ReviewsCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        let client = GitHubClient::with_settings(
+            Some(self.token.clone()),
+            &context.config.github_settings(),
+        )?;
+
+        let reviewers = match &self.reviewer {
+            Some(reviewer) => vec![reviewer.clone()],
+            None => {
+                let mut reviewers = vec![client.current_user().await?.login];
+                // Listing teams needs the read:org scope; fall back to the user only
+                if let Ok(teams) = client.current_user_teams().await {
+                    reviewers.extend(teams.into_iter().map(|t| t.slug));
+                }
+                reviewers
+            }
+        };
+
+        let tasks: Vec<_> = repositories
+            .iter()
+            .map(|repo| {
+                let client = &client;
+                async move {
+                    let result = match client.parse_github_url(&repo.url) {
+                        Ok((owner, name)) => client.list_pull_requests(&owner, &name).await,
+                        Err(e) => Err(e),
+                    };
+                    (repo, result)
+                }
+            })
+            .collect();
+
+        let mut queue: Vec<(String, PullRequest)> = Vec::new();
+        for (repo, result) in futures::future::join_all(tasks).await {
+            match result {
+                Ok(pulls) => queue.extend(
+                    pulls
+                        .into_iter()
+                        .filter(|pr| self.include_drafts || !pr.draft)
+                        .filter(|pr| awaits_review(pr, &reviewers))
+                        .map(|pr| (repo.name.clone(), pr)),
+                ),
+                Err(e) => eprintln!(
+                    "{} | {}",
+                    repo.name.cyan().bold(),
+                    format!("Error: {e}").red()
+                ),
+            }
+        }
+
+        // Oldest first
+        queue.sort_by(|(_, a), (_, b)| a.created_at.cmp(&b.created_at));
+
+        for (repo, pr) in &queue {
+            println!(
+                "{:>4}  {} #{} {} ({})\n      {}",
+                age(&pr.created_at),
+                repo.cyan().bold(),
+                pr.number,
+                pr.title,
+                pr.user.login.dimmed(),
+                pr.html_url
+            );
+        }
+
+        println!(
+            "{}",
+            format!("{} pull requests awaiting review", queue.len()).green()
+        );
+        Ok(())
+    }
+}
+
+/// Check whether review is requested from any of the given users or teams.
+/// Teams may be given as `org/slug` or just `slug`.
+fn awaits_review(pr: &PullRequest, reviewers: &[String]) -> bool {
+    reviewers.iter().any(|reviewer| {
+        let slug = reviewer.rsplit('/').next().unwrap_or(reviewer);
+        pr.requested_reviewers
+            .iter()
+            .any(|u| u.login.eq_ignore_ascii_case(reviewer))
+            || pr
+                .requested_teams
+                .iter()
+                .any(|t| t.slug.eq_ignore_ascii_case(slug))
+    })
+}
+
+/// Age of a pull request in days, e.g. `12d`
+fn age(created_at: &str) -> String {
+    DateTime::parse_from_rfc3339(created_at)
+        .map(|created| format!("{}d", (Utc::now() - created.with_timezone(&Utc)).num_days()))
+        .unwrap_or_else(|_| "?".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::types::{Team, User};
+
+    fn pull_request(reviewers: &[&str], teams: &[&str]) -> PullRequest {
+        let user = |login: &str| User {
+            id: 1,
+            login: login.to_string(),
+            html_url: String::new(),
+        };
+        PullRequest {
+            id: 1,
+            number: 1,
+            title: "Change".to_string(),
+            body: None,
+            html_url: String::new(),
+            state: "open".to_string(),
+            user: user("author"),
+            draft: false,
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            requested_reviewers: reviewers.iter().map(|r| user(r)).collect(),
+            requested_teams: teams
+                .iter()
+                .map(|t| Team {
+                    slug: t.to_string(),
+                    name: t.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_awaits_review() {
+        let pr = pull_request(&["alice"], &["platform"]);
+
+        assert!(awaits_review(&pr, &["alice".to_string()]));
+        assert!(awaits_review(&pr, &["my-org/platform".to_string()]));
+        assert!(!awaits_review(&pr, &["bob".to_string(), "web".to_string()]));
+    }
+}
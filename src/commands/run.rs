@@ -1,23 +1,144 @@
 //! Run command implementation
 
 use super::{Command, CommandContext};
-use crate::runner::CommandRunner;
-use anyhow::Result;
+use crate::cache::{self, ResultCache};
+use crate::config::{Config, Repository, dependency_levels};
+use crate::environment::Environment;
+use crate::events::EventSink;
+use crate::git::{self, Logger};
+use crate::github::{self, PrOptions};
+use crate::metrics::Metrics;
+use crate::runner::{
+    CommandOutput, CommandRunner, MissingSubdir, PathFilter, ResourceLimits, Shell,
+};
+use crate::runs;
+use crate::sarif;
+use crate::scheduler::{self, HostLimiter};
+use crate::state::{self, ResumeMode, RunState, StateTracker};
+use crate::util::approve;
+use anyhow::{Context, Result};
 use async_trait::async_trait;
 use colored::*;
+use serde_json::Value;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use uuid::Uuid;
+
+/// Fleet-wide report aggregated from the command output (`--report KIND=PATH`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunReport {
+    /// Merge SARIF logs or compiler-style diagnostics into one SARIF file
+    Sarif(String),
+}
+
+impl FromStr for RunReport {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.split_once('=') {
+            Some(("sarif", path)) if !path.is_empty() => Ok(RunReport::Sarif(path.to_string())),
+            _ => anyhow::bail!("Invalid report '{}', expected sarif=PATH", value),
+        }
+    }
+}
+
+/// Baseline of `--only-changed-since`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangedSince {
+    /// Where the checked-out branch forked from a ref
+    Ref(String),
+    /// The commits recorded when the same command last succeeded
+    LastRun,
+}
+
+impl FromStr for ChangedSince {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "" => anyhow::bail!("Expected a ref or 'last-run'"),
+            "last-run" => Ok(ChangedSince::LastRun),
+            reference => Ok(ChangedSince::Ref(reference.to_string())),
+        }
+    }
+}
 
 /// Run command for executing commands in repositories
+#[derive(Default)]
 pub struct RunCommand {
     pub command: String,
     pub log_dir: String,
+    /// Run repositories in `depends_on` order, level by level
+    pub ordered: bool,
+    /// Run the command once per ref using temporary worktrees
+    pub matrix_refs: Vec<String>,
+    /// Local script to copy out and execute instead of `command`
+    pub script: Option<String>,
+    /// Arguments passed to the script
+    pub script_args: Vec<String>,
+    /// Shell used to interpret the command
+    pub shell: Shell,
+    /// Variables injected into each command (`--env`)
+    pub env: Vec<String>,
+    /// Dotenv-style files with variables to inject (`--env-file`)
+    pub env_files: Vec<String>,
+    /// Buffer stdin once and replay it to each command
+    pub stdin: bool,
+    /// Subdirectory of each repository to run in
+    pub cwd: Option<String>,
+    /// Behavior when `cwd` does not exist in a repository
+    pub cwd_missing: MissingSubdir,
+    /// Skip repositories with uncommitted changes
+    pub require_clean: bool,
+    /// Report which files each command modified
+    pub show_changes: bool,
+    /// When set, repositories changed by the command go through the pull
+    /// request flow. Implies `require_clean`.
+    pub pr_options: Option<PrOptions>,
+    /// Which repositories to process based on the previous run's state
+    pub resume: ResumeMode,
+    /// Reuse results recorded for the same command at the same HEAD, except
+    /// with `pr_options`: a replayed result can't redo the command's changes
+    pub cache: bool,
+    /// Ignore cached results, still refreshing them when `cache` is set
+    pub no_cache: bool,
+    /// Directory of the result cache
+    pub cache_dir: PathBuf,
+    /// Aggregate the output of every repository into a report
+    pub report: Option<RunReport>,
+    /// Skip the confirmation the policy may require
+    pub yes: bool,
+    /// Caps on the resources of each command
+    pub limits: ResourceLimits,
+    /// Run commands without network access, writing only to their repository
+    pub sandbox: bool,
+    /// Attach commands to a pseudo-terminal
+    pub pty: bool,
+    /// Where to report progress events
+    pub events: Option<Arc<EventSink>>,
+    /// Prometheus textfile to write the run's metrics to
+    pub metrics_file: Option<String>,
+    /// Skip repositories without changes since a ref or the last run
+    pub only_changed_since: Option<ChangedSince>,
+    /// Only run in repositories containing matching files
+    pub path_filter: PathFilter,
+}
+
+/// Outcome of a single repository/ref cell in a matrix run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MatrixOutcome {
+    Passed,
+    Failed,
+    MissingRef,
 }
 
 #[async_trait]
 impl Command for RunCommand {
     async fn execute(&self, context: &CommandContext) -> Result<()> {
-        let repositories = context
-            .config
-            .filter_repositories(context.tag.as_deref(), context.repos.as_deref());
+        let repositories = context.repositories();
 
         if repositories.is_empty() {
             let filter_desc = match (&context.tag, &context.repos) {
@@ -33,50 +154,871 @@ impl Command for RunCommand {
             return Ok(());
         }
 
+        let fingerprint = match &self.script {
+            Some(script) => format!("{script} {}", self.script_args.join(" ")),
+            None => self.command.clone(),
+        };
+        // Read before it is replaced, to compare with the last run
+        let state_file = state::state_file(context.config.config_dir.as_deref());
+        let previous = RunState::load(&state_file)
+            .ok()
+            .filter(|state| state.operation == "run" && state.fingerprint == fingerprint.trim());
+        let (state, repositories) = StateTracker::start(
+            state_file,
+            "run",
+            fingerprint.trim(),
+            repositories,
+            self.resume,
+        )?;
+        if repositories.is_empty() {
+            println!("{}", "Nothing left to run from the previous state".yellow());
+            return Ok(());
+        }
+
+        let repositories = match &self.only_changed_since {
+            Some(since) => {
+                let changed = changed_repositories(since, repositories, previous.as_ref(), &state);
+                if changed.is_empty() {
+                    println!("{}", "No repositories changed".yellow());
+                    return Ok(());
+                }
+                changed
+            }
+            None => repositories,
+        };
+
+        self.path_filter.validate()?;
+        let repositories = self.path_filter.apply(repositories);
+        if repositories.is_empty() {
+            println!("{}", "No repositories contain the required files".yellow());
+            return Ok(());
+        }
+
+        if !self.yes
+            && !approve(
+                &context.config.policy,
+                &format!(
+                    "Run '{}' in {} repositories?",
+                    self.script.as_deref().unwrap_or(&self.command),
+                    repositories.len()
+                ),
+                repositories.len(),
+                false,
+                false,
+            )?
+        {
+            println!("{}", "Aborted".yellow());
+            return Ok(());
+        }
+
+        let script_dir = std::env::temp_dir().join(format!(
+            "rrepos-script-{}",
+            &Uuid::new_v4().simple().to_string()[..8]
+        ));
+        let command = match &self.script {
+            Some(script) => self.prepare_script(script, &script_dir)?,
+            None => self.command.clone(),
+        };
+
         println!(
             "{}",
             format!(
                 "Running '{}' in {} repositories...",
-                self.command,
+                self.script.as_deref().unwrap_or(&command),
                 repositories.len()
             )
             .green()
         );
 
-        let runner = CommandRunner::new();
+        let mut environment =
+            Environment::default().with_passlist(context.config.env_passlist.clone());
+        for env_file in &self.env_files {
+            environment = environment.with_env_file(env_file)?;
+        }
+        for assignment in &self.env {
+            environment = environment.with_assignment(assignment)?;
+        }
 
-        if context.parallel {
-            let tasks: Vec<_> = repositories
-                .into_iter()
-                .map(|repo| {
-                    let runner = &runner;
-                    let command = self.command.clone();
-                    let log_dir = self.log_dir.clone();
-                    async move { runner.run_command(&repo, &command, Some(&log_dir)).await }
-                })
-                .collect();
+        // A script is keyed by its content, not the temporary copy it runs from
+        let program = match &self.script {
+            Some(script) => format!("script:{}", std::fs::read_to_string(script)?),
+            None => format!("command:{}", self.command),
+        };
+        let cache_key = cache_key(
+            &program,
+            &self.script_args,
+            self.shell,
+            self.cwd.as_deref(),
+            &environment.vars,
+        );
 
-            for task in tasks {
-                if let Err(e) = task.await {
-                    eprintln!("{}", format!("Error: {e}").red());
+        let result = self
+            .run_batches(
+                context,
+                &command,
+                &cache_key,
+                repositories,
+                environment,
+                &state,
+            )
+            .await;
+        let _ = std::fs::remove_dir_all(&script_dir);
+        result?;
+
+        println!("{}", "Done running commands".green());
+        Ok(())
+    }
+}
+
+impl RunCommand {
+    async fn run_batches(
+        &self,
+        context: &CommandContext,
+        command: &str,
+        cache_key: &str,
+        repositories: Vec<Repository>,
+        environment: Environment,
+        state: &StateTracker,
+    ) -> Result<()> {
+        let (config, parallel) = (&context.config, context.parallel);
+        let mut runner = CommandRunner::new()
+            .with_shell(self.shell)
+            .with_environment(environment)
+            .with_limits(self.limits)
+            .with_sandbox(self.sandbox)
+            .with_pty(self.pty);
+        if let Some(cwd) = &self.cwd {
+            runner = runner.with_subdir(cwd.clone(), self.cwd_missing);
+        }
+        if let Some(events) = &self.events {
+            runner = runner.with_events(Arc::clone(events));
+        }
+        if self.stdin {
+            let mut input = Vec::new();
+            std::io::stdin()
+                .read_to_end(&mut input)
+                .context("Failed to read stdin")?;
+            runner = runner.with_stdin(input);
+        }
+
+        let batches = if self.ordered {
+            dependency_levels(&repositories)?
+        } else {
+            vec![repositories]
+        };
+        let metrics = Metrics::new("run");
+
+        if !self.matrix_refs.is_empty() {
+            let mut results = Vec::new();
+            for repositories in batches {
+                results.extend(
+                    self.run_matrix(&runner, command, repositories, parallel)
+                        .await,
+                );
+            }
+            for (repo, _, _) in &results {
+                let failed = results
+                    .iter()
+                    .any(|(r, _, outcome)| r == repo && matches!(outcome, MatrixOutcome::Failed));
+                state.record(repo, !failed);
+                metrics.record(repo, !failed, None);
+            }
+            self.print_matrix(&results);
+            return self.write_metrics(&metrics);
+        }
+
+        let sarif_runs = Mutex::new(Vec::new());
+        let mut changes = Vec::new();
+        for repositories in batches {
+            if parallel {
+                let limiter = HostLimiter::new(config, &repositories);
+                let tasks: Vec<_> = repositories
+                    .into_iter()
+                    .map(|repo| {
+                        let runner = &runner;
+                        let sarif_runs = &sarif_runs;
+                        let limiter = &limiter;
+                        async move {
+                            let result = limiter
+                                .run(&repo, async {
+                                    let started = Instant::now();
+                                    let result = self
+                                        .run_repository(
+                                            config, runner, &repo, command, cache_key, sarif_runs,
+                                        )
+                                        .await;
+                                    (result, started.elapsed())
+                                })
+                                .await;
+                            (repo, result)
+                        }
+                    })
+                    .collect();
+
+                for (repo, (result, duration)) in futures::future::join_all(tasks).await {
+                    state.record(&repo.name, result.is_ok());
+                    metrics.record(&repo.name, result.is_ok(), Some(duration));
+                    match result {
+                        Ok(changed) => changes.extend(changed.map(|c| (repo.name, c))),
+                        Err(e) => eprintln!("{}", format!("Error: {e}").red()),
+                    }
+                }
+            } else {
+                for repo in repositories {
+                    let started = Instant::now();
+                    let result = self
+                        .run_repository(config, &runner, &repo, command, cache_key, &sarif_runs)
+                        .await;
+                    state.record(&repo.name, result.is_ok());
+                    metrics.record(&repo.name, result.is_ok(), Some(started.elapsed()));
+                    match result {
+                        Ok(changed) => changes.extend(changed.map(|c| (repo.name, c))),
+                        Err(e) => eprintln!(
+                            "{} | {}",
+                            repo.name.cyan().bold(),
+                            format!("Error: {e}").red()
+                        ),
+                    }
                 }
             }
+        }
+
+        if self.show_changes {
+            print_changes(&changes);
+        }
+
+        if let Some(RunReport::Sarif(path)) = &self.report {
+            let runs = sarif_runs.into_inner().unwrap_or_default();
+            let results: usize = runs
+                .iter()
+                .filter_map(|run| run["results"].as_array())
+                .map(Vec::len)
+                .sum();
+            std::fs::write(path, serde_json::to_string_pretty(&sarif::log(runs))?)?;
+            runs::record_artifact(Path::new(path));
+            println!(
+                "{}",
+                format!("SARIF report with {results} results written to '{path}'").green()
+            );
+        }
+
+        self.write_metrics(&metrics)
+    }
+
+    fn write_metrics(&self, metrics: &Metrics) -> Result<()> {
+        if let Some(path) = &self.metrics_file {
+            metrics.write_textfile(path)?;
+        }
+        Ok(())
+    }
+
+    /// Add a repository's output to the SARIF report, if one was requested
+    fn collect_report(
+        &self,
+        repo: &Repository,
+        command: &str,
+        output: &CommandOutput,
+        sarif_runs: &Mutex<Vec<Value>>,
+    ) {
+        if self.report.is_none() {
+            return;
+        }
+        // Linters report paths relative to where they ran
+        let prefix = match &self.cwd {
+            Some(cwd) => format!("{}/{}", repo.name, cwd.trim_end_matches('/')),
+            None => repo.name.clone(),
+        };
+        let tool = command.split_whitespace().next().unwrap_or("rrepos");
+        let runs = sarif::runs_from_output(tool, &prefix, output);
+        if let Ok(mut all) = sarif_runs.lock() {
+            all.extend(runs);
+        }
+    }
+
+    /// Run the command in one repository, returning the files it modified
+    /// when `show_changes` is enabled
+    async fn run_repository(
+        &self,
+        config: &Config,
+        runner: &CommandRunner,
+        repo: &Repository,
+        command: &str,
+        cache_key: &str,
+        sarif_runs: &Mutex<Vec<Value>>,
+    ) -> Result<Option<Vec<String>>> {
+        let repo_dir = repo.get_target_dir();
+
+        let require_clean = self.require_clean || self.pr_options.is_some();
+        if require_clean && Path::new(&repo_dir).exists() && git::has_changes(&repo_dir)? {
+            Logger.warn(repo, "Working tree has uncommitted changes, skipping");
+            return Ok(None);
+        }
+
+        let before = if self.show_changes {
+            Some(git::status_snapshot(&repo_dir)?)
         } else {
-            for repo in repositories {
-                if let Err(e) = runner
-                    .run_command(&repo, &self.command, Some(&self.log_dir))
-                    .await
-                {
+            None
+        };
+
+        // Results are only cached for clean working trees, where HEAD fully
+        // describes the content the command ran against. Auto PRs always run
+        // the command, since the files it changed are not part of the result.
+        let cache_sha = if self.cache
+            && self.pr_options.is_none()
+            && Path::new(&repo_dir).exists()
+            && !git::has_changes(&repo_dir)?
+        {
+            Some(git::head_sha(&repo_dir)?)
+        } else {
+            None
+        };
+        if let Some(sha) = &cache_sha
+            && !self.no_cache
+            && let Some(output) = ResultCache::at(&self.cache_dir).get(&repo.name, sha, cache_key)
+        {
+            Logger.info(
+                repo,
+                &format!("Using cached result for {}", &sha[..sha.len().min(8)]),
+            );
+            output.replay(repo);
+            self.collect_report(repo, command, &output, sarif_runs);
+            output.check()?;
+            return Ok(None);
+        }
+
+        let output = runner
+            .run_command_captured(repo, command, Some(&self.log_dir))
+            .await?;
+        if let Some(sha) = &cache_sha
+            && let Err(e) =
+                ResultCache::at(&self.cache_dir).put(&repo.name, sha, cache_key, &output)
+        {
+            Logger.warn(repo, &format!("Failed to cache result: {e}"));
+        }
+        self.collect_report(repo, command, &output, sarif_runs);
+        output.check()?;
+
+        if let Some(pr_options) = &self.pr_options {
+            github::create_pull_request(config, repo, pr_options).await?;
+        }
+
+        let Some(before) = before else {
+            return Ok(None);
+        };
+        let after = git::status_snapshot(&repo_dir)?;
+
+        let mut changed: Vec<String> = after
+            .iter()
+            .filter(|(path, state)| before.get(*path) != Some(state))
+            .map(|(path, _)| path.clone())
+            .chain(
+                before
+                    .keys()
+                    .filter(|path| !after.contains_key(*path))
+                    .cloned(),
+            )
+            .collect();
+        changed.sort();
+        Ok(Some(changed))
+    }
+
+    /// Copy a local script into a temporary location and build the command
+    /// line that executes it with the configured arguments
+    fn prepare_script(&self, script: &str, script_dir: &std::path::Path) -> Result<String> {
+        let content = std::fs::read_to_string(script)
+            .with_context(|| format!("Failed to read script '{script}'"))?;
+        let file_name = std::path::Path::new(script)
+            .file_name()
+            .ok_or_else(|| anyhow::anyhow!("Invalid script path: {}", script))?;
+
+        std::fs::create_dir_all(script_dir)?;
+        let target = script_dir.join(file_name);
+        std::fs::write(&target, &content)?;
+
+        let target = target.to_string_lossy().to_string();
+        let mut command = if content.starts_with("#!") || self.shell == Shell::None {
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                std::fs::set_permissions(
+                    script_dir.join(file_name),
+                    std::fs::Permissions::from_mode(0o755),
+                )?;
+            }
+            self.shell.quote(&target)
+        } else {
+            self.shell.script_command(&target)
+        };
+
+        for arg in &self.script_args {
+            command.push(' ');
+            command.push_str(&self.shell.quote_template(arg));
+        }
+
+        Ok(command)
+    }
+
+    async fn run_matrix(
+        &self,
+        runner: &CommandRunner,
+        command: &str,
+        repositories: Vec<Repository>,
+        parallel: bool,
+    ) -> Vec<(String, String, MatrixOutcome)> {
+        let worktree_root = std::env::temp_dir().join(format!(
+            "rrepos-matrix-{}",
+            &Uuid::new_v4().simple().to_string()[..8]
+        ));
+
+        let cells = add_matrix_worktrees(&repositories, &self.matrix_refs, &worktree_root);
+        let tasks: Vec<_> = cells
+            .iter()
+            .map(|cell| async move {
+                let outcome = match &cell.worktree {
+                    Ok(worktree) => self.run_matrix_cell(runner, command, worktree).await,
+                    Err(outcome) => *outcome,
+                };
+                (cell.repo.name.clone(), cell.reference.clone(), outcome)
+            })
+            .collect();
+
+        let results = if parallel {
+            futures::future::join_all(tasks.into_iter().map(scheduler::limited)).await
+        } else {
+            let mut results = Vec::new();
+            for task in tasks {
+                results.push(task.await);
+            }
+            results
+        };
+
+        remove_matrix_worktrees(&cells);
+        let _ = std::fs::remove_dir_all(&worktree_root);
+        results
+    }
+
+    /// Run the command in a repository checked out at a matrix ref
+    async fn run_matrix_cell(
+        &self,
+        runner: &CommandRunner,
+        command: &str,
+        worktree: &Repository,
+    ) -> MatrixOutcome {
+        match runner
+            .run_command(worktree, command, Some(&self.log_dir))
+            .await
+        {
+            Ok(()) => MatrixOutcome::Passed,
+            Err(e) => {
+                eprintln!(
+                    "{} | {}",
+                    worktree.name.cyan().bold(),
+                    format!("Error: {e}").red()
+                );
+                MatrixOutcome::Failed
+            }
+        }
+    }
+
+    fn print_matrix(&self, results: &[(String, String, MatrixOutcome)]) {
+        let mut repos: Vec<&str> = Vec::new();
+        for (repo, _, _) in results {
+            if !repos.contains(&repo.as_str()) {
+                repos.push(repo);
+            }
+        }
+
+        let name_width = repos.iter().map(|r| r.len()).max().unwrap_or(0).max(10);
+        let col_width = |r: &String| r.len().max(7);
+
+        print!("\n{:<name_width$}", "Repository");
+        for reference in &self.matrix_refs {
+            print!("  {:<width$}", reference, width = col_width(reference));
+        }
+        println!();
+
+        for repo in repos {
+            print!("{:<name_width$}", repo);
+            for reference in &self.matrix_refs {
+                let width = col_width(reference);
+                let cell = results
+                    .iter()
+                    .find(|(r, re, _)| r == repo && re == reference)
+                    .map(|(_, _, outcome)| match outcome {
+                        MatrixOutcome::Passed => format!("{:<width$}", "ok").green(),
+                        MatrixOutcome::Failed => format!("{:<width$}", "FAILED").red(),
+                        MatrixOutcome::MissingRef => format!("{:<width$}", "missing").yellow(),
+                    })
+                    .unwrap_or_default();
+                print!("  {cell}");
+            }
+            println!();
+        }
+    }
+}
+
+fn print_changes(changes: &[(String, Vec<String>)]) {
+    println!("\n{}", "Changes made by the command:".bold());
+    for (repo, files) in changes {
+        if files.is_empty() {
+            println!("{} | {}", repo.cyan().bold(), "No changes".dimmed());
+            continue;
+        }
+        println!(
+            "{} | {}",
+            repo.cyan().bold(),
+            format!("{} files changed", files.len()).yellow()
+        );
+        for file in files {
+            println!("{} |   {file}", repo.cyan().bold());
+        }
+    }
+}
+
+/// The repositories that changed since `since`. Unchanged repositories are
+/// recorded as succeeded, and every repository's commit is recorded for the
+/// next `last-run` comparison.
+fn changed_repositories(
+    since: &ChangedSince,
+    repositories: Vec<Repository>,
+    previous: Option<&RunState>,
+    state: &StateTracker,
+) -> Vec<Repository> {
+    let logger = Logger;
+    repositories
+        .into_iter()
+        .filter(|repo| {
+            let repo_dir = repo.get_target_dir();
+            let head = git::head_sha(&repo_dir).ok();
+            if let Some(head) = &head {
+                state.record_head(&repo.name, head);
+            }
+
+            let changed = match since {
+                ChangedSince::Ref(reference) => git::changed_since(&repo_dir, reference)
+                    .unwrap_or_else(|e| {
+                        logger.warn(repo, &format!("{e}, running anyway"));
+                        true
+                    }),
+                ChangedSince::LastRun => {
+                    let unchanged = matches!(
+                        (previous, &head),
+                        (Some(previous), Some(head)) if previous.succeeded_at(&repo.name, head)
+                    );
+                    !unchanged || git::has_changes(&repo_dir).unwrap_or(true)
+                }
+            };
+
+            if !changed {
+                logger.info(repo, "No changes, skipping");
+                state.record(&repo.name, true);
+            }
+            changed
+        })
+        .collect()
+}
+
+/// A repository/ref cell of a matrix run
+struct MatrixCell {
+    repo: Repository,
+    reference: String,
+    /// The repository checked out at the ref in a worktree, under a name
+    /// that identifies the ref, or the outcome when that failed
+    worktree: Result<Repository, MatrixOutcome>,
+}
+
+/// Check out every repository at every ref in worktrees under
+/// `worktree_root`. Worktrees are added one at a time, since concurrent
+/// `git worktree add` calls race on the repository's administrative files.
+fn add_matrix_worktrees(
+    repositories: &[Repository],
+    refs: &[String],
+    worktree_root: &Path,
+) -> Vec<MatrixCell> {
+    let mut cells = Vec::new();
+    for repo in repositories {
+        let repo_dir = repo.get_target_dir();
+        for reference in refs {
+            let label = format!("{}@{}", repo.name, reference.replace('/', "-"));
+            let worktree = match git::resolve_ref(&repo_dir, reference) {
+                None => {
                     eprintln!(
                         "{} | {}",
-                        repo.name.cyan().bold(),
-                        format!("Error: {e}").red()
+                        label.cyan().bold(),
+                        format!("Reference '{reference}' not found").yellow()
                     );
+                    Err(MatrixOutcome::MissingRef)
                 }
-            }
+                Some(resolved) => {
+                    let worktree_dir = worktree_root.join(&label).to_string_lossy().to_string();
+                    match git::add_worktree(&repo_dir, &worktree_dir, &resolved) {
+                        Ok(()) => {
+                            let mut worktree = repo.clone();
+                            worktree.name = label;
+                            worktree.path = Some(worktree_dir);
+                            Ok(worktree)
+                        }
+                        Err(e) => {
+                            eprintln!("{} | {}", label.cyan().bold(), format!("Error: {e}").red());
+                            Err(MatrixOutcome::Failed)
+                        }
+                    }
+                }
+            };
+            cells.push(MatrixCell {
+                repo: repo.clone(),
+                reference: reference.clone(),
+                worktree,
+            });
         }
+    }
+    cells
+}
 
-        println!("{}", "Done running commands".green());
-        Ok(())
+/// Remove the worktrees of a matrix run, one at a time like they were added
+fn remove_matrix_worktrees(cells: &[MatrixCell]) {
+    for cell in cells {
+        let Ok(worktree) = &cell.worktree else {
+            continue;
+        };
+        if let Err(e) =
+            git::remove_worktree(&cell.repo.get_target_dir(), &worktree.get_target_dir())
+        {
+            eprintln!(
+                "{} | {}",
+                worktree.name.cyan().bold(),
+                format!("Error: {e}").red()
+            );
+        }
+    }
+}
+
+/// Key of cached results: a hash of what runs, with which arguments, shell,
+/// subdirectory and injected variables. Hashing keeps variable values such
+/// as tokens out of the cache.
+fn cache_key(
+    program: &str,
+    args: &[String],
+    shell: Shell,
+    cwd: Option<&str>,
+    vars: &[(String, String)],
+) -> String {
+    let mut key = format!("{shell:?}\0{}\0{program}", cwd.unwrap_or_default());
+    for arg in args {
+        key.push_str(&format!("\0arg:{arg}"));
+    }
+    for (name, value) in vars {
+        key.push_str(&format!("\0env:{name}={value}"));
+    }
+    format!("{:016x}", cache::fingerprint(&key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::OutputLine;
+    use crate::test_util::git;
+
+    #[test]
+    fn test_cache_key() {
+        let dir =
+            std::env::temp_dir().join(format!("rrepos-run-cache-test-{}", std::process::id()));
+        let cache = ResultCache::at(&dir);
+        let output = CommandOutput {
+            lines: vec![OutputLine {
+                stderr: false,
+                text: "ok".to_string(),
+            }],
+            exit_code: 0,
+        };
+        let vars = vec![("PROFILE".to_string(), "ci".to_string())];
+        let key = |program: &str, shell, cwd, vars: &[(String, String)]| {
+            cache_key(program, &["--fast".to_string()], shell, cwd, vars)
+        };
+
+        let stored = key("script:make lint", Shell::Sh, Some("web"), &vars);
+        cache.put("repo", "abc123", &stored, &output).unwrap();
+
+        // The same script content, arguments, shell, subdirectory and
+        // variables hit the cache
+        let same = key("script:make lint", Shell::Sh, Some("web"), &vars);
+        assert!(cache.get("repo", "abc123", &same).is_some());
+
+        // Any difference misses it
+        let misses = [
+            key("script:make test", Shell::Sh, Some("web"), &vars),
+            key("script:make lint", Shell::Bash, Some("web"), &vars),
+            key("script:make lint", Shell::Sh, None, &vars),
+            key("script:make lint", Shell::Sh, Some("web"), &[]),
+            cache_key("script:make lint", &[], Shell::Sh, Some("web"), &vars),
+        ];
+        let missed = misses
+            .iter()
+            .all(|miss| cache.get("repo", "abc123", miss).is_none());
+        std::fs::remove_dir_all(&dir).ok();
+        assert!(missed);
+    }
+
+    #[test]
+    fn test_matrix_worktrees() {
+        let root = std::env::temp_dir().join(format!("rrepos-matrix-test-{}", std::process::id()));
+        let repo_dir = root.join("api");
+        std::fs::create_dir_all(&repo_dir).unwrap();
+        git(&repo_dir, &["init", "--quiet"]);
+        git(
+            &repo_dir,
+            &["commit", "--quiet", "--allow-empty", "-m", "first"],
+        );
+        git(&repo_dir, &["tag", "v1"]);
+        git(
+            &repo_dir,
+            &["commit", "--quiet", "--allow-empty", "-m", "second"],
+        );
+
+        let mut repo = Repository::new("api".to_string(), "git@github.com:org/api.git".to_string());
+        repo.path = Some(repo_dir.to_string_lossy().to_string());
+        let refs = ["v1", "release/2", "HEAD"].map(str::to_string);
+        let worktree_root = root.join("worktrees");
+
+        let cells = add_matrix_worktrees(&[repo], &refs, &worktree_root);
+        let expanded: Vec<(&str, Option<&str>)> = cells
+            .iter()
+            .map(|cell| {
+                let name = cell.worktree.as_ref().ok().map(|w| w.name.as_str());
+                (cell.reference.as_str(), name)
+            })
+            .collect();
+        let checked_out = worktree_root.join("api@v1").join(".git").exists();
+        let worktrees_before = git(&repo_dir, &["worktree", "list"]).lines().count();
+
+        remove_matrix_worktrees(&cells);
+        let worktrees_after = git(&repo_dir, &["worktree", "list"]).lines().count();
+        let removed = !worktree_root.join("api@v1").exists();
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(
+            expanded,
+            vec![
+                ("v1", Some("api@v1")),
+                ("release/2", None),
+                ("HEAD", Some("api@HEAD"))
+            ]
+        );
+        assert!(matches!(cells[1].worktree, Err(MatrixOutcome::MissingRef)));
+        assert!(checked_out);
+        assert_eq!((worktrees_before, worktrees_after), (3, 1));
+        assert!(removed);
+    }
+
+    #[tokio::test]
+    async fn test_require_clean_and_show_changes() {
+        let dir = std::env::temp_dir().join(format!("rrepos-changes-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        git(&dir, &["init", "--quiet"]);
+        std::fs::write(dir.join("a.txt"), "a\n").unwrap();
+        std::fs::write(dir.join("b.txt"), "b\n").unwrap();
+        git(&dir, &["add", "."]);
+        git(&dir, &["commit", "--quiet", "-m", "first"]);
+        std::fs::write(dir.join("notes.txt"), "already dirty\n").unwrap();
+
+        let mut repo = Repository::new("api".to_string(), "git@github.com:org/api.git".to_string());
+        repo.path = Some(dir.to_string_lossy().to_string());
+        let log_dir = dir
+            .join(".git")
+            .join("logs-test")
+            .to_string_lossy()
+            .to_string();
+        let run = |command: &str, require_clean| {
+            let command = RunCommand {
+                command: command.to_string(),
+                log_dir: log_dir.clone(),
+                require_clean,
+                show_changes: true,
+                ..Default::default()
+            };
+            let repo = repo.clone();
+            async move {
+                let sarif_runs = Mutex::new(Vec::new());
+                command
+                    .run_repository(
+                        &Config::new(),
+                        &CommandRunner::new(),
+                        &repo,
+                        &command.command,
+                        "",
+                        &sarif_runs,
+                    )
+                    .await
+                    .unwrap()
+            }
+        };
+
+        // Files the command touched are reported, ones dirty before are not
+        let changed = run("echo more >> a.txt && rm b.txt && touch new.txt", false).await;
+        // A dirty working tree is skipped without running the command
+        let skipped = run("touch ran.txt", true).await;
+        let ran = dir.join("ran.txt").exists();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            changed,
+            Some(vec![
+                "a.txt".to_string(),
+                "b.txt".to_string(),
+                "new.txt".to_string()
+            ])
+        );
+        assert_eq!(skipped, None);
+        assert!(!ran);
+    }
+
+    #[tokio::test]
+    async fn test_auto_pr_runs_despite_cache() {
+        let root = std::env::temp_dir().join(format!("rrepos-auto-pr-test-{}", std::process::id()));
+        let repo_dir = root.join("api");
+        std::fs::create_dir_all(&repo_dir).unwrap();
+        git(&repo_dir, &["init", "--quiet"]);
+        git(&repo_dir, &["config", "user.name", "Test"]);
+        git(&repo_dir, &["config", "user.email", "test@example.com"]);
+        git(
+            &repo_dir,
+            &["commit", "--quiet", "--allow-empty", "-m", "first"],
+        );
+
+        let mut repo = Repository::new("api".to_string(), "git@github.com:org/api.git".to_string());
+        repo.path = Some(repo_dir.to_string_lossy().to_string());
+        let mut pr_options = PrOptions::new("Fix".to_string(), String::new(), String::new());
+        pr_options.create_only = true;
+        pr_options.branch_template = Some("fix/{name}".to_string());
+        let command = RunCommand {
+            command: "echo fixed > fix.txt".to_string(),
+            log_dir: root.join("logs").to_string_lossy().to_string(),
+            cache: true,
+            cache_dir: root.join("cache"),
+            pr_options: Some(pr_options),
+            ..Default::default()
+        };
+
+        let sarif_runs = Mutex::new(Vec::new());
+        let runner = CommandRunner::new();
+        command
+            .run_repository(
+                &Config::new(),
+                &runner,
+                &repo,
+                &command.command,
+                "key",
+                &sarif_runs,
+            )
+            .await
+            .unwrap();
+        let branch = git(&repo_dir, &["branch", "--show-current"]);
+        let changed = git(&repo_dir, &["show", "--name-only", "--format=", "HEAD"]);
+        let cached = root.join("cache").join("api").exists();
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(branch.trim(), "fix/api");
+        assert_eq!(changed.trim(), "fix.txt");
+        assert!(!cached);
     }
 }
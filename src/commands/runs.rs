@@ -0,0 +1,141 @@
+//! Runs command implementation
+
+use super::{Command, CommandContext};
+use crate::runs::{RunRecord, RunStatus, history_dir};
+use crate::state::RepoStatus;
+use crate::util::shell_quote;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::DateTime;
+use colored::*;
+use std::path::Path;
+
+/// Operations on the history of past runs
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum RunsAction {
+    /// List past runs, most recent first
+    List {
+        /// Only list runs of this command, e.g. `run`
+        #[arg(long)]
+        command: Option<String>,
+
+        /// Number of runs to list
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+
+        /// Configuration file path, the history is kept next to it
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+    },
+    /// Show a run's outcome, artifacts and per-repository results
+    Show {
+        /// Run ID, or a unique prefix of it
+        id: String,
+
+        /// Configuration file path, the history is kept next to it
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+    },
+}
+
+/// Runs command for inspecting past invocations by run ID
+pub struct RunsCommand {
+    pub action: RunsAction,
+}
+
+impl RunsAction {
+    fn config(&self) -> &str {
+        match self {
+            RunsAction::List { config, .. } | RunsAction::Show { config, .. } => config,
+        }
+    }
+}
+
+#[async_trait]
+impl Command for RunsCommand {
+    async fn execute(&self, _context: &CommandContext) -> Result<()> {
+        let dir = &history_dir(Path::new(self.action.config()).parent());
+        match &self.action {
+            RunsAction::List { command, limit, .. } => {
+                let runs: Vec<RunRecord> = RunRecord::list(dir)?
+                    .into_iter()
+                    .filter(|run| command.as_ref().is_none_or(|c| &run.command == c))
+                    .take(*limit)
+                    .collect();
+                if runs.is_empty() {
+                    println!("{}", "No runs recorded".yellow());
+                    return Ok(());
+                }
+                println!(
+                    "{:<24}  {:<12}  {:<7}  Command line",
+                    "Run ID", "Command", "Status"
+                );
+                for run in &runs {
+                    println!(
+                        "{:<24}  {:<12}  {}  {}",
+                        run.id,
+                        run.command,
+                        status(run.status),
+                        command_line(run)
+                    );
+                }
+            }
+            RunsAction::Show { id, .. } => {
+                let run = RunRecord::load(dir, id)?;
+                println!("{}", format!("Run {}", run.id).bold());
+                println!("Command:  {}", command_line(&run));
+                println!("Status:   {}", status(run.status));
+                println!("Started:  {}", run.started_at);
+                println!("Finished: {}", run.finished_at);
+                if let (Ok(started), Ok(finished)) = (
+                    DateTime::parse_from_rfc3339(&run.started_at),
+                    DateTime::parse_from_rfc3339(&run.finished_at),
+                ) {
+                    let duration = finished - started;
+                    println!(
+                        "Duration: {:.1}s",
+                        duration.num_milliseconds() as f64 / 1000.0
+                    );
+                }
+                if let Some(error) = &run.error {
+                    println!("Error:    {}", error.red());
+                }
+
+                if !run.artifacts.is_empty() {
+                    println!("\nArtifacts:");
+                    for artifact in &run.artifacts {
+                        println!("  {artifact}");
+                    }
+                }
+
+                if !run.repositories.is_empty() {
+                    println!("\nRepositories:");
+                    for (name, outcome) in &run.repositories {
+                        let outcome = match outcome {
+                            RepoStatus::Succeeded => "succeeded".green(),
+                            RepoStatus::Failed => "failed".red(),
+                            RepoStatus::Pending => "pending".yellow(),
+                        };
+                        println!("  {} {outcome}", name.cyan().bold());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn status(status: RunStatus) -> ColoredString {
+    match status {
+        RunStatus::Success => "success".green(),
+        RunStatus::Failure => "failure".red(),
+    }
+}
+
+fn command_line(run: &RunRecord) -> String {
+    run.args
+        .iter()
+        .map(|arg| shell_quote(arg))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
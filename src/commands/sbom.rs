@@ -0,0 +1,285 @@
+//! SBOM command implementation
+
+use super::{Command, CommandContext};
+use crate::config::Repository;
+use crate::git::Logger;
+use crate::runner::Shell;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use colored::*;
+use serde_json::{Value, json};
+use std::path::Path;
+use uuid::Uuid;
+
+/// Generator used for repositories without a committed SBOM
+pub const DEFAULT_SBOM_GENERATOR: &str = "syft . -o cyclonedx-json -q";
+
+/// SBOM files picked up from a repository instead of running the generator
+const SBOM_FILES: [&str; 4] = ["bom.json", "sbom.json", "bom.cdx.json", "sbom.cdx.json"];
+
+/// Property recording which repository a component came from
+const REPOSITORY_PROPERTY: &str = "rrepos:repository";
+
+/// SBOM formats that can be produced
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SbomFormat {
+    /// CycloneDX 1.5 JSON
+    Cyclonedx,
+}
+
+/// SBOM command for merging per-repository SBOMs into a fleet SBOM
+pub struct SbomCommand {
+    pub format: SbomFormat,
+    /// Command printing a CycloneDX JSON SBOM, run in each repository
+    pub generator: String,
+    pub output: String,
+}
+
+#[async_trait]
+impl Command for SbomCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            format!(
+                "Collecting SBOMs for {} repositories...",
+                repositories.len()
+            )
+            .green()
+        );
+
+        let logger = Logger;
+        let mut boms = Vec::new();
+        let mut failed = 0;
+
+        for repo in &repositories {
+            let repo_dir = repo.get_target_dir();
+            if !Path::new(&repo_dir).exists() {
+                logger.warn(repo, "Repository directory does not exist, skipping");
+                continue;
+            }
+
+            match self.repository_bom(&repo_dir) {
+                Ok((bom, source)) => {
+                    let count = bom["components"].as_array().map_or(0, Vec::len);
+                    logger.info(repo, &format!("{count} components from {source}"));
+                    boms.push((repo.clone(), bom));
+                }
+                Err(e) => {
+                    failed += 1;
+                    eprintln!(
+                        "{} | {}",
+                        repo.name.cyan().bold(),
+                        format!("Error: {e}").red()
+                    );
+                }
+            }
+        }
+
+        let merged = match self.format {
+            SbomFormat::Cyclonedx => merge_cyclonedx(&boms),
+        };
+        std::fs::write(&self.output, serde_json::to_string_pretty(&merged)?)?;
+        println!(
+            "{}",
+            format!(
+                "Fleet SBOM with {} components written to '{}'",
+                merged["components"].as_array().map_or(0, Vec::len),
+                self.output
+            )
+            .green()
+        );
+
+        if failed > 0 {
+            anyhow::bail!("{} repositories failed to produce an SBOM", failed);
+        }
+        Ok(())
+    }
+}
+
+impl SbomCommand {
+    /// A repository's SBOM and where it came from: a committed SBOM file, or
+    /// the generator's output
+    fn repository_bom(&self, repo_dir: &str) -> Result<(Value, String)> {
+        for file in SBOM_FILES {
+            let path = Path::new(repo_dir).join(file);
+            if path.is_file() {
+                let bom = serde_json::from_str(&std::fs::read_to_string(&path)?)
+                    .with_context(|| format!("Invalid SBOM in {file}"))?;
+                return Ok((bom, file.to_string()));
+            }
+        }
+
+        let output = Shell::default()
+            .command(&self.generator)?
+            .current_dir(repo_dir)
+            .output()
+            .with_context(|| format!("Failed to run '{}'", self.generator))?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "'{}' failed: {}",
+                self.generator,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        let bom = serde_json::from_slice(&output.stdout)
+            .with_context(|| format!("'{}' did not print a JSON SBOM", self.generator))?;
+        Ok((bom, self.generator.clone()))
+    }
+}
+
+/// Merge CycloneDX documents into one, with a component per repository and
+/// the repository recorded on every component. `bom-ref`s are prefixed with
+/// the repository name so identical references from different repositories
+/// stay distinct.
+fn merge_cyclonedx(boms: &[(Repository, Value)]) -> Value {
+    let mut components = Vec::new();
+    let mut dependencies = Vec::new();
+
+    for (repo, bom) in boms {
+        let repo_ref = format!("repository:{}", repo.name);
+        let prefix = format!("{}:", repo.name);
+        let root_ref = bom["metadata"]["component"]["bom-ref"].as_str();
+
+        components.push(json!({
+            "type": "application",
+            "bom-ref": repo_ref,
+            "name": repo.name,
+            "externalReferences": [{ "type": "vcs", "url": repo.url }],
+        }));
+
+        let top_level: Vec<Value> = match root_ref {
+            // The repository depends on what its root component depends on
+            Some(root) => bom["dependencies"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter(|dep| dep["ref"] == root)
+                .flat_map(|dep| dep["dependsOn"].as_array().cloned().unwrap_or_default())
+                .filter_map(|r| r.as_str().map(|r| json!(format!("{prefix}{r}"))))
+                .collect(),
+            None => bom["components"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|c| c["bom-ref"].as_str())
+                .map(|r| json!(format!("{prefix}{r}")))
+                .collect(),
+        };
+        dependencies.push(json!({ "ref": repo_ref, "dependsOn": top_level }));
+
+        for component in bom["components"].as_array().into_iter().flatten() {
+            let mut component = component.clone();
+            tag_component(&mut component, &repo.name, &prefix);
+            components.push(component);
+        }
+
+        for dependency in bom["dependencies"].as_array().into_iter().flatten() {
+            let Some(reference) = dependency["ref"].as_str() else {
+                continue;
+            };
+            if Some(reference) == root_ref {
+                continue;
+            }
+            let depends_on: Vec<Value> = dependency["dependsOn"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|r| r.as_str().map(|r| json!(format!("{prefix}{r}"))))
+                .collect();
+            dependencies.push(json!({
+                "ref": format!("{prefix}{reference}"),
+                "dependsOn": depends_on,
+            }));
+        }
+    }
+
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "serialNumber": format!("urn:uuid:{}", Uuid::new_v4()),
+        "version": 1,
+        "metadata": {
+            "timestamp": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            "tools": {
+                "components": [{
+                    "type": "application",
+                    "name": "rrepos",
+                    "version": env!("CARGO_PKG_VERSION"),
+                }]
+            },
+        },
+        "components": components,
+        "dependencies": dependencies,
+    })
+}
+
+/// Prefix a component's `bom-ref` (and its nested components') and record
+/// the repository it came from
+fn tag_component(component: &mut Value, repository: &str, prefix: &str) {
+    if let Some(reference) = component["bom-ref"].as_str() {
+        component["bom-ref"] = json!(format!("{prefix}{reference}"));
+    }
+
+    let property = json!({ "name": REPOSITORY_PROPERTY, "value": repository });
+    match component["properties"].as_array_mut() {
+        Some(properties) => properties.push(property),
+        None => component["properties"] = json!([property]),
+    }
+
+    if let Some(nested) = component["components"].as_array_mut() {
+        for child in nested {
+            tag_component(child, repository, prefix);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_cyclonedx() {
+        let repo = Repository::new(
+            "api".to_string(),
+            "git@github.com:owner/api.git".to_string(),
+        );
+        let bom = json!({
+            "metadata": { "component": { "bom-ref": "root", "name": "api" } },
+            "components": [
+                { "type": "library", "bom-ref": "pkg:cargo/serde@1.0.0", "name": "serde" }
+            ],
+            "dependencies": [
+                { "ref": "root", "dependsOn": ["pkg:cargo/serde@1.0.0"] },
+                { "ref": "pkg:cargo/serde@1.0.0", "dependsOn": [] }
+            ]
+        });
+
+        let merged = merge_cyclonedx(&[(repo, bom)]);
+        let components = merged["components"].as_array().unwrap();
+        assert_eq!(components.len(), 2);
+        assert_eq!(components[1]["bom-ref"], "api:pkg:cargo/serde@1.0.0");
+        assert_eq!(components[1]["properties"][0]["value"], "api");
+
+        let dependencies = merged["dependencies"].as_array().unwrap();
+        assert_eq!(dependencies[0]["ref"], "repository:api");
+        assert_eq!(dependencies[0]["dependsOn"][0], "api:pkg:cargo/serde@1.0.0");
+        assert_eq!(dependencies.len(), 2);
+    }
+}
@@ -0,0 +1,528 @@
+//! Scan command implementation
+
+use super::{Command, CommandContext};
+use crate::config::Repository;
+use crate::git::Logger;
+use crate::sarif;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use colored::*;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+
+/// Security scanners with a preset invocation and output parser
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ScanTool {
+    CargoAudit,
+    NpmAudit,
+    Trivy,
+}
+
+/// Normalized severity of a finding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The scanner reported no severity
+    Unknown,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+/// Format of the aggregated report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ScanFormat {
+    Json,
+    Sarif,
+}
+
+/// Scan command for running security scanners across repositories
+pub struct ScanCommand {
+    /// Scanners to run; detected from tags and manifests when empty
+    pub tools: Vec<ScanTool>,
+    /// Fail when a finding has at least this severity
+    pub fail_on: Option<Severity>,
+    pub format: ScanFormat,
+    pub output: Option<String>,
+}
+
+/// A vulnerability reported by a scanner
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    pub repository: String,
+    pub tool: &'static str,
+    pub id: String,
+    pub package: String,
+    pub version: String,
+    pub severity: Severity,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fixed_version: Option<String>,
+    /// File the finding was reported against, relative to the repository
+    pub file: String,
+}
+
+impl ScanTool {
+    /// Name used in reports
+    pub fn name(&self) -> &'static str {
+        match self {
+            ScanTool::CargoAudit => "cargo-audit",
+            ScanTool::NpmAudit => "npm-audit",
+            ScanTool::Trivy => "trivy",
+        }
+    }
+
+    fn command(&self) -> (&'static str, &'static [&'static str]) {
+        match self {
+            ScanTool::CargoAudit => ("cargo", &["audit", "--json"]),
+            ScanTool::NpmAudit => ("npm", &["audit", "--json"]),
+            ScanTool::Trivy => (
+                "trivy",
+                &[
+                    "fs",
+                    "--format",
+                    "json",
+                    "--quiet",
+                    "--scanners",
+                    "vuln",
+                    ".",
+                ],
+            ),
+        }
+    }
+
+    /// Whether the scanner applies to a repository, judged by its tags or
+    /// the manifests in its directory. Trivy scans any repository.
+    pub fn applies_to(&self, repo: &Repository, repo_dir: &Path) -> bool {
+        let tagged = |tags: &[&str]| repo.tags.iter().any(|t| tags.contains(&t.as_str()));
+        match self {
+            ScanTool::CargoAudit => tagged(&["rust"]) || repo_dir.join("Cargo.toml").exists(),
+            ScanTool::NpmAudit => {
+                tagged(&["node", "javascript", "typescript"])
+                    || repo_dir.join("package.json").exists()
+            }
+            ScanTool::Trivy => true,
+        }
+    }
+
+    /// Parse the scanner's JSON output into findings
+    fn parse(&self, repository: &str, output: &Value) -> Vec<Finding> {
+        match self {
+            ScanTool::CargoAudit => parse_cargo_audit(repository, output),
+            ScanTool::NpmAudit => parse_npm_audit(repository, output),
+            ScanTool::Trivy => parse_trivy(repository, output),
+        }
+    }
+}
+
+impl Severity {
+    fn from_name(name: &str) -> Self {
+        match name.to_lowercase().as_str() {
+            "critical" => Severity::Critical,
+            "high" => Severity::High,
+            "moderate" | "medium" => Severity::Medium,
+            "low" | "info" => Severity::Low,
+            _ => Severity::Unknown,
+        }
+    }
+
+    fn from_score(score: f64) -> Self {
+        match score {
+            s if s >= 9.0 => Severity::Critical,
+            s if s >= 7.0 => Severity::High,
+            s if s >= 4.0 => Severity::Medium,
+            _ => Severity::Low,
+        }
+    }
+
+    fn sarif_level(&self) -> &'static str {
+        match self {
+            Severity::Critical | Severity::High => "error",
+            Severity::Medium => "warning",
+            Severity::Low | Severity::Unknown => "note",
+        }
+    }
+}
+
+#[async_trait]
+impl Command for ScanCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            format!("Scanning {} repositories...", repositories.len()).green()
+        );
+
+        let logger = Logger;
+        let mut findings = Vec::new();
+        let mut failed = 0;
+
+        for repo in &repositories {
+            let repo_dir = repo.get_target_dir();
+            if !Path::new(&repo_dir).exists() {
+                logger.warn(repo, "Repository directory does not exist, skipping");
+                continue;
+            }
+
+            // Trivy is only run when asked for, as it overlaps the others
+            let candidates = if self.tools.is_empty() {
+                vec![ScanTool::CargoAudit, ScanTool::NpmAudit]
+            } else {
+                self.tools.clone()
+            };
+            let tools: Vec<ScanTool> = candidates
+                .into_iter()
+                .filter(|tool| tool.applies_to(repo, Path::new(&repo_dir)))
+                .collect();
+
+            if tools.is_empty() {
+                logger.info(repo, "No applicable scanners");
+                continue;
+            }
+
+            for tool in tools {
+                match run_scanner(tool, repo, &repo_dir) {
+                    Ok(found) => {
+                        logger.info(repo, &summary(tool, &found));
+                        findings.extend(found);
+                    }
+                    Err(e) => {
+                        failed += 1;
+                        eprintln!(
+                            "{} | {}",
+                            repo.name.cyan().bold(),
+                            format!("Error: {} failed: {e}", tool.name()).red()
+                        );
+                    }
+                }
+            }
+        }
+
+        if let Some(path) = &self.output {
+            let document = match self.format {
+                ScanFormat::Json => serde_json::to_string_pretty(&findings)?,
+                ScanFormat::Sarif => serde_json::to_string_pretty(&sarif_report(&findings))?,
+            };
+            std::fs::write(path, document)?;
+            println!(
+                "{}",
+                format!("{} findings written to '{path}'", findings.len()).green()
+            );
+        }
+
+        if failed > 0 {
+            anyhow::bail!("{} scans failed", failed);
+        }
+
+        if let Some(threshold) = self.fail_on {
+            let exceeding = findings.iter().filter(|f| f.severity >= threshold).count();
+            if exceeding > 0 {
+                anyhow::bail!(
+                    "{} findings at or above {:?} severity",
+                    exceeding,
+                    threshold
+                );
+            }
+        }
+
+        println!("{}", "Done scanning".green());
+        Ok(())
+    }
+}
+
+/// Run a scanner in a repository and parse its findings. Scanners exit with
+/// a failure status when they find vulnerabilities, so only output that is
+/// not valid JSON counts as an error.
+fn run_scanner(tool: ScanTool, repo: &Repository, repo_dir: &str) -> Result<Vec<Finding>> {
+    let (program, args) = tool.command();
+    let output = std::process::Command::new(program)
+        .args(args)
+        .current_dir(repo_dir)
+        .output()
+        .with_context(|| format!("Failed to execute {program}; is it installed?"))?;
+
+    let Ok(json) = serde_json::from_slice::<Value>(&output.stdout) else {
+        anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    };
+
+    Ok(tool.parse(&repo.name, &json))
+}
+
+fn summary(tool: ScanTool, findings: &[Finding]) -> String {
+    if findings.is_empty() {
+        return format!("{}: no findings", tool.name());
+    }
+    let worst = findings.iter().map(|f| f.severity).max().unwrap();
+    format!(
+        "{}: {} findings, highest severity {:?}",
+        tool.name(),
+        findings.len(),
+        worst
+    )
+}
+
+fn sarif_report(findings: &[Finding]) -> Value {
+    let mut tools: Vec<&str> = findings.iter().map(|f| f.tool).collect();
+    tools.sort();
+    tools.dedup();
+
+    let runs = tools
+        .into_iter()
+        .map(|tool| {
+            let results = findings
+                .iter()
+                .filter(|f| f.tool == tool)
+                .map(|f| {
+                    sarif::result(
+                        &f.id,
+                        f.severity.sarif_level(),
+                        &format!("{} {}: {}", f.package, f.version, f.title),
+                        &format!("{}/{}", f.repository, f.file),
+                    )
+                })
+                .collect();
+            sarif::run(tool, results)
+        })
+        .collect();
+
+    sarif::log(runs)
+}
+
+fn parse_cargo_audit(repository: &str, output: &Value) -> Vec<Finding> {
+    let Some(list) = output["vulnerabilities"]["list"].as_array() else {
+        return Vec::new();
+    };
+
+    list.iter()
+        .map(|vulnerability| {
+            let advisory = &vulnerability["advisory"];
+            Finding {
+                repository: repository.to_string(),
+                tool: ScanTool::CargoAudit.name(),
+                id: text(&advisory["id"]),
+                package: text(&vulnerability["package"]["name"]),
+                version: text(&vulnerability["package"]["version"]),
+                severity: advisory["cvss"]
+                    .as_str()
+                    .and_then(cvss_base_score)
+                    .map(Severity::from_score)
+                    .unwrap_or(Severity::Unknown),
+                title: text(&advisory["title"]),
+                fixed_version: vulnerability["versions"]["patched"]
+                    .as_array()
+                    .and_then(|patched| patched.first())
+                    .map(text),
+                file: "Cargo.lock".to_string(),
+            }
+        })
+        .collect()
+}
+
+fn parse_npm_audit(repository: &str, output: &Value) -> Vec<Finding> {
+    let Some(vulnerabilities) = output["vulnerabilities"].as_object() else {
+        return Vec::new();
+    };
+
+    vulnerabilities
+        .iter()
+        .map(|(package, vulnerability)| {
+            let via = vulnerability["via"].as_array().cloned().unwrap_or_default();
+            // `via` holds advisories, or names of vulnerable dependencies
+            let advisory = via.iter().find(|v| v.is_object());
+            let (id, title) = match advisory {
+                Some(advisory) => (
+                    advisory["url"]
+                        .as_str()
+                        .and_then(|url| url.rsplit('/').next())
+                        .map(String::from)
+                        .unwrap_or_else(|| text(&advisory["source"])),
+                    text(&advisory["title"]),
+                ),
+                None => {
+                    let names: Vec<String> = via.iter().map(text).collect();
+                    (
+                        format!("npm-{package}"),
+                        format!("Depends on vulnerable {}", names.join(", ")),
+                    )
+                }
+            };
+
+            Finding {
+                repository: repository.to_string(),
+                tool: ScanTool::NpmAudit.name(),
+                id,
+                package: package.clone(),
+                version: text(&vulnerability["range"]),
+                severity: Severity::from_name(&text(&vulnerability["severity"])),
+                title,
+                fixed_version: None,
+                file: "package-lock.json".to_string(),
+            }
+        })
+        .collect()
+}
+
+fn parse_trivy(repository: &str, output: &Value) -> Vec<Finding> {
+    let Some(results) = output["Results"].as_array() else {
+        return Vec::new();
+    };
+
+    results
+        .iter()
+        .flat_map(|result| {
+            let target = text(&result["Target"]);
+            result["Vulnerabilities"]
+                .as_array()
+                .cloned()
+                .unwrap_or_default()
+                .into_iter()
+                .map(move |vulnerability| Finding {
+                    repository: repository.to_string(),
+                    tool: ScanTool::Trivy.name(),
+                    id: text(&vulnerability["VulnerabilityID"]),
+                    package: text(&vulnerability["PkgName"]),
+                    version: text(&vulnerability["InstalledVersion"]),
+                    severity: Severity::from_name(&text(&vulnerability["Severity"])),
+                    title: text(&vulnerability["Title"]),
+                    fixed_version: vulnerability["FixedVersion"].as_str().map(String::from),
+                    file: target.clone(),
+                })
+        })
+        .collect()
+}
+
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Base score of a CVSS 3.x vector such as
+/// `CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H`
+fn cvss_base_score(vector: &str) -> Option<f64> {
+    let metric = |name: &str| {
+        vector
+            .split('/')
+            .find_map(|part| part.strip_prefix(name)?.strip_prefix(':'))
+    };
+    let changed = metric("S")? == "C";
+
+    let av = match metric("AV")? {
+        "N" => 0.85,
+        "A" => 0.62,
+        "L" => 0.55,
+        "P" => 0.2,
+        _ => return None,
+    };
+    let ac = match metric("AC")? {
+        "L" => 0.77,
+        "H" => 0.44,
+        _ => return None,
+    };
+    let pr = match (metric("PR")?, changed) {
+        ("N", _) => 0.85,
+        ("L", false) => 0.62,
+        ("L", true) => 0.68,
+        ("H", false) => 0.27,
+        ("H", true) => 0.5,
+        _ => return None,
+    };
+    let ui = match metric("UI")? {
+        "N" => 0.85,
+        "R" => 0.62,
+        _ => return None,
+    };
+    let impact_of = |name: &str| match metric(name)? {
+        "H" => Some(0.56),
+        "L" => Some(0.22),
+        "N" => Some(0.0),
+        _ => None,
+    };
+    let iss = 1.0 - (1.0 - impact_of("C")?) * (1.0 - impact_of("I")?) * (1.0 - impact_of("A")?);
+
+    let impact = if changed {
+        7.52 * (iss - 0.029) - 3.25 * (iss - 0.02f64).powi(15)
+    } else {
+        6.42 * iss
+    };
+    if impact <= 0.0 {
+        return Some(0.0);
+    }
+
+    let exploitability = 8.22 * av * ac * pr * ui;
+    let score = if changed {
+        1.08 * (impact + exploitability)
+    } else {
+        impact + exploitability
+    };
+    // CVSS rounds up to one decimal
+    Some((score.min(10.0) * 10.0 - 1e-9).ceil() / 10.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_cvss_base_score() {
+        assert_eq!(
+            cvss_base_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"),
+            Some(9.8)
+        );
+        assert_eq!(
+            cvss_base_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:R/S:C/C:L/I:L/A:N"),
+            Some(6.1)
+        );
+        assert_eq!(cvss_base_score("not a vector"), None);
+    }
+
+    #[test]
+    fn test_parse_scanner_output() {
+        let npm = json!({
+            "vulnerabilities": {
+                "lodash": {
+                    "severity": "moderate",
+                    "range": "<4.17.21",
+                    "via": [{"source": 1, "title": "Prototype Pollution",
+                             "url": "https://github.com/advisories/GHSA-xxxx"}]
+                }
+            }
+        });
+        let findings = parse_npm_audit("web", &npm);
+        assert_eq!(findings[0].id, "GHSA-xxxx");
+        assert_eq!(findings[0].severity, Severity::Medium);
+
+        let trivy = json!({
+            "Results": [{
+                "Target": "go.sum",
+                "Vulnerabilities": [{"VulnerabilityID": "CVE-1", "PkgName": "x/net",
+                    "InstalledVersion": "0.1.0", "FixedVersion": "0.2.0",
+                    "Severity": "CRITICAL", "Title": "Bad"}]
+            }]
+        });
+        let findings = parse_trivy("api", &trivy);
+        assert_eq!(findings[0].severity, Severity::Critical);
+        assert_eq!(findings[0].file, "go.sum");
+        assert!(findings[0].severity >= Severity::High);
+    }
+}
@@ -0,0 +1,128 @@
+//! Remote code search command implementation
+
+use super::{Command, CommandContext};
+use crate::github::{CodeSearchItem, GitHubClient};
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+
+/// GitHub rejects search queries longer than this
+const MAX_QUERY_LENGTH: usize = 256;
+
+/// Search remote command for finding code across repositories without cloning
+pub struct SearchRemoteCommand {
+    pub query: String,
+    /// Search the whole organization instead of the configured repositories
+    pub org: Option<String>,
+    pub token: String,
+}
+
+#[async_trait]
+impl Command for SearchRemoteCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let client = GitHubClient::with_settings(
+            Some(self.token.clone()),
+            &context.config.github_settings(),
+        )?;
+
+        let queries = match &self.org {
+            Some(org) => vec![format!("{} org:{org}", self.query)],
+            None => {
+                let repositories = context.repositories();
+
+                if repositories.is_empty() {
+                    let filter_desc = match (&context.tag, &context.repos) {
+                        (Some(tag), Some(repos)) => {
+                            format!("tag '{tag}' and repositories {repos:?}")
+                        }
+                        (Some(tag), None) => format!("tag '{tag}'"),
+                        (None, Some(repos)) => format!("repositories {repos:?}"),
+                        (None, None) => "no repositories found".to_string(),
+                    };
+                    println!(
+                        "{}",
+                        format!("No repositories found with {filter_desc}").yellow()
+                    );
+                    return Ok(());
+                }
+
+                let full_names: Vec<String> = repositories
+                    .iter()
+                    .filter_map(|repo| client.parse_github_url(&repo.url).ok())
+                    .map(|(owner, name)| format!("{owner}/{name}"))
+                    .collect();
+                scoped_queries(&self.query, &full_names)?
+            }
+        };
+
+        let mut matches: Vec<CodeSearchItem> = Vec::new();
+        for query in &queries {
+            matches.extend(client.search_code(query).await?);
+        }
+        matches.sort_by(|a, b| {
+            (&a.repository.full_name, &a.path).cmp(&(&b.repository.full_name, &b.path))
+        });
+
+        for item in &matches {
+            println!("{} | {}", item.repository.name.cyan().bold(), item.path);
+        }
+
+        let mut repos: Vec<&str> = matches
+            .iter()
+            .map(|m| m.repository.full_name.as_str())
+            .collect();
+        repos.dedup();
+        println!(
+            "{}",
+            format!("{} matches in {} repositories", matches.len(), repos.len()).green()
+        );
+
+        Ok(())
+    }
+}
+
+/// Split the repository qualifiers over as few queries as possible while
+/// staying under the search API's query length limit
+fn scoped_queries(query: &str, full_names: &[String]) -> Result<Vec<String>> {
+    let mut queries = Vec::new();
+    let mut current = query.to_string();
+
+    for full_name in full_names {
+        let qualifier = format!(" repo:{full_name}");
+        if current.len() > query.len() && current.len() + qualifier.len() > MAX_QUERY_LENGTH {
+            queries.push(std::mem::replace(&mut current, query.to_string()));
+        }
+        if current.len() + qualifier.len() > MAX_QUERY_LENGTH {
+            anyhow::bail!("Search query is too long to scope to repository {full_name}");
+        }
+        current.push_str(&qualifier);
+    }
+    if current.len() > query.len() {
+        queries.push(current);
+    }
+
+    Ok(queries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scoped_queries() {
+        let names: Vec<String> = (0..30)
+            .map(|i| format!("owner/repository-{i:02}"))
+            .collect();
+        let queries = scoped_queries("filename:Dockerfile", &names).unwrap();
+
+        assert!(queries.len() > 1);
+        assert!(queries.iter().all(|q| q.len() <= MAX_QUERY_LENGTH));
+        assert!(
+            queries
+                .iter()
+                .all(|q| q.starts_with("filename:Dockerfile repo:"))
+        );
+        let scoped: usize = queries.iter().map(|q| q.matches(" repo:").count()).sum();
+        assert_eq!(scoped, names.len());
+    }
+}
@@ -0,0 +1,253 @@
+//! Settings command implementation
+
+use super::{Command, CommandContext};
+use crate::config::Repository;
+use crate::git::Logger;
+use crate::github::GitHubClient;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use colored::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Repository settings operations
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum SettingsAction {
+    /// Update GitHub repository settings to match a settings file
+    Apply {
+        /// Settings file
+        file: String,
+
+        /// Specific repository names to update (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Only show the differences without applying them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// GitHub token (falls back to GITHUB_TOKEN)
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+}
+
+/// Desired repository settings; unset fields are left as they are
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RepoSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_merge_commit: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_squash_merge: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_rebase_merge: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delete_branch_on_merge: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_wiki: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_issues: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_projects: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_branch: Option<String>,
+    /// Replaces the full topic list
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topics: Option<Vec<String>>,
+}
+
+impl RepoSettings {
+    /// Load settings from a YAML file
+    pub fn load(path: &str) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read settings file '{path}'"))?;
+        serde_yaml::from_str(&content).with_context(|| format!("Invalid settings file '{path}'"))
+    }
+
+    /// Settings whose desired value differs from the repository resource
+    /// returned by the API, as `(name, current, desired)`
+    pub fn diff(&self, current: &Value) -> Vec<(String, Value, Value)> {
+        let Ok(Value::Object(desired)) = serde_json::to_value(self) else {
+            return Vec::new();
+        };
+
+        desired
+            .into_iter()
+            .filter_map(|(key, desired)| {
+                let current = current.get(&key).cloned().unwrap_or(Value::Null);
+                let same = match (&current, &desired) {
+                    // Topic order is not significant
+                    (Value::Array(a), Value::Array(b)) => {
+                        let mut a: Vec<String> = a.iter().map(Value::to_string).collect();
+                        let mut b: Vec<String> = b.iter().map(Value::to_string).collect();
+                        a.sort();
+                        b.sort();
+                        a == b
+                    }
+                    (current, desired) => current == desired,
+                };
+                (!same).then_some((key, current, desired))
+            })
+            .collect()
+    }
+}
+
+/// Settings command for syncing repository settings through the GitHub API
+pub struct SettingsApplyCommand {
+    pub settings: RepoSettings,
+    pub dry_run: bool,
+    pub token: Option<String>,
+}
+
+#[async_trait]
+impl Command for SettingsApplyCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            format!(
+                "{} settings of {} repositories...",
+                if self.dry_run {
+                    "Comparing"
+                } else {
+                    "Applying"
+                },
+                repositories.len()
+            )
+            .green()
+        );
+
+        let client =
+            GitHubClient::with_settings(self.token.clone(), &context.config.github_settings())?;
+
+        let mut changed = 0;
+        let mut failed = 0;
+        for repo in &repositories {
+            match self.apply(&client, repo).await {
+                Ok(true) => changed += 1,
+                Ok(false) => {}
+                Err(e) => {
+                    failed += 1;
+                    eprintln!(
+                        "{} | {}",
+                        repo.name.cyan().bold(),
+                        format!("Error: {e}").red()
+                    );
+                }
+            }
+        }
+
+        let verb = if self.dry_run {
+            "would change"
+        } else {
+            "changed"
+        };
+        println!(
+            "{}",
+            format!(
+                "Settings {verb} in {changed} of {} repositories",
+                repositories.len()
+            )
+            .green()
+        );
+
+        if failed > 0 {
+            anyhow::bail!("{} repositories failed", failed);
+        }
+        Ok(())
+    }
+}
+
+impl SettingsApplyCommand {
+    /// Compare and update one repository, returning whether anything differed
+    async fn apply(&self, client: &GitHubClient, repo: &Repository) -> Result<bool> {
+        let logger = Logger;
+        let (owner, name) = client.parse_github_url(&repo.url)?;
+        let current = client.repository_settings(&owner, &name).await?;
+
+        let changes = self.settings.diff(&current);
+        if changes.is_empty() {
+            logger.info(repo, "Up to date");
+            return Ok(false);
+        }
+
+        for (key, current, desired) in &changes {
+            println!(
+                "{} | {key}: {current} -> {desired}",
+                repo.name.cyan().bold()
+            );
+        }
+        if self.dry_run {
+            return Ok(true);
+        }
+
+        let mut update = serde_json::Map::new();
+        for (key, _, desired) in changes {
+            if key == "topics" {
+                let topics: Vec<String> = serde_json::from_value(desired)?;
+                client.replace_topics(&owner, &name, &topics).await?;
+            } else {
+                update.insert(key, desired);
+            }
+        }
+        if !update.is_empty() {
+            client
+                .update_repository(&owner, &name, &Value::Object(update))
+                .await?;
+        }
+
+        logger.success(repo, "Settings updated");
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_diff() {
+        let settings: RepoSettings = serde_yaml::from_str(
+            "allow_merge_commit: false\ndelete_branch_on_merge: true\nhas_wiki: false\ntopics: [rust, cli]\n",
+        )
+        .unwrap();
+        let current = serde_json::json!({
+            "allow_merge_commit": true,
+            "delete_branch_on_merge": true,
+            "has_wiki": true,
+            "has_issues": true,
+            "topics": ["cli", "rust"],
+        });
+
+        let keys: Vec<String> = settings
+            .diff(&current)
+            .into_iter()
+            .map(|(key, _, _)| key)
+            .collect();
+        assert_eq!(keys, vec!["allow_merge_commit", "has_wiki"]);
+        assert!(serde_yaml::from_str::<RepoSettings>("wiki: false").is_err());
+    }
+}
@@ -0,0 +1,95 @@
+//! Shell command implementation
+
+use super::{Command, CommandContext};
+use crate::config::Repository;
+use crate::environment;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use colored::*;
+use std::io::IsTerminal;
+use std::path::Path;
+
+/// Exit status of a subshell that stops the remaining repositories
+pub const STOP_EXIT_CODE: i32 = 100;
+
+/// Shell command for opening an interactive subshell in each repository
+pub struct ShellCommand {
+    /// Shell to start instead of `$SHELL`
+    pub shell: Option<String>,
+}
+
+#[async_trait]
+impl Command for ShellCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        if !std::io::stdin().is_terminal() {
+            anyhow::bail!("The shell command requires an interactive terminal");
+        }
+
+        let repositories: Vec<Repository> = context
+            .repositories()
+            .into_iter()
+            .filter(|repo| {
+                if !repo.exists() {
+                    println!("{} | {}", repo.name.cyan().bold(), "Not cloned".dimmed());
+                }
+                repo.exists()
+            })
+            .collect();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        let shell = self.shell.clone().unwrap_or_else(default_shell);
+        let total = repositories.len();
+
+        for (index, repo) in repositories.iter().enumerate() {
+            println!(
+                "{} | {}",
+                repo.name.cyan().bold(),
+                format!(
+                    "Entering {} ({}/{total}); exit to continue, `exit {STOP_EXIT_CODE}` to stop",
+                    repo.get_target_dir(),
+                    index + 1
+                )
+                .green()
+            );
+
+            let status = std::process::Command::new(&shell)
+                .current_dir(Path::new(&repo.get_target_dir()))
+                .envs(environment::repository_vars(repo))
+                .status()
+                .with_context(|| format!("Failed to start shell '{shell}'"))?;
+
+            if status.code() == Some(STOP_EXIT_CODE) {
+                println!(
+                    "{}",
+                    format!("Stopped, skipping {} repositories", total - index - 1).yellow()
+                );
+                return Ok(());
+            }
+        }
+
+        println!("{}", "Done with all repositories".green());
+        Ok(())
+    }
+}
+
+/// The user's login shell, falling back to the platform default
+fn default_shell() -> String {
+    if cfg!(windows) {
+        std::env::var("COMSPEC").unwrap_or_else(|_| "cmd".to_string())
+    } else {
+        std::env::var("SHELL").unwrap_or_else(|_| "sh".to_string())
+    }
+}
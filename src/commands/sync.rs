@@ -0,0 +1,217 @@
+//! Sync command implementation
+
+use super::verify::same_remote;
+use super::{Command, CommandContext};
+use crate::config::{HostSettings, Repository};
+use crate::git::{self, Logger};
+use crate::scheduler::{self, HostLimiter};
+use crate::util;
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+use std::path::Path;
+
+/// Sync command updating clones: branches are fast-forwarded to their
+/// upstream, repositories with a `ref` are kept at that tag or commit
+pub struct SyncCommand {
+    /// Point `origin` at the configured URL when it differs
+    pub fix_remote: bool,
+}
+
+#[async_trait]
+impl Command for SyncCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            format!("Syncing {} repositories...", repositories.len()).green()
+        );
+
+        let mut results = Vec::new();
+        if context.parallel {
+            let limiter = HostLimiter::new(&context.config, &repositories);
+            let tasks: Vec<_> = repositories
+                .into_iter()
+                .map(|repo| {
+                    let limiter = limiter.clone();
+                    let settings = repo.host_settings(&context.config);
+                    let fix_remote = self.fix_remote;
+                    tokio::spawn(async move {
+                        let _permit = limiter.acquire(&repo).await;
+                        let _slot = scheduler::job_slot().await;
+                        tokio::task::spawn_blocking(move || {
+                            let result = sync_repository(&repo, &settings, fix_remote);
+                            (repo, result)
+                        })
+                        .await
+                    })
+                })
+                .collect();
+            for task in tasks {
+                results.push(task.await??);
+            }
+        } else {
+            for repo in repositories {
+                let settings = repo.host_settings(&context.config);
+                let result = sync_repository(&repo, &settings, self.fix_remote);
+                results.push((repo, result));
+            }
+        }
+
+        for (repo, result) in &results {
+            if let Err(e) = result {
+                eprintln!(
+                    "{} | {}",
+                    repo.name.cyan().bold(),
+                    format!("Error: {e}").red()
+                );
+            }
+        }
+
+        println!("{}", "Done syncing repositories".green());
+        Ok(())
+    }
+}
+
+/// Fetch a clone, then fast-forward its branch or check out its pinned `ref`
+pub(crate) fn sync_repository(
+    repo: &Repository,
+    settings: &HostSettings,
+    fix_remote: bool,
+) -> Result<()> {
+    let logger = Logger;
+    let repo_dir = repo.get_target_dir();
+    if !Path::new(&repo_dir).exists() {
+        logger.warn(repo, "Repository directory does not exist, skipping");
+        return Ok(());
+    }
+
+    // Never fetch from a remote the config no longer points at
+    check_origin(repo, fix_remote)?;
+
+    // Tags are needed to resolve pinned refs
+    git::fetch(repo, settings, repo.reference.is_some())?;
+
+    if let Some(reference) = &repo.reference {
+        let pinned = git::resolve_commit(&repo_dir, reference)?;
+        if git::head_sha(&repo_dir)? == pinned {
+            logger.info(repo, &format!("Pinned at {reference}"));
+            check_size(repo);
+            return Ok(());
+        }
+        if git::has_changes(&repo_dir)? {
+            anyhow::bail!("Uncommitted changes, not checking out {}", reference);
+        }
+        git::checkout(&repo_dir, &pinned)?;
+        logger.success(repo, &format!("Checked out {reference}"));
+        check_size(repo);
+        return Ok(());
+    }
+
+    if git::current_branch(&repo_dir)?.is_none() {
+        logger.warn(repo, "HEAD is detached, skipping");
+        return Ok(());
+    }
+    if git::fast_forward(&repo_dir)? {
+        logger.success(repo, "Fast-forwarded");
+    } else {
+        logger.info(repo, "Already up to date");
+    }
+    check_size(repo);
+    Ok(())
+}
+
+/// Warn when a clone outgrew its `max_size`, e.g. after accidentally
+/// pulling large LFS objects
+fn check_size(repo: &Repository) {
+    let Some(max_size) = repo.max_size() else {
+        return;
+    };
+    let size = util::dir_size(Path::new(&repo.get_target_dir()));
+    if size > max_size {
+        Logger.warn(
+            repo,
+            &format!(
+                "Clone is {}, over its max_size of {}",
+                util::format_size(size),
+                util::format_size(max_size)
+            ),
+        );
+    }
+}
+
+/// Check that a clone's `origin` is the configured URL, e.g. after the
+/// repository was renamed or moved to another organization; with
+/// `fix_remote` a differing `origin` is repointed instead of reported
+pub(crate) fn check_origin(repo: &Repository, fix_remote: bool) -> Result<()> {
+    let repo_dir = repo.get_target_dir();
+    let origin = git::remote_url(&repo_dir, "origin")?;
+    if same_remote(&origin, &repo.url) {
+        return Ok(());
+    }
+    if !fix_remote {
+        anyhow::bail!(
+            "Remote 'origin' points to {}, expected {} (use --fix-remote to repoint it)",
+            origin,
+            repo.url
+        );
+    }
+    git::set_remote_url(&repo_dir, "origin", &repo.url)?;
+    Logger.info(
+        repo,
+        &format!("Repointed origin from {origin} to {}", repo.url),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+
+    #[test]
+    fn test_check_origin() {
+        let dir = std::env::temp_dir().join(format!("rrepos-sync-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        test_util::git(&dir, &["init", "--quiet"]);
+        test_util::git(
+            &dir,
+            &["remote", "add", "origin", "git@github.com:old-org/api.git"],
+        );
+
+        let mut repo = Repository::new("api".to_string(), "git@github.com:new-org/api".to_string());
+        repo.path = Some(dir.to_string_lossy().to_string());
+        let origin = || git::remote_url(&dir.to_string_lossy(), "origin").unwrap();
+
+        let drift = check_origin(&repo, false).unwrap_err().to_string();
+        let before_fix = origin();
+        check_origin(&repo, true).unwrap();
+        let after_fix = origin();
+        let matches = check_origin(&repo, false).is_ok();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            drift,
+            "Remote 'origin' points to git@github.com:old-org/api.git, expected \
+             git@github.com:new-org/api (use --fix-remote to repoint it)"
+        );
+        assert_eq!(before_fix, "git@github.com:old-org/api.git");
+        assert_eq!(after_fix, "git@github.com:new-org/api");
+        assert!(matches);
+    }
+}
@@ -0,0 +1,163 @@
+//! Sync-files command implementation
+
+use super::{Command, CommandContext};
+use crate::config::Repository;
+use crate::git::Logger;
+use crate::template;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// A single file to propagate into each repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncFile {
+    /// Source file, relative to the map file
+    pub source: String,
+    /// Target path, relative to the repository root
+    pub target: String,
+    /// Render `{placeholder}` variables in the source before writing
+    #[serde(default)]
+    pub render: bool,
+}
+
+/// File map describing which shared files to sync
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncMap {
+    pub files: Vec<SyncFile>,
+    /// Extra per-repository variables, keyed by repository name
+    #[serde(default)]
+    pub variables: HashMap<String, HashMap<String, String>>,
+    #[serde(skip)]
+    pub base_dir: PathBuf,
+}
+
+impl SyncMap {
+    /// Load a sync map from a YAML file
+    pub fn load(path: &str) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read sync map '{path}'"))?;
+        let mut map: SyncMap = serde_yaml::from_str(&content)?;
+        map.base_dir = Path::new(path)
+            .parent()
+            .map(|p| p.to_path_buf())
+            .unwrap_or_default();
+        Ok(map)
+    }
+
+    /// Render the content of a sync file for a given repository
+    pub fn content_for(&self, file: &SyncFile, repo: &Repository) -> Result<String> {
+        let source = self.base_dir.join(&file.source);
+        let content = std::fs::read_to_string(&source)
+            .with_context(|| format!("Failed to read source file '{}'", source.display()))?;
+
+        if !file.render {
+            return Ok(content);
+        }
+
+        let mut vars = template::repository_variables(repo);
+        if let Some(extra) = self.variables.get(&repo.name) {
+            vars.extend(extra.clone());
+        }
+        Ok(template::render(&content, &vars))
+    }
+
+    /// Files whose target in `repo` differs from the rendered source,
+    /// with the content they should have
+    pub fn outdated_files(&self, repo: &Repository) -> Result<Vec<(&SyncFile, String)>> {
+        let repo_dir = repo.get_target_dir();
+        let mut outdated = Vec::new();
+
+        for file in &self.files {
+            let content = self.content_for(file, repo)?;
+            let current = std::fs::read_to_string(Path::new(&repo_dir).join(&file.target)).ok();
+            if current.as_deref() != Some(content.as_str()) {
+                outdated.push((file, content));
+            }
+        }
+
+        Ok(outdated)
+    }
+}
+
+/// Sync-files command for propagating shared files across repositories
+pub struct SyncFilesCommand {
+    pub map: String,
+    pub dry_run: bool,
+}
+
+#[async_trait]
+impl Command for SyncFilesCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let map = SyncMap::load(&self.map)?;
+
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            format!(
+                "Syncing {} files into {} repositories...",
+                map.files.len(),
+                repositories.len()
+            )
+            .green()
+        );
+
+        for repo in repositories {
+            if let Err(e) = self.sync_repository(&map, &repo) {
+                eprintln!(
+                    "{} | {}",
+                    repo.name.cyan().bold(),
+                    format!("Error: {e}").red()
+                );
+            }
+        }
+
+        println!("{}", "Done syncing files".green());
+        Ok(())
+    }
+}
+
+impl SyncFilesCommand {
+    fn sync_repository(&self, map: &SyncMap, repo: &Repository) -> Result<()> {
+        let logger = Logger;
+        let repo_dir = repo.get_target_dir();
+
+        if !Path::new(&repo_dir).exists() {
+            anyhow::bail!("Repository directory does not exist: {}", repo_dir);
+        }
+
+        for (file, content) in map.outdated_files(repo)? {
+            let target = Path::new(&repo_dir).join(&file.target);
+
+            if self.dry_run {
+                logger.info(repo, &format!("Would update {}", file.target));
+                continue;
+            }
+
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&target, content)?;
+            logger.success(repo, &format!("Updated {}", file.target));
+        }
+
+        Ok(())
+    }
+}
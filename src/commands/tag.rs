@@ -0,0 +1,175 @@
+//! Tag command implementation
+
+use super::{Command, CommandContext};
+use crate::github::GitHubClient;
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+
+/// Operations on repository tags
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum TagAction {
+    /// Merge each repository's GitHub topics into its tags
+    ImportTopics {
+        /// Specific repository names to update (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Prefix added to topic tags; stale tags with this prefix are removed
+        #[arg(long, default_value = "topic:")]
+        prefix: String,
+
+        /// Only show the tag changes
+        #[arg(long)]
+        dry_run: bool,
+
+        /// GitHub token (falls back to GITHUB_TOKEN; optional for public repositories)
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+}
+
+/// Import topics command for syncing GitHub topics into config tags
+pub struct ImportTopicsCommand {
+    pub prefix: String,
+    pub dry_run: bool,
+    pub token: Option<String>,
+    /// Config file to update
+    pub config_path: String,
+}
+
+#[async_trait]
+impl Command for ImportTopicsCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            format!(
+                "Importing topics for {} repositories...",
+                repositories.len()
+            )
+            .green()
+        );
+
+        let client =
+            GitHubClient::with_settings(self.token.clone(), &context.config.github_settings())?;
+        let mut config = context.config.clone();
+        let mut updated = 0;
+
+        for repo in &repositories {
+            let topics = match client.parse_github_url(&repo.url) {
+                Ok((owner, name)) => client.get_repository(&owner, &name).await.map(|r| r.topics),
+                Err(e) => Err(e),
+            };
+            let topics = match topics {
+                Ok(topics) => topics,
+                Err(e) => {
+                    eprintln!(
+                        "{} | {}",
+                        repo.name.cyan().bold(),
+                        format!("Error: {e}").red()
+                    );
+                    continue;
+                }
+            };
+
+            let tags = merge_topics(&repo.tags, &topics, &self.prefix);
+            if tags == repo.tags {
+                println!("{} | {}", repo.name.cyan().bold(), "Up to date".dimmed());
+                continue;
+            }
+
+            println!(
+                "{} | {} -> {}",
+                repo.name.cyan().bold(),
+                repo.tags.join(", "),
+                tags.join(", ").green()
+            );
+            if let Some(entry) = config.get_repository_mut(&repo.name) {
+                entry.tags = tags;
+                updated += 1;
+            }
+        }
+
+        if self.dry_run {
+            println!("{}", "Dry run, config not updated".yellow());
+            return Ok(());
+        }
+
+        if updated > 0 {
+            config.save(&self.config_path)?;
+            println!(
+                "{}",
+                format!(
+                    "Updated tags of {updated} repositories in {}",
+                    self.config_path
+                )
+                .green()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Replace the prefixed tags in `tags` with the given topics, keeping all
+/// other tags in place
+fn merge_topics(tags: &[String], topics: &[String], prefix: &str) -> Vec<String> {
+    let mut merged: Vec<String> = tags
+        .iter()
+        .filter(|tag| !tag.starts_with(prefix))
+        .cloned()
+        .collect();
+
+    let mut topic_tags: Vec<String> = topics.iter().map(|t| format!("{prefix}{t}")).collect();
+    topic_tags.sort();
+    topic_tags.dedup();
+    merged.extend(topic_tags);
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_merge_topics() {
+        let tags = strings(&["backend", "topic:stale", "rust"]);
+        let topics = strings(&["web", "api"]);
+
+        assert_eq!(
+            merge_topics(&tags, &topics, "topic:"),
+            strings(&["backend", "rust", "topic:api", "topic:web"])
+        );
+        assert_eq!(
+            merge_topics(&tags, &[], "topic:"),
+            strings(&["backend", "rust"])
+        );
+    }
+}
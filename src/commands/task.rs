@@ -0,0 +1,110 @@
+//! Task command implementation
+
+use super::{Command, CommandContext};
+use crate::config::Repository;
+use crate::git::Logger;
+use crate::runner::{CommandRunner, PathFilter};
+use crate::scheduler;
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+
+/// Task command for running a named task from the config in each repository
+pub struct TaskCommand {
+    /// Task to run; the configured tasks are listed when unset
+    pub name: Option<String>,
+    pub log_dir: String,
+    /// Only run in repositories containing matching files
+    pub path_filter: PathFilter,
+}
+
+#[async_trait]
+impl Command for TaskCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let Some(name) = &self.name else {
+            if context.config.tasks.is_empty() {
+                println!("{}", "No tasks configured".yellow());
+            }
+            for name in context.config.tasks.keys() {
+                println!("{name}");
+            }
+            return Ok(());
+        };
+
+        let Some(task) = context.config.tasks.get(name) else {
+            anyhow::bail!("Unknown task '{}'", name);
+        };
+
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        self.path_filter.validate()?;
+        let logger = Logger;
+        let runs: Vec<(Repository, String)> = self
+            .path_filter
+            .apply(repositories)
+            .into_iter()
+            .filter_map(|repo| match task.command_for(&repo) {
+                Some(command) => {
+                    let command = command.to_string();
+                    Some((repo, command))
+                }
+                None => {
+                    logger.info(&repo, &format!("Task '{name}' is not defined, skipping"));
+                    None
+                }
+            })
+            .collect();
+
+        println!(
+            "{}",
+            format!("Running task '{name}' in {} repositories...", runs.len()).green()
+        );
+
+        let runner = CommandRunner::new();
+        let results = if context.parallel {
+            let tasks = runs.iter().map(|(repo, command)| {
+                scheduler::limited(runner.run_command(repo, command, Some(&self.log_dir)))
+            });
+            futures::future::join_all(tasks).await
+        } else {
+            let mut results = Vec::new();
+            for (repo, command) in &runs {
+                results.push(runner.run_command(repo, command, Some(&self.log_dir)).await);
+            }
+            results
+        };
+
+        let mut failed = 0;
+        for ((repo, _), result) in runs.iter().zip(results) {
+            if let Err(e) = result {
+                failed += 1;
+                eprintln!(
+                    "{} | {}",
+                    repo.name.cyan().bold(),
+                    format!("Error: {e}").red()
+                );
+            }
+        }
+
+        if failed > 0 {
+            anyhow::bail!("Task '{}' failed in {} repositories", name, failed);
+        }
+
+        println!("{}", format!("Task '{name}' completed").green());
+        Ok(())
+    }
+}
@@ -0,0 +1,448 @@
+//! Test command implementation
+
+use super::{Command, CommandContext};
+use crate::config::Repository;
+use crate::git::Logger;
+use crate::runner::{CommandOutput, CommandRunner};
+use crate::runs;
+use crate::scheduler;
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+use regex::Regex;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::Instant;
+
+/// Test runners with a preset invocation and output parser
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum TestPreset {
+    Cargo,
+    Npm,
+    Maven,
+}
+
+/// Outcome of running a repository's tests
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TestStatus {
+    Passed,
+    Failed,
+    /// The test command failed without reporting any results
+    Error,
+}
+
+/// Test counts reported by a test runner
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct TestCounts {
+    pub passed: u64,
+    pub failed: u64,
+    pub skipped: u64,
+}
+
+/// Result of running one preset in one repository
+#[derive(Debug, Clone, Serialize)]
+pub struct TestResult {
+    pub repository: String,
+    pub preset: TestPreset,
+    pub status: TestStatus,
+    #[serde(flatten)]
+    pub counts: TestCounts,
+    pub exit_code: i32,
+    pub duration_secs: f64,
+}
+
+/// Test command for running test suites across repositories
+pub struct TestCommand {
+    /// Presets to run; detected from tags and manifests when empty
+    pub presets: Vec<TestPreset>,
+    /// Write a JSON report of the results to this file
+    pub output: Option<String>,
+}
+
+impl TestPreset {
+    fn command(&self) -> &'static str {
+        match self {
+            TestPreset::Cargo => "cargo test",
+            TestPreset::Npm => "npm test",
+            TestPreset::Maven => "mvn -B test",
+        }
+    }
+
+    /// Whether the preset applies to a repository, judged by its tags or the
+    /// manifests in its directory
+    pub fn applies_to(&self, repo: &Repository, repo_dir: &Path) -> bool {
+        let tagged = |tags: &[&str]| repo.tags.iter().any(|t| tags.contains(&t.as_str()));
+        match self {
+            TestPreset::Cargo => tagged(&["rust"]) || repo_dir.join("Cargo.toml").exists(),
+            TestPreset::Npm => {
+                tagged(&["node", "javascript", "typescript"])
+                    || repo_dir.join("package.json").exists()
+            }
+            TestPreset::Maven => tagged(&["java", "maven"]) || repo_dir.join("pom.xml").exists(),
+        }
+    }
+
+    /// Parse the test counts from a test run's output, if it reported any
+    pub fn parse(&self, output: &[&str]) -> Option<TestCounts> {
+        match self {
+            TestPreset::Cargo => parse_cargo(output),
+            TestPreset::Npm => parse_npm(output),
+            TestPreset::Maven => parse_maven(output),
+        }
+    }
+}
+
+#[async_trait]
+impl Command for TestCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            format!("Testing {} repositories...", repositories.len()).green()
+        );
+
+        let logger = Logger;
+        let runner = CommandRunner::new();
+        let mut runs = Vec::new();
+
+        for repo in &repositories {
+            let repo_dir = repo.get_target_dir();
+            if !Path::new(&repo_dir).exists() {
+                logger.warn(repo, "Repository directory does not exist, skipping");
+                continue;
+            }
+
+            let candidates = if self.presets.is_empty() {
+                vec![TestPreset::Cargo, TestPreset::Npm, TestPreset::Maven]
+            } else {
+                self.presets.clone()
+            };
+            let presets: Vec<TestPreset> = candidates
+                .into_iter()
+                .filter(|preset| preset.applies_to(repo, Path::new(&repo_dir)))
+                .collect();
+
+            if presets.is_empty() {
+                logger.info(repo, "No applicable test presets");
+                continue;
+            }
+            runs.extend(presets.into_iter().map(|preset| (repo, preset)));
+        }
+
+        let results = if context.parallel {
+            let tasks = runs
+                .iter()
+                .map(|(repo, preset)| scheduler::limited(run_tests(&runner, repo, *preset)));
+            futures::future::join_all(tasks).await
+        } else {
+            let mut results = Vec::new();
+            for (repo, preset) in &runs {
+                results.push(run_tests(&runner, repo, *preset).await);
+            }
+            results
+        };
+
+        let mut reported = Vec::new();
+        let mut failed = 0;
+        for ((repo, preset), result) in runs.iter().zip(results) {
+            match result {
+                Ok(result) => {
+                    if result.status != TestStatus::Passed {
+                        failed += 1;
+                    }
+                    reported.push(result);
+                }
+                Err(e) => {
+                    failed += 1;
+                    eprintln!(
+                        "{} | {}",
+                        repo.name.cyan().bold(),
+                        format!("Error: {} failed: {e}", preset.command()).red()
+                    );
+                }
+            }
+        }
+
+        print_summary(&reported);
+
+        if let Some(path) = &self.output {
+            let report = serde_json::json!({
+                "run_id": runs::id(),
+                "results": reported,
+            });
+            std::fs::write(path, serde_json::to_string_pretty(&report)?)?;
+            runs::record_artifact(Path::new(path));
+            println!("{}", format!("Test report written to '{path}'").green());
+        }
+
+        if failed > 0 {
+            anyhow::bail!("{} test runs failed", failed);
+        }
+
+        println!("{}", "All tests passed".green());
+        Ok(())
+    }
+}
+
+async fn run_tests(
+    runner: &CommandRunner,
+    repo: &Repository,
+    preset: TestPreset,
+) -> Result<TestResult> {
+    let started = Instant::now();
+    let output = runner
+        .run_command_captured(repo, preset.command(), None)
+        .await?;
+    Ok(test_result(
+        repo,
+        preset,
+        &output,
+        started.elapsed().as_secs_f64(),
+    ))
+}
+
+fn test_result(
+    repo: &Repository,
+    preset: TestPreset,
+    output: &CommandOutput,
+    duration_secs: f64,
+) -> TestResult {
+    let lines: Vec<&str> = output.lines.iter().map(|l| l.text.as_str()).collect();
+    let counts = preset.parse(&lines);
+    let status = match counts {
+        Some(counts) if output.exit_code == 0 && counts.failed == 0 => TestStatus::Passed,
+        Some(_) => TestStatus::Failed,
+        None if output.exit_code == 0 => TestStatus::Passed,
+        None => TestStatus::Error,
+    };
+
+    TestResult {
+        repository: repo.name.clone(),
+        preset,
+        status,
+        counts: counts.unwrap_or_default(),
+        exit_code: output.exit_code,
+        duration_secs,
+    }
+}
+
+fn print_summary(results: &[TestResult]) {
+    let name_width = results
+        .iter()
+        .map(|r| r.repository.len())
+        .max()
+        .unwrap_or(0)
+        .max(10);
+
+    println!(
+        "\n{:<name_width$}  {:<6}  {:>7}  {:>7}  {:>7}  Status",
+        "Repository", "Preset", "Passed", "Failed", "Skipped"
+    );
+
+    let mut total = TestCounts::default();
+    for result in results {
+        let status = match result.status {
+            TestStatus::Passed => "ok".green(),
+            TestStatus::Failed => "FAILED".red(),
+            TestStatus::Error => "ERROR".red(),
+        };
+        println!(
+            "{:<name_width$}  {:<6}  {:>7}  {:>7}  {:>7}  {status}",
+            result.repository,
+            format!("{:?}", result.preset).to_lowercase(),
+            result.counts.passed,
+            result.counts.failed,
+            result.counts.skipped,
+        );
+        total.passed += result.counts.passed;
+        total.failed += result.counts.failed;
+        total.skipped += result.counts.skipped;
+    }
+
+    println!(
+        "{}",
+        format!(
+            "{:<name_width$}  {:<6}  {:>7}  {:>7}  {:>7}",
+            "Total", "", total.passed, total.failed, total.skipped
+        )
+        .bold()
+    );
+}
+
+/// Sum the `test result:` lines cargo prints for every test binary
+fn parse_cargo(output: &[&str]) -> Option<TestCounts> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let pattern = PATTERN.get_or_init(|| {
+        Regex::new(r"test result: \w+\. (\d+) passed; (\d+) failed; (\d+) ignored").unwrap()
+    });
+
+    let mut counts: Option<TestCounts> = None;
+    for caps in output.iter().filter_map(|line| pattern.captures(line)) {
+        let counts = counts.get_or_insert_with(TestCounts::default);
+        counts.passed += caps[1].parse::<u64>().unwrap_or(0);
+        counts.failed += caps[2].parse::<u64>().unwrap_or(0);
+        counts.skipped += caps[3].parse::<u64>().unwrap_or(0);
+    }
+    counts
+}
+
+/// Parse the summary of the common JavaScript test runners: Jest, Vitest,
+/// Mocha and the Node.js test runner
+fn parse_npm(output: &[&str]) -> Option<TestCounts> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let pattern = PATTERN.get_or_init(|| {
+        Regex::new(r"(\d+) (passed|passing|failed|failing|skipped|pending|todo)\b").unwrap()
+    });
+    static NODE: OnceLock<Regex> = OnceLock::new();
+    let node = NODE.get_or_init(|| Regex::new(r"^# (pass|fail|skipped|todo) (\d+)$").unwrap());
+
+    let mut counts: Option<TestCounts> = None;
+    for line in output.iter().map(|line| line.trim()) {
+        let found: Vec<(&str, u64)> = if let Some(caps) = node.captures(line) {
+            vec![(caps.get(1).unwrap().as_str(), caps[2].parse().unwrap_or(0))]
+        } else if line.starts_with("Tests:")
+            || line.starts_with("Tests ")
+            || pattern.find(line).is_some_and(|m| m.start() == 0)
+        {
+            pattern
+                .captures_iter(line)
+                .map(|caps| (caps.get(2).unwrap().as_str(), caps[1].parse().unwrap_or(0)))
+                .collect()
+        } else {
+            continue;
+        };
+
+        let counts = counts.get_or_insert_with(TestCounts::default);
+        for (kind, count) in found {
+            match kind {
+                "passed" | "passing" | "pass" => counts.passed += count,
+                "failed" | "failing" | "fail" => counts.failed += count,
+                _ => counts.skipped += count,
+            }
+        }
+    }
+    counts
+}
+
+/// Parse the totals Maven Surefire prints after the per-class results
+fn parse_maven(output: &[&str]) -> Option<TestCounts> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let pattern = PATTERN.get_or_init(|| {
+        Regex::new(r"Tests run: (\d+), Failures: (\d+), Errors: (\d+), Skipped: (\d+)\s*$").unwrap()
+    });
+
+    // Each module prints its totals under `Results:`, after the per-class
+    // lines; sum the totals of every module
+    let mut counts: Option<TestCounts> = None;
+    let mut in_results = false;
+    for line in output {
+        if line.contains("Results:") {
+            in_results = true;
+            continue;
+        }
+        if !in_results {
+            continue;
+        }
+        if let Some(caps) = pattern.captures(line) {
+            let [run, failures, errors, skipped] =
+                [1, 2, 3, 4].map(|i| caps[i].parse::<u64>().unwrap_or(0));
+            let counts = counts.get_or_insert_with(TestCounts::default);
+            counts.failed += failures + errors;
+            counts.skipped += skipped;
+            counts.passed += run.saturating_sub(failures + errors + skipped);
+            in_results = false;
+        }
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cargo() {
+        let output = [
+            "test result: ok. 73 passed; 0 failed; 1 ignored; 0 measured; 0 filtered out; finished in 0.86s",
+            "test result: FAILED. 2 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out",
+        ];
+        assert_eq!(
+            parse_cargo(&output),
+            Some(TestCounts {
+                passed: 75,
+                failed: 1,
+                skipped: 1
+            })
+        );
+        assert_eq!(parse_cargo(&["Compiling app v0.1.0"]), None);
+    }
+
+    #[test]
+    fn test_parse_npm() {
+        let jest = ["Tests:       1 failed, 2 skipped, 5 passed, 8 total"];
+        assert_eq!(
+            parse_npm(&jest),
+            Some(TestCounts {
+                passed: 5,
+                failed: 1,
+                skipped: 2
+            })
+        );
+
+        let mocha = ["  5 passing (12ms)", "  1 pending", "  2 failing"];
+        assert_eq!(
+            parse_npm(&mocha),
+            Some(TestCounts {
+                passed: 5,
+                failed: 2,
+                skipped: 1
+            })
+        );
+
+        let node = ["# tests 4", "# pass 3", "# fail 1"];
+        assert_eq!(
+            parse_npm(&node),
+            Some(TestCounts {
+                passed: 3,
+                failed: 1,
+                skipped: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_maven() {
+        let output = [
+            "[INFO] Tests run: 3, Failures: 0, Errors: 0, Skipped: 0, Time elapsed: 0.1 s -- in com.example.AppTest",
+            "[INFO] Results:",
+            "[INFO] ",
+            "[ERROR] Tests run: 10, Failures: 1, Errors: 1, Skipped: 2",
+        ];
+        assert_eq!(
+            parse_maven(&output),
+            Some(TestCounts {
+                passed: 6,
+                failed: 2,
+                skipped: 2
+            })
+        );
+    }
+}
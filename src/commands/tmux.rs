@@ -0,0 +1,153 @@
+//! Tmux command implementation
+
+use super::{Command, CommandContext};
+use crate::config::Repository;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use colored::*;
+use std::io::IsTerminal;
+
+/// Tmux command for opening a session with a window per repository
+pub struct TmuxCommand {
+    pub session: String,
+    /// Command typed into every window after it opens
+    pub command: Option<String>,
+    /// Use one tiled pane per repository in a single window instead of windows
+    pub panes: bool,
+    /// Create the session without attaching to it
+    pub detach: bool,
+}
+
+#[async_trait]
+impl Command for TmuxCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories: Vec<Repository> = context
+            .repositories()
+            .into_iter()
+            .filter(|repo| {
+                if !repo.exists() {
+                    println!("{} | {}", repo.name.cyan().bold(), "Not cloned".dimmed());
+                }
+                repo.exists()
+            })
+            .collect();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        if tmux(&["has-session", "-t", &format!("={}", self.session)]).is_ok() {
+            anyhow::bail!(
+                "tmux session '{}' already exists; use --session to pick another name",
+                self.session
+            );
+        }
+
+        for (index, repo) in repositories.iter().enumerate() {
+            let dir = repo.get_target_dir();
+            let target = if index == 0 {
+                tmux(&[
+                    "new-session",
+                    "-d",
+                    "-P",
+                    "-F",
+                    "#{pane_id}",
+                    "-s",
+                    &self.session,
+                    "-n",
+                    if self.panes { "rrepos" } else { &repo.name },
+                    "-c",
+                    &dir,
+                ])?
+            } else if self.panes {
+                let pane = tmux(&[
+                    "split-window",
+                    "-P",
+                    "-F",
+                    "#{pane_id}",
+                    "-t",
+                    &self.session,
+                    "-c",
+                    &dir,
+                ])?;
+                // Re-tile after every split so panes don't get too small to split
+                tmux(&["select-layout", "-t", &self.session, "tiled"])?;
+                pane
+            } else {
+                tmux(&[
+                    "new-window",
+                    "-P",
+                    "-F",
+                    "#{pane_id}",
+                    "-t",
+                    &self.session,
+                    "-n",
+                    &repo.name,
+                    "-c",
+                    &dir,
+                ])?
+            };
+
+            if let Some(command) = &self.command {
+                tmux(&["send-keys", "-t", &target, command, "Enter"])?;
+            }
+            println!("{} | {}", repo.name.cyan().bold(), "Opened".green());
+        }
+
+        println!(
+            "{}",
+            format!(
+                "Created tmux session '{}' with {} {}",
+                self.session,
+                repositories.len(),
+                if self.panes { "panes" } else { "windows" }
+            )
+            .green()
+        );
+
+        if self.detach || !std::io::stdin().is_terminal() {
+            return Ok(());
+        }
+
+        // Inside tmux, attaching would nest sessions; switch to it instead
+        let attach = if std::env::var_os("TMUX").is_some() {
+            "switch-client"
+        } else {
+            "attach-session"
+        };
+        std::process::Command::new("tmux")
+            .args([attach, "-t", &self.session])
+            .status()
+            .context("Failed to execute tmux")?;
+
+        Ok(())
+    }
+}
+
+/// Run a tmux command, returning its trimmed output
+fn tmux(args: &[&str]) -> Result<String> {
+    let output = std::process::Command::new("tmux")
+        .args(args)
+        .output()
+        .context("Failed to execute tmux; is it installed?")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "tmux {} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
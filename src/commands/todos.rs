@@ -0,0 +1,207 @@
+//! Todos command implementation
+
+use super::{Command, CommandContext};
+use crate::config::Repository;
+use crate::git::{self, Logger};
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+use regex::Regex;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// Default pattern for code annotations
+pub const DEFAULT_TODO_PATTERN: &str = r"\b(TODO|FIXME|HACK)\b";
+
+/// Output format for the annotation report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum TodosFormat {
+    Markdown,
+    Json,
+}
+
+/// Todos command for collecting code annotations across repositories
+pub struct TodosCommand {
+    pub pattern: String,
+    /// Look up the last author of every annotated line
+    pub assignee_from_blame: bool,
+    pub format: TodosFormat,
+    pub output: Option<String>,
+}
+
+/// A single annotation found in a repository
+#[derive(Debug, Serialize)]
+struct Annotation {
+    repository: String,
+    file: String,
+    line: usize,
+    /// The text matched by the pattern, e.g. `TODO`
+    tag: String,
+    text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    author: Option<String>,
+}
+
+#[async_trait]
+impl Command for TodosCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let regex = Regex::new(&self.pattern)?;
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            eprintln!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        let logger = Logger;
+        let mut annotations = Vec::new();
+
+        for repo in &repositories {
+            if !Path::new(&repo.get_target_dir()).exists() {
+                logger.warn(repo, "Repository directory does not exist, skipping");
+                continue;
+            }
+
+            match self.scan_repository(repo, &regex) {
+                Ok(found) => annotations.extend(found),
+                Err(e) => eprintln!(
+                    "{} | {}",
+                    repo.name.cyan().bold(),
+                    format!("Error: {e}").red()
+                ),
+            }
+        }
+
+        let document = match self.format {
+            TodosFormat::Markdown => render_markdown(&annotations),
+            TodosFormat::Json => serde_json::to_string_pretty(&annotations)?,
+        };
+
+        match &self.output {
+            Some(path) => {
+                std::fs::write(path, document)?;
+                eprintln!(
+                    "{}",
+                    format!("{} annotations written to '{path}'", annotations.len()).green()
+                );
+            }
+            None => println!("{document}"),
+        }
+
+        Ok(())
+    }
+}
+
+impl TodosCommand {
+    fn scan_repository(&self, repo: &Repository, regex: &Regex) -> Result<Vec<Annotation>> {
+        let repo_dir = repo.get_target_dir();
+        let mut annotations = Vec::new();
+
+        for file in git::tracked_files(&repo_dir)? {
+            // Skip binary, non-UTF-8 and deleted files
+            let Ok(content) = std::fs::read_to_string(Path::new(&repo_dir).join(&file)) else {
+                continue;
+            };
+
+            let found = find_annotations(&content, regex);
+            if found.is_empty() {
+                continue;
+            }
+
+            let authors = if self.assignee_from_blame {
+                git::blame_authors(&repo_dir, &file).unwrap_or_default()
+            } else {
+                Vec::new()
+            };
+
+            annotations.extend(found.into_iter().map(|(line, tag, text)| Annotation {
+                repository: repo.name.clone(),
+                file: file.clone(),
+                line,
+                tag,
+                text,
+                author: authors.get(line - 1).cloned(),
+            }));
+        }
+
+        Ok(annotations)
+    }
+}
+
+/// Find `(line number, matched tag, line text)` for every line matching `regex`
+fn find_annotations(content: &str, regex: &Regex) -> Vec<(usize, String, String)> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let tag = regex.find(line)?;
+            Some((index + 1, tag.as_str().to_string(), line.trim().to_string()))
+        })
+        .collect()
+}
+
+fn render_markdown(annotations: &[Annotation]) -> String {
+    let mut out = String::from("# Annotations\n");
+
+    let mut repositories: Vec<&str> = Vec::new();
+    for annotation in annotations {
+        if !repositories.contains(&annotation.repository.as_str()) {
+            repositories.push(&annotation.repository);
+        }
+    }
+
+    for repository in repositories {
+        let entries: Vec<&Annotation> = annotations
+            .iter()
+            .filter(|a| a.repository == repository)
+            .collect();
+        let _ = writeln!(out, "\n## {repository} ({})\n", entries.len());
+
+        for entry in entries {
+            let author = entry
+                .author
+                .as_deref()
+                .map(|a| format!(" ({a})"))
+                .unwrap_or_default();
+            let _ = writeln!(
+                out,
+                "- **{}** `{}:{}`{author}: {}",
+                entry.tag, entry.file, entry.line, entry.text
+            );
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_annotations() {
+        let regex = Regex::new(DEFAULT_TODO_PATTERN).unwrap();
+        let content = "fn main() {\n    // TODO: handle errors\n    let todos = 1; // FIXME\n}\n// TODOS are fine\n";
+        assert_eq!(
+            find_annotations(content, &regex),
+            vec![
+                (2, "TODO".to_string(), "// TODO: handle errors".to_string()),
+                (
+                    3,
+                    "FIXME".to_string(),
+                    "let todos = 1; // FIXME".to_string()
+                ),
+            ]
+        );
+    }
+}
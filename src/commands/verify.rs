@@ -0,0 +1,150 @@
+//! Verify command implementation
+
+use super::{Command, CommandContext};
+use crate::config::Repository;
+use crate::git;
+use crate::scheduler;
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+use std::path::Path;
+
+/// Verify command for checking the integrity of cloned repositories
+pub struct VerifyCommand;
+
+/// Result of verifying a single clone
+enum Verification {
+    Ok,
+    NotCloned,
+    Problems(Vec<String>),
+}
+
+#[async_trait]
+impl Command for VerifyCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            format!("Verifying {} repositories...", repositories.len()).green()
+        );
+
+        let mut results = Vec::new();
+        if context.parallel {
+            let tasks: Vec<_> = repositories
+                .into_iter()
+                .map(|repo| {
+                    scheduler::spawn_blocking(move || {
+                        let verification = verify_repository(&repo);
+                        (repo, verification)
+                    })
+                })
+                .collect();
+            for task in tasks {
+                results.push(task.await?);
+            }
+        } else {
+            for repo in repositories {
+                let verification = verify_repository(&repo);
+                results.push((repo, verification));
+            }
+        }
+
+        let mut failed = 0;
+        for (repo, verification) in &results {
+            match verification {
+                Verification::Ok => {
+                    println!("{} | {}", repo.name.cyan().bold(), "OK".green())
+                }
+                Verification::NotCloned => {
+                    println!("{} | {}", repo.name.cyan().bold(), "Not cloned".dimmed())
+                }
+                Verification::Problems(problems) => {
+                    failed += 1;
+                    for problem in problems {
+                        eprintln!("{} | {}", repo.name.cyan().bold(), problem.red());
+                    }
+                }
+            }
+        }
+
+        if failed > 0 {
+            anyhow::bail!("{} repositories failed verification", failed);
+        }
+
+        println!("{}", "All repositories verified".green());
+        Ok(())
+    }
+}
+
+fn verify_repository(repo: &Repository) -> Verification {
+    let repo_dir = repo.get_target_dir();
+    if !Path::new(&repo_dir).exists() {
+        return Verification::NotCloned;
+    }
+
+    let mut problems = Vec::new();
+
+    if let Err(e) = git::fsck(&repo_dir) {
+        problems.push(e.to_string());
+    }
+
+    match git::remote_url(&repo_dir, "origin") {
+        Ok(url) if !same_remote(&url, &repo.url) => problems.push(format!(
+            "Remote 'origin' points to {url}, expected {}",
+            repo.url
+        )),
+        Ok(_) => {}
+        Err(e) => problems.push(e.to_string()),
+    }
+
+    if problems.is_empty() {
+        Verification::Ok
+    } else {
+        Verification::Problems(problems)
+    }
+}
+
+/// Compare remote URLs, ignoring a trailing `.git` or slash
+pub(crate) fn same_remote(actual: &str, expected: &str) -> bool {
+    let normalize = |url: &str| {
+        let url = url.trim().trim_end_matches('/');
+        url.strip_suffix(".git").unwrap_or(url).to_string()
+    };
+    normalize(actual) == normalize(expected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_remote() {
+        assert!(same_remote(
+            "git@github.com:owner/repo.git",
+            "git@github.com:owner/repo"
+        ));
+        assert!(same_remote(
+            "https://github.com/owner/repo/",
+            "https://github.com/owner/repo.git"
+        ));
+        assert!(!same_remote(
+            "git@github.com:fork/repo.git",
+            "git@github.com:owner/repo.git"
+        ));
+    }
+}
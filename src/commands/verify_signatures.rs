@@ -0,0 +1,216 @@
+//! Verify signatures command implementation
+
+use super::{Command, CommandContext};
+use crate::config::Repository;
+use crate::git;
+use crate::scheduler;
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+use std::path::Path;
+
+/// Verify signatures command reporting unsigned or untrusted commits and tags
+pub struct VerifySignaturesCommand {
+    /// Reference whose history is checked; the checked out branch when unset
+    pub reference: Option<String>,
+    /// How many commits (and tags) to check
+    pub last: usize,
+    /// Check the latest tags too
+    pub tags: bool,
+}
+
+/// A commit or tag whose signature is missing or not trusted
+struct Finding {
+    object: String,
+    status: char,
+    /// Printed after the status, e.g. `: subject (author)`
+    detail: String,
+}
+
+/// Result of checking a single repository
+enum Report {
+    NotCloned,
+    Checked {
+        commits: usize,
+        tags: usize,
+        findings: Vec<Finding>,
+    },
+    Failed(String),
+}
+
+#[async_trait]
+impl Command for VerifySignaturesCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.repositories();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        let reference = self.reference.clone().unwrap_or_else(|| "HEAD".to_string());
+        println!(
+            "{}",
+            format!(
+                "Checking signatures of the last {} commits on {reference} in {} repositories...",
+                self.last,
+                repositories.len()
+            )
+            .green()
+        );
+
+        let mut results = Vec::new();
+        if context.parallel {
+            let tasks: Vec<_> = repositories
+                .into_iter()
+                .map(|repo| {
+                    let reference = reference.clone();
+                    let (last, tags) = (self.last, self.tags);
+                    scheduler::spawn_blocking(move || {
+                        let report = check_repository(&repo, &reference, last, tags);
+                        (repo, report)
+                    })
+                })
+                .collect();
+            for task in tasks {
+                results.push(task.await?);
+            }
+        } else {
+            for repo in repositories {
+                let report = check_repository(&repo, &reference, self.last, self.tags);
+                results.push((repo, report));
+            }
+        }
+
+        let mut flagged = 0;
+        for (repo, report) in &results {
+            match report {
+                Report::NotCloned => {
+                    println!("{} | {}", repo.name.cyan().bold(), "Not cloned".dimmed())
+                }
+                Report::Failed(e) => {
+                    flagged += 1;
+                    eprintln!(
+                        "{} | {}",
+                        repo.name.cyan().bold(),
+                        format!("Error: {e}").red()
+                    );
+                }
+                Report::Checked {
+                    commits,
+                    tags,
+                    findings,
+                } if findings.is_empty() => {
+                    let checked = if self.tags {
+                        format!("{commits} commits and {tags} tags signed")
+                    } else {
+                        format!("{commits} commits signed")
+                    };
+                    println!("{} | {}", repo.name.cyan().bold(), checked.green());
+                }
+                Report::Checked { findings, .. } => {
+                    flagged += 1;
+                    for finding in findings {
+                        eprintln!(
+                            "{} | {} {}{}",
+                            repo.name.cyan().bold(),
+                            finding.object.yellow(),
+                            describe(finding.status).red(),
+                            finding.detail
+                        );
+                    }
+                }
+            }
+        }
+
+        if flagged > 0 {
+            anyhow::bail!(
+                "{} repositories have unsigned or untrusted commits or tags",
+                flagged
+            );
+        }
+
+        println!("{}", "All signatures verified".green());
+        Ok(())
+    }
+}
+
+fn check_repository(repo: &Repository, reference: &str, last: usize, tags: bool) -> Report {
+    let repo_dir = repo.get_target_dir();
+    if !Path::new(&repo_dir).exists() {
+        return Report::NotCloned;
+    }
+
+    let commits = match git::commit_signatures(&repo_dir, reference, last) {
+        Ok(commits) => commits,
+        Err(e) => return Report::Failed(e.to_string()),
+    };
+    let mut findings: Vec<Finding> = commits
+        .iter()
+        .filter(|commit| commit.status != 'G')
+        .map(|commit| {
+            let signer = if commit.key.is_empty() {
+                String::new()
+            } else if commit.signer.is_empty() {
+                format!(", key {}", commit.key)
+            } else {
+                format!(", signed by {} with key {}", commit.signer, commit.key)
+            };
+            Finding {
+                object: commit.sha.chars().take(7).collect(),
+                status: commit.status,
+                detail: format!(": {} ({}{signer})", commit.subject, commit.author),
+            }
+        })
+        .collect();
+
+    let mut tag_count = 0;
+    if tags {
+        let names = match git::recent_tags(&repo_dir, last) {
+            Ok(names) => names,
+            Err(e) => return Report::Failed(e.to_string()),
+        };
+        tag_count = names.len();
+        for name in names {
+            match git::tag_signature(&repo_dir, &name) {
+                Ok('G') => {}
+                Ok(status) => findings.push(Finding {
+                    object: format!("tag {name}"),
+                    status,
+                    detail: String::new(),
+                }),
+                Err(e) => return Report::Failed(e.to_string()),
+            }
+        }
+    }
+
+    Report::Checked {
+        commits: commits.len(),
+        tags: tag_count,
+        findings,
+    }
+}
+
+/// Describe a `%G?` signature status letter
+fn describe(status: char) -> &'static str {
+    match status {
+        'G' => "good signature",
+        'U' => "signed with an untrusted key",
+        'X' => "expired signature",
+        'Y' => "signed with an expired key",
+        'R' => "signed with a revoked key",
+        'E' => "signature can't be checked (key not available)",
+        'B' => "bad signature",
+        'N' => "unsigned",
+        _ => "unknown signature status",
+    }
+}
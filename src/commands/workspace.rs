@@ -0,0 +1,273 @@
+//! Workspace command implementation
+
+use super::{Command, CommandContext};
+use crate::config::Repository;
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::*;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+
+/// Editor workspaces that can be generated
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum WorkspaceAction {
+    /// Write a VS Code multi-root workspace file
+    Code {
+        /// Specific repository names to include (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Workspace file to write
+        #[arg(short, long, default_value = "rrepos.code-workspace")]
+        output: String,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Write an IntelliJ IDEA project with a module per repository
+    Idea {
+        /// Specific repository names to include (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Project directory to write the .idea directory into
+        #[arg(short, long, default_value = ".")]
+        output: String,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+}
+
+/// Editor a workspace is generated for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Editor {
+    Code,
+    Idea,
+}
+
+/// Workspace command for generating editor workspaces from the config
+pub struct WorkspaceCommand {
+    pub editor: Editor,
+    pub output: String,
+}
+
+#[async_trait]
+impl Command for WorkspaceCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories: Vec<Repository> = context
+            .repositories()
+            .into_iter()
+            .filter(|repo| {
+                if !repo.exists() {
+                    println!("{} | {}", repo.name.cyan().bold(), "Not cloned".dimmed());
+                }
+                repo.exists()
+            })
+            .collect();
+
+        if repositories.is_empty() {
+            let filter_desc = match (&context.tag, &context.repos) {
+                (Some(tag), Some(repos)) => format!("tag '{tag}' and repositories {repos:?}"),
+                (Some(tag), None) => format!("tag '{tag}'"),
+                (None, Some(repos)) => format!("repositories {repos:?}"),
+                (None, None) => "no repositories found".to_string(),
+            };
+            println!(
+                "{}",
+                format!("No repositories found with {filter_desc}").yellow()
+            );
+            return Ok(());
+        }
+
+        let written = match self.editor {
+            Editor::Code => self.write_code_workspace(&repositories)?,
+            Editor::Idea => self.write_idea_project(&repositories)?,
+        };
+
+        println!(
+            "{}",
+            format!(
+                "Wrote {} with {} repositories",
+                written.display(),
+                repositories.len()
+            )
+            .green()
+        );
+        Ok(())
+    }
+}
+
+impl WorkspaceCommand {
+    fn write_code_workspace(&self, repositories: &[Repository]) -> Result<PathBuf> {
+        let output = PathBuf::from(&self.output);
+        let base = absolute(output.parent().unwrap_or(Path::new("")))?;
+
+        let folders = repositories
+            .iter()
+            .map(|repo| {
+                Ok((
+                    repo.name.clone(),
+                    workspace_path(&base, &absolute(Path::new(&repo.get_target_dir()))?),
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        std::fs::write(&output, code_workspace(&folders))?;
+        Ok(output)
+    }
+
+    fn write_idea_project(&self, repositories: &[Repository]) -> Result<PathBuf> {
+        let project_dir = absolute(Path::new(&self.output))?;
+        let idea_dir = project_dir.join(".idea");
+        let modules_dir = idea_dir.join("modules");
+        std::fs::create_dir_all(&modules_dir)?;
+
+        let mut modules = Vec::new();
+        let mut mappings = Vec::new();
+        for repo in repositories {
+            let repo_dir = absolute(Path::new(&repo.get_target_dir()))?;
+            let url = idea_url(&project_dir, &repo_dir);
+
+            std::fs::write(
+                modules_dir.join(format!("{}.iml", repo.name)),
+                format!(
+                    r#"<?xml version="1.0" encoding="UTF-8"?>
+<module type="WEB_MODULE" version="4">
+  <component name="NewModuleRootManager" inherit-compiler-output="true">
+    <exclude-output />
+    <content url="file://{url}" />
+    <orderEntry type="inheritedJdk" />
+    <orderEntry type="sourceFolder" forTests="false" />
+  </component>
+</module>
+"#,
+                    url = xml_escape(&url)
+                ),
+            )?;
+
+            let module_path = format!("$PROJECT_DIR$/.idea/modules/{}.iml", repo.name);
+            modules.push(format!(
+                r#"      <module fileurl="file://{path}" filepath="{path}" />"#,
+                path = xml_escape(&module_path)
+            ));
+            mappings.push(format!(
+                r#"    <mapping directory="{}" vcs="Git" />"#,
+                xml_escape(&url)
+            ));
+        }
+
+        std::fs::write(
+            idea_dir.join("modules.xml"),
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<project version="4">
+  <component name="ProjectModuleManager">
+    <modules>
+{}
+    </modules>
+  </component>
+</project>
+"#,
+                modules.join("\n")
+            ),
+        )?;
+        std::fs::write(
+            idea_dir.join("vcs.xml"),
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<project version="4">
+  <component name="VcsDirectoryMappings">
+{}
+  </component>
+</project>
+"#,
+                mappings.join("\n")
+            ),
+        )?;
+
+        Ok(idea_dir)
+    }
+}
+
+/// Render a VS Code workspace with one folder per `(name, path)`
+fn code_workspace(folders: &[(String, String)]) -> String {
+    let folders: Vec<_> = folders
+        .iter()
+        .map(|(name, path)| json!({ "name": name, "path": path }))
+        .collect();
+    let workspace = json!({ "folders": folders, "settings": {} });
+    format!("{:#}\n", workspace)
+}
+
+/// Path of a repository as seen from the workspace file: relative when the
+/// repository is below it, so the workspace can be committed, else absolute
+fn workspace_path(base: &Path, repo_dir: &Path) -> String {
+    match repo_dir.strip_prefix(base) {
+        Ok(relative) if relative.as_os_str().is_empty() => ".".to_string(),
+        Ok(relative) => relative.to_string_lossy().to_string(),
+        Err(_) => repo_dir.to_string_lossy().to_string(),
+    }
+}
+
+/// Directory URL for IntelliJ files, using `$PROJECT_DIR$` when possible
+fn idea_url(project_dir: &Path, repo_dir: &Path) -> String {
+    match repo_dir.strip_prefix(project_dir) {
+        Ok(relative) if relative.as_os_str().is_empty() => "$PROJECT_DIR$".to_string(),
+        Ok(relative) => format!("$PROJECT_DIR$/{}", relative.to_string_lossy()),
+        Err(_) => repo_dir.to_string_lossy().to_string(),
+    }
+}
+
+fn absolute(path: &Path) -> Result<PathBuf> {
+    let path = if path.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        path
+    };
+    Ok(std::fs::canonicalize(path)?)
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workspace_paths() {
+        let base = Path::new("/work/fleet");
+        assert_eq!(workspace_path(base, Path::new("/work/fleet/api")), "api");
+        assert_eq!(
+            workspace_path(base, Path::new("/elsewhere/web")),
+            "/elsewhere/web"
+        );
+        assert_eq!(
+            idea_url(base, Path::new("/work/fleet/api")),
+            "$PROJECT_DIR$/api"
+        );
+    }
+
+    #[test]
+    fn test_code_workspace() {
+        let workspace = code_workspace(&[("api".to_string(), "api".to_string())]);
+        let parsed: serde_json::Value = serde_json::from_str(&workspace).unwrap();
+        assert_eq!(parsed["folders"][0]["name"], "api");
+        assert_eq!(parsed["folders"][0]["path"], "api");
+    }
+}
@@ -9,6 +9,8 @@ pub struct RepositoryBuilder {
     tags: Vec<String>,
     path: Option<String>,
     branch: Option<String>,
+    reference: Option<String>,
+    depends_on: Vec<String>,
 }
 
 impl RepositoryBuilder {
@@ -20,6 +22,8 @@ impl RepositoryBuilder {
             tags: Vec::new(),
             path: None,
             branch: None,
+            reference: None,
+            depends_on: Vec::new(),
         }
     }
 
@@ -41,6 +45,18 @@ impl RepositoryBuilder {
         self
     }
 
+    /// Pin the repository to a tag or commit
+    pub fn with_ref(mut self, reference: String) -> Self {
+        self.reference = Some(reference);
+        self
+    }
+
+    /// Set the repositories this repository depends on
+    pub fn with_depends_on(mut self, depends_on: Vec<String>) -> Self {
+        self.depends_on = depends_on;
+        self
+    }
+
     /// Build the repository
     pub fn build(self) -> Repository {
         Repository {
@@ -49,7 +65,22 @@ impl RepositoryBuilder {
             tags: self.tags,
             path: self.path,
             branch: self.branch,
+            reference: self.reference,
+            depends_on: self.depends_on,
+            archived: false,
+            default_branch: None,
+            ssh_key: None,
+            ssh_command: None,
             config_dir: None,
+            env: Default::default(),
+            provider: Default::default(),
+            branch_template: None,
+            commit_lint: None,
+            if_exists: None,
+            max_size: None,
+            visibility: None,
+            owner: None,
+            layout: Default::default(),
         }
     }
 }
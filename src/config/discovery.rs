@@ -0,0 +1,115 @@
+//! Locating the config file when `-c/--config` is not given
+//!
+//! Like git, rrepos looks for its config in a chain of well-known places:
+//! `RREPOS_CONFIG`, then `config.yaml` in the current directory, then
+//! `rrepos.yaml` or `.rrepos/config.yaml` in the current directory or any
+//! parent, and finally `$XDG_CONFIG_HOME/rrepos/config.yaml`.
+
+use std::path::{Path, PathBuf};
+
+/// Environment variable naming the config file to use
+pub const CONFIG_ENV: &str = "RREPOS_CONFIG";
+
+/// Config file in the current directory, used when nothing is discovered
+pub const DEFAULT_CONFIG: &str = "config.yaml";
+
+/// Names searched for in the current directory and its parents
+const WORKSPACE_CONFIGS: [&str; 2] = ["rrepos.yaml", ".rrepos/config.yaml"];
+
+/// The config file to use when none is given on the command line
+pub fn discover() -> String {
+    let override_path = std::env::var(CONFIG_ENV).ok().filter(|p| !p.is_empty());
+    let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    discover_from(&cwd, override_path, user_config_dir())
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|| DEFAULT_CONFIG.to_string())
+}
+
+/// Walk the discovery chain starting at `cwd`
+fn discover_from(
+    cwd: &Path,
+    override_path: Option<String>,
+    user_config_dir: Option<PathBuf>,
+) -> Option<PathBuf> {
+    if let Some(path) = override_path {
+        return Some(PathBuf::from(path));
+    }
+
+    // A plain config.yaml in the working directory keeps working as before
+    if cwd.join(DEFAULT_CONFIG).is_file() {
+        return Some(PathBuf::from(DEFAULT_CONFIG));
+    }
+
+    let workspace = cwd.ancestors().find_map(|dir| {
+        WORKSPACE_CONFIGS
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file())
+    });
+    if workspace.is_some() {
+        return workspace;
+    }
+
+    user_config_dir
+        .map(|dir| dir.join("rrepos").join(DEFAULT_CONFIG))
+        .filter(|path| path.is_file())
+}
+
+/// `$XDG_CONFIG_HOME`, falling back to `~/.config`
+pub(crate) fn user_config_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discovery_chain() {
+        let root =
+            std::env::temp_dir().join(format!("rrepos-discovery-test-{}", std::process::id()));
+        let nested = root.join("workspace").join("team").join("service");
+        let user_dir = root.join("xdg");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::create_dir_all(user_dir.join("rrepos")).unwrap();
+
+        let discover = |cwd: &Path, override_path: Option<&str>| {
+            discover_from(cwd, override_path.map(String::from), Some(user_dir.clone()))
+        };
+
+        // Nothing found yet
+        assert_eq!(discover(&nested, None), None);
+
+        std::fs::write(user_dir.join("rrepos/config.yaml"), "").unwrap();
+        assert_eq!(
+            discover(&nested, None),
+            Some(user_dir.join("rrepos/config.yaml"))
+        );
+
+        std::fs::create_dir_all(root.join("workspace/.rrepos")).unwrap();
+        std::fs::write(root.join("workspace/.rrepos/config.yaml"), "").unwrap();
+        assert_eq!(
+            discover(&nested, None),
+            Some(root.join("workspace/.rrepos/config.yaml"))
+        );
+
+        std::fs::write(root.join("workspace/team/rrepos.yaml"), "").unwrap();
+        assert_eq!(
+            discover(&nested, None),
+            Some(root.join("workspace/team/rrepos.yaml"))
+        );
+
+        std::fs::write(nested.join("config.yaml"), "").unwrap();
+        assert_eq!(discover(&nested, None), Some(PathBuf::from("config.yaml")));
+
+        assert_eq!(
+            discover(&nested, Some("/etc/rrepos.yaml")),
+            Some(PathBuf::from("/etc/rrepos.yaml"))
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,117 @@
+//! Dependency ordering between repositories (`depends_on`)
+
+use super::Repository;
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+
+/// Group repositories into levels in topological order.
+///
+/// Every repository in a level only depends on repositories from earlier
+/// levels, so levels must run one after another while repositories within a
+/// level may run in parallel. Dependencies on repositories outside `repos`
+/// are ignored.
+pub fn dependency_levels(repos: &[Repository]) -> Result<Vec<Vec<Repository>>> {
+    let names: HashSet<&str> = repos.iter().map(|r| r.name.as_str()).collect();
+    let mut remaining: HashMap<&str, HashSet<&str>> = repos
+        .iter()
+        .map(|r| {
+            let deps = r
+                .depends_on
+                .iter()
+                .map(String::as_str)
+                .filter(|d| names.contains(d) && *d != r.name)
+                .collect();
+            (r.name.as_str(), deps)
+        })
+        .collect();
+
+    let mut levels = Vec::new();
+    while !remaining.is_empty() {
+        // Preserve config order within a level
+        let ready: Vec<&str> = repos
+            .iter()
+            .map(|r| r.name.as_str())
+            .filter(|name| remaining.get(name).is_some_and(|deps| deps.is_empty()))
+            .collect();
+
+        if ready.is_empty() {
+            let mut cycle: Vec<&str> = remaining.keys().copied().collect();
+            cycle.sort();
+            anyhow::bail!(
+                "Dependency cycle detected between repositories: {}",
+                cycle.join(", ")
+            );
+        }
+
+        for name in &ready {
+            remaining.remove(name);
+        }
+        for deps in remaining.values_mut() {
+            deps.retain(|d| !ready.contains(d));
+        }
+
+        levels.push(
+            repos
+                .iter()
+                .filter(|r| ready.contains(&r.name.as_str()))
+                .cloned()
+                .collect(),
+        );
+    }
+
+    Ok(levels)
+}
+
+/// Flatten dependency levels into a single execution order
+pub fn dependency_order(repos: &[Repository]) -> Result<Vec<Repository>> {
+    Ok(dependency_levels(repos)?.into_iter().flatten().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo(name: &str, depends_on: &[&str]) -> Repository {
+        let mut repo =
+            Repository::new(name.to_string(), format!("git@github.com:owner/{name}.git"));
+        repo.depends_on = depends_on.iter().map(|d| d.to_string()).collect();
+        repo
+    }
+
+    fn names(level: &[Repository]) -> Vec<&str> {
+        level.iter().map(|r| r.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_dependency_levels() {
+        let repos = vec![
+            repo("service", &["lib-a", "lib-b"]),
+            repo("lib-b", &["lib-a"]),
+            repo("lib-a", &[]),
+            repo("tool", &[]),
+        ];
+
+        let levels = dependency_levels(&repos).unwrap();
+        assert_eq!(levels.len(), 3);
+        assert_eq!(names(&levels[0]), vec!["lib-a", "tool"]);
+        assert_eq!(names(&levels[1]), vec!["lib-b"]);
+        assert_eq!(names(&levels[2]), vec!["service"]);
+    }
+
+    #[test]
+    fn test_unselected_dependencies_are_ignored() {
+        let repos = vec![repo("service", &["not-selected"])];
+
+        let levels = dependency_levels(&repos).unwrap();
+        assert_eq!(levels.len(), 1);
+        assert_eq!(names(&levels[0]), vec!["service"]);
+    }
+
+    #[test]
+    fn test_dependency_cycle() {
+        let repos = vec![repo("a", &["b"]), repo("b", &["a"]), repo("c", &[])];
+
+        let err = dependency_levels(&repos).unwrap_err().to_string();
+        assert!(err.contains("a, b"));
+    }
+}
@@ -0,0 +1,245 @@
+//! Per-host connection settings
+
+use super::secrets;
+use crate::offline;
+use crate::util::shell_quote;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+
+/// Host whose settings apply to the GitHub API client
+pub const GITHUB_HOST: &str = "github.com";
+
+/// Settings applied to every repository whose URL points at a host
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HostSettings {
+    /// Private key used for SSH connections to the host
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssh_key: Option<String>,
+    /// Full SSH command, overriding `ssh_key`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssh_command: Option<String>,
+    /// Environment variable holding an access token for HTTPS remotes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_env: Option<String>,
+    /// Access token for HTTPS remotes, usually age-encrypted; kept as
+    /// written so saving the config never writes it out in plain text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// age identity file that decrypts `token`, resolved when the config is
+    /// loaded
+    #[serde(skip)]
+    pub age_identity: Option<PathBuf>,
+    /// `token` once decrypted, shared by copies of these settings so it is
+    /// decrypted at most once, when first used
+    #[serde(skip)]
+    pub decrypted_token: Arc<OnceLock<String>>,
+    /// Proxy for HTTPS connections, overriding the `HTTPS_PROXY` environment
+    /// variable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub https_proxy: Option<String>,
+    /// PEM file with additional trusted CA certificates, e.g. for networks
+    /// with TLS interception
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_bundle: Option<String>,
+    /// Most repositories processed against the host at once, to stay under
+    /// its connection throttling
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent: Option<usize>,
+}
+
+impl HostSettings {
+    /// SSH command git should use, if any is configured
+    pub fn ssh_command(&self) -> Option<String> {
+        ssh_command(self.ssh_command.as_deref(), self.ssh_key.as_deref())
+    }
+
+    /// These settings, with unset fields taken from `fallback`
+    pub fn or(&self, fallback: &HostSettings) -> HostSettings {
+        // The token comes with what decrypts it
+        let token = if self.token.is_some() { self } else { fallback };
+        HostSettings {
+            ssh_key: self.ssh_key.clone().or(fallback.ssh_key.clone()),
+            ssh_command: self.ssh_command.clone().or(fallback.ssh_command.clone()),
+            token_env: self.token_env.clone().or(fallback.token_env.clone()),
+            token: token.token.clone(),
+            age_identity: token.age_identity.clone(),
+            decrypted_token: Arc::clone(&token.decrypted_token),
+            https_proxy: self.https_proxy.clone().or(fallback.https_proxy.clone()),
+            ca_bundle: self.ca_bundle.clone().or(fallback.ca_bundle.clone()),
+            max_concurrent: self.max_concurrent.or(fallback.max_concurrent),
+        }
+    }
+
+    /// HTTP client for the host's API, using its proxy and CA bundle
+    pub fn http_client(&self) -> Result<reqwest::Client> {
+        offline::require_network("Calling the host's API")?;
+        let mut builder = reqwest::Client::builder();
+        if let Some(proxy) = &self.https_proxy {
+            builder = builder.proxy(
+                reqwest::Proxy::https(proxy)
+                    .with_context(|| format!("Invalid HTTPS proxy '{proxy}'"))?,
+            );
+        }
+        if let Some(ca_bundle) = &self.ca_bundle {
+            let pem = std::fs::read(ca_bundle)
+                .with_context(|| format!("Failed to read CA bundle '{ca_bundle}'"))?;
+            let certificates = reqwest::Certificate::from_pem_bundle(&pem)
+                .with_context(|| format!("Invalid CA bundle '{ca_bundle}'"))?;
+            if certificates.is_empty() {
+                anyhow::bail!("CA bundle '{}' contains no certificates", ca_bundle);
+            }
+            for certificate in certificates {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+        Ok(builder.build()?)
+    }
+
+    /// Access token for HTTPS remotes, from `token` or `token_env`. An
+    /// encrypted `token` is decrypted on first use.
+    pub fn token(&self) -> Result<Option<String>> {
+        if let Some(token) = &self.token {
+            if !secrets::is_encrypted(token) {
+                return Ok(Some(token.clone()));
+            }
+            if let Some(decrypted) = self.decrypted_token.get() {
+                return Ok(Some(decrypted.clone()));
+            }
+            let identity = self.age_identity.as_deref().ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Token is encrypted but no age identity is configured (set age_identity or {})",
+                    secrets::AGE_IDENTITY_ENV
+                )
+            })?;
+            let decrypted = secrets::decrypt_age(token, identity)?;
+            return Ok(Some(self.decrypted_token.get_or_init(|| decrypted).clone()));
+        }
+        let Some(name) = self.token_env.as_deref() else {
+            return Ok(None);
+        };
+        Ok(std::env::var(name).ok().filter(|token| !token.is_empty()))
+    }
+}
+
+/// Build the SSH command for an explicit command or a key
+pub fn ssh_command(command: Option<&str>, key: Option<&str>) -> Option<String> {
+    match (command, key) {
+        (Some(command), _) => Some(command.to_string()),
+        (None, Some(key)) => Some(format!("ssh -i {} -o IdentitiesOnly=yes", shell_quote(key))),
+        (None, None) => None,
+    }
+}
+
+/// Extract the host from an SSH (`git@host:owner/repo`, `ssh://...`) or
+/// HTTP(S) repository URL
+pub fn url_host(url: &str) -> Option<&str> {
+    let rest = match url.split_once("://") {
+        Some((_, rest)) => rest,
+        None => url,
+    };
+    let authority = rest.split(['/', ':']).next()?;
+    // Drop credentials of the form user@host
+    let host = authority.rsplit('@').next()?;
+    (!host.is_empty()).then_some(host)
+}
+
+/// Extract the owner, the first path segment, from an SSH or HTTP(S)
+/// repository URL
+pub fn url_owner(url: &str) -> Option<&str> {
+    let path = match url.split_once("://") {
+        Some((_, rest)) => rest.split_once('/')?.1,
+        None => url.split_once(':')?.1,
+    };
+    let (owner, _) = path.trim_start_matches('/').split_once('/')?;
+    (!owner.is_empty()).then_some(owner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_host() {
+        assert_eq!(
+            url_host("git@github.com:owner/repo.git"),
+            Some("github.com")
+        );
+        assert_eq!(
+            url_host("https://github.example.com/owner/repo"),
+            Some("github.example.com")
+        );
+        assert_eq!(
+            url_host("ssh://git@gitlab.example.com:2222/owner/repo.git"),
+            Some("gitlab.example.com")
+        );
+    }
+
+    #[test]
+    fn test_url_owner() {
+        assert_eq!(url_owner("git@github.com:owner/repo.git"), Some("owner"));
+        assert_eq!(
+            url_owner("https://github.example.com/owner/repo"),
+            Some("owner")
+        );
+        assert_eq!(
+            url_owner("ssh://git@gitlab.example.com:2222/group/sub/repo.git"),
+            Some("group")
+        );
+        assert_eq!(url_owner("https://github.com/repo"), None);
+        assert_eq!(url_owner("/srv/git/repo"), None);
+    }
+
+    #[test]
+    fn test_ssh_command_prefers_command() {
+        assert_eq!(
+            ssh_command(None, Some("~/.ssh/work key")),
+            Some("ssh -i '~/.ssh/work key' -o IdentitiesOnly=yes".to_string())
+        );
+        assert_eq!(
+            ssh_command(Some("ssh -F work.conf"), Some("~/.ssh/work")),
+            Some("ssh -F work.conf".to_string())
+        );
+        assert_eq!(ssh_command(None, None), None);
+    }
+
+    #[test]
+    fn test_host_settings_fallback() {
+        let project = HostSettings {
+            https_proxy: Some("http://proxy:3128".to_string()),
+            ..Default::default()
+        };
+        let user = HostSettings {
+            token_env: Some("WORK_GITHUB_TOKEN".to_string()),
+            https_proxy: Some("http://home-proxy:3128".to_string()),
+            ..Default::default()
+        };
+
+        let merged = project.or(&user);
+        assert_eq!(merged.https_proxy.as_deref(), Some("http://proxy:3128"));
+        assert_eq!(merged.token_env.as_deref(), Some("WORK_GITHUB_TOKEN"));
+    }
+
+    #[test]
+    fn test_encrypted_token_is_decrypted_on_use() {
+        let plain = HostSettings {
+            token: Some("ghp_plain".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(plain.token().unwrap().as_deref(), Some("ghp_plain"));
+
+        let mut encrypted = HostSettings {
+            token: Some(format!(
+                "{}\nYWdl\n-----END AGE ENCRYPTED FILE-----",
+                secrets::AGE_HEADER
+            )),
+            ..Default::default()
+        };
+        let error = encrypted.token().unwrap_err().to_string();
+        assert!(error.contains("no age identity is configured"), "{error}");
+
+        encrypted.age_identity = Some(PathBuf::from("/nonexistent/rrepos-age.key"));
+        assert!(encrypted.token().is_err());
+    }
+}
@@ -0,0 +1,69 @@
+//! Workspace layouts deciding where clones without an explicit path live
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+
+/// Directory used for repositories without tags in the `by-tag` layout
+pub const UNTAGGED_DIR: &str = "untagged";
+
+/// How default clone paths are arranged below the config directory
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Layout {
+    /// `<name>`
+    #[default]
+    Flat,
+    /// `<primary-tag>/<name>`, where the primary tag is the first one
+    ByTag,
+}
+
+impl Layout {
+    pub const ALL: [Layout; 2] = [Layout::Flat, Layout::ByTag];
+
+    /// Path of a repository relative to the workspace
+    pub fn relative_dir(&self, name: &str, tags: &[String]) -> PathBuf {
+        match self {
+            Layout::Flat => PathBuf::from(name),
+            Layout::ByTag => {
+                let group = tags.first().map(String::as_str).unwrap_or(UNTAGGED_DIR);
+                PathBuf::from(group).join(name)
+            }
+        }
+    }
+
+    pub fn is_flat(&self) -> bool {
+        *self == Layout::Flat
+    }
+}
+
+impl fmt::Display for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Layout::Flat => write!(f, "flat"),
+            Layout::ByTag => write!(f, "by-tag"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_dir() {
+        let tags = vec!["java".to_string(), "backend".to_string()];
+        assert_eq!(
+            Layout::Flat.relative_dir("api", &tags),
+            PathBuf::from("api")
+        );
+        assert_eq!(
+            Layout::ByTag.relative_dir("api", &tags),
+            PathBuf::from("java/api")
+        );
+        assert_eq!(
+            Layout::ByTag.relative_dir("api", &[]),
+            PathBuf::from("untagged/api")
+        );
+    }
+}
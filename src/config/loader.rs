@@ -1,28 +1,116 @@
 //! Configuration file loading and saving
 
-use super::{ConfigValidator, Repository};
-use anyhow::Result;
+use super::{
+    CommitLint, ConfigValidator, HostSettings, IfExists, Layout, Policy, Repository, Task,
+    UserSettings, secrets,
+};
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub repositories: Vec<Repository>,
+    /// Inherited environment variables passed to commands (all when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_passlist: Option<Vec<String>>,
+    /// Directory that `rm --trash` moves repositories into
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trash_dir: Option<String>,
+    /// Local git settings that `git-config apply` enforces in every clone
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub git_config: BTreeMap<String, String>,
+    /// Connection settings keyed by host name (e.g. `github.com`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub hosts: BTreeMap<String, HostSettings>,
+    /// Named tasks that `task` runs, keyed by task name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tasks: BTreeMap<String, Task>,
+    /// Where clones without an explicit `path` live: `flat` (`<name>`) or
+    /// `by-tag` (`<primary-tag>/<name>`)
+    #[serde(default, skip_serializing_if = "Layout::is_flat")]
+    pub layout: Layout,
+    /// Template for the branches pull requests are opened from, e.g.
+    /// `chore/{campaign}-{date}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch_template: Option<String>,
+    /// Rules commit messages of pull requests must follow, e.g.
+    /// `conventional`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_lint: Option<CommitLint>,
+    /// What `clone` does when a repository's directory already exists:
+    /// `skip` (the default), `pull`, `recreate` or `fail`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub if_exists: Option<IfExists>,
+    /// Jinja template file rendering pull request bodies, relative to the
+    /// config file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pr_body_template: Option<String>,
+    /// age identity file used to decrypt encrypted values, relative to the
+    /// config file (overridden by `RREPOS_AGE_IDENTITY`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub age_identity: Option<String>,
+    /// Whether the file was decrypted with sops, in which case it can't be
+    /// saved back
+    #[serde(skip)]
+    pub sops_encrypted: bool,
+    /// Host settings from the user's settings file, used for any field the
+    /// `hosts` section leaves unset; never saved back into the config
+    #[serde(skip)]
+    pub user_hosts: BTreeMap<String, HostSettings>,
+    /// Confirmation policy from the user's settings file
+    #[serde(skip)]
+    pub policy: Policy,
+    /// Directory of the loaded config file, which relative paths such as
+    /// `trash_dir` are resolved against
+    #[serde(skip)]
+    pub config_dir: Option<PathBuf>,
+}
+
+/// Context of errors from loading a configuration file, so callers can tell
+/// them from failures of the command itself
+#[derive(Debug)]
+pub struct LoadError(pub String);
+
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Failed to load config '{}'", self.0)
+    }
 }
 
 impl Config {
     /// Load configuration from a file
     pub fn load(path: &str) -> Result<Self> {
+        Self::read(path).with_context(|| LoadError(path.to_string()))
+    }
+
+    /// Read, decrypt and validate the configuration file
+    fn read(path: &str) -> Result<Self> {
         let content = std::fs::read_to_string(path)?;
 
+        let config_path = Path::new(path);
+        let sops_encrypted = secrets::is_sops_document(&content);
+        let content = if sops_encrypted {
+            secrets::decrypt_sops(config_path)?
+        } else {
+            content
+        };
+
         let mut config: Config = serde_yaml::from_str(&content)?;
+        config.sops_encrypted = sops_encrypted;
+        let settings = UserSettings::load()?;
+        config.user_hosts = settings.hosts;
+        config.policy = settings.policy;
 
         // Set the config directory for each repository
-        let config_path = Path::new(path);
         let config_dir = config_path.parent().map(|p| p.to_path_buf());
 
+        config.set_age_identity(config_dir.as_deref().unwrap_or(Path::new(".")));
+        config.config_dir = config_dir.clone();
+
         for repo in &mut config.repositories {
-            repo.set_config_dir(config_dir.clone());
+            repo.set_workspace(config_dir.clone(), config.layout);
         }
 
         // Validate the loaded configuration
@@ -31,8 +119,28 @@ impl Config {
         Ok(config)
     }
 
+    /// Point the host tokens at the age identity that decrypts them, when
+    /// they are first used
+    fn set_age_identity(&mut self, config_dir: &Path) {
+        let identity = std::env::var(secrets::AGE_IDENTITY_ENV)
+            .ok()
+            .map(PathBuf::from)
+            .or_else(|| self.age_identity.as_ref().map(|i| config_dir.join(i)));
+
+        for settings in self.hosts.values_mut().chain(self.user_hosts.values_mut()) {
+            settings.age_identity = identity.clone();
+        }
+    }
+
     /// Save configuration to a file
     pub fn save(&self, path: &str) -> Result<()> {
+        if self.sops_encrypted {
+            anyhow::bail!(
+                "Config '{}' is encrypted with sops and can't be updated in place; edit it with `sops` instead",
+                path
+            );
+        }
+
         let yaml = serde_yaml::to_string(self)?;
 
         std::fs::write(path, yaml)?;
@@ -147,9 +255,49 @@ impl Config {
     pub fn new() -> Self {
         Self {
             repositories: Vec::new(),
+            env_passlist: None,
+            trash_dir: None,
+            git_config: BTreeMap::new(),
+            hosts: BTreeMap::new(),
+            tasks: BTreeMap::new(),
+            layout: Layout::default(),
+            branch_template: None,
+            commit_lint: None,
+            if_exists: None,
+            pr_body_template: None,
+            age_identity: None,
+            sops_encrypted: false,
+            user_hosts: BTreeMap::new(),
+            policy: Policy::default(),
+            config_dir: None,
+        }
+    }
+
+    /// Settings used for GitHub API requests
+    pub fn github_settings(&self) -> HostSettings {
+        self.host_settings(super::hosts::GITHUB_HOST)
+            .unwrap_or_default()
+    }
+
+    /// Settings for a host from the `hosts` section, completed by the user's
+    /// settings file
+    pub fn host_settings(&self, host: &str) -> Option<HostSettings> {
+        match (self.hosts.get(host), self.user_hosts.get(host)) {
+            (Some(project), Some(user)) => Some(project.or(user)),
+            (project, user) => project.or(user).cloned(),
         }
     }
 
+    /// Template file for pull request bodies, relative paths being resolved
+    /// against the config file's directory
+    pub fn pr_body_template_file(&self) -> Option<PathBuf> {
+        let template = self.pr_body_template.as_ref()?;
+        Some(match &self.config_dir {
+            Some(config_dir) => config_dir.join(template),
+            None => PathBuf::from(template),
+        })
+    }
+
     /// Alias for load method for backwards compatibility
     pub fn load_config(path: &str) -> Result<Self> {
         Self::load(path)
@@ -212,6 +360,7 @@ mod tests {
 
         Config {
             repositories: vec![repo1, repo2],
+            ..Config::new()
         }
     }
 
@@ -227,6 +376,30 @@ mod tests {
         assert_eq!(all_repos.len(), 2);
     }
 
+    #[test]
+    fn test_encrypted_token_is_not_decrypted_on_load() {
+        let dir = std::env::temp_dir().join(format!("rrepos-loader-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.yaml");
+        std::fs::write(
+            &path,
+            "hosts:\n  git.example.com:\n    token: |\n      -----BEGIN AGE ENCRYPTED FILE-----\n      YWdl\n      -----END AGE ENCRYPTED FILE-----\nrepositories:\n  - name: api\n    url: https://git.example.com/org/api.git\n    tags: []\n",
+        )
+        .unwrap();
+
+        let config = Config::load(&path.to_string_lossy());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // Loading works without the identity; only using the token fails
+        let config = config.unwrap();
+        let repo = &config.repositories[0];
+        let error = repo.https_token(&repo.host_settings(&config)).unwrap_err();
+        assert!(
+            format!("{error:#}").contains("host 'git.example.com'"),
+            "{error:#}"
+        );
+    }
+
     #[test]
     fn test_filter_by_any_tag() {
         let config = create_test_config();
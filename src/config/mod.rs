@@ -1,11 +1,23 @@
 //! Configuration management module
 
 pub mod builder;
+pub mod discovery;
+pub mod graph;
+pub mod hosts;
+pub mod layout;
 pub mod loader;
 pub mod repository;
+pub mod secrets;
+pub mod settings;
+pub mod tasks;
 pub mod validation;
 
 pub use builder::RepositoryBuilder;
+pub use graph::{dependency_levels, dependency_order};
+pub use hosts::HostSettings;
+pub use layout::Layout;
 pub use loader::Config;
-pub use repository::Repository;
+pub use repository::{CommitLint, IfExists, Provider, Repository, Visibility};
+pub use settings::{Policy, UserSettings};
+pub use tasks::Task;
 pub use validation::ConfigValidator;
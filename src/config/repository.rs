@@ -1,9 +1,64 @@
 //! Repository configuration and utilities
 
-use anyhow::Result;
+use super::hosts::{self, HostSettings};
+use super::layout::Layout;
+use super::loader::Config;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
+/// Service hosting a repository
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    #[default]
+    GitHub,
+    /// Gerrit code review; `pr` pushes to `refs/for/<branch>`
+    Gerrit,
+    /// A plain git server such as cgit; `pr` only pushes the branch
+    None,
+}
+
+impl Provider {
+    pub fn is_default(&self) -> bool {
+        *self == Provider::default()
+    }
+}
+
+/// Rules commit messages of pull requests must follow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommitLint {
+    /// Conventional Commits, as checked by commitlint's conventional config
+    Conventional,
+}
+
+/// What `clone` does when a repository's directory already exists
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum IfExists {
+    /// Leave the directory as it is
+    #[default]
+    Skip,
+    /// Fetch and fast-forward, or check out the pinned `ref`
+    Pull,
+    /// Delete the directory and clone again, unless it has unsaved work
+    Recreate,
+    /// Report an error
+    Fail,
+}
+
+/// Who can see a repository on its host
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Visibility {
+    Public,
+    Private,
+    /// Visible to members of the enterprise
+    Internal,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Repository {
     pub name: String,
@@ -13,8 +68,60 @@ pub struct Repository {
     pub path: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub branch: Option<String>,
+    /// Tag or commit to pin the clone to, checked out detached; `sync`
+    /// keeps it there instead of following a branch
+    #[serde(default, rename = "ref", skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+    /// Set by `refresh-metadata` when the GitHub repository is archived or
+    /// disabled; such repositories are skipped unless `--include-archived`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub archived: bool,
+    /// Default branch on GitHub, recorded by `refresh-metadata`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_branch: Option<String>,
+    /// Private key used for SSH connections, overriding the host settings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssh_key: Option<String>,
+    /// Full SSH command, overriding `ssh_key` and the host settings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssh_command: Option<String>,
+    /// Environment variables set for commands run in this repository
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// Service hosting the repository, deciding how pull requests are opened
+    #[serde(default, skip_serializing_if = "Provider::is_default")]
+    pub provider: Provider,
+    /// Template for the branches pull requests are opened from, overriding
+    /// the config's `branch_template`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch_template: Option<String>,
+    /// Rules commit messages of pull requests must follow, overriding the
+    /// config's `commit_lint`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_lint: Option<CommitLint>,
+    /// What `clone` does when the directory exists, overriding the config's
+    /// `if_exists`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub if_exists: Option<IfExists>,
+    /// Size budget of the clone, e.g. `500M`; `sync` warns when it is
+    /// exceeded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_size: Option<String>,
+    /// Visibility on the host, as recorded by `refresh-metadata`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<Visibility>,
+    /// Owner (organization or user) on the host when it differs from the
+    /// one in the URL, e.g. after a transfer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
     #[serde(skip)]
     pub config_dir: Option<PathBuf>,
+    /// Workspace layout deciding the default path, set along with
+    /// `config_dir`
+    #[serde(skip)]
+    pub layout: Layout,
 }
 
 impl Repository {
@@ -26,13 +133,30 @@ impl Repository {
             tags: Vec::new(),
             path: None,
             branch: None,
+            reference: None,
+            depends_on: Vec::new(),
+            archived: false,
+            default_branch: None,
+            ssh_key: None,
+            ssh_command: None,
+            env: BTreeMap::new(),
+            provider: Provider::default(),
+            branch_template: None,
+            commit_lint: None,
+            if_exists: None,
+            max_size: None,
+            visibility: None,
+            owner: None,
             config_dir: None,
+            layout: Layout::default(),
         }
     }
 
-    /// Check if repository has a specific tag
+    /// Check if repository has a specific tag. Virtual tags such as
+    /// `lang:rust` are detected from the working tree instead.
     pub fn has_tag(&self, tag: &str) -> bool {
         self.tags.iter().any(|t| t == tag)
+            || crate::detect::has_virtual_tag(Path::new(&self.get_target_dir()), tag)
     }
 
     /// Check if repository has any of the specified tags
@@ -45,6 +169,8 @@ impl Repository {
         self.url.starts_with("git@")
             || self.url.starts_with("https://")
             || self.url.starts_with("http://")
+            || self.url.starts_with("ssh://")
+            || self.url.starts_with("git://")
     }
 
     /// Validate repository configuration
@@ -61,6 +187,17 @@ impl Repository {
             return Err(anyhow::anyhow!("Invalid repository URL: {}", self.url));
         }
 
+        if let Some(max_size) = &self.max_size {
+            crate::util::parse_size(max_size)?;
+        }
+
+        if self.branch.is_some() && self.reference.is_some() {
+            return Err(anyhow::anyhow!(
+                "Repository '{}' cannot set both branch and ref",
+                self.name
+            ));
+        }
+
         Ok(())
     }
 
@@ -86,24 +223,95 @@ impl Repository {
                     }
                 }
             }
-            None => {
-                // Default to repository name as relative path
-                if let Some(config_dir) = &self.config_dir {
-                    config_dir.join(&self.name).to_string_lossy().to_string()
-                } else {
-                    std::env::current_dir()
-                        .unwrap_or_else(|_| PathBuf::from("."))
-                        .join(&self.name)
-                        .to_string_lossy()
-                        .to_string()
-                }
-            }
+            None => self.default_dir(self.layout),
+        }
+    }
+
+    /// Directory the repository is cloned into when no path is configured,
+    /// under the given layout
+    pub fn default_dir(&self, layout: Layout) -> String {
+        let relative = layout.relative_dir(&self.name, &self.tags);
+        if let Some(config_dir) = &self.config_dir {
+            config_dir.join(relative).to_string_lossy().to_string()
+        } else {
+            std::env::current_dir()
+                .unwrap_or_else(|_| PathBuf::from("."))
+                .join(relative)
+                .to_string_lossy()
+                .to_string()
         }
     }
 
-    /// Set the configuration directory (used by config loader)
-    pub fn set_config_dir(&mut self, config_dir: Option<PathBuf>) {
+    /// Template for the branches pull requests are opened from, if any: the
+    /// repository's own, else the config's
+    pub fn branch_template<'a>(&'a self, config: &'a Config) -> Option<&'a str> {
+        self.branch_template
+            .as_deref()
+            .or(config.branch_template.as_deref())
+    }
+
+    /// Rules commit messages of pull requests must follow, if any
+    pub fn commit_lint(&self, config: &Config) -> Option<CommitLint> {
+        self.commit_lint.or(config.commit_lint)
+    }
+
+    /// Size budget of the clone in bytes, if any
+    pub fn max_size(&self) -> Option<u64> {
+        self.max_size
+            .as_deref()
+            .and_then(|size| crate::util::parse_size(size).ok())
+    }
+
+    /// What `clone` does when the directory already exists
+    pub fn if_exists(&self, config: &Config) -> IfExists {
+        self.if_exists.or(config.if_exists).unwrap_or_default()
+    }
+
+    /// Owner of the repository: the recorded `owner`, else the one in the URL
+    pub fn owner(&self) -> Option<&str> {
+        self.owner
+            .as_deref()
+            .or_else(|| hosts::url_owner(&self.url))
+    }
+
+    /// Host part of the repository URL
+    pub fn host(&self) -> Option<&str> {
+        hosts::url_host(&self.url)
+    }
+
+    /// Settings of the repository's host from the config's `hosts` section
+    pub fn host_settings(&self, config: &Config) -> HostSettings {
+        self.host()
+            .and_then(|host| config.host_settings(host))
+            .unwrap_or_default()
+    }
+
+    /// SSH command git should use for this repository: the repository's own
+    /// `ssh_command` or `ssh_key`, falling back to its host's settings
+    pub fn ssh_command(&self, settings: &HostSettings) -> Option<String> {
+        hosts::ssh_command(self.ssh_command.as_deref(), self.ssh_key.as_deref())
+            .or_else(|| settings.ssh_command())
+    }
+
+    /// Access token to authenticate HTTPS clones and pushes with, when the
+    /// URL is HTTPS and its host has a `token` or `token_env` configured
+    pub fn https_token(&self, settings: &HostSettings) -> Result<Option<String>> {
+        if !self.url.starts_with("https://") {
+            return Ok(None);
+        }
+        settings.token().with_context(|| {
+            format!(
+                "Failed to get the token for host '{}'",
+                self.host().unwrap_or_default()
+            )
+        })
+    }
+
+    /// Set the workspace the default path is resolved in: the configuration
+    /// directory and its layout (used by config loader)
+    pub fn set_workspace(&mut self, config_dir: Option<PathBuf>, layout: Layout) {
         self.config_dir = config_dir;
+        self.layout = layout;
     }
 
     /// Add a tag to the repository
@@ -137,7 +345,22 @@ mod tests {
             tags: vec![],
             path: Some("journey".to_string()),
             branch: None,
+            reference: None,
+            depends_on: vec![],
+            archived: false,
+            default_branch: None,
+            ssh_key: None,
+            ssh_command: None,
+            env: BTreeMap::new(),
+            provider: Provider::default(),
+            branch_template: None,
+            commit_lint: None,
+            if_exists: None,
+            max_size: None,
+            visibility: None,
+            owner: None,
             config_dir: Some(PathBuf::from("/some/config/dir")),
+            layout: Layout::default(),
         };
 
         let target_dir = repo.get_target_dir();
@@ -152,6 +375,12 @@ mod tests {
         repo.path = None;
         let target_dir = repo.get_target_dir();
         assert_eq!(target_dir, "/some/config/dir/test-repo");
+
+        // Test default path under the by-tag layout
+        repo.layout = Layout::ByTag;
+        repo.tags = vec!["java".to_string()];
+        let target_dir = repo.get_target_dir();
+        assert_eq!(target_dir, "/some/config/dir/java/test-repo");
     }
 
     #[test]
@@ -164,7 +393,22 @@ mod tests {
             tags: vec![],
             path: Some("journey".to_string()),
             branch: None,
+            reference: None,
+            depends_on: Vec::new(),
+            archived: false,
+            default_branch: None,
+            ssh_key: None,
+            ssh_command: None,
+            env: BTreeMap::new(),
+            provider: Provider::default(),
+            branch_template: None,
+            commit_lint: None,
+            if_exists: None,
+            max_size: None,
+            visibility: None,
+            owner: None,
             config_dir: None,
+            layout: Layout::default(),
         };
 
         let target_dir = repo.get_target_dir();
@@ -186,6 +430,12 @@ mod tests {
         );
         assert!(repo_https.is_url_valid());
 
+        let repo_gerrit = Repository::new(
+            "test".to_string(),
+            "ssh://user@review.example.com:29418/project".to_string(),
+        );
+        assert!(repo_gerrit.is_url_valid());
+
         let repo_invalid = Repository::new("test".to_string(), "invalid-url".to_string());
         assert!(!repo_invalid.is_url_valid());
     }
@@ -239,7 +489,8 @@ mod tests {
             "test-repo".to_string(),
             "git@github.com:owner/test-repo.git".to_string(),
         );
-        repo_with_config_dir.set_config_dir(Some(std::path::PathBuf::from("/config/dir")));
+        repo_with_config_dir
+            .set_workspace(Some(std::path::PathBuf::from("/config/dir")), Layout::Flat);
 
         let target_dir_with_config = repo_with_config_dir.get_target_dir();
         assert!(target_dir_with_config.contains("/config/dir"));
@@ -263,5 +514,11 @@ mod tests {
 
         let invalid_url = Repository::new("test".to_string(), "invalid-url".to_string());
         assert!(invalid_url.validate().is_err());
+
+        let mut pinned = valid_repo.clone();
+        pinned.reference = Some("v1.2.3".to_string());
+        assert!(pinned.validate().is_ok());
+        pinned.branch = Some("main".to_string());
+        assert!(pinned.validate().is_err());
     }
 }
@@ -0,0 +1,94 @@
+//! Decryption of secrets stored in the config file, via the `age` and
+//! `sops` command line tools
+
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// First line of an ASCII-armored age ciphertext
+pub const AGE_HEADER: &str = "-----BEGIN AGE ENCRYPTED FILE-----";
+
+/// Environment variable pointing at the age identity, overriding the
+/// config's `age_identity`
+pub const AGE_IDENTITY_ENV: &str = "RREPOS_AGE_IDENTITY";
+
+/// Check whether a value is an armored age ciphertext
+pub fn is_encrypted(value: &str) -> bool {
+    value.trim_start().starts_with(AGE_HEADER)
+}
+
+/// Check whether YAML content is a SOPS-encrypted document, recognized by
+/// its top-level `sops` metadata
+pub fn is_sops_document(content: &str) -> bool {
+    serde_yaml::from_str::<serde_yaml::Value>(content)
+        .ok()
+        .and_then(|value| value.get("sops").cloned())
+        .is_some_and(|metadata| metadata.is_mapping())
+}
+
+/// Decrypt an armored age ciphertext with the given identity file
+pub fn decrypt_age(ciphertext: &str, identity: &Path) -> Result<String> {
+    let mut child = Command::new("age")
+        .arg("--decrypt")
+        .arg("--identity")
+        .arg(identity)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to execute age; is it installed?")?;
+
+    child
+        .stdin
+        .take()
+        .context("Failed to open age stdin")?
+        .write_all(ciphertext.trim().as_bytes())?;
+    let output = child.wait_with_output()?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to decrypt secret: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Decrypt a SOPS-encrypted YAML file; keys are found the way `sops` itself
+/// finds them (e.g. `SOPS_AGE_KEY_FILE`)
+pub fn decrypt_sops(path: &Path) -> Result<String> {
+    let output = Command::new("sops")
+        .args(["--decrypt", "--input-type", "yaml", "--output-type", "yaml"])
+        .arg(path)
+        .output()
+        .context("Failed to execute sops; is it installed?")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to decrypt config with sops: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_encrypted_values() {
+        assert!(is_encrypted(
+            "-----BEGIN AGE ENCRYPTED FILE-----\nYWdl\n-----END AGE ENCRYPTED FILE-----\n"
+        ));
+        assert!(!is_encrypted("ghp_plaintext"));
+
+        assert!(is_sops_document(
+            "repositories: []\nsops:\n  age: []\n  version: 3.8.1\n"
+        ));
+        assert!(!is_sops_document("repositories: []\n"));
+    }
+}
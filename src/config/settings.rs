@@ -0,0 +1,156 @@
+//! User-level settings kept outside the shared repository manifest
+
+use super::HostSettings;
+use super::discovery::user_config_dir;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Environment variable naming the settings file to use
+pub const SETTINGS_ENV: &str = "RREPOS_SETTINGS";
+
+/// When to color output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+    /// Color when writing to a terminal and `NO_COLOR` is unset
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// Safeguards against running a hard-to-undo operation on more repositories
+/// than intended
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Policy {
+    /// Operations on more repositories than this need `--yes` or a typed
+    /// confirmation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirm_above: Option<usize>,
+    /// Operations using `--force` need `--yes` or a typed confirmation
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub confirm_force: bool,
+}
+
+impl Policy {
+    /// Why an operation on `count` repositories needs approval, if it does
+    pub fn approval_reason(&self, count: usize, force: bool) -> Option<String> {
+        if force && self.confirm_force {
+            return Some("the policy requires confirming --force".to_string());
+        }
+        match self.confirm_above {
+            Some(limit) if count > limit => Some(format!(
+                "the policy requires confirming operations on more than {limit} repositories"
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Personal preferences from `~/.config/rrepos/settings.yaml`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UserSettings {
+    #[serde(default)]
+    pub color: ColorMode,
+    /// Most repositories processed at once by commands run with `-p`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jobs: Option<usize>,
+    /// Default for `--logs`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_dir: Option<String>,
+    /// Host settings such as tokens, used for any field the project config
+    /// leaves unset
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub hosts: BTreeMap<String, HostSettings>,
+    /// URLs that receive a JSON summary after every command
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub webhooks: Vec<String>,
+    /// Jinja template file for the `text` of webhook notifications
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notification_template: Option<String>,
+    /// Jinja template file for a summary printed to stderr after every command
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary_template: Option<String>,
+    /// Confirmations required before destructive operations
+    #[serde(default)]
+    pub policy: Policy,
+}
+
+impl UserSettings {
+    /// Location of the settings file: `RREPOS_SETTINGS`, else
+    /// `$XDG_CONFIG_HOME/rrepos/settings.yaml`
+    pub fn path() -> Option<PathBuf> {
+        std::env::var_os(SETTINGS_ENV)
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| user_config_dir().map(|dir| dir.join("rrepos").join("settings.yaml")))
+    }
+
+    /// Load the settings file; a missing file means default settings
+    pub fn load() -> Result<Self> {
+        let Some(path) = Self::path().filter(|path| path.is_file()) else {
+            return Ok(Self::default());
+        };
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read settings '{}'", path.display()))?;
+        serde_yaml::from_str(&content)
+            .with_context(|| format!("Invalid settings '{}'", path.display()))
+    }
+
+    /// Path of a template file named in the settings, relative paths being
+    /// resolved against the settings file's directory
+    pub fn template_path(template: &str) -> PathBuf {
+        let settings_dir = Self::path().and_then(|path| path.parent().map(PathBuf::from));
+        match settings_dir {
+            Some(dir) => dir.join(template),
+            None => PathBuf::from(template),
+        }
+    }
+
+    /// Apply the color preference to all output
+    pub fn apply_color(&self) {
+        match self.color {
+            ColorMode::Auto => {}
+            ColorMode::Always => colored::control::set_override(true),
+            ColorMode::Never => colored::control::set_override(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_settings() {
+        let settings: UserSettings = serde_yaml::from_str(
+            "color: never\njobs: 4\nlog_dir: /tmp/rrepos-logs\nhosts:\n  github.com:\n    token_env: MY_TOKEN\nwebhooks: [https://hooks.example.com/rrepos]\n",
+        )
+        .unwrap();
+
+        assert_eq!(settings.color, ColorMode::Never);
+        assert_eq!(settings.jobs, Some(4));
+        assert_eq!(settings.log_dir.as_deref(), Some("/tmp/rrepos-logs"));
+        assert_eq!(
+            settings.hosts["github.com"].token_env.as_deref(),
+            Some("MY_TOKEN")
+        );
+        assert_eq!(settings.webhooks.len(), 1);
+        assert!(serde_yaml::from_str::<UserSettings>("colour: never").is_err());
+    }
+
+    #[test]
+    fn test_policy_approval_reason() {
+        let policy: Policy =
+            serde_yaml::from_str("confirm_above: 10\nconfirm_force: true\n").unwrap();
+
+        assert!(policy.approval_reason(10, false).is_none());
+        assert!(policy.approval_reason(11, false).is_some());
+        assert!(policy.approval_reason(1, true).is_some());
+        assert!(Policy::default().approval_reason(1000, true).is_none());
+    }
+}
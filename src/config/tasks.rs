@@ -0,0 +1,68 @@
+//! Named tasks mapping one logical command to each repository's incantation
+
+use super::Repository;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A task from the `tasks:` section: either a single command for every
+/// repository, or a default with overrides keyed by repository name or tag
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Task {
+    Command(String),
+    Overridable {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        default: Option<String>,
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        overrides: BTreeMap<String, String>,
+    },
+}
+
+impl Task {
+    /// The command to run in a repository: an override for its name, then
+    /// for the first of its tags that has one, then the default
+    pub fn command_for(&self, repo: &Repository) -> Option<&str> {
+        match self {
+            Task::Command(command) => Some(command),
+            Task::Overridable { default, overrides } => overrides
+                .get(&repo.name)
+                .or_else(|| repo.tags.iter().find_map(|tag| overrides.get(tag)))
+                .or(default.as_ref())
+                .map(String::as_str),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_for() {
+        let tasks: BTreeMap<String, Task> = serde_yaml::from_str(
+            "test: cargo test\nbuild:\n  default: make\n  overrides:\n    legacy-api: ./build.sh\n    java: mvn package\n",
+        )
+        .unwrap();
+
+        let mut legacy = Repository::new(
+            "legacy-api".to_string(),
+            "git@github.com:owner/legacy-api.git".to_string(),
+        );
+        legacy.add_tag("java".to_string());
+        let mut service = Repository::new(
+            "billing".to_string(),
+            "git@github.com:owner/billing.git".to_string(),
+        );
+        service.add_tag("java".to_string());
+        let web = Repository::new(
+            "web".to_string(),
+            "git@github.com:owner/web.git".to_string(),
+        );
+
+        let build = &tasks["build"];
+        assert_eq!(build.command_for(&legacy), Some("./build.sh"));
+        assert_eq!(build.command_for(&service), Some("mvn package"));
+        assert_eq!(build.command_for(&web), Some("make"));
+        assert_eq!(tasks["test"].command_for(&web), Some("cargo test"));
+    }
+}
@@ -31,6 +31,22 @@ impl ConfigValidator {
             }
         }
 
+        // Check that dependencies refer to known repositories
+        for repo in repos {
+            for dependency in &repo.depends_on {
+                if !names.contains(dependency) {
+                    errors.push(format!(
+                        "Repository '{}': unknown dependency '{}'",
+                        repo.name, dependency
+                    ));
+                }
+            }
+        }
+
+        if let Err(e) = super::dependency_levels(repos) {
+            errors.push(e.to_string());
+        }
+
         if !errors.is_empty() {
             return Err(anyhow::anyhow!("Validation errors: {}", errors.join("; ")));
         }
@@ -98,6 +114,17 @@ mod tests {
         assert!(ConfigValidator::validate_repositories(&repos).is_err());
     }
 
+    #[test]
+    fn test_unknown_dependency() {
+        let mut repo1 = Repository::new(
+            "repo1".to_string(),
+            "git@github.com:owner/repo1.git".to_string(),
+        );
+        repo1.depends_on = vec!["missing".to_string()];
+
+        assert!(ConfigValidator::validate_repositories(&[repo1]).is_err());
+    }
+
     #[test]
     fn test_tag_filter_validation() {
         assert!(ConfigValidator::validate_tag_filter("frontend").is_ok());
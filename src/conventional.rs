@@ -0,0 +1,148 @@
+//! Conventional Commits parsing
+
+use regex::Regex;
+use std::sync::LazyLock;
+
+static HEADER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?P<type>[a-zA-Z]+)(?:\((?P<scope>[^)]+)\))?(?P<breaking>!)?: (?P<description>.+)$",
+    )
+    .unwrap()
+});
+
+/// Commit types accepted by commitlint's conventional config
+pub const TYPES: [&str; 11] = [
+    "build", "chore", "ci", "docs", "feat", "fix", "perf", "refactor", "revert", "style", "test",
+];
+
+/// Longest header commitlint's conventional config accepts
+const MAX_HEADER_LENGTH: usize = 100;
+
+/// A parsed Conventional Commits header, e.g. `feat(api)!: add paging`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConventionalCommit {
+    pub commit_type: String,
+    pub scope: Option<String>,
+    pub breaking: bool,
+    pub description: String,
+}
+
+impl ConventionalCommit {
+    /// Parse the first line of a commit message
+    pub fn parse(message: &str) -> Option<Self> {
+        let header = message.lines().next()?.trim();
+        let captures = HEADER.captures(header)?;
+
+        Some(Self {
+            commit_type: captures["type"].to_lowercase(),
+            scope: captures.name("scope").map(|m| m.as_str().to_string()),
+            breaking: captures.name("breaking").is_some() || message.contains("BREAKING CHANGE:"),
+            description: captures["description"].to_string(),
+        })
+    }
+
+    /// Human readable section heading for the commit type
+    pub fn section(&self) -> &'static str {
+        section_for(&self.commit_type)
+    }
+}
+
+/// Check a commit message the way commitlint's conventional config does,
+/// returning why it is rejected
+pub fn lint(message: &str) -> Result<(), String> {
+    let header = message.lines().next().unwrap_or_default().trim();
+    if header.chars().count() > MAX_HEADER_LENGTH {
+        return Err(format!(
+            "the header is longer than {MAX_HEADER_LENGTH} characters"
+        ));
+    }
+    let commit = ConventionalCommit::parse(message)
+        .ok_or_else(|| "expected '<type>[(<scope>)]: <description>'".to_string())?;
+    if !header.starts_with(&commit.commit_type) {
+        return Err(format!(
+            "the type '{}' must be lowercase",
+            commit.commit_type
+        ));
+    }
+    if !TYPES.contains(&commit.commit_type.as_str()) {
+        return Err(format!(
+            "unknown type '{}', expected one of {}",
+            commit.commit_type,
+            TYPES.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Prefix `message` with a commit type, unless it already has one
+pub fn compose(commit_type: Option<&str>, message: String) -> String {
+    match commit_type {
+        Some(commit_type) if ConventionalCommit::parse(&message).is_none() => {
+            format!("{commit_type}: {message}")
+        }
+        _ => message,
+    }
+}
+
+/// Human readable section heading for a commit type
+pub fn section_for(commit_type: &str) -> &'static str {
+    match commit_type {
+        "feat" => "Features",
+        "fix" => "Bug Fixes",
+        "perf" => "Performance",
+        "refactor" => "Refactoring",
+        "docs" => "Documentation",
+        "test" => "Tests",
+        "build" | "ci" => "Build System",
+        "chore" | "style" | "revert" => "Miscellaneous",
+        _ => "Other Changes",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_full_header() {
+        let commit = ConventionalCommit::parse("feat(api)!: add paging\n\nbody").unwrap();
+        assert_eq!(commit.commit_type, "feat");
+        assert_eq!(commit.scope.as_deref(), Some("api"));
+        assert!(commit.breaking);
+        assert_eq!(commit.description, "add paging");
+        assert_eq!(commit.section(), "Features");
+    }
+
+    #[test]
+    fn test_parse_breaking_footer() {
+        let commit =
+            ConventionalCommit::parse("fix: handle nulls\n\nBREAKING CHANGE: api changed").unwrap();
+        assert_eq!(commit.scope, None);
+        assert!(commit.breaking);
+    }
+
+    #[test]
+    fn test_lint_and_compose() {
+        assert!(lint("feat(api): add paging").is_ok());
+        assert!(lint("Update README").is_err());
+        assert!(lint("Feat: add paging").is_err());
+        assert!(lint("feature: add paging").is_err());
+        assert!(lint(&format!("fix: {}", "x".repeat(100))).is_err());
+
+        let composed = compose(Some("chore"), "bump serde".to_string());
+        assert_eq!(composed, "chore: bump serde");
+        assert!(lint(&composed).is_ok());
+        assert_eq!(
+            compose(Some("chore"), "fix: bump serde".to_string()),
+            "fix: bump serde"
+        );
+        assert_eq!(compose(None, "bump serde".to_string()), "bump serde");
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(ConventionalCommit::parse("Update README").is_none());
+        assert!(ConventionalCommit::parse("feat:missing space").is_none());
+        assert!(ConventionalCommit::parse("").is_none());
+    }
+}
@@ -0,0 +1,124 @@
+//! Detection of languages, tooling and CI systems from a repository's working
+//! tree, exposed as virtual tags such as `lang:rust`, `has:dockerfile` and
+//! `ci:github-actions`. Virtual tags work in `--tag` filters but are never
+//! written to the config; they are recomputed from the files on disk.
+
+use std::path::Path;
+
+/// Prefixes of the virtual tags
+pub const VIRTUAL_TAG_PREFIXES: &[&str] = &["lang:", "has:", "ci:"];
+
+/// Languages, each with files at the repository root that indicate it
+const LANGUAGES: &[(&str, &[&str])] = &[
+    ("rust", &["Cargo.toml"]),
+    ("go", &["go.mod", "main.go"]),
+    ("javascript", &["package.json"]),
+    ("typescript", &["tsconfig.json"]),
+    (
+        "python",
+        &["pyproject.toml", "setup.py", "requirements.txt", "Pipfile"],
+    ),
+    ("java", &["pom.xml", "build.gradle", "build.gradle.kts"]),
+    ("kotlin", &["build.gradle.kts", "settings.gradle.kts"]),
+    ("ruby", &["Gemfile"]),
+    ("php", &["composer.json"]),
+    ("swift", &["Package.swift"]),
+    ("elixir", &["mix.exs"]),
+    ("scala", &["build.sbt"]),
+    ("dart", &["pubspec.yaml"]),
+];
+
+/// Tooling, each with files that indicate it
+const TOOLS: &[(&str, &[&str])] = &[
+    ("dockerfile", &["Dockerfile"]),
+    (
+        "docker-compose",
+        &[
+            "docker-compose.yml",
+            "docker-compose.yaml",
+            "compose.yml",
+            "compose.yaml",
+        ],
+    ),
+    ("makefile", &["Makefile", "GNUmakefile"]),
+    ("justfile", &["justfile", "Justfile"]),
+    ("helm", &["Chart.yaml"]),
+    ("pre-commit", &[".pre-commit-config.yaml"]),
+    (
+        "codeowners",
+        &["CODEOWNERS", ".github/CODEOWNERS", "docs/CODEOWNERS"],
+    ),
+];
+
+/// CI systems, each with the files configuring it
+const CI_SYSTEMS: &[(&str, &[&str])] = &[
+    ("github-actions", &[".github/workflows"]),
+    ("gitlab-ci", &[".gitlab-ci.yml"]),
+    ("circleci", &[".circleci/config.yml"]),
+    ("jenkins", &["Jenkinsfile"]),
+    ("azure-pipelines", &["azure-pipelines.yml"]),
+    ("travis", &[".travis.yml"]),
+    ("buildkite", &[".buildkite"]),
+];
+
+/// Whether a tag is one of the detected ones rather than a configured one
+pub fn is_virtual_tag(tag: &str) -> bool {
+    VIRTUAL_TAG_PREFIXES
+        .iter()
+        .any(|prefix| tag.starts_with(prefix))
+}
+
+fn detected<'a>(path: &Path, table: &[(&'a str, &[&str])]) -> Vec<&'a str> {
+    table
+        .iter()
+        .filter(|(_, files)| files.iter().any(|file| path.join(file).exists()))
+        .map(|(name, _)| *name)
+        .collect()
+}
+
+/// Languages used in the working tree at `path`
+pub fn languages(path: &Path) -> Vec<&'static str> {
+    detected(path, LANGUAGES)
+}
+
+/// Virtual tags of the working tree at `path`; none when it doesn't exist
+pub fn virtual_tags(path: &Path) -> Vec<String> {
+    let languages = languages(path)
+        .into_iter()
+        .map(|name| format!("lang:{name}"));
+    let tools = detected(path, TOOLS)
+        .into_iter()
+        .map(|name| format!("has:{name}"));
+    let ci = detected(path, CI_SYSTEMS)
+        .into_iter()
+        .map(|name| format!("ci:{name}"));
+    languages.chain(tools).chain(ci).collect()
+}
+
+/// Whether the working tree at `path` has the virtual tag
+pub fn has_virtual_tag(path: &Path, tag: &str) -> bool {
+    is_virtual_tag(tag) && virtual_tags(path).iter().any(|t| t == tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_virtual_tags() {
+        let dir = std::env::temp_dir().join(format!("rrepos-detect-test-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join(".github/workflows")).unwrap();
+        std::fs::write(dir.join("Cargo.toml"), "[package]\n").unwrap();
+        std::fs::write(dir.join("Dockerfile"), "FROM scratch\n").unwrap();
+
+        assert_eq!(
+            virtual_tags(&dir),
+            vec!["lang:rust", "has:dockerfile", "ci:github-actions"]
+        );
+        assert!(has_virtual_tag(&dir, "lang:rust"));
+        assert!(!has_virtual_tag(&dir, "lang:go"));
+        assert!(!has_virtual_tag(&dir, "rust"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
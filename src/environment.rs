@@ -0,0 +1,151 @@
+//! Environment variables passed to commands spawned in repositories
+
+use crate::config::Repository;
+use anyhow::{Context, Result};
+use std::process::Command;
+
+/// Controls which environment variables child processes receive
+#[derive(Debug, Clone, Default)]
+pub struct Environment {
+    /// Variables injected into every child process
+    pub vars: Vec<(String, String)>,
+    /// When set, only inherited variables matching these names are passed on.
+    /// A trailing `*` matches any suffix, e.g. `LC_*`.
+    pub passlist: Option<Vec<String>>,
+}
+
+impl Environment {
+    /// Add a `KEY=VALUE` assignment
+    pub fn with_assignment(mut self, assignment: &str) -> Result<Self> {
+        self.vars.push(parse_assignment(assignment)?);
+        Ok(self)
+    }
+
+    /// Add all assignments from a dotenv-style file
+    pub fn with_env_file(mut self, path: &str) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read env file '{path}'"))?;
+        self.vars.extend(parse_env_file(&content)?);
+        Ok(self)
+    }
+
+    /// Restrict inherited variables to the given names
+    pub fn with_passlist(mut self, passlist: Option<Vec<String>>) -> Self {
+        self.passlist = passlist;
+        self
+    }
+
+    /// Check whether an inherited variable may be passed to child processes
+    pub fn is_passed(&self, name: &str) -> bool {
+        match &self.passlist {
+            None => true,
+            Some(passlist) => passlist
+                .iter()
+                .any(|pattern| match pattern.strip_suffix('*') {
+                    Some(prefix) => name.starts_with(prefix),
+                    None => name == pattern,
+                }),
+        }
+    }
+
+    /// Apply the environment to a process about to be spawned
+    pub fn apply(&self, cmd: &mut Command) {
+        if self.passlist.is_some() {
+            cmd.env_clear();
+            cmd.envs(std::env::vars().filter(|(name, _)| self.is_passed(name)));
+        }
+        cmd.envs(self.vars.iter().map(|(k, v)| (k, v)));
+    }
+}
+
+/// Variables describing a repository, followed by the `env` configured for it
+pub fn repository_vars(repo: &Repository) -> Vec<(String, String)> {
+    let path = repo.get_target_dir();
+    let path = std::path::absolute(&path)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or(path);
+    let mut vars = vec![
+        ("RREPOS_REPO".to_string(), repo.name.clone()),
+        ("RREPOS_REPO_PATH".to_string(), path),
+        ("RREPOS_REPO_URL".to_string(), repo.url.clone()),
+        ("RREPOS_REPO_TAGS".to_string(), repo.tags.join(",")),
+    ];
+    if let Some(branch) = repo.branch.as_ref().or(repo.default_branch.as_ref()) {
+        vars.push(("RREPOS_REPO_BRANCH".to_string(), branch.clone()));
+    }
+    vars.extend(repo.env.iter().map(|(k, v)| (k.clone(), v.clone())));
+    vars
+}
+
+/// Parse a single `KEY=VALUE` assignment
+pub fn parse_assignment(assignment: &str) -> Result<(String, String)> {
+    let (key, value) = assignment.split_once('=').ok_or_else(|| {
+        anyhow::anyhow!(
+            "Invalid environment assignment (expected KEY=VALUE): {}",
+            assignment
+        )
+    })?;
+
+    let key = key.trim();
+    if key.is_empty() || key.contains(char::is_whitespace) {
+        anyhow::bail!("Invalid environment variable name: '{}'", key);
+    }
+
+    Ok((key.to_string(), unquote(value.trim()).to_string()))
+}
+
+/// Parse dotenv-style content: `KEY=VALUE` lines, `#` comments and an
+/// optional `export ` prefix
+pub fn parse_env_file(content: &str) -> Result<Vec<(String, String)>> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| parse_assignment(line.strip_prefix("export ").unwrap_or(line)))
+        .collect()
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if value.len() >= 2 && value.starts_with(quote) && value.ends_with(quote) {
+            return &value[1..value.len() - 1];
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_env_file() {
+        let vars = parse_env_file(
+            "# comment\nexport NODE_ENV=test\n\nTOKEN=\"a b\"\nEMPTY=\nURL=http://x?a=b\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            vars,
+            vec![
+                ("NODE_ENV".to_string(), "test".to_string()),
+                ("TOKEN".to_string(), "a b".to_string()),
+                ("EMPTY".to_string(), "".to_string()),
+                ("URL".to_string(), "http://x?a=b".to_string()),
+            ]
+        );
+        assert!(parse_env_file("NOT AN ASSIGNMENT").is_err());
+    }
+
+    #[test]
+    fn test_passlist() {
+        let env = Environment::default();
+        assert!(env.is_passed("GITHUB_TOKEN"));
+
+        let env = env.with_passlist(Some(vec!["PATH".to_string(), "LC_*".to_string()]));
+        assert!(env.is_passed("PATH"));
+        assert!(env.is_passed("LC_ALL"));
+        assert!(!env.is_passed("GITHUB_TOKEN"));
+        assert!(!env.is_passed("PATHX"));
+    }
+}
@@ -0,0 +1,193 @@
+//! Newline-delimited JSON events describing a run's progress, for external
+//! dashboards and wrappers
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::Serialize;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+/// Formats of the event stream written to stdout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum EventFormat {
+    /// One JSON object per line
+    Ndjson,
+}
+
+/// Command-line options selecting where events go
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct EventOptions {
+    /// Send newline-delimited JSON progress events to this Unix socket
+    #[arg(long, value_name = "PATH")]
+    pub event_socket: Option<String>,
+
+    /// Write progress events to stdout instead of the usual output, which moves to stderr
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        conflicts_with = "event_socket"
+    )]
+    pub event_stdout: Option<EventFormat>,
+}
+
+impl EventOptions {
+    /// The sink the options select, if any
+    pub fn sink(&self) -> Result<Option<Arc<EventSink>>> {
+        let sink = match (&self.event_socket, self.event_stdout) {
+            (Some(path), _) => EventSink::connect(path)?,
+            (None, Some(format)) => EventSink::stdout(format)?,
+            (None, None) => return Ok(None),
+        };
+        Ok(Some(Arc::new(sink)))
+    }
+}
+
+/// Output stream a line was printed on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// Something that happened while running a command in a repository
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event<'a> {
+    Started {
+        repo: &'a str,
+        command: &'a str,
+    },
+    Output {
+        repo: &'a str,
+        stream: Stream,
+        line: &'a str,
+    },
+    Finished {
+        repo: &'a str,
+        exit_code: i32,
+        duration_ms: u128,
+    },
+}
+
+#[derive(Serialize)]
+struct TimedEvent<'a> {
+    time: String,
+    #[serde(flatten)]
+    event: &'a Event<'a>,
+}
+
+/// Destination of the events
+pub struct EventSink {
+    writer: Mutex<Box<dyn Write + Send>>,
+}
+
+impl EventSink {
+    /// Send events to a Unix socket another program listens on
+    #[cfg(unix)]
+    pub fn connect(path: &str) -> Result<Self> {
+        let stream = std::os::unix::net::UnixStream::connect(path)
+            .with_context(|| format!("Failed to connect to event socket '{path}'"))?;
+        Ok(Self::new(Box::new(stream)))
+    }
+
+    #[cfg(not(unix))]
+    pub fn connect(_path: &str) -> Result<Self> {
+        anyhow::bail!("--event-socket is only supported on Unix")
+    }
+
+    /// Write events to stdout. Everything else printed to stdout moves to
+    /// stderr, so stdout carries only events.
+    #[cfg(unix)]
+    pub fn stdout(_format: EventFormat) -> Result<Self> {
+        use std::os::fd::FromRawFd;
+
+        // SAFETY: the duplicated descriptor is owned by the returned file
+        let events = unsafe {
+            let fd = libc::dup(libc::STDOUT_FILENO);
+            if fd < 0 || libc::dup2(libc::STDERR_FILENO, libc::STDOUT_FILENO) < 0 {
+                anyhow::bail!(
+                    "Failed to redirect stdout: {}",
+                    std::io::Error::last_os_error()
+                );
+            }
+            std::fs::File::from_raw_fd(fd)
+        };
+        Ok(Self::new(Box::new(events)))
+    }
+
+    #[cfg(not(unix))]
+    pub fn stdout(_format: EventFormat) -> Result<Self> {
+        anyhow::bail!("--event-stdout is only supported on Unix")
+    }
+
+    pub fn new(writer: Box<dyn Write + Send>) -> Self {
+        Self {
+            writer: Mutex::new(writer),
+        }
+    }
+
+    /// Write an event. Failures are ignored so a consumer that went away
+    /// doesn't fail the run.
+    pub fn emit(&self, event: &Event) {
+        let timed = TimedEvent {
+            time: Utc::now().to_rfc3339(),
+            event,
+        };
+        let Ok(line) = serde_json::to_string(&timed) else {
+            return;
+        };
+        if let Ok(mut writer) = self.writer.lock() {
+            writeln!(writer, "{line}").ok();
+            writer.flush().ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_emit_ndjson() {
+        let buffer = Buffer::default();
+        let sink = EventSink::new(Box::new(buffer.clone()));
+        sink.emit(&Event::Output {
+            repo: "api",
+            stream: Stream::Stderr,
+            line: "warning: unused",
+        });
+        sink.emit(&Event::Finished {
+            repo: "api",
+            exit_code: 0,
+            duration_ms: 12,
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let events: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["event"], "output");
+        assert_eq!(events[0]["stream"], "stderr");
+        assert_eq!(events[0]["line"], "warning: unused");
+        assert_eq!(events[1]["event"], "finished");
+        assert_eq!(events[1]["exit_code"], 0);
+        assert!(events[1]["time"].is_string());
+    }
+}
@@ -0,0 +1,211 @@
+//! Gerrit code review: submitting changes by pushing to `refs/for/<branch>`,
+//! and querying their state through the REST API
+
+use crate::config::{HostSettings, Repository};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// Prefix Gerrit puts before JSON responses to prevent XSSI
+const JSON_PREFIX: &str = ")]}'";
+
+/// A new `Change-Id` trailer value: `I` followed by 40 hex digits
+pub fn change_id() -> String {
+    let digits = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    format!("I{}", &digits[..40])
+}
+
+/// Gerrit project of a repository URL, such as `platform/build` for
+/// `ssh://user@review.example.com:29418/platform/build`
+pub fn project(url: &str) -> Option<String> {
+    let path = match url.split_once("://") {
+        Some((_, rest)) => rest.split_once('/')?.1,
+        None => url.split_once(':')?.1,
+    };
+    // Authenticated HTTPS remotes live under /a/
+    let path = path.strip_prefix("a/").unwrap_or(path);
+    let project = path.trim_matches('/').trim_end_matches(".git");
+    (!project.is_empty()).then(|| project.to_string())
+}
+
+/// URL of the change Gerrit reported when accepting a push
+pub fn pushed_change_url(output: &str) -> Option<String> {
+    output
+        .lines()
+        .filter_map(|line| line.strip_prefix("remote:"))
+        .flat_map(str::split_whitespace)
+        .find(|word| word.starts_with("https://") || word.starts_with("http://"))
+        .map(str::to_string)
+}
+
+/// A change as returned by the REST API
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChangeInfo {
+    #[serde(rename = "_number")]
+    pub number: u64,
+    pub subject: String,
+    pub status: String,
+    #[serde(default)]
+    pub topic: Option<String>,
+    #[serde(default)]
+    pub labels: BTreeMap<String, LabelInfo>,
+}
+
+/// Votes on a label, as summarized by the `LABELS` option
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LabelInfo {
+    #[serde(default)]
+    approved: Option<serde_json::Value>,
+    #[serde(default)]
+    rejected: Option<serde_json::Value>,
+    #[serde(default)]
+    recommended: Option<serde_json::Value>,
+    #[serde(default)]
+    disliked: Option<serde_json::Value>,
+}
+
+impl LabelInfo {
+    /// The strongest vote on the label, such as `+2` or `-1`, if any
+    pub fn vote(&self) -> Option<&'static str> {
+        if self.rejected.is_some() {
+            Some("-2")
+        } else if self.approved.is_some() {
+            Some("+2")
+        } else if self.disliked.is_some() {
+            Some("-1")
+        } else if self.recommended.is_some() {
+            Some("+1")
+        } else {
+            None
+        }
+    }
+}
+
+/// Client for a Gerrit server's REST API
+pub struct GerritClient {
+    client: reqwest::Client,
+    base_url: String,
+    credentials: Option<(String, String)>,
+}
+
+impl GerritClient {
+    /// Client for the Gerrit server hosting a repository, authenticated with
+    /// an HTTP password when `username` and the host's token are set
+    pub fn for_repository(
+        repo: &Repository,
+        settings: &HostSettings,
+        username: Option<&str>,
+    ) -> Result<Self> {
+        let host = repo
+            .host()
+            .with_context(|| format!("No host in repository URL '{}'", repo.url))?;
+        Self::new(
+            &format!("https://{host}"),
+            settings,
+            username.zip(
+                settings
+                    .token()
+                    .with_context(|| format!("Failed to get the token for host '{host}'"))?,
+            ),
+        )
+    }
+
+    pub fn new(
+        base_url: &str,
+        settings: &HostSettings,
+        credentials: Option<(&str, String)>,
+    ) -> Result<Self> {
+        Ok(Self {
+            client: settings.http_client()?,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            credentials: credentials.map(|(user, password)| (user.to_string(), password)),
+        })
+    }
+
+    /// Web URL of a change
+    pub fn change_url(&self, project: &str, number: u64) -> String {
+        format!("{}/c/{project}/+/{number}", self.base_url)
+    }
+
+    /// Changes of a project, limited to a topic or else to open changes
+    pub async fn changes(&self, project: &str, topic: Option<&str>) -> Result<Vec<ChangeInfo>> {
+        let query = match topic {
+            Some(topic) => format!("project:{project} topic:{topic}"),
+            None => format!("project:{project} status:open"),
+        };
+        // Authenticated endpoints live under /a/
+        let prefix = if self.credentials.is_some() { "/a" } else { "" };
+        let mut request = self
+            .client
+            .get(format!("{}{prefix}/changes/", self.base_url))
+            .query(&[("q", query.as_str()), ("o", "LABELS")]);
+        if let Some((user, password)) = &self.credentials {
+            request = request.basic_auth(user, Some(password));
+        }
+
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to query Gerrit at {}", self.base_url))?;
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            anyhow::bail!("Gerrit returned {}: {}", status, body.trim());
+        }
+        parse_response(&body)
+    }
+}
+
+fn parse_response<T: serde::de::DeserializeOwned>(body: &str) -> Result<T> {
+    let json = body.strip_prefix(JSON_PREFIX).unwrap_or(body);
+    serde_json::from_str(json).context("Failed to parse Gerrit response")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project() {
+        assert_eq!(
+            project("ssh://user@review.example.com:29418/platform/build"),
+            Some("platform/build".to_string())
+        );
+        assert_eq!(
+            project("https://review.example.com/a/tools.git"),
+            Some("tools".to_string())
+        );
+        assert_eq!(
+            project("git@review.example.com:tools"),
+            Some("tools".to_string())
+        );
+        assert_eq!(project("https://review.example.com/"), None);
+    }
+
+    #[test]
+    fn test_change_id() {
+        let id = change_id();
+        assert_eq!(id.len(), 41);
+        assert!(id.starts_with('I'));
+        assert!(id[1..].chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    #[test]
+    fn test_parse_changes() {
+        let body = r#")]}'
+[{"_number": 42, "subject": "Bump deps", "status": "NEW", "topic": "deps",
+  "labels": {"Code-Review": {"recommended": {"_account_id": 1}}, "Verified": {}}}]"#;
+        let changes: Vec<ChangeInfo> = parse_response(body).unwrap();
+        assert_eq!(changes[0].number, 42);
+        assert_eq!(changes[0].labels["Code-Review"].vote(), Some("+1"));
+        assert_eq!(changes[0].labels["Verified"].vote(), None);
+
+        let output =
+            "remote: SUCCESS\nremote:   https://review.example.com/c/tools/+/42 Bump deps [NEW]\n";
+        assert_eq!(
+            pushed_change_url(output),
+            Some("https://review.example.com/c/tools/+/42".to_string())
+        );
+    }
+}
@@ -2,19 +2,102 @@
 
 use super::client::GitHubClient;
 use super::types::{PrOptions, PullRequestParams};
-use crate::config::Repository;
+use crate::config::{CommitLint, Config, HostSettings, Provider, Repository};
+use crate::conventional;
+use crate::gerrit;
 use crate::git;
+use crate::runs;
+use crate::template;
 use anyhow::Result;
+use chrono::Utc;
 use colored::*;
+use std::collections::HashMap;
 use uuid::Uuid;
 
 // Constants for maintainability
-const DEFAULT_BRANCH_PREFIX: &str = "automated-changes";
+pub const DEFAULT_BRANCH_PREFIX: &str = "automated-changes";
 const UUID_LENGTH: usize = 6;
-const DEFAULT_BASE_BRANCH: &str = "main";
+pub const DEFAULT_BASE_BRANCH: &str = "main";
+/// Branch template of campaigns when none is configured
+pub const DEFAULT_CAMPAIGN_BRANCH_TEMPLATE: &str = "campaign/{campaign}";
+
+/// Name of the branch a pull request is opened from: the explicit branch
+/// name, or else the first of the options', the repository's and the
+/// config's branch templates, rendered
+pub fn branch_name(config: &Config, repo: &Repository, options: &PrOptions) -> String {
+    render_branch_name(
+        config,
+        repo,
+        options,
+        &Uuid::new_v4().simple().to_string()[..UUID_LENGTH],
+    )
+}
+
+/// Like `branch_name`, with `uuid` for the `{uuid}` placeholder
+pub fn render_branch_name(
+    config: &Config,
+    repo: &Repository,
+    options: &PrOptions,
+    uuid: &str,
+) -> String {
+    if let Some(branch_name) = &options.branch_name {
+        return branch_name.clone();
+    }
+
+    let template = match options
+        .branch_template
+        .as_deref()
+        .or(repo.branch_template(config))
+    {
+        Some(template) => template.to_string(),
+        None if options.campaign.is_some() => DEFAULT_CAMPAIGN_BRANCH_TEMPLATE.to_string(),
+        None => format!("{DEFAULT_BRANCH_PREFIX}-{{uuid}}"),
+    };
+
+    let vars = HashMap::from([
+        (
+            "date".to_string(),
+            Utc::now().format("%Y-%m-%d").to_string(),
+        ),
+        (
+            "campaign".to_string(),
+            options
+                .campaign
+                .clone()
+                .unwrap_or_else(|| slug(&options.title)),
+        ),
+        ("uuid".to_string(), uuid.to_string()),
+        ("name".to_string(), repo.name.clone()),
+    ]);
+    template::render(&template, &vars)
+}
+
+/// Lowercase `text` with runs of other characters than letters and digits
+/// replaced by a dash, for use in branch names
+fn slug(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
 
 /// Create a pull request for a repository
-pub async fn create_pull_request(repo: &Repository, options: &PrOptions) -> Result<()> {
+pub async fn create_pull_request(
+    config: &Config,
+    repo: &Repository,
+    options: &PrOptions,
+) -> Result<()> {
+    open_pull_request(config, repo, options).await.map(|_| ())
+}
+
+/// Create a pull request for a repository, returning its URL when one was
+/// opened
+pub async fn open_pull_request(
+    config: &Config,
+    repo: &Repository,
+    options: &PrOptions,
+) -> Result<Option<String>> {
     let repo_path = repo.get_target_dir();
 
     // Check if repository has changes
@@ -24,17 +107,24 @@ pub async fn create_pull_request(repo: &Repository, options: &PrOptions) -> Resu
             repo.name.cyan().bold(),
             "No changes detected".yellow()
         );
-        return Ok(());
+        return Ok(None);
     }
 
-    // Generate branch name if not provided
-    let branch_name = options.branch_name.clone().unwrap_or_else(|| {
-        format!(
-            "{}-{}",
-            DEFAULT_BRANCH_PREFIX,
-            &Uuid::new_v4().simple().to_string()[..UUID_LENGTH]
-        )
-    });
+    let mut commit_message = options
+        .commit_msg
+        .clone()
+        .unwrap_or_else(|| options.title.clone());
+    if repo.commit_lint(config) == Some(CommitLint::Conventional)
+        && let Err(reason) = conventional::lint(&commit_message)
+    {
+        anyhow::bail!(
+            "Commit message '{}' is not a Conventional Commit: {reason} (add a type with --type)",
+            commit_message.lines().next().unwrap_or_default()
+        );
+    }
+
+    let branch_name = branch_name(config, repo, options);
+    let settings = repo.host_settings(config);
 
     // Create and checkout new branch
     git::create_and_checkout_branch(&repo_path, &branch_name)?;
@@ -43,25 +133,88 @@ pub async fn create_pull_request(repo: &Repository, options: &PrOptions) -> Resu
     git::add_all_changes(&repo_path)?;
 
     // Commit changes
-    let commit_message = options
-        .commit_msg
-        .clone()
-        .unwrap_or_else(|| options.title.clone());
+    if repo.provider == Provider::Gerrit {
+        commit_message = format!("{commit_message}\n\nChange-Id: {}", gerrit::change_id());
+    }
     git::commit_changes(&repo_path, &commit_message)?;
 
+    if !options.create_only && repo.provider == Provider::Gerrit {
+        return submit_gerrit_change(repo, &settings, &branch_name, options).map(Some);
+    }
+
     if !options.create_only {
         // Push branch
-        git::push_branch(&repo_path, &branch_name)?;
+        git::push_branch(repo, &settings, &branch_name)?;
+
+        if repo.provider == Provider::None {
+            print_merge_instructions(repo, &branch_name, options);
+            return Ok(None);
+        }
 
         // Create PR via GitHub API
-        create_github_pr(repo, &branch_name, options).await?;
+        return create_github_pr(config, repo, &settings, &branch_name, options)
+            .await
+            .map(Some);
     }
 
-    Ok(())
+    Ok(None)
+}
+
+/// Push the commit to Gerrit for review, with the branch name as its topic
+fn submit_gerrit_change(
+    repo: &Repository,
+    settings: &HostSettings,
+    branch_name: &str,
+    options: &PrOptions,
+) -> Result<String> {
+    let base_branch = options
+        .base_branch
+        .as_deref()
+        .unwrap_or(DEFAULT_BASE_BRANCH);
+    let output = git::push_for_review(repo, settings, base_branch, branch_name)?;
+
+    let change_url = gerrit::pushed_change_url(&output).unwrap_or_else(|| "unknown".to_string());
+    println!(
+        "{} | {} {}",
+        repo.name.cyan().bold(),
+        "Change submitted:".green(),
+        change_url
+    );
+    Ok(change_url)
+}
+
+/// Tell the user how to get a pushed branch merged on a server without a
+/// pull request API
+fn print_merge_instructions(repo: &Repository, branch_name: &str, options: &PrOptions) {
+    let base_branch = options
+        .base_branch
+        .as_deref()
+        .unwrap_or(DEFAULT_BASE_BRANCH);
+    println!(
+        "{} | {} '{}'",
+        repo.name.cyan().bold(),
+        "Pushed branch".green(),
+        branch_name
+    );
+    println!(
+        "{} | {}",
+        repo.name.cyan().bold(),
+        format!(
+            "{} has no pull request API; ask for '{branch_name}' to be merged into '{base_branch}' there",
+            repo.host().unwrap_or("The remote")
+        )
+        .yellow()
+    );
 }
 
-async fn create_github_pr(repo: &Repository, branch_name: &str, options: &PrOptions) -> Result<()> {
-    let client = GitHubClient::new(Some(options.token.clone()));
+async fn create_github_pr(
+    config: &Config,
+    repo: &Repository,
+    settings: &HostSettings,
+    branch_name: &str,
+    options: &PrOptions,
+) -> Result<String> {
+    let client = GitHubClient::with_settings(Some(options.token.clone()), settings)?;
 
     // Extract owner and repo name from URL
     let (owner, repo_name) = client.parse_github_url(&repo.url)?;
@@ -72,12 +225,13 @@ async fn create_github_pr(repo: &Repository, branch_name: &str, options: &PrOpti
         .clone()
         .unwrap_or_else(|| DEFAULT_BASE_BRANCH.to_string());
 
+    let body = pull_request_body(config, repo, branch_name, &base_branch, options)?;
     let result = client
         .create_pull_request(PullRequestParams::new(
             &owner,
             &repo_name,
             &options.title,
-            &options.body,
+            &body,
             branch_name,
             &base_branch,
             options.draft,
@@ -92,5 +246,126 @@ async fn create_github_pr(repo: &Repository, branch_name: &str, options: &PrOpti
         pr_url
     );
 
-    Ok(())
+    Ok(pr_url.to_string())
+}
+
+/// Body of a pull request: the config's `pr_body_template` rendered with
+/// the given body and details of the change, or else the body itself
+fn pull_request_body(
+    config: &Config,
+    repo: &Repository,
+    branch_name: &str,
+    base_branch: &str,
+    options: &PrOptions,
+) -> Result<String> {
+    let Some(template) = config.pr_body_template_file() else {
+        return Ok(options.body.clone());
+    };
+    let context = serde_json::json!({
+        "repo": {
+            "name": repo.name,
+            "url": repo.url,
+            "path": repo.get_target_dir(),
+            "branch": repo.branch,
+            "tags": repo.tags,
+        },
+        "title": options.title,
+        "body": options.body,
+        "branch": branch_name,
+        "base_branch": base_branch,
+        "campaign": options.campaign,
+        "draft": options.draft,
+        "run_id": runs::id(),
+    });
+    template::render_file(&template, context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_branch_name() {
+        let mut config = Config::new();
+        let mut repo = Repository::new(
+            "api".to_string(),
+            "git@github.com:owner/api.git".to_string(),
+        );
+        let mut options = PrOptions::new(
+            "Bump serde to 1.0.200".to_string(),
+            String::new(),
+            String::new(),
+        );
+        assert_eq!(
+            render_branch_name(&config, &repo, &options, "abc123"),
+            "automated-changes-abc123"
+        );
+
+        config.branch_template = Some("chore/{campaign}-{name}".to_string());
+        assert_eq!(
+            render_branch_name(&config, &repo, &options, "abc123"),
+            "chore/bump-serde-to-1-0-200-api"
+        );
+
+        options.campaign = Some("bump-serde".to_string());
+        repo.branch_template = Some("deps/{campaign}/{uuid}".to_string());
+        assert_eq!(
+            render_branch_name(&config, &repo, &options, "abc123"),
+            "deps/bump-serde/abc123"
+        );
+
+        repo.branch_template = None;
+        config.branch_template = None;
+        assert_eq!(
+            render_branch_name(&config, &repo, &options, "abc123"),
+            "campaign/bump-serde"
+        );
+
+        options.branch_template = Some("{date}".to_string());
+        assert_eq!(
+            render_branch_name(&config, &repo, &options, "abc123"),
+            Utc::now().format("%Y-%m-%d").to_string()
+        );
+
+        options.branch_name = Some("fixed".to_string());
+        assert_eq!(
+            render_branch_name(&config, &repo, &options, "abc123"),
+            "fixed"
+        );
+    }
+
+    #[test]
+    fn test_pull_request_body() {
+        let dir = std::env::temp_dir().join(format!("rrepos-api-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("pr.md"),
+            "{{ body }}\n\nPart of {{ campaign or title }} for {{ repo.name }} ({{ branch }} -> {{ base_branch }})",
+        )
+        .unwrap();
+
+        let mut config = Config::new();
+        let repo = Repository::new(
+            "api".to_string(),
+            "git@github.com:owner/api.git".to_string(),
+        );
+        let options = PrOptions::new(
+            "Bump serde".to_string(),
+            "Updates serde.".to_string(),
+            String::new(),
+        );
+        assert_eq!(
+            pull_request_body(&config, &repo, "deps", "main", &options).unwrap(),
+            "Updates serde."
+        );
+
+        config.config_dir = Some(dir.clone());
+        config.pr_body_template = Some("pr.md".to_string());
+        assert_eq!(
+            pull_request_body(&config, &repo, "deps", "main", &options).unwrap(),
+            "Updates serde.\n\nPart of Bump serde for api (deps -> main)"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -1,10 +1,17 @@
 //! GitHub API client implementation
 
 use super::auth::GitHubAuth;
-use super::types::{PullRequestParams, constants::*};
+use super::types::{
+    CodeSearchItem, CodeSearchResult, GitHubRepo, PullRequest, PullRequestParams, Release, Team,
+    User, constants::*,
+};
+use crate::config::HostSettings;
 use anyhow::Result;
-use reqwest::Client;
+use colored::*;
+use reqwest::{Client, StatusCode};
+use serde::de::DeserializeOwned;
 use serde_json::{Value, json};
+use std::time::Duration;
 
 /// GitHub API client
 pub struct GitHubClient {
@@ -22,6 +29,14 @@ impl GitHubClient {
         }
     }
 
+    /// Create a GitHub client using a host's proxy and CA bundle settings
+    pub fn with_settings(token: Option<String>, settings: &HostSettings) -> Result<Self> {
+        Ok(Self {
+            client: settings.http_client()?,
+            auth: token.map(GitHubAuth::new),
+        })
+    }
+
     /// Parse GitHub URL to extract owner and repository name
     /// Supports both github.com and enterprise GitHub instances
     pub fn parse_github_url(&self, url: &str) -> Result<(String, String)> {
@@ -52,6 +67,327 @@ impl GitHubClient {
         Err(anyhow::anyhow!("Invalid GitHub URL: {}", url))
     }
 
+    /// Fetch repository information
+    pub async fn get_repository(&self, owner: &str, repo: &str) -> Result<GitHubRepo> {
+        self.get_json(&format!("{GITHUB_API_BASE}/repos/{owner}/{repo}"))
+            .await
+    }
+
+    /// List all repositories of an organization, following pagination
+    pub async fn list_org_repositories(&self, org: &str) -> Result<Vec<GitHubRepo>> {
+        self.get_all_pages(&format!("{GITHUB_API_BASE}/orgs/{org}/repos?"))
+            .await
+    }
+
+    /// List open pull requests of a repository, following pagination
+    pub async fn list_pull_requests(&self, owner: &str, repo: &str) -> Result<Vec<PullRequest>> {
+        self.get_all_pages(&format!(
+            "{GITHUB_API_BASE}/repos/{owner}/{repo}/pulls?state=open&"
+        ))
+        .await
+    }
+
+    /// The most recently opened pull request of a repository, in any state
+    pub async fn latest_pull_request(
+        &self,
+        owner: &str,
+        repo: &str,
+    ) -> Result<Option<PullRequest>> {
+        let pulls: Vec<PullRequest> = self
+            .get_json(&format!(
+                "{GITHUB_API_BASE}/repos/{owner}/{repo}/pulls?state=all&sort=created&direction=desc&per_page=1"
+            ))
+            .await?;
+        Ok(pulls.into_iter().next())
+    }
+
+    /// The most recent release of a repository
+    pub async fn latest_release(&self, owner: &str, repo: &str) -> Result<Option<Release>> {
+        let releases: Vec<Release> = self
+            .get_json(&format!(
+                "{GITHUB_API_BASE}/repos/{owner}/{repo}/releases?per_page=1"
+            ))
+            .await?;
+        Ok(releases.into_iter().next())
+    }
+
+    /// Fetch a single pull request
+    pub async fn get_pull_request(&self, owner: &str, repo: &str, number: u64) -> Result<Value> {
+        self.get_json(&format!(
+            "{GITHUB_API_BASE}/repos/{owner}/{repo}/pulls/{number}"
+        ))
+        .await
+    }
+
+    /// Combined state of the commit statuses of a commit
+    pub async fn commit_status(&self, owner: &str, repo: &str, sha: &str) -> Result<Value> {
+        self.get_json(&format!(
+            "{GITHUB_API_BASE}/repos/{owner}/{repo}/commits/{sha}/status"
+        ))
+        .await
+    }
+
+    /// Check runs reported for a commit
+    pub async fn check_runs(&self, owner: &str, repo: &str, sha: &str) -> Result<Value> {
+        self.get_json(&format!(
+            "{GITHUB_API_BASE}/repos/{owner}/{repo}/commits/{sha}/check-runs?per_page={PAGE_SIZE}"
+        ))
+        .await
+    }
+
+    /// Commit SHA a branch, tag or SHA prefix points to
+    pub async fn commit_sha(&self, owner: &str, repo: &str, reference: &str) -> Result<String> {
+        let commit: Value = self
+            .get_json(&format!(
+                "{GITHUB_API_BASE}/repos/{owner}/{repo}/commits/{reference}"
+            ))
+            .await?;
+        commit["sha"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("No commit found for {owner}/{repo}@{reference}"))
+    }
+
+    /// Fetch the user the token belongs to
+    pub async fn current_user(&self) -> Result<User> {
+        self.get_json(&format!("{GITHUB_API_BASE}/user")).await
+    }
+
+    /// List the teams the token's user belongs to
+    pub async fn current_user_teams(&self) -> Result<Vec<Team>> {
+        self.get_all_pages(&format!("{GITHUB_API_BASE}/user/teams?"))
+            .await
+    }
+
+    /// Check whether a repository exists
+    pub async fn repository_exists(&self, owner: &str, repo: &str) -> Result<bool> {
+        self.exists(&format!("{GITHUB_API_BASE}/repos/{owner}/{repo}"))
+            .await
+    }
+
+    /// Check whether a user or organization login exists
+    pub async fn user_exists(&self, login: &str) -> Result<bool> {
+        self.exists(&format!("{GITHUB_API_BASE}/users/{login}"))
+            .await
+    }
+
+    /// Check whether a team exists in an organization (requires a token
+    /// with the read:org scope)
+    pub async fn team_exists(&self, org: &str, slug: &str) -> Result<bool> {
+        self.exists(&format!("{GITHUB_API_BASE}/orgs/{org}/teams/{slug}"))
+            .await
+    }
+
+    /// Check whether a resource exists, treating 404 as absent
+    async fn exists(&self, url: &str) -> Result<bool> {
+        let response = self.get(url).await?;
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(false),
+            status if status.is_success() => Ok(true),
+            _ => Err(anyhow::anyhow!(
+                "GitHub API error: {}",
+                response.text().await?
+            )),
+        }
+    }
+
+    /// Fetch every page of a list endpoint; `url` must end in `?` or `&`
+    async fn get_all_pages<T: DeserializeOwned>(&self, url: &str) -> Result<Vec<T>> {
+        let mut items = Vec::new();
+        for page in 1.. {
+            let batch: Vec<T> = self
+                .get_json(&format!("{url}per_page={PAGE_SIZE}&page={page}"))
+                .await?;
+            let done = batch.len() < PAGE_SIZE;
+            items.extend(batch);
+            if done {
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    /// Search code, following pagination up to the API's result limit
+    pub async fn search_code(&self, query: &str) -> Result<Vec<CodeSearchItem>> {
+        let mut items = Vec::new();
+        for page in 1.. {
+            let url = reqwest::Url::parse_with_params(
+                &format!("{GITHUB_API_BASE}/search/code"),
+                &[
+                    ("q", query),
+                    ("per_page", &PAGE_SIZE.to_string()),
+                    ("page", &page.to_string()),
+                ],
+            )?;
+            let result: CodeSearchResult = self.get_json(url.as_str()).await?;
+            let done = result.items.len() < PAGE_SIZE
+                || items.len() + result.items.len() >= result.total_count.min(SEARCH_RESULT_LIMIT);
+            items.extend(result.items);
+            if done {
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    /// Send an authenticated (when a token is set) GET request and decode
+    /// the JSON response
+    async fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        let response = self.get(url).await?;
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            return Err(anyhow::anyhow!("GitHub API error: {}", error_text));
+        }
+        Ok(response.json().await?)
+    }
+
+    /// Send an authenticated (when a token is set) GET request, waiting for
+    /// the rate limit to reset when it is hit
+    async fn get(&self, url: &str) -> Result<reqwest::Response> {
+        for attempt in 0..=RATE_LIMIT_RETRIES {
+            let mut request = self
+                .client
+                .get(url)
+                .header("User-Agent", DEFAULT_USER_AGENT)
+                .header("Accept", "application/vnd.github.v3+json");
+            if let Some(auth) = &self.auth {
+                request = request.header("Authorization", format!("token {}", auth.token()));
+            }
+
+            let response = request.send().await?;
+
+            if attempt < RATE_LIMIT_RETRIES
+                && let Some(wait) = rate_limit_wait(&response)
+            {
+                eprintln!(
+                    "{}",
+                    format!("GitHub rate limit reached, waiting {}s...", wait.as_secs()).yellow()
+                );
+                tokio::time::sleep(wait).await;
+                continue;
+            }
+
+            return Ok(response);
+        }
+        unreachable!("the last attempt always returns")
+    }
+
+    /// Create an empty repository owned by an organization or by the token's
+    /// user
+    pub async fn create_repository(
+        &self,
+        owner: &str,
+        name: &str,
+        private: bool,
+    ) -> Result<GitHubRepo> {
+        let auth = self
+            .auth
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("GitHub token is required"))?;
+
+        // Repositories of the token's user are created through /user/repos
+        let url = if self.current_user().await?.login.eq_ignore_ascii_case(owner) {
+            format!("{GITHUB_API_BASE}/user/repos")
+        } else {
+            format!("{GITHUB_API_BASE}/orgs/{owner}/repos")
+        };
+
+        let payload = json!({
+            "name": name,
+            "private": private
+        });
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("token {}", auth.token()))
+            .header("User-Agent", DEFAULT_USER_AGENT)
+            .header("Accept", "application/vnd.github.v3+json")
+            .json(&payload)
+            .send()
+            .await?;
+
+        if response.status().is_success() {
+            Ok(response.json().await?)
+        } else {
+            let error_text = response.text().await?;
+            Err(anyhow::anyhow!("GitHub API error: {}", error_text))
+        }
+    }
+
+    /// Full repository resource, including the merge settings that are only
+    /// returned to administrators
+    pub async fn repository_settings(&self, owner: &str, repo: &str) -> Result<Value> {
+        self.get_json(&format!("{GITHUB_API_BASE}/repos/{owner}/{repo}"))
+            .await
+    }
+
+    /// Update repository settings such as merge strategies and features
+    pub async fn update_repository(&self, owner: &str, repo: &str, settings: &Value) -> Result<()> {
+        let url = format!("{GITHUB_API_BASE}/repos/{owner}/{repo}");
+        self.send_json(reqwest::Method::PATCH, &url, settings).await
+    }
+
+    /// Replace all topics of a repository
+    pub async fn replace_topics(&self, owner: &str, repo: &str, topics: &[String]) -> Result<()> {
+        let url = format!("{GITHUB_API_BASE}/repos/{owner}/{repo}/topics");
+        self.send_json(reqwest::Method::PUT, &url, &json!({ "names": topics }))
+            .await
+    }
+
+    /// Archive a repository, making it read-only
+    pub async fn archive_repository(&self, owner: &str, repo: &str) -> Result<()> {
+        self.update_repository(owner, repo, &json!({ "archived": true }))
+            .await
+    }
+
+    /// Rename a repository; GitHub redirects the old name afterwards
+    pub async fn rename_repository(&self, owner: &str, repo: &str, new_name: &str) -> Result<()> {
+        self.update_repository(owner, repo, &json!({ "name": new_name }))
+            .await
+    }
+
+    /// Transfer a repository to another user or organization
+    pub async fn transfer_repository(
+        &self,
+        owner: &str,
+        repo: &str,
+        new_owner: &str,
+    ) -> Result<()> {
+        let url = format!("{GITHUB_API_BASE}/repos/{owner}/{repo}/transfer");
+        self.send_json(
+            reqwest::Method::POST,
+            &url,
+            &json!({ "new_owner": new_owner }),
+        )
+        .await
+    }
+
+    /// Send an authenticated request with a JSON payload
+    async fn send_json(&self, method: reqwest::Method, url: &str, payload: &Value) -> Result<()> {
+        let auth = self
+            .auth
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("GitHub token is required"))?;
+
+        let response = self
+            .client
+            .request(method, url)
+            .header("Authorization", format!("token {}", auth.token()))
+            .header("User-Agent", DEFAULT_USER_AGENT)
+            .header("Accept", "application/vnd.github.v3+json")
+            .json(payload)
+            .send()
+            .await?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            let error_text = response.text().await?;
+            Err(anyhow::anyhow!("GitHub API error: {}", error_text))
+        }
+    }
+
     /// Create a pull request
     pub async fn create_pull_request(&self, params: PullRequestParams<'_>) -> Result<Value> {
         let auth = self
@@ -92,6 +428,38 @@ impl GitHubClient {
     }
 }
 
+/// How long to wait before retrying a rate-limited response, if it is one
+fn rate_limit_wait(response: &reqwest::Response) -> Option<Duration> {
+    let status = response.status();
+    if status != StatusCode::FORBIDDEN && status != StatusCode::TOO_MANY_REQUESTS {
+        return None;
+    }
+
+    let header = |name: &str| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok())
+    };
+
+    // Secondary rate limits send Retry-After, primary ones a reset timestamp
+    let seconds = if let Some(retry_after) = header("retry-after") {
+        retry_after
+    } else if header("x-ratelimit-remaining") == Some(0) {
+        let reset = header("x-ratelimit-reset")?;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()?
+            .as_secs();
+        reset.saturating_sub(now) + 1
+    } else {
+        return None;
+    };
+
+    Some(Duration::from_secs(seconds.min(MAX_RATE_LIMIT_WAIT_SECS)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
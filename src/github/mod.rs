@@ -3,10 +3,12 @@
 pub mod api;
 pub mod auth;
 pub mod client;
+pub mod rollout;
 pub mod types;
 
 // Re-export commonly used items for convenience
-pub use api::create_pull_request;
+pub use api::{create_pull_request, open_pull_request};
 pub use auth::GitHubAuth;
 pub use client::GitHubClient;
-pub use types::{PrOptions, PullRequestParams};
+pub use rollout::WaitFor;
+pub use types::{CodeSearchItem, GitHubRepo, PrOptions, PullRequest, PullRequestParams, Release};
@@ -0,0 +1,193 @@
+//! Waiting on the pull requests of a rollout batch before starting the next
+
+use super::client::GitHubClient;
+use anyhow::Result;
+use colored::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::{Duration, Instant};
+
+/// Time between checks of a batch's pull requests
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// What a batch's pull requests must reach before the next batch starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum WaitFor {
+    /// Every pull request is merged
+    Merged,
+    /// Every pull request's checks and statuses passed
+    CiGreen,
+}
+
+/// Where a pull request stands with respect to a wait condition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Readiness {
+    Ready,
+    Waiting,
+    Failed(String),
+}
+
+/// Split a pull request URL such as `https://github.com/owner/repo/pull/12`
+/// into owner, repository and number
+pub fn parse_pull_request_url(url: &str) -> Option<(String, String, u64)> {
+    let parts: Vec<&str> = url.trim_end_matches('/').rsplitn(4, '/').collect();
+    match parts.as_slice() {
+        [number, "pull", repo, rest] => {
+            let owner = rest.rsplit('/').next()?;
+            Some((owner.to_string(), repo.to_string(), number.parse().ok()?))
+        }
+        _ => None,
+    }
+}
+
+/// Readiness of a pull request resource for `WaitFor::Merged`
+pub fn merge_readiness(pull_request: &Value) -> Readiness {
+    if pull_request["merged"].as_bool() == Some(true) {
+        Readiness::Ready
+    } else if pull_request["state"].as_str() == Some("closed") {
+        Readiness::Failed("closed without merging".to_string())
+    } else {
+        Readiness::Waiting
+    }
+}
+
+/// Readiness of a commit for `WaitFor::CiGreen`, from its combined status
+/// and check runs. A commit without any CI reported yet is still waiting.
+pub fn ci_readiness(status: &Value, check_runs: &Value) -> Readiness {
+    let runs = check_runs["check_runs"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    for run in &runs {
+        let conclusion = run["conclusion"].as_str().unwrap_or_default();
+        if run["status"].as_str() == Some("completed")
+            && !matches!(conclusion, "success" | "neutral" | "skipped")
+        {
+            let name = run["name"].as_str().unwrap_or("unknown");
+            return Readiness::Failed(format!("check '{name}' {conclusion}"));
+        }
+    }
+
+    let state = status["state"].as_str().unwrap_or_default();
+    let statuses = status["total_count"].as_u64().unwrap_or(0);
+    if statuses > 0 && matches!(state, "failure" | "error") {
+        return Readiness::Failed(format!("commit status is {state}"));
+    }
+
+    let running = runs
+        .iter()
+        .any(|run| run["status"].as_str() != Some("completed"));
+    if running || (statuses > 0 && state != "success") || (runs.is_empty() && statuses == 0) {
+        Readiness::Waiting
+    } else {
+        Readiness::Ready
+    }
+}
+
+async fn readiness(client: &GitHubClient, url: &str, condition: WaitFor) -> Result<Readiness> {
+    let (owner, repo, number) = parse_pull_request_url(url)
+        .ok_or_else(|| anyhow::anyhow!("Invalid pull request URL: {}", url))?;
+    let pull_request = client.get_pull_request(&owner, &repo, number).await?;
+
+    let merge = merge_readiness(&pull_request);
+    if condition == WaitFor::Merged || merge != Readiness::Waiting {
+        // A merged pull request is past CI too
+        return Ok(merge);
+    }
+
+    let sha = pull_request["head"]["sha"].as_str().unwrap_or_default();
+    let status = client.commit_status(&owner, &repo, sha).await?;
+    let check_runs = client.check_runs(&owner, &repo, sha).await?;
+    Ok(ci_readiness(&status, &check_runs))
+}
+
+/// Wait until every pull request in `urls` meets `condition`, failing as
+/// soon as one can't anymore or when `timeout` passes
+pub async fn wait_for_batch(
+    client: &GitHubClient,
+    urls: &[String],
+    condition: WaitFor,
+    timeout: Duration,
+) -> Result<()> {
+    let deadline = Instant::now() + timeout;
+    let goal = match condition {
+        WaitFor::Merged => "merged",
+        WaitFor::CiGreen => "green",
+    };
+
+    let mut pending: Vec<&String> = urls.iter().collect();
+    loop {
+        let mut waiting = Vec::new();
+        for url in pending {
+            match readiness(client, url, condition).await? {
+                Readiness::Ready => {}
+                Readiness::Waiting => waiting.push(url),
+                Readiness::Failed(reason) => anyhow::bail!("{}: {}", url, reason),
+            }
+        }
+
+        if waiting.is_empty() {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            anyhow::bail!(
+                "Timed out waiting for {} pull requests to be {}",
+                waiting.len(),
+                goal
+            );
+        }
+
+        println!(
+            "{}",
+            format!(
+                "Waiting for {} pull requests to be {goal}...",
+                waiting.len()
+            )
+            .yellow()
+        );
+        tokio::time::sleep(POLL_INTERVAL).await;
+        pending = waiting;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_pull_request_url() {
+        assert_eq!(
+            parse_pull_request_url("https://github.com/owner/api/pull/12"),
+            Some(("owner".to_string(), "api".to_string(), 12))
+        );
+        assert_eq!(parse_pull_request_url("https://github.com/owner/api"), None);
+    }
+
+    #[test]
+    fn test_ci_readiness() {
+        let passed = json!({"state": "success", "total_count": 1});
+        let no_statuses = json!({"state": "pending", "total_count": 0});
+        let green = json!({"check_runs": [
+            {"name": "build", "status": "completed", "conclusion": "success"},
+            {"name": "lint", "status": "completed", "conclusion": "skipped"},
+        ]});
+        let running = json!({"check_runs": [
+            {"name": "build", "status": "in_progress", "conclusion": null},
+        ]});
+        let red = json!({"check_runs": [
+            {"name": "build", "status": "completed", "conclusion": "failure"},
+        ]});
+        let none = json!({"check_runs": []});
+
+        assert_eq!(ci_readiness(&no_statuses, &green), Readiness::Ready);
+        assert_eq!(ci_readiness(&passed, &none), Readiness::Ready);
+        assert_eq!(ci_readiness(&passed, &running), Readiness::Waiting);
+        assert_eq!(ci_readiness(&no_statuses, &none), Readiness::Waiting);
+        assert_eq!(
+            ci_readiness(&passed, &red),
+            Readiness::Failed("check 'build' failure".to_string())
+        );
+    }
+}
@@ -49,6 +49,11 @@ pub struct PrOptions {
     pub draft: bool,
     pub token: String,
     pub create_only: bool,
+    /// Template for the branch name when `branch_name` is unset, overriding
+    /// the repository's; see `api::branch_name`
+    pub branch_template: Option<String>,
+    /// Campaign the pull request belongs to
+    pub campaign: Option<String>,
 }
 
 impl PrOptions {
@@ -62,6 +67,8 @@ impl PrOptions {
             draft: false,
             token,
             create_only: false,
+            branch_template: None,
+            campaign: None,
         }
     }
 
@@ -114,26 +121,73 @@ impl fmt::Display for GitHubError {
 impl Error for GitHubError {}
 
 /// GitHub repository information
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitHubRepo {
     pub id: u64,
     pub name: String,
     pub full_name: String,
     pub html_url: String,
     pub clone_url: String,
+    #[serde(default)]
+    pub ssh_url: String,
     pub default_branch: String,
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub disabled: bool,
+    #[serde(default)]
+    pub topics: Vec<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub private: bool,
+    /// `public`, `private` or `internal`
+    #[serde(default)]
+    pub visibility: Option<String>,
+    #[serde(default)]
+    pub pushed_at: Option<String>,
+}
+
+/// Response of the code search API
+#[derive(Debug, Deserialize)]
+pub struct CodeSearchResult {
+    pub total_count: usize,
+    pub items: Vec<CodeSearchItem>,
+}
+
+/// A file matching a code search
+#[derive(Debug, Clone, Deserialize)]
+pub struct CodeSearchItem {
+    pub name: String,
+    pub path: String,
+    pub html_url: String,
+    pub repository: CodeSearchRepository,
+}
+
+/// Repository of a code search match
+#[derive(Debug, Clone, Deserialize)]
+pub struct CodeSearchRepository {
+    pub name: String,
+    pub full_name: String,
 }
 
 /// GitHub user information
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct User {
     pub id: u64,
     pub login: String,
     pub html_url: String,
 }
 
+/// GitHub team reference
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Team {
+    pub slug: String,
+    pub name: String,
+}
+
 /// Pull request response from GitHub API
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PullRequest {
     pub id: u64,
     pub number: u64,
@@ -142,10 +196,37 @@ pub struct PullRequest {
     pub html_url: String,
     pub state: String,
     pub user: User,
+    #[serde(default)]
+    pub draft: bool,
+    #[serde(default)]
+    pub created_at: String,
+    #[serde(default)]
+    pub requested_reviewers: Vec<User>,
+    #[serde(default)]
+    pub requested_teams: Vec<Team>,
+}
+
+/// A release as returned by the releases API
+#[derive(Debug, Clone, Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    pub html_url: String,
+    pub created_at: String,
+    /// Unset for draft releases
+    #[serde(default)]
+    pub published_at: Option<String>,
 }
 
 /// Constants for GitHub API
 pub mod constants {
     pub const GITHUB_API_BASE: &str = "https://api.github.com";
     pub const DEFAULT_USER_AGENT: &str = "rrepos/0.1.0";
+    /// Maximum page size accepted by list endpoints
+    pub const PAGE_SIZE: usize = 100;
+    /// The search API returns at most this many results per query
+    pub const SEARCH_RESULT_LIMIT: usize = 1000;
+    /// Number of times a rate-limited request is retried
+    pub const RATE_LIMIT_RETRIES: usize = 3;
+    /// Upper bound on a single wait for the rate limit to reset
+    pub const MAX_RATE_LIMIT_WAIT_SECS: u64 = 120;
 }
@@ -1,11 +1,35 @@
 //! RRepos library - shared types and utilities for managing multiple repositories
 
+pub mod cache;
+pub mod campaign;
 pub mod commands;
 pub mod config;
+pub mod conventional;
+pub mod detect;
+pub mod environment;
+pub mod events;
+pub mod gerrit;
 pub mod git;
 pub mod github;
+pub mod manifest;
+pub mod metrics;
+pub mod notify;
+pub mod offline;
+pub mod preflight;
+pub mod pty;
+pub mod registry;
+pub mod remote_filter;
 pub mod runner;
+pub mod runs;
+pub mod sandbox;
+pub mod sarif;
+pub mod scheduler;
+pub mod state;
+pub mod template;
+#[cfg(test)]
+mod test_util;
 pub mod util;
+pub mod workflows;
 
 pub type Result<T> = anyhow::Result<T>;
 
@@ -1,6 +1,24 @@
 use anyhow::Result;
-use clap::{Parser, Subcommand};
-use rrepos::{commands::*, config::Config};
+use clap::builder::PossibleValuesParser;
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
+use colored::*;
+use rrepos::{
+    PrOptions, cache,
+    campaign::Campaign,
+    commands::*,
+    config::{Config, IfExists, UserSettings, Visibility, discovery, loader::LoadError},
+    conventional,
+    events::EventOptions,
+    github::WaitFor,
+    manifest::BumpLevel,
+    notify, offline,
+    remote_filter::{self, RemoteFilter},
+    runner::{MissingSubdir, PathFilter, ResourceLimits, Shell},
+    runs::{self, RunRecord},
+    scheduler,
+    state::{self, ResumeMode},
+    template, util,
+};
 use std::env;
 
 #[derive(Parser)]
@@ -10,8 +28,51 @@ use std::env;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Include repositories marked as archived by refresh-metadata
+    #[arg(long, global = true)]
+    include_archived: bool,
+
+    /// Only operate on repositories that are cloned
+    #[arg(long, global = true, conflicts_with = "missing_only")]
+    cloned_only: bool,
+
+    /// Only operate on repositories that are not cloned yet
+    #[arg(long, global = true)]
+    missing_only: bool,
+
+    /// Only operate on repositories with this visibility, as recorded by refresh-metadata
+    #[arg(long, global = true, value_enum)]
+    visibility: Option<Visibility>,
+
+    /// Only operate on repositories of this owner (organization or user)
+    #[arg(long, global = true)]
+    owner: Option<String>,
+
+    /// Work only with local clones: skip fetches and fail instead of using the network
+    #[arg(long, global = true)]
+    offline: bool,
 }
 
+/// Commands that can't do anything without the network, refused up front
+/// with `--offline`
+const NETWORK_COMMANDS: &[&str] = &[
+    "admin",
+    "clone",
+    "discover",
+    "extract",
+    "gerrit",
+    "mirror",
+    "pr",
+    "refresh-metadata",
+    "reviews",
+    "search-remote",
+    "settings",
+    "sync",
+];
+
+// Parsed once per invocation, so the size of `Run` doesn't matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 enum Commands {
     /// Clone repositories specified in config
@@ -30,12 +91,82 @@ enum Commands {
         /// Execute operations in parallel
         #[arg(short, long)]
         parallel: bool,
+
+        /// Verify each remote is reachable with the current credentials before starting
+        #[arg(long)]
+        preflight: bool,
+
+        /// What to do when a directory already exists (overrides the config's if_exists)
+        #[arg(long, value_enum)]
+        if_exists: Option<IfExists>,
+
+        /// Point origin of existing clones at the configured URL when it differs
+        #[arg(long)]
+        fix_remote: bool,
+
+        /// Retry only repositories that failed or were not processed in the previous invocation
+        #[arg(long, conflicts_with = "rerun_failed")]
+        resume: bool,
+
+        /// Retry only repositories that failed in the previous invocation
+        #[arg(long)]
+        rerun_failed: bool,
+
+        /// Select repositories by GitHub metadata, e.g. language=Go, topic=api,
+        /// visibility=private, archived=false or pushed>30d (can be repeated)
+        #[arg(long = "remote-filter")]
+        remote_filter: Vec<RemoteFilter>,
+    },
+
+    /// Fetch and fast-forward clones, keeping repositories with a ref pinned to it
+    Sync {
+        /// Specific repository names to sync (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+
+        /// Execute operations in parallel
+        #[arg(short, long)]
+        parallel: bool,
+
+        /// Point origin at the configured URL when it differs instead of reporting it
+        #[arg(long)]
+        fix_remote: bool,
+    },
+
+    /// Report the disk usage of each clone's working tree and .git directory
+    Du {
+        /// Specific repository names to measure (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// List the largest clones first
+        #[arg(long)]
+        sort: bool,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+
+        /// Execute operations in parallel
+        #[arg(short, long)]
+        parallel: bool,
     },
 
     /// Run a command in each repository
     Run {
-        /// Command to execute
-        command: String,
+        /// Command to execute (omit when using --script)
+        #[arg(required_unless_present = "script")]
+        command: Option<String>,
 
         /// Specific repository names to run command in (if not provided, uses tag filter or all repos)
         repos: Vec<String>,
@@ -44,6 +175,149 @@ enum Commands {
         #[arg(short, long, default_value = "logs")]
         logs: String,
 
+        /// Run repositories in dependency order (see `depends_on`)
+        #[arg(long)]
+        ordered: bool,
+
+        /// Run the command against each of these refs using temporary worktrees
+        #[arg(long, value_delimiter = ',')]
+        matrix_ref: Vec<String>,
+
+        /// Local script file to execute in each repository instead of a command
+        #[arg(long)]
+        script: Option<String>,
+
+        /// Arguments passed to the script
+        #[arg(long, num_args = 1.., allow_hyphen_values = true, requires = "script")]
+        args: Vec<String>,
+
+        /// Shell used to interpret the command (none = execute directly); defaults to sh, or cmd on Windows
+        #[arg(long, value_enum)]
+        shell: Option<Shell>,
+
+        /// Set an environment variable for the command (KEY=VALUE, repeatable)
+        #[arg(long)]
+        env: Vec<String>,
+
+        /// Load environment variables from a dotenv-style file (repeatable)
+        #[arg(long)]
+        env_file: Vec<String>,
+
+        /// Read stdin once and pipe it to the command in every repository
+        #[arg(long)]
+        stdin: bool,
+
+        /// Run in this subdirectory of each repository
+        #[arg(long)]
+        cwd: Option<String>,
+
+        /// What to do when the --cwd subdirectory does not exist
+        #[arg(long, value_enum, default_value = "skip", requires = "cwd")]
+        cwd_missing: MissingSubdir,
+
+        /// Skip repositories with uncommitted changes
+        #[arg(long)]
+        require_clean: bool,
+
+        /// Report which files the command modified in each repository
+        #[arg(long)]
+        show_changes: bool,
+
+        /// Create pull requests for repositories changed by the command (implies --require-clean)
+        #[arg(long)]
+        auto_pr: bool,
+
+        /// Retry only repositories that failed or were not processed in the previous invocation
+        #[arg(long, conflicts_with = "rerun_failed")]
+        resume: bool,
+
+        /// Retry only repositories that failed in the previous invocation
+        #[arg(long)]
+        rerun_failed: bool,
+
+        /// Reuse results of a previous run at the same commit (clean repositories only, ignored with --auto-pr)
+        #[arg(long)]
+        cache: bool,
+
+        /// Ignore cached results and run again, refreshing the cache when --cache is set
+        #[arg(long)]
+        no_cache: bool,
+
+        /// Aggregate output into a fleet-wide report, e.g. sarif=out.sarif
+        #[arg(long, conflicts_with = "matrix_ref")]
+        report: Option<RunReport>,
+
+        /// Select repositories by GitHub metadata, e.g. language=Go, topic=api,
+        /// visibility=private, archived=false or pushed>30d (can be repeated)
+        #[arg(long = "remote-filter")]
+        remote_filter: Vec<RemoteFilter>,
+
+        /// Title for the pull request
+        #[arg(long, default_value = "Automated changes")]
+        title: String,
+
+        /// Body text for the pull request
+        #[arg(long, default_value = "This PR was created automatically")]
+        body: String,
+
+        /// Branch name to create
+        #[arg(long)]
+        branch: Option<String>,
+
+        /// Template for the branch name, e.g. 'chore/{campaign}-{date}' (placeholders:
+        /// {date}, {campaign}, {uuid}, {name})
+        #[arg(long, conflicts_with = "branch")]
+        branch_template: Option<String>,
+
+        /// Base branch for the PR
+        #[arg(long)]
+        base: Option<String>,
+
+        /// Commit message
+        #[arg(long)]
+        message: Option<String>,
+
+        /// Conventional Commits type prefixed to the commit message and title
+        #[arg(long = "type", value_name = "TYPE", value_parser = PossibleValuesParser::new(conventional::TYPES))]
+        commit_type: Option<String>,
+
+        /// Create PR as draft
+        #[arg(long)]
+        draft: bool,
+
+        /// GitHub token
+        #[arg(long)]
+        token: Option<String>,
+
+        #[command(flatten)]
+        limits: Box<ResourceLimits>,
+
+        /// Run commands without network access, only allowed to write to their repository (outside .git) and the temporary directory
+        #[arg(long)]
+        sandbox: bool,
+
+        /// Attach each command to a pseudo-terminal, keeping colors and progress output (stdout and stderr are merged)
+        #[arg(long)]
+        pty: bool,
+
+        #[command(flatten)]
+        events: Box<EventOptions>,
+
+        #[command(flatten)]
+        path_filter: PathFilter,
+
+        /// Only run in repositories that changed since this ref (from where the branch forked, including uncommitted changes), or since the command last succeeded with last-run
+        #[arg(long, value_name = "REF")]
+        only_changed_since: Option<ChangedSince>,
+
+        /// Write Prometheus metrics to this textfile, e.g. for node_exporter
+        #[arg(long, value_name = "PATH")]
+        metrics_file: Option<String>,
+
+        /// Don't ask for the confirmation the settings' policy may require
+        #[arg(short, long)]
+        yes: bool,
+
         /// Configuration file path
         #[arg(short, long, default_value = "config.yaml")]
         config: String,
@@ -74,6 +348,11 @@ enum Commands {
         #[arg(long)]
         branch: Option<String>,
 
+        /// Template for the branch name, e.g. 'chore/{campaign}-{date}' (placeholders:
+        /// {date}, {campaign}, {uuid}, {name})
+        #[arg(long, conflicts_with = "branch")]
+        branch_template: Option<String>,
+
         /// Base branch for the PR
         #[arg(long)]
         base: Option<String>,
@@ -82,6 +361,10 @@ enum Commands {
         #[arg(long)]
         message: Option<String>,
 
+        /// Conventional Commits type prefixed to the commit message and title
+        #[arg(long = "type", value_name = "TYPE", value_parser = PossibleValuesParser::new(conventional::TYPES))]
+        commit_type: Option<String>,
+
         /// Create PR as draft
         #[arg(long)]
         draft: bool,
@@ -94,6 +377,30 @@ enum Commands {
         #[arg(long)]
         create_only: bool,
 
+        /// Verify each remote is reachable with the current credentials before starting
+        #[arg(long)]
+        preflight: bool,
+
+        /// Open pull requests this many repositories at a time
+        #[arg(long)]
+        batch_size: Option<usize>,
+
+        /// Wait for each batch's pull requests to be merged or pass CI before the next batch
+        #[arg(long, value_enum, requires = "batch_size")]
+        wait_for: Option<WaitFor>,
+
+        /// Minutes to wait for a batch before stopping the rollout
+        #[arg(long, default_value_t = 120)]
+        wait_timeout: u64,
+
+        /// Retry only repositories that failed or were not processed in the previous invocation
+        #[arg(long, conflicts_with = "rerun_failed")]
+        resume: bool,
+
+        /// Retry only repositories that failed in the previous invocation
+        #[arg(long)]
+        rerun_failed: bool,
+
         /// Configuration file path
         #[arg(short, long, default_value = "config.yaml")]
         config: String,
@@ -112,6 +419,26 @@ enum Commands {
         /// Specific repository names to remove (if not provided, uses tag filter or all repos)
         repos: Vec<String>,
 
+        /// Remove repositories even if they have uncommitted or unpushed work
+        #[arg(long)]
+        force: bool,
+
+        /// Only list the directories that would be removed
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Don't ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Move repositories to the trash directory (`trash_dir`, default .rrepos/trash) instead of deleting them
+        #[arg(long)]
+        trash: bool,
+
+        /// Permanently delete everything in the trash directory
+        #[arg(long, conflicts_with = "trash")]
+        purge_trash: bool,
+
         /// Configuration file path
         #[arg(short, long, default_value = "config.yaml")]
         config: String,
@@ -125,101 +452,2968 @@ enum Commands {
         parallel: bool,
     },
 
-    /// Create a config.yaml file from discovered Git repositories
-    Init {
-        /// Output file name
+    /// Check clone integrity and that remotes match the config
+    Verify {
+        /// Specific repository names to verify (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Configuration file path
         #[arg(short, long, default_value = "config.yaml")]
-        output: String,
+        config: String,
 
-        /// Overwrite existing file if it exists
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+
+        /// Execute operations in parallel
+        #[arg(short, long)]
+        parallel: bool,
+    },
+
+    /// Report unsigned commits and tags, or ones signed with untrusted keys
+    VerifySignatures {
+        /// Specific repository names to check (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Reference whose history is checked (defaults to the checked out branch)
+        #[arg(long = "ref", value_name = "REF")]
+        reference: Option<String>,
+
+        /// Number of recent commits (and tags) to check
+        #[arg(long, default_value_t = 20)]
+        last: usize,
+
+        /// Check the most recent tags too
         #[arg(long)]
-        overwrite: bool,
+        tags: bool,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+
+        /// Execute operations in parallel
+        #[arg(short, long)]
+        parallel: bool,
     },
-}
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    let cli = Cli::parse();
+    /// Open an interactive subshell in each repository, one after another
+    Shell {
+        /// Specific repository names to visit (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
 
-    // Execute the appropriate command
-    match cli.command {
-        Commands::Clone {
-            repos,
-            config,
-            tag,
-            parallel,
-        } => {
-            let config = Config::load_config(&config)?;
-            let context = CommandContext {
-                config,
-                tag,
-                parallel,
-                repos: if repos.is_empty() { None } else { Some(repos) },
-            };
-            CloneCommand.execute(&context).await?;
-        }
-        Commands::Run {
-            command,
-            repos,
-            logs,
-            config,
-            tag,
-            parallel,
-        } => {
-            let config = Config::load_config(&config)?;
-            let context = CommandContext {
-                config,
-                tag,
-                parallel,
-                repos: if repos.is_empty() { None } else { Some(repos) },
-            };
-            RunCommand {
-                command,
-                log_dir: logs,
-            }
-            .execute(&context)
-            .await?;
-        }
-        Commands::Pr {
-            repos,
-            title,
-            body,
-            branch,
-            base,
-            message,
-            draft,
-            token,
-            create_only,
-            config,
-            tag,
-            parallel,
-        } => {
-            let config = Config::load_config(&config)?;
-            let context = CommandContext {
-                config,
-                tag,
-                parallel,
-                repos: if repos.is_empty() { None } else { Some(repos) },
-            };
+        /// Shell to start (defaults to $SHELL)
+        #[arg(long)]
+        shell: Option<String>,
 
-            let token = token.or_else(|| env::var("GITHUB_TOKEN").ok())
-                .ok_or_else(|| anyhow::anyhow!("GitHub token not provided. Use --token flag or set GITHUB_TOKEN environment variable."))?;
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
 
-            PrCommand {
-                title,
-                body,
-                branch_name: branch,
-                base_branch: base,
-                commit_msg: message,
-                draft,
-                token,
-                create_only,
-            }
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Open a tmux session with a window per repository
+    Tmux {
+        /// Specific repository names to open (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Name of the tmux session to create
+        #[arg(long, default_value = "rrepos")]
+        session: String,
+
+        /// Command to start in every window, e.g. "git status"
+        #[arg(long)]
+        command: Option<String>,
+
+        /// Use one tiled pane per repository in a single window
+        #[arg(long)]
+        panes: bool,
+
+        /// Create the session without attaching to it
+        #[arg(long)]
+        detach: bool,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Push all branches of each repository to a mirror remote
+    Mirror {
+        /// Specific repository names to mirror (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Remote prefix the repository name is appended to, e.g. git@backup-host:backup-org/
+        #[arg(long)]
+        to: String,
+
+        /// Also push all tags
+        #[arg(long)]
+        push_tags: bool,
+
+        /// GitHub token used to create missing mirror repositories (falls back to GITHUB_TOKEN)
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Move clones to a new directory and update their paths in the config
+    Move {
+        /// Specific repository names to move (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Directory to move clones into
+        #[arg(long)]
+        to: String,
+
+        /// Group clones into subdirectories named after their first tag
+        #[arg(long)]
+        by_tag: bool,
+
+        /// Only show where each clone would be moved
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Record archived status and default branch from GitHub in the config
+    RefreshMetadata {
+        /// Specific repository names to refresh (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// GitHub token (falls back to GITHUB_TOKEN; optional for public repositories)
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+
+        /// Execute operations in parallel
+        #[arg(short, long)]
+        parallel: bool,
+    },
+
+    /// Compare a GitHub organization's repositories with the config
+    Discover {
+        /// GitHub organization to list repositories from
+        #[arg(long, alias = "github-org")]
+        org: String,
+
+        /// Update the config with the differences instead of only reporting them
+        #[arg(long)]
+        apply: bool,
+
+        /// Use HTTPS URLs for new repositories instead of SSH
+        #[arg(long)]
+        https: bool,
+
+        /// GitHub token (falls back to GITHUB_TOKEN; needed for private repositories)
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+    },
+
+    /// Search code on GitHub across the configured repositories without cloning
+    SearchRemote {
+        /// GitHub code search query, e.g. 'filename:Dockerfile "FROM ubuntu:18.04"'
+        query: String,
+
+        /// Specific repository names to search (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Search all repositories of this organization instead of the configured ones
+        #[arg(long)]
+        org: Option<String>,
+
+        /// GitHub token
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// List pull requests awaiting review across repositories, oldest first
+    Reviews {
+        /// Specific repository names to check (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// User login or team (slug or org/slug) to list reviews for; defaults to you and your teams
+        #[arg(long)]
+        reviewer: Option<String>,
+
+        /// Include draft pull requests
+        #[arg(long)]
+        include_drafts: bool,
+
+        /// GitHub token
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Query changes in Gerrit-backed repositories
+    Gerrit {
+        #[command(subcommand)]
+        action: GerritAction,
+    },
+
+    /// Manage repository tags
+    Tag {
+        #[command(subcommand)]
+        action: TagAction,
+    },
+
+    /// Archive or transfer repositories on GitHub
+    Admin {
+        #[command(subcommand)]
+        action: AdminAction,
+    },
+
+    /// Audit and apply CODEOWNERS files
+    Codeowners {
+        #[command(subcommand)]
+        action: CodeownersAction,
+    },
+
+    /// Manage Dependabot/Renovate configuration
+    Dependabot {
+        #[command(subcommand)]
+        action: DependabotAction,
+    },
+
+    /// Build Docker images across repositories
+    Docker {
+        #[command(subcommand)]
+        action: DockerAction,
+    },
+
+    /// Sync GitHub repository settings
+    Settings {
+        #[command(subcommand)]
+        action: SettingsAction,
+    },
+
+    /// Print what pr, clone, rm or sync-files would do, without doing it
+    Plan {
+        #[command(subcommand)]
+        action: PlanAction,
+    },
+
+    /// Roll out a campaign file's changes as pull requests in batches
+    Campaign {
+        #[command(subcommand)]
+        action: CampaignAction,
+    },
+
+    /// Manage the local git config of each clone
+    GitConfig {
+        #[command(subcommand)]
+        action: GitConfigAction,
+    },
+
+    /// Manage git hooks
+    Hooks {
+        #[command(subcommand)]
+        action: HooksAction,
+    },
+
+    /// Render the dependencies between repositories
+    Graph {
+        /// Specific repository names to include (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "dot")]
+        format: GraphFormat,
+
+        /// Also detect dependencies from the cloned repositories' package manifests
+        #[arg(long)]
+        detect: bool,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Generate editor workspaces for the cloned repositories
+    Workspace {
+        #[command(subcommand)]
+        action: WorkspaceAction,
+    },
+
+    /// Manage cached command results
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+
+    /// Print a shell completion script
+    Completions {
+        /// Shell to generate completions for
+        shell: clap_complete::Shell,
+    },
+
+    /// Create a config.yaml file from discovered Git repositories
+    Init {
+        /// Output file name
+        #[arg(short, long, default_value = "config.yaml")]
+        output: String,
+
+        /// Overwrite existing file if it exists
+        #[arg(long)]
+        overwrite: bool,
+    },
+
+    /// Propagate shared files into each repository
+    SyncFiles {
+        /// Specific repository names to sync files into (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// File map declaring source templates and target paths
+        #[arg(short, long, default_value = "sync.yaml")]
+        map: String,
+
+        /// Show which files would change without writing them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Find and replace a regex pattern across repositories
+    Replace {
+        /// Specific repository names to modify (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Regular expression to search for
+        #[arg(long)]
+        pattern: String,
+
+        /// Replacement text (supports capture groups like $1)
+        #[arg(long)]
+        replacement: String,
+
+        /// Only modify files matching this glob pattern
+        #[arg(long)]
+        glob: Option<String>,
+
+        /// Show which files would change without writing them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Create pull requests for repositories with replacements
+        #[arg(long)]
+        pr: bool,
+
+        /// Title for the pull request
+        #[arg(long, default_value = "Automated changes")]
+        title: String,
+
+        /// Body text for the pull request
+        #[arg(long, default_value = "This PR was created automatically")]
+        body: String,
+
+        /// Branch name to create
+        #[arg(long)]
+        branch: Option<String>,
+
+        /// Base branch for the PR
+        #[arg(long)]
+        base: Option<String>,
+
+        /// Commit message
+        #[arg(long)]
+        message: Option<String>,
+
+        /// Create PR as draft
+        #[arg(long)]
+        draft: bool,
+
+        /// GitHub token
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Bump package versions in Cargo.toml, package.json or pom.xml
+    Bump {
+        /// Specific repository names to bump (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Version component to increment
+        #[arg(long, value_enum, default_value = "patch")]
+        level: BumpLevel,
+
+        /// Also update dependency pins on other bumped repositories
+        #[arg(long)]
+        update_dependents: bool,
+
+        /// Show version changes without writing them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Create pull requests for bumped repositories
+        #[arg(long)]
+        pr: bool,
+
+        /// Title for the pull request
+        #[arg(long, default_value = "Bump version")]
+        title: String,
+
+        /// Body text for the pull request
+        #[arg(long, default_value = "This PR was created automatically")]
+        body: String,
+
+        /// Branch to commit on, or the PR branch to create with --pr
+        #[arg(long)]
+        branch: Option<String>,
+
+        /// Base branch for the PR
+        #[arg(long)]
+        base: Option<String>,
+
+        /// Commit message
+        #[arg(long)]
+        message: Option<String>,
+
+        /// Create PR as draft
+        #[arg(long)]
+        draft: bool,
+
+        /// GitHub token
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Generate a combined changelog across repositories
+    Changelog {
+        /// Specific repository names to include (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Starting reference (tag, branch or commit)
+        #[arg(long)]
+        since: String,
+
+        /// Ending reference
+        #[arg(long, default_value = "HEAD")]
+        until: String,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "markdown")]
+        format: ChangelogFormat,
+
+        /// Group commits by Conventional Commits type
+        #[arg(long)]
+        conventional: bool,
+
+        /// Write the changelog to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Run security scanners and aggregate their findings
+    Scan {
+        /// Specific repository names to include (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Scanner to run (can be repeated); detected per repository when omitted
+        #[arg(long = "tool", value_enum)]
+        tools: Vec<ScanTool>,
+
+        /// Exit with an error when a finding has at least this severity
+        #[arg(long, value_enum)]
+        fail_on: Option<Severity>,
+
+        /// Report format
+        #[arg(long, value_enum, default_value = "json")]
+        format: ScanFormat,
+
+        /// Write the aggregated report to a file
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Merge per-repository SBOMs into a single fleet SBOM
+    Sbom {
+        /// Specific repository names to include (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// SBOM format
+        #[arg(long, value_enum, default_value = "cyclonedx")]
+        format: SbomFormat,
+
+        /// Command printing a CycloneDX JSON SBOM for repositories without a committed one
+        #[arg(long, default_value = sbom::DEFAULT_SBOM_GENERATOR)]
+        generator: String,
+
+        /// File to write the fleet SBOM to
+        #[arg(short, long, default_value = "fleet.cdx.json")]
+        output: String,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Report which repositories use which versions of each dependency
+    Deps {
+        /// Specific repository names to include (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Only report this package (Maven packages may be given by artifact id)
+        #[arg(long)]
+        package: Option<String>,
+
+        /// Only report packages behind the latest published version
+        #[arg(long)]
+        outdated: bool,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Run each repository's tests and summarize the results
+    Test {
+        /// Specific repository names to test (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Test preset to run (can be repeated; detected from tags and manifests by default)
+        #[arg(long = "preset", value_enum)]
+        presets: Vec<TestPreset>,
+
+        /// Write a JSON report of the results to a file
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+
+        /// Execute operations in parallel
+        #[arg(short, long)]
+        parallel: bool,
+    },
+
+    /// Merge per-repository coverage reports and check coverage thresholds
+    Coverage {
+        /// Specific repository names to include (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Coverage report format
+        #[arg(long, value_enum, default_value = "lcov")]
+        format: CoverageFormat,
+
+        /// Report path relative to each repository (defaults to well-known locations)
+        #[arg(long)]
+        report: Option<String>,
+
+        /// Fail when a repository's line coverage is below this percentage
+        #[arg(long)]
+        threshold: Option<f64>,
+
+        /// File to write the merged report to
+        #[arg(short, long, default_value = "coverage.lcov")]
+        output: String,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Time a command in each repository and compare against a baseline
+    Bench {
+        /// Command to time
+        command: String,
+
+        /// Specific repository names to time the command in (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Number of measured runs
+        #[arg(long, default_value_t = 3)]
+        runs: usize,
+
+        /// Number of unmeasured runs before measuring
+        #[arg(long, default_value_t = 0)]
+        warmup: usize,
+
+        /// Baseline file to compare the timings against
+        #[arg(long)]
+        baseline: Option<String>,
+
+        /// Write the timings to the baseline file instead of comparing
+        #[arg(long, requires = "baseline")]
+        save_baseline: bool,
+
+        /// Allowed slowdown of the mean, in percent, before it counts as a regression
+        #[arg(long, default_value_t = 10.0)]
+        tolerance: f64,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Run a task from the config's `tasks` section in each repository
+    Task {
+        /// Task to run (lists the configured tasks if omitted)
+        name: Option<String>,
+
+        /// Specific repository names to run the task in (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Directory to store log files
+        #[arg(short, long, default_value = "logs")]
+        logs: String,
+
+        #[command(flatten)]
+        path_filter: PathFilter,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+
+        /// Execute operations in parallel
+        #[arg(short, long)]
+        parallel: bool,
+    },
+
+    /// Report which repositories a branch would conflict in when merged
+    Conflicts {
+        /// Specific repository names to check (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Branch to test-merge
+        #[arg(long)]
+        branch: String,
+
+        /// Branch to merge into (defaults to each repository's default branch, or main)
+        #[arg(long)]
+        against: Option<String>,
+
+        /// Fetch from origin before test-merging
+        #[arg(long)]
+        fetch: bool,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Rebase matching local branches onto an updated base
+    Rebase {
+        /// Specific repository names to rebase in (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Base to rebase onto (a local branch, or the same branch on origin)
+        #[arg(long)]
+        onto: String,
+
+        /// Glob matching the local branches to rebase, e.g. 'automated-changes-*'
+        #[arg(long)]
+        branch: String,
+
+        /// Fetch from origin before rebasing
+        #[arg(long)]
+        fetch: bool,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Apply a commit from one repository to the others
+    CherryPick {
+        /// Commit to apply
+        commit: String,
+
+        /// Repository containing the commit
+        #[arg(long)]
+        from: String,
+
+        /// Specific repository names to apply it to (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Create pull requests instead of committing on the current branch
+        #[arg(long)]
+        pr: bool,
+
+        /// Title for the pull request (defaults to the commit subject)
+        #[arg(long)]
+        title: Option<String>,
+
+        /// Body text for the pull request
+        #[arg(long, default_value = "This PR was created automatically")]
+        body: String,
+
+        /// Branch name to create
+        #[arg(long)]
+        branch: Option<String>,
+
+        /// Base branch for the PR
+        #[arg(long)]
+        base: Option<String>,
+
+        /// Commit message (defaults to the commit subject)
+        #[arg(long)]
+        message: Option<String>,
+
+        /// Create PR as draft
+        #[arg(long)]
+        draft: bool,
+
+        /// GitHub token
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Report which repositories' copy of a shared file has drifted
+    FileDiff {
+        /// File path, relative to each repository root
+        path: String,
+
+        /// Specific repository names to compare (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Compare against this file instead of the most common version
+        #[arg(long)]
+        baseline: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Create a repository from a template and add it to the config
+    New {
+        /// Name of the new repository
+        name: String,
+
+        /// Git URL of the template repository
+        #[arg(long)]
+        template: String,
+
+        /// URL of the new repository (defaults to the template's owner and host)
+        #[arg(long)]
+        url: Option<String>,
+
+        /// Directory to create the repository in
+        #[arg(long)]
+        path: Option<String>,
+
+        /// Tag for the new repository (repeatable)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
+        /// Extra template variable as KEY=VALUE (repeatable)
+        #[arg(long = "var")]
+        vars: Vec<String>,
+
+        /// Initial branch
+        #[arg(long, default_value = "main")]
+        branch: String,
+
+        /// Create the repository on GitHub and push the initial commit
+        #[arg(long)]
+        create_remote: bool,
+
+        /// Make the created GitHub repository public
+        #[arg(long, requires = "create_remote")]
+        public: bool,
+
+        /// GitHub token (falls back to GITHUB_TOKEN)
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+    },
+
+    /// Move clones to where the configured layout expects them
+    Relayout {
+        /// Specific repository names to move (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Only show where each clone would be moved
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Print a repository's variables for shell scripts and direnv
+    Env {
+        /// Repository name
+        repo: String,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "sh")]
+        format: EnvFormat,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+    },
+
+    /// Collect TODO/FIXME/HACK annotations across repositories
+    Todos {
+        /// Specific repository names to include (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Regular expression matching annotations
+        #[arg(long, default_value = todos::DEFAULT_TODO_PATTERN)]
+        pattern: String,
+
+        /// Add the last author of each annotated line from git blame
+        #[arg(long)]
+        assignee_from_blame: bool,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "markdown")]
+        format: TodosFormat,
+
+        /// Write the report to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Report commit authors per repository and highlight bus-factor-1 repositories
+    Contributors {
+        /// Specific repository names to include (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Only count commits newer than an age such as 90d or 1y, or a date
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Number of top committers listed per repository
+        #[arg(long, default_value_t = 3)]
+        top: usize,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "table")]
+        format: ContributorsFormat,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Report repositories whose last commit, pull request and release are all older than a threshold
+    Inactive {
+        /// Specific repository names to check (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Age such as 180d or 1y, or a date; older activity counts as inactive
+        #[arg(long, default_value = "180d")]
+        threshold: String,
+
+        /// GitHub token (falls back to GITHUB_TOKEN)
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Combine repositories into one monorepo, keeping their histories under per-repository directories
+    Consolidate {
+        /// Specific repository names to combine (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Target repository, created when it does not exist
+        #[arg(long)]
+        into: String,
+
+        /// Directory for each repository: its name, or its first tag and name
+        #[arg(long, value_enum, default_value = "name")]
+        prefix_by: PrefixBy,
+
+        /// Rewrite each history with git filter-repo so past commits use the new paths too
+        #[arg(long)]
+        rewrite_history: bool,
+
+        /// Only show where each repository would be placed
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Split a subdirectory with its history out into a new repository, push it and add it to the config
+    Extract {
+        /// Configured repository to extract from
+        #[arg(long)]
+        repo: String,
+
+        /// Subdirectory to extract
+        #[arg(long)]
+        path: String,
+
+        /// URL of the new repository
+        #[arg(long)]
+        to: String,
+
+        /// Name of the new repository (defaults to the last part of its URL)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Tag for the new repository (repeatable; defaults to the source's tags)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
+        /// Make the repository public when it is created on GitHub
+        #[arg(long)]
+        public: bool,
+
+        /// GitHub token (falls back to GITHUB_TOKEN)
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+    },
+
+    /// Expire reflogs and run git gc in each clone, reporting the space reclaimed
+    Maintenance {
+        /// Specific repository names to maintain (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Recompute deltas and prune unreachable objects and reflogs immediately
+        #[arg(long)]
+        aggressive: bool,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+
+        /// Execute operations in parallel
+        #[arg(short, long)]
+        parallel: bool,
+    },
+
+    /// List past runs and show their outcomes, logs and reports by run ID
+    Runs {
+        #[command(subcommand)]
+        action: RunsAction,
+    },
+
+    /// Rename a repository in the config, its clone directory and remote URL
+    Rename {
+        /// Current repository name
+        old_name: String,
+
+        /// New repository name
+        new_name: String,
+
+        /// Also rename the repository on GitHub and point the remote at it
+        #[arg(long)]
+        remote: bool,
+
+        /// GitHub token (falls back to GITHUB_TOKEN)
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+    },
+
+    /// Audit GitHub Actions workflows against a CI policy
+    Ci {
+        #[command(subcommand)]
+        action: CiAction,
+    },
+
+    /// Report who last changed the code under a path, with its CODEOWNERS entries
+    Owners {
+        /// Directory, file or glob pattern, relative to each repository root
+        path: String,
+
+        /// Specific repository names to include (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Only count lines changed after an age such as 90d or 1y, or a date
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Number of authors listed per repository
+        #[arg(long, default_value_t = 5)]
+        top: usize,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "table")]
+        format: OwnersFormat,
+
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: String,
+
+        /// Filter repositories by tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Pin the GitHub Actions used by workflows to commit SHAs
+    Actions {
+        #[command(subcommand)]
+        action: ActionsAction,
+    },
+
+    /// Render registry configuration such as .npmrc into each clone without committing it
+    Devfiles {
+        #[command(subcommand)]
+        action: DevfilesAction,
+    },
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Answer dynamic completion requests (COMPLETE=<shell>) before parsing
+    clap_complete::CompleteEnv::with_factory(|| {
+        completions::with_dynamic_completers(Cli::command())
+    })
+    .complete();
+
+    let settings = UserSettings::load()?;
+    settings.apply_color();
+    if let Some(jobs) = settings.jobs {
+        scheduler::set_max_jobs(jobs);
+    }
+
+    // Commands default to the config found by the discovery chain
+    let mut cli = util::with_arg_default(Cli::command(), "config", &discovery::discover());
+    if let Some(log_dir) = &settings.log_dir {
+        cli = util::with_arg_default(cli, "logs", log_dir);
+    }
+    let mut matches = cli.get_matches();
+    let command = matches.subcommand_name().unwrap_or_default().to_string();
+    let config_path = util::subcommand_arg(&matches, "config").unwrap_or_else(discovery::discover);
+    let cli = Cli::from_arg_matches_mut(&mut matches)?;
+
+    if cli.offline {
+        offline::enable();
+    }
+
+    let started_at = chrono::Utc::now().to_rfc3339();
+    let result = if offline::is_enabled() && NETWORK_COMMANDS.contains(&command.as_str()) {
+        offline::require_network(&format!("'{command}'"))
+    } else {
+        run(cli).await
+    };
+    let config_dir = std::path::Path::new(&config_path).parent();
+    let record = RunRecord::finish(
+        &command,
+        env::args().collect(),
+        started_at,
+        &result,
+        &state::state_file(config_dir),
+    );
+
+    let recorded = runs::is_recorded(&command);
+    if recorded {
+        // On stderr, so it never mixes with output piped to other tools,
+        // such as `--format json`
+        if let Some(template) = &settings.summary_template {
+            match template::render_file(&UserSettings::template_path(template), &record) {
+                Ok(summary) => eprint!("{summary}"),
+                Err(e) => eprintln!("{}", format!("{e:#}").yellow()),
+            }
+        }
+        if let Err(e) = record.save(&runs::history_dir(config_dir)) {
+            eprintln!(
+                "{}",
+                format!("Failed to record run {}: {e}", record.id).yellow()
+            );
+        }
+    }
+
+    // A command that never got past loading its config has nothing to report
+    let config_failed = result
+        .as_ref()
+        .is_err_and(|e| e.downcast_ref::<LoadError>().is_some());
+    if notify::is_notified(&command) && !config_failed {
+        let notification_template = settings
+            .notification_template
+            .as_deref()
+            .map(UserSettings::template_path);
+        notify::send(
+            &settings.webhooks,
+            notification_template.as_deref(),
+            &record,
+        )
+        .await;
+    }
+    result
+}
+
+/// Execute the parsed command
+async fn run(cli: Cli) -> Result<()> {
+    let include_archived = cli.include_archived;
+    let visibility = cli.visibility;
+    let owner = cli.owner;
+    let presence = if cli.cloned_only {
+        Some(Presence::Cloned)
+    } else if cli.missing_only {
+        Some(Presence::Missing)
+    } else {
+        None
+    };
+
+    match cli.command {
+        Commands::Clone {
+            repos,
+            config,
+            tag,
+            parallel,
+            preflight,
+            if_exists,
+            fix_remote,
+            resume,
+            rerun_failed,
+            remote_filter,
+        } => {
+            let mut config = Config::load_config(&config)?;
+            remote_filter::apply(
+                &mut config,
+                tag.as_deref(),
+                Some(&repos).filter(|r| !r.is_empty()).map(Vec::as_slice),
+                &remote_filter,
+                env::var("GITHUB_TOKEN").ok(),
+            )
+            .await?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            CloneCommand {
+                resume: ResumeMode::from_flags(resume, rerun_failed),
+                preflight,
+                if_exists,
+                fix_remote,
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::Sync {
+            repos,
+            config,
+            tag,
+            parallel,
+            fix_remote,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            SyncCommand { fix_remote }.execute(&context).await?;
+        }
+        Commands::Du {
+            repos,
+            sort,
+            config,
+            tag,
+            parallel,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            DuCommand { sort }.execute(&context).await?;
+        }
+        Commands::Run {
+            command,
+            repos,
+            logs,
+            ordered,
+            matrix_ref,
+            script,
+            args,
+            shell,
+            env,
+            env_file,
+            stdin,
+            cwd,
+            cwd_missing,
+            require_clean,
+            show_changes,
+            auto_pr,
+            resume,
+            rerun_failed,
+            cache,
+            no_cache,
+            report,
+            remote_filter,
+            title,
+            body,
+            branch,
+            branch_template,
+            base,
+            message,
+            commit_type,
+            draft,
+            token,
+            limits,
+            sandbox,
+            pty,
+            events,
+            metrics_file,
+            only_changed_since,
+            path_filter,
+            yes,
+            config,
+            tag,
+            parallel,
+        } => {
+            // With --script, the first positional argument is a repository name
+            let (command, repos) = match (&script, command) {
+                (Some(_), Some(first)) => (String::new(), [vec![first], repos].concat()),
+                (_, command) => (command.unwrap_or_default(), repos),
+            };
+
+            let mut config = Config::load_config(&config)?;
+            let token = token.or_else(|| env::var("GITHUB_TOKEN").ok());
+            remote_filter::apply(
+                &mut config,
+                tag.as_deref(),
+                Some(&repos).filter(|r| !r.is_empty()).map(Vec::as_slice),
+                &remote_filter,
+                token.clone(),
+            )
+            .await?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+
+            let pr_options = if auto_pr {
+                let title = conventional::compose(commit_type.as_deref(), title);
+                let message =
+                    message.map(|message| conventional::compose(commit_type.as_deref(), message));
+                let token = token
+                    .ok_or_else(|| anyhow::anyhow!("GitHub token not provided. Use --token flag or set GITHUB_TOKEN environment variable."))?;
+                Some(PrOptions {
+                    title,
+                    body,
+                    branch_name: branch,
+                    base_branch: base,
+                    commit_msg: message,
+                    draft,
+                    token,
+                    create_only: false,
+                    branch_template,
+                    campaign: None,
+                })
+            } else {
+                None
+            };
+
+            let events = events.sink()?;
+
+            RunCommand {
+                command,
+                log_dir: logs,
+                ordered,
+                matrix_refs: matrix_ref,
+                script,
+                script_args: args,
+                shell: shell.unwrap_or_default(),
+                env,
+                env_files: env_file,
+                stdin,
+                cwd,
+                cwd_missing,
+                require_clean,
+                show_changes,
+                pr_options,
+                resume: ResumeMode::from_flags(resume, rerun_failed),
+                cache,
+                no_cache,
+                cache_dir: cache::cache_dir(context.config.config_dir.as_deref()),
+                report,
+                yes,
+                limits: *limits,
+                sandbox,
+                pty,
+                events,
+                metrics_file,
+                only_changed_since,
+                path_filter,
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::Pr {
+            repos,
+            title,
+            body,
+            branch,
+            branch_template,
+            base,
+            message,
+            commit_type,
+            draft,
+            token,
+            create_only,
+            preflight,
+            batch_size,
+            wait_for,
+            wait_timeout,
+            resume,
+            rerun_failed,
+            config,
+            tag,
+            parallel,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+
+            let token = token.or_else(|| env::var("GITHUB_TOKEN").ok())
+                .ok_or_else(|| anyhow::anyhow!("GitHub token not provided. Use --token flag or set GITHUB_TOKEN environment variable."))?;
+
+            PrCommand {
+                title: conventional::compose(commit_type.as_deref(), title),
+                body,
+                branch_name: branch,
+                branch_template,
+                base_branch: base,
+                commit_msg: message
+                    .map(|message| conventional::compose(commit_type.as_deref(), message)),
+                draft,
+                token,
+                create_only,
+                resume: ResumeMode::from_flags(resume, rerun_failed),
+                preflight,
+                batch_size,
+                wait_for,
+                wait_timeout,
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::Rm {
+            repos,
+            force,
+            dry_run,
+            yes,
+            trash,
+            purge_trash,
+            config,
+            tag,
+            parallel,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            RemoveCommand {
+                force,
+                dry_run,
+                yes,
+                trash,
+                purge_trash,
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::Verify {
+            repos,
+            config,
+            tag,
+            parallel,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            VerifyCommand.execute(&context).await?;
+        }
+        Commands::VerifySignatures {
+            repos,
+            reference,
+            last,
+            tags,
+            config,
+            tag,
+            parallel,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            VerifySignaturesCommand {
+                reference,
+                last,
+                tags,
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::Shell {
+            repos,
+            shell,
+            config,
+            tag,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel: false,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            ShellCommand { shell }.execute(&context).await?;
+        }
+        Commands::Tmux {
+            repos,
+            session,
+            command,
+            panes,
+            detach,
+            config,
+            tag,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel: false,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            TmuxCommand {
+                session,
+                command,
+                panes,
+                detach,
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::Mirror {
+            repos,
+            to,
+            push_tags,
+            token,
+            config,
+            tag,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel: false,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            MirrorCommand {
+                to,
+                push_tags,
+                token: token.or_else(|| env::var("GITHUB_TOKEN").ok()),
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::Move {
+            repos,
+            to,
+            by_tag,
+            dry_run,
+            config: config_path,
+            tag,
+        } => {
+            let config = Config::load_config(&config_path)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel: false,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            MoveCommand {
+                to,
+                by_tag,
+                dry_run,
+                config_path,
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::RefreshMetadata {
+            repos,
+            token,
+            config: config_path,
+            tag,
+            parallel,
+        } => {
+            let config = Config::load_config(&config_path)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            RefreshMetadataCommand {
+                token: token.or_else(|| env::var("GITHUB_TOKEN").ok()),
+                config_path,
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::Discover {
+            org,
+            apply,
+            https,
+            token,
+            config: config_path,
+        } => {
+            let config = Config::load_config(&config_path)?;
+            let context = CommandContext {
+                config,
+                tag: None,
+                parallel: false,
+                repos: None,
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            DiscoverCommand {
+                org,
+                apply,
+                https,
+                token: token.or_else(|| env::var("GITHUB_TOKEN").ok()),
+                config_path,
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::SearchRemote {
+            query,
+            repos,
+            org,
+            token,
+            config,
+            tag,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel: false,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+
+            let token = token.or_else(|| env::var("GITHUB_TOKEN").ok())
+                .ok_or_else(|| anyhow::anyhow!("GitHub token not provided. Use --token flag or set GITHUB_TOKEN environment variable."))?;
+
+            SearchRemoteCommand { query, org, token }
+                .execute(&context)
+                .await?;
+        }
+        Commands::Reviews {
+            repos,
+            reviewer,
+            include_drafts,
+            token,
+            config,
+            tag,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel: false,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+
+            let token = token.or_else(|| env::var("GITHUB_TOKEN").ok())
+                .ok_or_else(|| anyhow::anyhow!("GitHub token not provided. Use --token flag or set GITHUB_TOKEN environment variable."))?;
+
+            ReviewsCommand {
+                reviewer,
+                include_drafts,
+                token,
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::Gerrit { action } => match action {
+            GerritAction::Status {
+                repos,
+                topic,
+                user,
+                config,
+                tag,
+            } => {
+                let config = Config::load_config(&config)?;
+                let context = CommandContext {
+                    config,
+                    tag,
+                    parallel: false,
+                    repos: if repos.is_empty() { None } else { Some(repos) },
+                    include_archived,
+                    presence,
+                    visibility,
+                    owner,
+                };
+                GerritStatusCommand {
+                    topic,
+                    user: user.or_else(|| env::var("GERRIT_USER").ok()),
+                }
+                .execute(&context)
+                .await?;
+            }
+        },
+        Commands::Tag { action } => match action {
+            TagAction::ImportTopics {
+                repos,
+                prefix,
+                dry_run,
+                token,
+                config: config_path,
+                tag,
+            } => {
+                let config = Config::load_config(&config_path)?;
+                let context = CommandContext {
+                    config,
+                    tag,
+                    parallel: false,
+                    repos: if repos.is_empty() { None } else { Some(repos) },
+                    include_archived,
+                    presence,
+                    visibility,
+                    owner,
+                };
+                ImportTopicsCommand {
+                    prefix,
+                    dry_run,
+                    token: token.or_else(|| env::var("GITHUB_TOKEN").ok()),
+                    config_path,
+                }
+                .execute(&context)
+                .await?;
+            }
+        },
+        Commands::Admin { action } => {
+            let (repos, operation, dry_run, yes, token, config_path, tag) = match action {
+                AdminAction::Archive {
+                    repos,
+                    dry_run,
+                    yes,
+                    token,
+                    config,
+                    tag,
+                } => (
+                    repos,
+                    AdminOperation::Archive,
+                    dry_run,
+                    yes,
+                    token,
+                    config,
+                    tag,
+                ),
+                AdminAction::Transfer {
+                    repos,
+                    to,
+                    dry_run,
+                    yes,
+                    token,
+                    config,
+                    tag,
+                } => (
+                    repos,
+                    AdminOperation::Transfer { to },
+                    dry_run,
+                    yes,
+                    token,
+                    config,
+                    tag,
+                ),
+            };
+
+            let config = Config::load_config(&config_path)?;
+            let names = admin::expand_patterns(&config, &repos)?;
+            // An empty name list would select every repository
+            if names.is_empty() && !repos.is_empty() {
+                anyhow::bail!("No repositories match {:?}", repos);
+            }
+            let context = CommandContext {
+                config,
+                tag,
+                parallel: false,
+                repos: if names.is_empty() { None } else { Some(names) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            AdminCommand {
+                operation,
+                dry_run,
+                yes,
+                token: token.or_else(|| env::var("GITHUB_TOKEN").ok()),
+                config_path,
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::Codeowners { action } => match action {
+            CodeownersAction::Audit {
+                repos,
+                required,
+                token,
+                config,
+                tag,
+            } => {
+                let config = Config::load_config(&config)?;
+                let context = CommandContext {
+                    config,
+                    tag,
+                    parallel: false,
+                    repos: if repos.is_empty() { None } else { Some(repos) },
+                    include_archived,
+                    presence,
+                    visibility,
+                    owner,
+                };
+                CodeownersAuditCommand {
+                    required,
+                    token: token.or_else(|| env::var("GITHUB_TOKEN").ok()),
+                }
+                .execute(&context)
+                .await?;
+            }
+            CodeownersAction::Apply {
+                repos,
+                template,
+                dry_run,
+                title,
+                body,
+                branch,
+                base,
+                draft,
+                token,
+                config,
+                tag,
+            } => {
+                let config = Config::load_config(&config)?;
+                let context = CommandContext {
+                    config,
+                    tag,
+                    parallel: false,
+                    repos: if repos.is_empty() { None } else { Some(repos) },
+                    include_archived,
+                    presence,
+                    visibility,
+                    owner,
+                };
+
+                // A dry run never opens pull requests, so it doesn't need a token
+                let token = match token.or_else(|| env::var("GITHUB_TOKEN").ok()) {
+                    Some(token) => token,
+                    None if dry_run => String::new(),
+                    None => anyhow::bail!(
+                        "GitHub token not provided. Use --token flag or set GITHUB_TOKEN environment variable."
+                    ),
+                };
+
+                CodeownersApplyCommand {
+                    template,
+                    dry_run,
+                    pr_options: PrOptions {
+                        title,
+                        body,
+                        branch_name: branch,
+                        base_branch: base,
+                        commit_msg: None,
+                        draft,
+                        token,
+                        create_only: false,
+                        branch_template: None,
+                        campaign: None,
+                    },
+                }
+                .execute(&context)
+                .await?;
+            }
+        },
+        Commands::Dependabot { action } => match action {
+            DependabotAction::Apply {
+                repos,
+                template,
+                path,
+                interval,
+                dry_run,
+                title,
+                body,
+                branch,
+                base,
+                draft,
+                token,
+                config,
+                tag,
+            } => {
+                let config = Config::load_config(&config)?;
+                let context = CommandContext {
+                    config,
+                    tag,
+                    parallel: false,
+                    repos: if repos.is_empty() { None } else { Some(repos) },
+                    include_archived,
+                    presence,
+                    visibility,
+                    owner,
+                };
+
+                // A dry run never opens pull requests, so it doesn't need a token
+                let token = match token.or_else(|| env::var("GITHUB_TOKEN").ok()) {
+                    Some(token) => token,
+                    None if dry_run => String::new(),
+                    None => anyhow::bail!(
+                        "GitHub token not provided. Use --token flag or set GITHUB_TOKEN environment variable."
+                    ),
+                };
+
+                DependabotApplyCommand {
+                    template,
+                    path,
+                    interval,
+                    dry_run,
+                    pr_options: PrOptions {
+                        title,
+                        body,
+                        branch_name: branch,
+                        base_branch: base,
+                        commit_msg: None,
+                        draft,
+                        token,
+                        create_only: false,
+                        branch_template: None,
+                        campaign: None,
+                    },
+                }
+                .execute(&context)
+                .await?;
+            }
+        },
+        Commands::Docker { action } => match action {
+            DockerAction::Build {
+                repos,
+                tag_template,
+                file,
+                push,
+                config,
+                tag,
+                parallel,
+            } => {
+                let config = Config::load_config(&config)?;
+                let context = CommandContext {
+                    config,
+                    tag,
+                    parallel,
+                    repos: if repos.is_empty() { None } else { Some(repos) },
+                    include_archived,
+                    presence,
+                    visibility,
+                    owner,
+                };
+                DockerBuildCommand {
+                    tag_template,
+                    file,
+                    push,
+                }
+                .execute(&context)
+                .await?;
+            }
+        },
+        Commands::Settings { action } => match action {
+            SettingsAction::Apply {
+                file,
+                repos,
+                dry_run,
+                token,
+                config,
+                tag,
+            } => {
+                let config = Config::load_config(&config)?;
+                let context = CommandContext {
+                    config,
+                    tag,
+                    parallel: false,
+                    repos: if repos.is_empty() { None } else { Some(repos) },
+                    include_archived,
+                    presence,
+                    visibility,
+                    owner,
+                };
+                SettingsApplyCommand {
+                    settings: RepoSettings::load(&file)?,
+                    dry_run,
+                    token: token.or_else(|| env::var("GITHUB_TOKEN").ok()),
+                }
+                .execute(&context)
+                .await?;
+            }
+        },
+        Commands::Plan { action } => {
+            let (operation, format, repos, config, tag) = match action {
+                PlanAction::Pr {
+                    repos,
+                    title,
+                    body,
+                    branch,
+                    base,
+                    message,
+                    draft,
+                    create_only,
+                    format,
+                    config,
+                    tag,
+                } => {
+                    let options = PrOptions {
+                        title,
+                        body,
+                        branch_name: branch,
+                        base_branch: base,
+                        commit_msg: message,
+                        draft,
+                        token: String::new(),
+                        create_only,
+                        branch_template: None,
+                        campaign: None,
+                    };
+                    (PlannedOperation::Pr(options), format, repos, config, tag)
+                }
+                PlanAction::Clone {
+                    repos,
+                    format,
+                    config,
+                    tag,
+                } => (PlannedOperation::Clone, format, repos, config, tag),
+                PlanAction::Rm {
+                    repos,
+                    force,
+                    trash,
+                    format,
+                    config,
+                    tag,
+                } => (
+                    PlannedOperation::Rm { force, trash },
+                    format,
+                    repos,
+                    config,
+                    tag,
+                ),
+                PlanAction::SyncFiles {
+                    repos,
+                    map,
+                    format,
+                    config,
+                    tag,
+                } => (
+                    PlannedOperation::SyncFiles { map },
+                    format,
+                    repos,
+                    config,
+                    tag,
+                ),
+            };
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel: false,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            PlanCommand { operation, format }.execute(&context).await?;
+        }
+        Commands::Campaign { action } => {
+            let (operation, file, token, config) = match action {
+                CampaignAction::Run {
+                    file,
+                    token,
+                    config,
+                } => (CampaignOperation::Run, file, token, config),
+                CampaignAction::Resume {
+                    file,
+                    token,
+                    config,
+                } => (CampaignOperation::Resume, file, token, config),
+                CampaignAction::Status { file, config } => {
+                    (CampaignOperation::Status, file, None, config)
+                }
+                CampaignAction::Abort { file, config } => {
+                    (CampaignOperation::Abort, file, None, config)
+                }
+            };
+            let campaign = Campaign::load(&file)?;
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag: campaign.tag.clone(),
+                parallel: false,
+                repos: if campaign.repos.is_empty() {
+                    None
+                } else {
+                    Some(campaign.repos.clone())
+                },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            CampaignCommand {
+                campaign,
+                operation,
+                token,
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::GitConfig { action } => match action {
+            GitConfigAction::Apply {
+                repos,
+                settings,
+                dry_run,
+                config,
+                tag,
+            } => {
+                let config = Config::load_config(&config)?;
+                let context = CommandContext {
+                    config,
+                    tag,
+                    parallel: false,
+                    repos: if repos.is_empty() { None } else { Some(repos) },
+                    include_archived,
+                    presence,
+                    visibility,
+                    owner,
+                };
+                GitConfigApplyCommand { settings, dry_run }
+                    .execute(&context)
+                    .await?;
+            }
+        },
+        Commands::Hooks { action } => match action {
+            HooksAction::Install {
+                repos,
+                framework,
+                scripts,
+                config,
+                tag,
+            } => {
+                let config = Config::load_config(&config)?;
+                let context = CommandContext {
+                    config,
+                    tag,
+                    parallel: false,
+                    repos: if repos.is_empty() { None } else { Some(repos) },
+                    include_archived,
+                    presence,
+                    visibility,
+                    owner,
+                };
+                HooksInstallCommand { framework, scripts }
+                    .execute(&context)
+                    .await?;
+            }
+        },
+        Commands::Graph {
+            repos,
+            format,
+            detect,
+            config,
+            tag,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel: false,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            GraphCommand { format, detect }.execute(&context).await?;
+        }
+        Commands::Workspace { action } => {
+            let (editor, repos, output, config, tag) = match action {
+                WorkspaceAction::Code {
+                    repos,
+                    output,
+                    config,
+                    tag,
+                } => (Editor::Code, repos, output, config, tag),
+                WorkspaceAction::Idea {
+                    repos,
+                    output,
+                    config,
+                    tag,
+                } => (Editor::Idea, repos, output, config, tag),
+            };
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel: false,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            WorkspaceCommand { editor, output }
+                .execute(&context)
+                .await?;
+        }
+        Commands::Cache { action } => {
+            let context = CommandContext {
+                config: Config::new(),
+                tag: None,
+                parallel: false,
+                repos: None,
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            CacheCommand { action }.execute(&context).await?;
+        }
+        Commands::Completions { shell } => {
+            let context = CommandContext {
+                config: Config::new(),
+                tag: None,
+                parallel: false,
+                repos: None,
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            CompletionsCommand {
+                shell,
+                cli: Cli::command(),
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::Init { output, overwrite } => {
+            // Init command doesn't need config since it creates one
+            let context = CommandContext {
+                config: Config::new(),
+                tag: None,
+                parallel: false,
+                repos: None,
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            InitCommand { output, overwrite }.execute(&context).await?;
+        }
+        Commands::SyncFiles {
+            repos,
+            map,
+            dry_run,
+            config,
+            tag,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel: false,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            SyncFilesCommand { map, dry_run }.execute(&context).await?;
+        }
+        Commands::Replace {
+            repos,
+            pattern,
+            replacement,
+            glob,
+            dry_run,
+            pr,
+            title,
+            body,
+            branch,
+            base,
+            message,
+            draft,
+            token,
+            config,
+            tag,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel: false,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+
+            let pr_options = if pr {
+                let token = token.or_else(|| env::var("GITHUB_TOKEN").ok())
+                    .ok_or_else(|| anyhow::anyhow!("GitHub token not provided. Use --token flag or set GITHUB_TOKEN environment variable."))?;
+                Some(PrOptions {
+                    title,
+                    body,
+                    branch_name: branch,
+                    base_branch: base,
+                    commit_msg: message,
+                    draft,
+                    token,
+                    create_only: false,
+                    branch_template: None,
+                    campaign: None,
+                })
+            } else {
+                None
+            };
+
+            ReplaceCommand {
+                pattern,
+                replacement,
+                glob,
+                dry_run,
+                pr_options,
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::Bump {
+            repos,
+            level,
+            update_dependents,
+            dry_run,
+            pr,
+            title,
+            body,
+            branch,
+            base,
+            message,
+            draft,
+            token,
+            config,
+            tag,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel: false,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+
+            let pr_options = if pr {
+                let token = token.or_else(|| env::var("GITHUB_TOKEN").ok())
+                    .ok_or_else(|| anyhow::anyhow!("GitHub token not provided. Use --token flag or set GITHUB_TOKEN environment variable."))?;
+                Some(PrOptions {
+                    title,
+                    body,
+                    branch_name: branch.clone(),
+                    base_branch: base,
+                    commit_msg: message.clone(),
+                    draft,
+                    token,
+                    create_only: false,
+                    branch_template: None,
+                    campaign: None,
+                })
+            } else {
+                None
+            };
+
+            BumpCommand {
+                level,
+                update_dependents,
+                dry_run,
+                pr_options,
+                branch,
+                commit_msg: message,
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::Changelog {
+            repos,
+            since,
+            until,
+            format,
+            conventional,
+            output,
+            config,
+            tag,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel: false,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            ChangelogCommand {
+                since,
+                until,
+                format,
+                conventional,
+                output,
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::Scan {
+            repos,
+            tools,
+            fail_on,
+            format,
+            output,
+            config,
+            tag,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel: false,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            ScanCommand {
+                tools,
+                fail_on,
+                format,
+                output,
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::Sbom {
+            repos,
+            format,
+            generator,
+            output,
+            config,
+            tag,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel: false,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            SbomCommand {
+                format,
+                generator,
+                output,
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::Deps {
+            repos,
+            package,
+            outdated,
+            config,
+            tag,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel: false,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            DepsCommand { package, outdated }.execute(&context).await?;
+        }
+        Commands::Coverage {
+            repos,
+            format,
+            report,
+            threshold,
+            output,
+            config,
+            tag,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel: false,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            CoverageCommand {
+                format,
+                report,
+                threshold,
+                output,
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::Bench {
+            command,
+            repos,
+            runs,
+            warmup,
+            baseline,
+            save_baseline,
+            tolerance,
+            config,
+            tag,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel: false,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            BenchCommand {
+                command,
+                runs,
+                warmup,
+                baseline,
+                save_baseline,
+                tolerance,
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::Task {
+            name,
+            repos,
+            logs,
+            path_filter,
+            config,
+            tag,
+            parallel,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            TaskCommand {
+                name,
+                log_dir: logs,
+                path_filter,
+            }
             .execute(&context)
             .await?;
         }
-        Commands::Rm {
+        Commands::Conflicts {
+            repos,
+            branch,
+            against,
+            fetch,
+            config,
+            tag,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel: false,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            ConflictsCommand {
+                branch,
+                against,
+                fetch,
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::Rebase {
+            repos,
+            onto,
+            branch,
+            fetch,
+            config,
+            tag,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel: false,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            RebaseCommand {
+                onto,
+                branch,
+                fetch,
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::CherryPick {
+            commit,
+            from,
+            repos,
+            pr,
+            title,
+            body,
+            branch,
+            base,
+            message,
+            draft,
+            token,
+            config,
+            tag,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel: false,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+
+            let pr_options = if pr {
+                let token = token.or_else(|| env::var("GITHUB_TOKEN").ok())
+                    .ok_or_else(|| anyhow::anyhow!("GitHub token not provided. Use --token flag or set GITHUB_TOKEN environment variable."))?;
+                Some(PrOptions {
+                    title: title.unwrap_or_default(),
+                    body,
+                    branch_name: branch,
+                    base_branch: base,
+                    commit_msg: message,
+                    draft,
+                    token,
+                    create_only: false,
+                    branch_template: None,
+                    campaign: None,
+                })
+            } else {
+                None
+            };
+
+            CherryPickCommand {
+                commit,
+                from,
+                pr_options,
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::FileDiff {
+            path,
+            repos,
+            baseline,
+            config,
+            tag,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel: false,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            FileDiffCommand { path, baseline }.execute(&context).await?;
+        }
+        Commands::New {
+            name,
+            template,
+            url,
+            path,
+            tags,
+            vars,
+            branch,
+            create_remote,
+            public,
+            token,
+            config: config_path,
+        } => {
+            // The first repository can be created before any config exists
+            let config = if std::path::Path::new(&config_path).exists() {
+                Config::load_config(&config_path)?
+            } else {
+                Config {
+                    user_hosts: UserSettings::load()?.hosts,
+                    ..Config::new()
+                }
+            };
+            let context = CommandContext {
+                config,
+                tag: None,
+                parallel: false,
+                repos: None,
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            NewCommand {
+                name,
+                template,
+                url,
+                path,
+                tags,
+                vars,
+                branch,
+                create_remote,
+                private: !public,
+                token: token.or_else(|| env::var("GITHUB_TOKEN").ok()),
+                config_path,
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::Relayout {
+            repos,
+            dry_run,
+            config,
+            tag,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel: false,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            RelayoutCommand { dry_run }.execute(&context).await?;
+        }
+        Commands::Env {
+            repo,
+            format,
+            config,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag: None,
+                parallel: false,
+                repos: None,
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            EnvCommand { repo, format }.execute(&context).await?;
+        }
+        Commands::Test {
             repos,
+            presets,
+            output,
             config,
             tag,
             parallel,
@@ -230,19 +3424,388 @@ async fn main() -> Result<()> {
                 tag,
                 parallel,
                 repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
             };
-            RemoveCommand.execute(&context).await?;
+            TestCommand { presets, output }.execute(&context).await?;
         }
-        Commands::Init { output, overwrite } => {
-            // Init command doesn't need config since it creates one
+        Commands::Todos {
+            repos,
+            pattern,
+            assignee_from_blame,
+            format,
+            output,
+            config,
+            tag,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel: false,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            TodosCommand {
+                pattern,
+                assignee_from_blame,
+                format,
+                output,
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::Contributors {
+            repos,
+            since,
+            top,
+            format,
+            config,
+            tag,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel: false,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            ContributorsCommand { since, top, format }
+                .execute(&context)
+                .await?;
+        }
+        Commands::Inactive {
+            repos,
+            threshold,
+            token,
+            config,
+            tag,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel: false,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            InactiveCommand {
+                threshold,
+                token: token.or_else(|| env::var("GITHUB_TOKEN").ok()),
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::Consolidate {
+            repos,
+            into,
+            prefix_by,
+            rewrite_history,
+            dry_run,
+            config,
+            tag,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel: false,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            ConsolidateCommand {
+                into,
+                prefix_by,
+                rewrite_history,
+                dry_run,
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::Extract {
+            repo,
+            path,
+            to,
+            name,
+            tags,
+            public,
+            token,
+            config: config_path,
+        } => {
+            let config = Config::load_config(&config_path)?;
+            let context = CommandContext {
+                config,
+                tag: None,
+                parallel: false,
+                repos: None,
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            ExtractCommand {
+                repo,
+                path,
+                to,
+                name,
+                tags,
+                private: !public,
+                token: token.or_else(|| env::var("GITHUB_TOKEN").ok()),
+                config_path,
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::Maintenance {
+            repos,
+            aggressive,
+            config,
+            tag,
+            parallel,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            MaintenanceCommand { aggressive }.execute(&context).await?;
+        }
+        Commands::Runs { action } => {
             let context = CommandContext {
                 config: Config::new(),
                 tag: None,
                 parallel: false,
                 repos: None,
+                include_archived,
+                presence,
+                visibility,
+                owner,
             };
-            InitCommand { output, overwrite }.execute(&context).await?;
+            RunsCommand { action }.execute(&context).await?;
+        }
+        Commands::Rename {
+            old_name,
+            new_name,
+            remote,
+            token,
+            config: config_path,
+        } => {
+            let config = Config::load_config(&config_path)?;
+            let context = CommandContext {
+                config,
+                tag: None,
+                parallel: false,
+                repos: None,
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            RenameCommand {
+                old_name,
+                new_name,
+                remote,
+                token,
+                config_path,
+            }
+            .execute(&context)
+            .await?;
+        }
+        Commands::Ci { action } => match action {
+            CiAction::Audit {
+                repos,
+                policy,
+                output,
+                fix,
+                title,
+                body,
+                branch,
+                base,
+                draft,
+                token,
+                config,
+                tag,
+            } => {
+                let config = Config::load_config(&config)?;
+                let context = CommandContext {
+                    config,
+                    tag,
+                    parallel: false,
+                    repos: if repos.is_empty() { None } else { Some(repos) },
+                    include_archived,
+                    presence,
+                    visibility,
+                    owner,
+                };
+
+                // Only fixing opens pull requests, so auditing doesn't need a token
+                let fix = if fix {
+                    let token = token.or_else(|| env::var("GITHUB_TOKEN").ok()).ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "GitHub token not provided. Use --token flag or set GITHUB_TOKEN environment variable."
+                        )
+                    })?;
+                    Some(PrOptions {
+                        title,
+                        body,
+                        branch_name: branch,
+                        base_branch: base,
+                        commit_msg: None,
+                        draft,
+                        token,
+                        create_only: false,
+                        branch_template: None,
+                        campaign: None,
+                    })
+                } else {
+                    None
+                };
+
+                CiAuditCommand {
+                    policy: CiPolicy::load(&policy)?,
+                    output,
+                    fix,
+                }
+                .execute(&context)
+                .await?;
+            }
+        },
+        Commands::Owners {
+            path,
+            repos,
+            since,
+            top,
+            format,
+            config,
+            tag,
+        } => {
+            let config = Config::load_config(&config)?;
+            let context = CommandContext {
+                config,
+                tag,
+                parallel: false,
+                repos: if repos.is_empty() { None } else { Some(repos) },
+                include_archived,
+                presence,
+                visibility,
+                owner,
+            };
+            OwnersCommand {
+                path,
+                since,
+                top,
+                format,
+            }
+            .execute(&context)
+            .await?;
         }
+        Commands::Actions { action } => match action {
+            ActionsAction::Pin {
+                repos,
+                pr,
+                dry_run,
+                title,
+                body,
+                branch,
+                base,
+                draft,
+                token,
+                config,
+                tag,
+            } => {
+                let config = Config::load_config(&config)?;
+                let context = CommandContext {
+                    config,
+                    tag,
+                    parallel: false,
+                    repos: if repos.is_empty() { None } else { Some(repos) },
+                    include_archived,
+                    presence,
+                    visibility,
+                    owner,
+                };
+
+                let token = token.or_else(|| env::var("GITHUB_TOKEN").ok());
+                // Pull requests are only opened for real runs, which need a token
+                let pr = if pr && !dry_run {
+                    let token = token.clone().ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "GitHub token not provided. Use --token flag or set GITHUB_TOKEN environment variable."
+                        )
+                    })?;
+                    Some(PrOptions {
+                        title,
+                        body,
+                        branch_name: branch,
+                        base_branch: base,
+                        commit_msg: None,
+                        draft,
+                        token,
+                        create_only: false,
+                        branch_template: None,
+                        campaign: None,
+                    })
+                } else {
+                    None
+                };
+
+                ActionsPinCommand { dry_run, token, pr }
+                    .execute(&context)
+                    .await?;
+            }
+        },
+        Commands::Devfiles { action } => match action {
+            DevfilesAction::Apply {
+                repos,
+                kind,
+                template,
+                path,
+                dry_run,
+                config,
+                tag,
+            } => {
+                let config = Config::load_config(&config)?;
+                let context = CommandContext {
+                    config,
+                    tag,
+                    parallel: false,
+                    repos: if repos.is_empty() { None } else { Some(repos) },
+                    include_archived,
+                    presence,
+                    visibility,
+                    owner,
+                };
+                DevfilesApplyCommand {
+                    kind,
+                    template,
+                    path: path.unwrap_or_else(|| kind.path().to_string()),
+                    dry_run,
+                }
+                .execute(&context)
+                .await?;
+            }
+        },
     }
 
     Ok(())
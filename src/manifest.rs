@@ -0,0 +1,692 @@
+//! Package manifest detection and editing (Cargo.toml, package.json, pom.xml)
+
+use anyhow::Result;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+/// Supported manifest formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManifestKind {
+    Cargo,
+    Npm,
+    Maven,
+}
+
+impl ManifestKind {
+    /// File name of the manifest at the repository root
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            ManifestKind::Cargo => "Cargo.toml",
+            ManifestKind::Npm => "package.json",
+            ManifestKind::Maven => "pom.xml",
+        }
+    }
+
+    /// All supported manifest kinds
+    pub fn all() -> [ManifestKind; 3] {
+        [ManifestKind::Cargo, ManifestKind::Npm, ManifestKind::Maven]
+    }
+
+    /// Lockfile kept next to the manifest, with the command that brings it
+    /// in line with the manifest without upgrading other dependencies
+    pub fn lockfile(&self) -> Option<(&'static str, &'static [&'static str])> {
+        match self {
+            ManifestKind::Cargo => Some((
+                "Cargo.lock",
+                &["cargo", "update", "--workspace", "--offline"],
+            )),
+            ManifestKind::Npm => Some((
+                "package-lock.json",
+                &[
+                    "npm",
+                    "install",
+                    "--package-lock-only",
+                    "--ignore-scripts",
+                    "--no-audit",
+                    "--no-fund",
+                ],
+            )),
+            ManifestKind::Maven => None,
+        }
+    }
+
+    /// Package ecosystem the manifest belongs to
+    pub fn ecosystem(&self) -> &'static str {
+        match self {
+            ManifestKind::Cargo => "cargo",
+            ManifestKind::Npm => "npm",
+            ManifestKind::Maven => "maven",
+        }
+    }
+}
+
+/// A dependency declared in a manifest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dependency {
+    pub ecosystem: &'static str,
+    pub name: String,
+    /// Version requirement as written, or how the dependency is sourced
+    /// (`path`, `git`, `workspace`) when it has none
+    pub version: String,
+}
+
+/// Semantic version bump level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum BumpLevel {
+    Major,
+    Minor,
+    Patch,
+}
+
+/// A manifest file found in a repository
+#[derive(Debug, Clone)]
+pub struct Manifest {
+    pub kind: ManifestKind,
+    pub path: PathBuf,
+    pub content: String,
+}
+
+impl Manifest {
+    /// Find the first supported manifest at the root of a repository
+    pub fn detect(repo_dir: &Path) -> Result<Option<Self>> {
+        for kind in ManifestKind::all() {
+            let path = repo_dir.join(kind.file_name());
+            if path.exists() {
+                let content = std::fs::read_to_string(&path)?;
+                return Ok(Some(Self {
+                    kind,
+                    path,
+                    content,
+                }));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Package name declared by the manifest
+    pub fn package_name(&self) -> Option<String> {
+        match self.kind {
+            ManifestKind::Cargo => capture(
+                r#"(?m)^\s*name\s*=\s*"([^"]+)""#,
+                cargo_package_section(&self.content),
+            ),
+            ManifestKind::Npm => serde_json::from_str::<serde_json::Value>(&self.content)
+                .ok()?
+                .get("name")?
+                .as_str()
+                .map(String::from),
+            ManifestKind::Maven => capture(
+                r"<artifactId>\s*([^<\s]+)\s*</artifactId>",
+                &strip_pom_parent(&self.content),
+            ),
+        }
+    }
+
+    /// Package version declared by the manifest
+    pub fn version(&self) -> Option<String> {
+        match self.kind {
+            ManifestKind::Cargo => capture(
+                r#"(?m)^\s*version\s*=\s*"([^"]+)""#,
+                cargo_package_section(&self.content),
+            ),
+            ManifestKind::Npm => serde_json::from_str::<serde_json::Value>(&self.content)
+                .ok()?
+                .get("version")?
+                .as_str()
+                .map(String::from),
+            ManifestKind::Maven => capture(
+                r"<version>\s*([^<\s]+)\s*</version>",
+                &strip_pom_parent(&self.content),
+            ),
+        }
+    }
+
+    /// Replace the package version, preserving the rest of the file's formatting
+    pub fn set_version(&mut self, new_version: &str) -> Result<()> {
+        if self.version().is_none() {
+            anyhow::bail!("No version found in {}", self.kind.file_name());
+        }
+
+        let (start, end) = match self.kind {
+            ManifestKind::Cargo => {
+                let range = cargo_package_range(&self.content);
+                let re = Regex::new(r#"(?m)^\s*version\s*=\s*"([^"]+)""#)?;
+                let m = re
+                    .captures(&self.content[range.clone()])
+                    .and_then(|c| c.get(1))
+                    .unwrap();
+                (range.start + m.start(), range.start + m.end())
+            }
+            ManifestKind::Npm => {
+                let re = Regex::new(r#""version"\s*:\s*"([^"]+)""#)?;
+                let m = re.captures(&self.content).and_then(|c| c.get(1)).unwrap();
+                (m.start(), m.end())
+            }
+            ManifestKind::Maven => {
+                let skip = pom_parent_end(&self.content);
+                let re = Regex::new(r"<version>\s*([^<\s]+)\s*</version>")?;
+                let m = re
+                    .captures_iter(&self.content)
+                    .filter_map(|c| c.get(1))
+                    .find(|m| m.start() >= skip)
+                    .ok_or_else(|| anyhow::anyhow!("No project version found in pom.xml"))?;
+                (m.start(), m.end())
+            }
+        };
+
+        self.content.replace_range(start..end, new_version);
+        Ok(())
+    }
+
+    /// Update the version pin of a dependency on `package`, returning whether
+    /// anything changed. Range operators such as `^` and `~` are preserved.
+    pub fn set_dependency_version(&mut self, package: &str, new_version: &str) -> Result<bool> {
+        let name = regex::escape(package);
+        let pattern = match self.kind {
+            ManifestKind::Cargo => {
+                format!(
+                    r#"(?m)^(\s*{name}\s*=\s*(?:\{{[^}}\n]*version\s*=\s*)?"[\^~=]?)([^"]+)(")"#
+                )
+            }
+            ManifestKind::Npm => format!(r#"("{name}"\s*:\s*"[\^~]?)([^"]+)(")"#),
+            ManifestKind::Maven => {
+                format!(r"(<artifactId>{name}</artifactId>\s*<version>)([^<]+)(</version>)")
+            }
+        };
+
+        let re = Regex::new(&pattern)?;
+        let updated = re
+            .replace_all(&self.content, |caps: &regex::Captures| {
+                format!("{}{}{}", &caps[1], new_version, &caps[3])
+            })
+            .to_string();
+
+        let changed = updated != self.content;
+        self.content = updated;
+        Ok(changed)
+    }
+
+    /// Whether the manifest declares a dependency on `package`
+    pub fn depends_on(&self, package: &str) -> bool {
+        let name = regex::escape(package);
+        match self.kind {
+            ManifestKind::Cargo => {
+                let range = cargo_package_range(&self.content);
+                let rest = format!(
+                    "{}{}",
+                    &self.content[..range.start],
+                    &self.content[range.end..]
+                );
+                Regex::new(&format!(
+                    r"(?m)^\s*(?:{name}\s*=|\[[\w.-]*dependencies\.{name}\])"
+                ))
+                .is_ok_and(|re| re.is_match(&rest))
+            }
+            ManifestKind::Npm => {
+                let Ok(json) = serde_json::from_str::<serde_json::Value>(&self.content) else {
+                    return false;
+                };
+                [
+                    "dependencies",
+                    "devDependencies",
+                    "peerDependencies",
+                    "optionalDependencies",
+                ]
+                .iter()
+                .any(|section| {
+                    json.get(section)
+                        .and_then(|deps| deps.get(package))
+                        .is_some()
+                })
+            }
+            ManifestKind::Maven => {
+                let (Ok(block), Ok(artifact)) = (
+                    Regex::new(r"(?s)<dependency>.*?</dependency>"),
+                    Regex::new(&format!(r"<artifactId>\s*{name}\s*</artifactId>")),
+                ) else {
+                    return false;
+                };
+                block
+                    .find_iter(&self.content)
+                    .any(|m| artifact.is_match(m.as_str()))
+            }
+        }
+    }
+
+    /// Dependencies declared by the manifest
+    pub fn dependencies(&self) -> Vec<Dependency> {
+        let ecosystem = self.kind.ecosystem();
+        let dependency = |name: &str, version: &str| Dependency {
+            ecosystem,
+            name: name.to_string(),
+            version: version.to_string(),
+        };
+
+        match self.kind {
+            ManifestKind::Cargo => cargo_dependencies(&self.content)
+                .into_iter()
+                .map(|(name, version)| dependency(&name, &version))
+                .collect(),
+            ManifestKind::Npm => {
+                let Ok(json) = serde_json::from_str::<serde_json::Value>(&self.content) else {
+                    return Vec::new();
+                };
+                [
+                    "dependencies",
+                    "devDependencies",
+                    "peerDependencies",
+                    "optionalDependencies",
+                ]
+                .iter()
+                .filter_map(|section| json.get(section)?.as_object())
+                .flatten()
+                .map(|(name, version)| dependency(name, version.as_str().unwrap_or_default()))
+                .collect()
+            }
+            ManifestKind::Maven => {
+                let Ok(block) = Regex::new(r"(?s)<dependency>.*?</dependency>") else {
+                    return Vec::new();
+                };
+                block
+                    .find_iter(&self.content)
+                    .filter_map(|m| {
+                        let group = capture(r"<groupId>\s*([^<\s]+)\s*</groupId>", m.as_str())?;
+                        let artifact =
+                            capture(r"<artifactId>\s*([^<\s]+)\s*</artifactId>", m.as_str())?;
+                        let version = capture(r"<version>\s*([^<\s]+)\s*</version>", m.as_str())
+                            .map(|v| resolve_pom_property(&self.content, &v))
+                            .unwrap_or_else(|| "managed".to_string());
+                        Some(dependency(&format!("{group}:{artifact}"), &version))
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Write the manifest back to disk
+    pub fn save(&self) -> Result<()> {
+        std::fs::write(&self.path, &self.content)?;
+        Ok(())
+    }
+
+    /// Regenerate the lockfile next to the manifest after it was saved,
+    /// returning its path, or `None` when there is no lockfile
+    pub fn update_lockfile(&self) -> Result<Option<PathBuf>> {
+        let Some((file_name, command)) = self.kind.lockfile() else {
+            return Ok(None);
+        };
+        let dir = self.path.parent().unwrap_or(Path::new("."));
+        let lockfile = dir.join(file_name);
+        if !lockfile.exists() {
+            return Ok(None);
+        }
+
+        let output = std::process::Command::new(command[0])
+            .args(&command[1..])
+            .current_dir(dir)
+            .output()
+            .map_err(|e| anyhow::anyhow!("Failed to run {}: {e}", command[0]))?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "{} failed: {}",
+                command.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(Some(lockfile))
+    }
+}
+
+/// Dependencies declared by every manifest at the root of a repository,
+/// including Go modules
+pub fn repository_dependencies(repo_dir: &Path) -> Result<Vec<Dependency>> {
+    let mut dependencies = Vec::new();
+    for kind in ManifestKind::all() {
+        let path = repo_dir.join(kind.file_name());
+        if path.exists() {
+            let manifest = Manifest {
+                kind,
+                content: std::fs::read_to_string(&path)?,
+                path,
+            };
+            dependencies.extend(manifest.dependencies());
+        }
+    }
+
+    let go_mod = repo_dir.join("go.mod");
+    if go_mod.exists() {
+        dependencies.extend(go_mod_dependencies(&std::fs::read_to_string(go_mod)?));
+    }
+
+    Ok(dependencies)
+}
+
+/// Modules required by a go.mod file
+pub fn go_mod_dependencies(content: &str) -> Vec<Dependency> {
+    let mut dependencies = Vec::new();
+    let mut in_block = false;
+
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or_default().trim();
+        let requirement = if in_block {
+            if line == ")" {
+                in_block = false;
+                continue;
+            }
+            line
+        } else if line == "require (" {
+            in_block = true;
+            continue;
+        } else if let Some(rest) = line.strip_prefix("require ") {
+            rest
+        } else {
+            continue;
+        };
+
+        if let [name, version] = requirement.split_whitespace().collect::<Vec<_>>()[..] {
+            dependencies.push(Dependency {
+                ecosystem: "go",
+                name: name.to_string(),
+                version: version.to_string(),
+            });
+        }
+    }
+
+    dependencies
+}
+
+/// Compare two dotted versions numerically, ignoring range operators and a
+/// leading `v`. Returns `None` when either is not a version.
+pub fn compare_versions(a: &str, b: &str) -> Option<std::cmp::Ordering> {
+    let parse = |version: &str| -> Option<Vec<u64>> {
+        let core = version
+            .trim_start_matches(['^', '~', '=', '>', '<', ' '])
+            .trim_start_matches('v');
+        let core = core.split(['-', '+', ' ']).next()?;
+        core.split('.').map(|p| p.parse().ok()).collect()
+    };
+    let (mut a, mut b) = (parse(a)?, parse(b)?);
+    let len = a.len().max(b.len());
+    a.resize(len, 0);
+    b.resize(len, 0);
+    Some(a.cmp(&b))
+}
+
+/// Bump a `MAJOR.MINOR.PATCH` version, dropping any pre-release suffix
+pub fn bump_version(version: &str, level: BumpLevel) -> Result<String> {
+    let core = version
+        .split(['-', '+'])
+        .next()
+        .unwrap_or(version)
+        .trim_start_matches('v');
+    let parts: Vec<u64> = core
+        .split('.')
+        .map(|p| p.parse::<u64>())
+        .collect::<std::result::Result<_, _>>()
+        .map_err(|_| anyhow::anyhow!("Invalid version: {}", version))?;
+
+    let (major, minor, patch) = match parts.as_slice() {
+        [major] => (*major, 0, 0),
+        [major, minor] => (*major, *minor, 0),
+        [major, minor, patch, ..] => (*major, *minor, *patch),
+        [] => anyhow::bail!("Invalid version: {}", version),
+    };
+
+    Ok(match level {
+        BumpLevel::Major => format!("{}.0.0", major + 1),
+        BumpLevel::Minor => format!("{}.{}.0", major, minor + 1),
+        BumpLevel::Patch => format!("{}.{}.{}", major, minor, patch + 1),
+    })
+}
+
+fn capture(pattern: &str, text: &str) -> Option<String> {
+    Regex::new(pattern)
+        .ok()?
+        .captures(text)?
+        .get(1)
+        .map(|m| m.as_str().to_string())
+}
+
+/// `(name, version)` of every dependency in a Cargo.toml's dependency tables
+fn cargo_dependencies(content: &str) -> Vec<(String, String)> {
+    let entry = Regex::new(r#"^([A-Za-z0-9_-]+)(\.workspace)?\s*=\s*(.+)$"#).unwrap();
+    let mut dependencies = Vec::new();
+    // Inside a `[dependencies]`-style table, or a `[dependencies.<name>]` one
+    let mut in_table = false;
+    let mut table_dependency: Option<String> = None;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            let header = line.trim_matches(['[', ']']);
+            in_table = header.ends_with("dependencies");
+            table_dependency = header
+                .rsplit_once("dependencies.")
+                .map(|(_, name)| name.to_string());
+            continue;
+        }
+
+        if let Some(name) = &table_dependency {
+            if let Some(version) = capture(r#"^version\s*=\s*"([^"]+)""#, line) {
+                dependencies.push((name.clone(), version));
+            }
+            continue;
+        }
+
+        if !in_table {
+            continue;
+        }
+        let Some(caps) = entry.captures(line) else {
+            continue;
+        };
+        let value = &caps[3];
+        let version = if caps.get(2).is_some() {
+            "workspace".to_string()
+        } else if let Some(version) = capture(r#"^"([^"]+)""#, value) {
+            version
+        } else if let Some(version) = capture(r#"version\s*=\s*"([^"]+)""#, value) {
+            version
+        } else if value.contains("workspace") {
+            "workspace".to_string()
+        } else if value.contains("git") {
+            "git".to_string()
+        } else {
+            "path".to_string()
+        };
+        dependencies.push((caps[1].to_string(), version));
+    }
+
+    dependencies
+}
+
+/// Replace a `${property}` reference with its value from the pom's
+/// `<properties>`, leaving anything else as is
+fn resolve_pom_property(content: &str, value: &str) -> String {
+    let Some(property) = value.strip_prefix("${").and_then(|v| v.strip_suffix('}')) else {
+        return value.to_string();
+    };
+    capture(
+        &format!(r"<{0}>\s*([^<\s]+)\s*</{0}>", regex::escape(property)),
+        content,
+    )
+    .unwrap_or_else(|| value.to_string())
+}
+
+/// Byte range of a Cargo.toml covering the `[package]` table
+fn cargo_package_range(content: &str) -> std::ops::Range<usize> {
+    let Some(start) = content.find("[package]") else {
+        return 0..0;
+    };
+    let start = start + "[package]".len();
+    let end = content[start..]
+        .find("\n[")
+        .map(|i| start + i + 1)
+        .unwrap_or(content.len());
+    start..end
+}
+
+/// Slice of a Cargo.toml covering the `[package]` table
+fn cargo_package_section(content: &str) -> &str {
+    &content[cargo_package_range(content)]
+}
+
+/// Byte offset just past the `<parent>` block of a pom.xml, or 0 if absent
+fn pom_parent_end(content: &str) -> usize {
+    content
+        .find("</parent>")
+        .map(|i| i + "</parent>".len())
+        .unwrap_or(0)
+}
+
+fn strip_pom_parent(content: &str) -> String {
+    content[pom_parent_end(content)..].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(kind: ManifestKind, content: &str) -> Manifest {
+        Manifest {
+            kind,
+            path: PathBuf::from(kind.file_name()),
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_bump_version() {
+        assert_eq!(bump_version("1.2.3", BumpLevel::Patch).unwrap(), "1.2.4");
+        assert_eq!(bump_version("1.2.3", BumpLevel::Minor).unwrap(), "1.3.0");
+        assert_eq!(bump_version("1.2.3", BumpLevel::Major).unwrap(), "2.0.0");
+        assert_eq!(
+            bump_version("1.2.3-SNAPSHOT", BumpLevel::Patch).unwrap(),
+            "1.2.4"
+        );
+        assert!(bump_version("latest", BumpLevel::Patch).is_err());
+    }
+
+    #[test]
+    fn test_cargo_version() {
+        let mut m = manifest(
+            ManifestKind::Cargo,
+            "[package]\nname = \"core\"\nversion = \"0.1.0\"\n\n[dependencies]\nserde = { version = \"1.0\" }\n",
+        );
+        assert_eq!(m.package_name().as_deref(), Some("core"));
+        assert_eq!(m.version().as_deref(), Some("0.1.0"));
+
+        m.set_version("0.2.0").unwrap();
+        assert!(m.content.contains("version = \"0.2.0\""));
+        assert!(m.content.contains("serde = { version = \"1.0\" }"));
+    }
+
+    #[test]
+    fn test_npm_version() {
+        let mut m = manifest(
+            ManifestKind::Npm,
+            "{\n  \"name\": \"web\",\n  \"version\": \"1.0.0\",\n  \"dependencies\": {\n    \"core\": \"^1.0.0\"\n  }\n}\n",
+        );
+        assert_eq!(m.package_name().as_deref(), Some("web"));
+
+        m.set_version("1.1.0").unwrap();
+        assert_eq!(m.version().as_deref(), Some("1.1.0"));
+
+        assert!(m.set_dependency_version("core", "2.0.0").unwrap());
+        assert!(m.content.contains("\"core\": \"^2.0.0\""));
+        assert!(!m.set_dependency_version("missing", "2.0.0").unwrap());
+    }
+
+    #[test]
+    fn test_maven_version_skips_parent() {
+        let mut m = manifest(
+            ManifestKind::Maven,
+            "<project>\n  <parent>\n    <artifactId>parent</artifactId>\n    <version>3.0.0</version>\n  </parent>\n  <artifactId>service</artifactId>\n  <version>1.4.0</version>\n</project>\n",
+        );
+        assert_eq!(m.package_name().as_deref(), Some("service"));
+        assert_eq!(m.version().as_deref(), Some("1.4.0"));
+
+        m.set_version("1.5.0").unwrap();
+        assert!(m.content.contains("<version>3.0.0</version>"));
+        assert!(m.content.contains("<version>1.5.0</version>"));
+    }
+
+    #[test]
+    fn test_depends_on() {
+        let cargo = manifest(
+            ManifestKind::Cargo,
+            "[package]\nname = \"app\"\n\n[dependencies]\ncore = \"0.1\"\n\n[dev-dependencies.fixtures]\npath = \"../fixtures\"\n",
+        );
+        assert!(cargo.depends_on("core"));
+        assert!(cargo.depends_on("fixtures"));
+        assert!(!cargo.depends_on("app"));
+
+        let npm = manifest(
+            ManifestKind::Npm,
+            "{\"name\": \"web\", \"devDependencies\": {\"core\": \"^1.0.0\"}}",
+        );
+        assert!(npm.depends_on("core"));
+        assert!(!npm.depends_on("web"));
+
+        let maven = manifest(
+            ManifestKind::Maven,
+            "<project>\n  <artifactId>service</artifactId>\n  <dependencies>\n    <dependency>\n      <groupId>org.example</groupId>\n      <artifactId>core</artifactId>\n    </dependency>\n  </dependencies>\n</project>\n",
+        );
+        assert!(maven.depends_on("core"));
+        assert!(!maven.depends_on("service"));
+    }
+
+    #[test]
+    fn test_dependencies() {
+        let cargo = manifest(
+            ManifestKind::Cargo,
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\nserde = { version = \"1.0\", features = [\"derive\"] }\ncore = { path = \"../core\" }\ntokio.workspace = true\n\n[dev-dependencies.tempfile]\nversion = \"3\"\n",
+        );
+        let deps: Vec<_> = cargo
+            .dependencies()
+            .into_iter()
+            .map(|d| (d.name, d.version))
+            .collect();
+        assert_eq!(
+            deps,
+            vec![
+                ("serde".to_string(), "1.0".to_string()),
+                ("core".to_string(), "path".to_string()),
+                ("tokio".to_string(), "workspace".to_string()),
+                ("tempfile".to_string(), "3".to_string()),
+            ]
+        );
+
+        let maven = manifest(
+            ManifestKind::Maven,
+            "<project>\n  <properties>\n    <junit.version>5.10.0</junit.version>\n  </properties>\n  <dependencies>\n    <dependency>\n      <groupId>org.junit</groupId>\n      <artifactId>junit</artifactId>\n      <version>${junit.version}</version>\n    </dependency>\n  </dependencies>\n</project>\n",
+        );
+        assert_eq!(maven.dependencies()[0].name, "org.junit:junit");
+        assert_eq!(maven.dependencies()[0].version, "5.10.0");
+
+        let go = go_mod_dependencies(
+            "module example.com/api\n\nrequire github.com/pkg/errors v0.9.1\n\nrequire (\n\tgolang.org/x/net v0.20.0 // indirect\n)\n",
+        );
+        assert_eq!(go.len(), 2);
+        assert_eq!(go[1].name, "golang.org/x/net");
+        assert_eq!(go[1].version, "v0.20.0");
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        use std::cmp::Ordering;
+        assert_eq!(compare_versions("^1.2", "1.10.0"), Some(Ordering::Less));
+        assert_eq!(compare_versions("v0.9.1", "0.9.1"), Some(Ordering::Equal));
+        assert_eq!(compare_versions("path", "1.0.0"), None);
+    }
+
+    #[test]
+    fn test_cargo_dependency_pin() {
+        let mut m = manifest(
+            ManifestKind::Cargo,
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\ncore = \"0.1.0\"\nother = { version = \"0.1.0\", path = \"../core\" }\n",
+        );
+        assert!(m.set_dependency_version("core", "0.2.0").unwrap());
+        assert!(m.content.contains("core = \"0.2.0\""));
+        assert!(m.content.contains("other = { version = \"0.1.0\""));
+    }
+}
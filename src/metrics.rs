@@ -0,0 +1,295 @@
+//! Prometheus metrics of a run, written in the text exposition format for
+//! node_exporter's textfile collector
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Outcome of a command in one repository
+#[derive(Debug, Clone, Copy)]
+struct RepoOutcome {
+    success: bool,
+    duration: Option<Duration>,
+}
+
+/// Per-repository outcomes of a command, rendered as Prometheus metrics
+pub struct Metrics {
+    command: String,
+    started: Instant,
+    outcomes: Mutex<BTreeMap<String, RepoOutcome>>,
+}
+
+/// Counters and timestamps carried over from a previous textfile, keyed by
+/// metric name and repository
+type Previous = BTreeMap<(String, String), f64>;
+
+impl Metrics {
+    pub fn new(command: &str) -> Self {
+        Self {
+            command: command.to_string(),
+            started: Instant::now(),
+            outcomes: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Record the outcome of a repository
+    pub fn record(&self, repo: &str, success: bool, duration: Option<Duration>) {
+        if let Ok(mut outcomes) = self.outcomes.lock() {
+            outcomes.insert(repo.to_string(), RepoOutcome { success, duration });
+        }
+    }
+
+    /// Write the metrics to a textfile, continuing the counters of the
+    /// previous file at the same path. The file is replaced atomically so
+    /// a scrape never sees it half-written.
+    pub fn write_textfile(&self, path: &str) -> Result<()> {
+        let previous = std::fs::read_to_string(path)
+            .map(|content| parse_previous(&content, &self.command))
+            .unwrap_or_default();
+        let content = self.render(&previous, Utc::now().timestamp());
+
+        let temp = format!("{path}.{}.tmp", std::process::id());
+        if let Some(parent) = Path::new(path).parent() {
+            std::fs::create_dir_all(parent).ok();
+        }
+        std::fs::write(&temp, content)
+            .with_context(|| format!("Failed to write metrics to '{temp}'"))?;
+        std::fs::rename(&temp, path)
+            .with_context(|| format!("Failed to write metrics to '{path}'"))?;
+        Ok(())
+    }
+
+    fn render(&self, previous: &Previous, now: i64) -> String {
+        let outcomes = self.outcomes.lock().map(|o| o.clone()).unwrap_or_default();
+        let command = escape(&self.command);
+        let mut out = String::new();
+
+        // Counters keep going for repositories that weren't part of this run
+        let mut runs: BTreeMap<(String, &str), f64> = BTreeMap::new();
+        let mut last_success: BTreeMap<String, f64> = BTreeMap::new();
+        for ((metric, repo), value) in previous {
+            match metric.as_str() {
+                "succeeded" => {
+                    runs.insert((repo.clone(), "success"), *value);
+                }
+                "failed" => {
+                    runs.insert((repo.clone(), "failure"), *value);
+                }
+                "last_success" => {
+                    last_success.insert(repo.clone(), *value);
+                }
+                _ => {}
+            }
+        }
+        for (repo, outcome) in &outcomes {
+            let result = if outcome.success {
+                "success"
+            } else {
+                "failure"
+            };
+            *runs.entry((repo.clone(), result)).or_default() += 1.0;
+            if outcome.success {
+                last_success.insert(repo.clone(), now as f64);
+            }
+        }
+
+        header(
+            &mut out,
+            "rrepos_repository_runs_total",
+            "counter",
+            "Commands run in each repository, by result",
+        );
+        for ((repo, result), value) in &runs {
+            let repo = escape(repo);
+            writeln!(
+                out,
+                "rrepos_repository_runs_total{{command=\"{command}\",repo=\"{repo}\",result=\"{result}\"}} {value}"
+            )
+            .ok();
+        }
+
+        header(
+            &mut out,
+            "rrepos_repository_success",
+            "gauge",
+            "Whether the latest command succeeded in each repository",
+        );
+        for (repo, outcome) in &outcomes {
+            writeln!(
+                out,
+                "rrepos_repository_success{{command=\"{command}\",repo=\"{}\"}} {}",
+                escape(repo),
+                u8::from(outcome.success)
+            )
+            .ok();
+        }
+
+        header(
+            &mut out,
+            "rrepos_repository_duration_seconds",
+            "gauge",
+            "Duration of the latest command in each repository",
+        );
+        for (repo, outcome) in &outcomes {
+            if let Some(duration) = outcome.duration {
+                writeln!(
+                    out,
+                    "rrepos_repository_duration_seconds{{command=\"{command}\",repo=\"{}\"}} {:.3}",
+                    escape(repo),
+                    duration.as_secs_f64()
+                )
+                .ok();
+            }
+        }
+
+        header(
+            &mut out,
+            "rrepos_repository_last_success_timestamp_seconds",
+            "gauge",
+            "When the command last succeeded in each repository",
+        );
+        for (repo, timestamp) in &last_success {
+            writeln!(
+                out,
+                "rrepos_repository_last_success_timestamp_seconds{{command=\"{command}\",repo=\"{}\"}} {timestamp}",
+                escape(repo)
+            )
+            .ok();
+        }
+
+        header(
+            &mut out,
+            "rrepos_run_duration_seconds",
+            "gauge",
+            "Duration of the latest run",
+        );
+        writeln!(
+            out,
+            "rrepos_run_duration_seconds{{command=\"{command}\"}} {:.3}",
+            self.started.elapsed().as_secs_f64()
+        )
+        .ok();
+
+        header(
+            &mut out,
+            "rrepos_run_timestamp_seconds",
+            "gauge",
+            "When the latest run finished",
+        );
+        writeln!(
+            out,
+            "rrepos_run_timestamp_seconds{{command=\"{command}\"}} {now}"
+        )
+        .ok();
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    writeln!(out, "# HELP {name} {help}").ok();
+    writeln!(out, "# TYPE {name} {kind}").ok();
+}
+
+/// Escape a label value
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Read the counters and last-success timestamps of `command` from a
+/// textfile written earlier
+fn parse_previous(content: &str, command: &str) -> Previous {
+    let mut previous = Previous::new();
+    for line in content.lines().filter(|line| !line.starts_with('#')) {
+        let Some((series, value)) = line.rsplit_once(' ') else {
+            continue;
+        };
+        let Ok(value) = value.parse::<f64>() else {
+            continue;
+        };
+        let Some((name, labels)) = series.split_once('{') else {
+            continue;
+        };
+        let labels = parse_labels(labels.trim_end_matches('}'));
+        if labels.get("command").map(String::as_str) != Some(command) {
+            continue;
+        }
+        let Some(repo) = labels.get("repo") else {
+            continue;
+        };
+        let metric = match (name, labels.get("result").map(String::as_str)) {
+            ("rrepos_repository_runs_total", Some("success")) => "succeeded",
+            ("rrepos_repository_runs_total", Some("failure")) => "failed",
+            ("rrepos_repository_last_success_timestamp_seconds", _) => "last_success",
+            _ => continue,
+        };
+        previous.insert((metric.to_string(), repo.clone()), value);
+    }
+    previous
+}
+
+/// Parse `key="value",...`, undoing the escaping of `escape`
+fn parse_labels(labels: &str) -> BTreeMap<String, String> {
+    let mut parsed = BTreeMap::new();
+    let mut chars = labels.chars();
+    loop {
+        let key: String = chars.by_ref().take_while(|c| *c != '=').collect();
+        if key.is_empty() || chars.next() != Some('"') {
+            break;
+        }
+        let mut value = String::new();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some('n') => value.push('\n'),
+                    Some(other) => value.push(other),
+                    None => break,
+                },
+                '"' => break,
+                c => value.push(c),
+            }
+        }
+        parsed.insert(key.trim_start_matches(',').to_string(), value);
+    }
+    parsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_continues_counters() {
+        let first = Metrics::new("run");
+        first.record("api", true, Some(Duration::from_millis(1500)));
+        first.record("web", false, None);
+        let content = first.render(&Previous::new(), 1000);
+        assert!(content.contains(
+            "rrepos_repository_runs_total{command=\"run\",repo=\"api\",result=\"success\"} 1"
+        ));
+        assert!(
+            content
+                .contains("rrepos_repository_duration_seconds{command=\"run\",repo=\"api\"} 1.500")
+        );
+        assert!(content.contains("rrepos_repository_success{command=\"run\",repo=\"web\"} 0"));
+
+        let second = Metrics::new("run");
+        second.record("api", true, None);
+        let content = second.render(&parse_previous(&content, "run"), 2000);
+        assert!(content.contains(
+            "rrepos_repository_runs_total{command=\"run\",repo=\"api\",result=\"success\"} 2"
+        ));
+        assert!(content.contains(
+            "rrepos_repository_runs_total{command=\"run\",repo=\"web\",result=\"failure\"} 1"
+        ));
+        assert!(content.contains(
+            "rrepos_repository_last_success_timestamp_seconds{command=\"run\",repo=\"api\"} 2000"
+        ));
+    }
+}
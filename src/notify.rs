@@ -0,0 +1,76 @@
+//! Webhook notifications sent when a command finishes
+
+use crate::offline;
+use crate::runs::RunRecord;
+use crate::template;
+use colored::*;
+use serde_json::json;
+use std::path::Path;
+use std::time::Duration;
+
+/// How long to wait for a webhook before giving up
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Commands whose completion is worth a notification: long-running operations
+/// across many repositories, not every recorded command
+const NOTIFIED_COMMANDS: &[&str] = &["campaign", "clone", "pr", "run", "sync", "task", "test"];
+
+/// Whether webhooks are notified when `command` finishes
+pub fn is_notified(command: &str) -> bool {
+    NOTIFIED_COMMANDS.contains(&command)
+}
+
+/// Post a JSON summary of a finished command to every webhook, with a `text`
+/// rendered from `template` when given. Delivery failures are reported but
+/// never change the command's outcome.
+pub async fn send(webhooks: &[String], template: Option<&Path>, run: &RunRecord) {
+    if webhooks.is_empty() || offline::is_enabled() {
+        return;
+    }
+
+    let mut payload = json!({
+        "run_id": run.id,
+        "command": run.command,
+        "status": run.status,
+        "error": run.error,
+        "finished_at": run.finished_at,
+    });
+    if let Some(template) = template {
+        match template::render_file(template, run) {
+            Ok(text) => payload["text"] = json!(text),
+            Err(e) => eprintln!("{}", format!("{e:#}").yellow()),
+        }
+    }
+
+    let client = reqwest::Client::new();
+    for url in webhooks {
+        let response = client
+            .post(url)
+            .timeout(WEBHOOK_TIMEOUT)
+            .json(&payload)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        if let Err(e) = response {
+            eprintln!(
+                "{}",
+                format!("Failed to notify webhook {url}: {e}").yellow()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_notified() {
+        assert!(is_notified("run"));
+        assert!(is_notified("pr"));
+        assert!(is_notified("clone"));
+        assert!(!is_notified("list"));
+        assert!(!is_notified("replace"));
+        assert!(!is_notified("runs"));
+    }
+}
@@ -0,0 +1,23 @@
+//! Offline mode, in which commands only use local clones
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// Turn offline mode on for the rest of the invocation
+pub fn enable() {
+    OFFLINE.store(true, Ordering::Relaxed);
+}
+
+/// Whether `--offline` was given
+pub fn is_enabled() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+/// Fail when offline, naming what needs the network, e.g. `Pushing`
+pub fn require_network(what: &str) -> anyhow::Result<()> {
+    if is_enabled() {
+        anyhow::bail!("{} needs the network; run without --offline", what);
+    }
+    Ok(())
+}
@@ -0,0 +1,84 @@
+//! Pre-flight connectivity checks run before long operations
+
+use crate::config::{Config, Repository};
+use crate::git;
+use anyhow::Result;
+use colored::*;
+
+/// Check that every repository's remote is reachable with the current
+/// credentials, reporting failures up front.
+///
+/// Returns the repositories that passed the check.
+pub async fn check_remotes(
+    config: &Config,
+    repositories: Vec<Repository>,
+) -> Result<Vec<Repository>> {
+    println!(
+        "{}",
+        format!("Checking access to {} remotes...", repositories.len()).green()
+    );
+
+    let tasks: Vec<_> = repositories
+        .into_iter()
+        .map(|repo| {
+            let settings = repo.host_settings(config);
+            tokio::task::spawn_blocking(move || {
+                let result = git::check_remote_access(&repo, &settings);
+                (repo, result)
+            })
+        })
+        .collect();
+
+    let mut reachable = Vec::new();
+    let mut unreachable = 0;
+    for task in tasks {
+        let (repo, result) = task.await?;
+        match result {
+            Ok(()) => reachable.push(repo),
+            Err(e) => {
+                unreachable += 1;
+                eprintln!(
+                    "{} | {}",
+                    repo.name.cyan().bold(),
+                    format!("Remote not reachable: {e}").red()
+                );
+            }
+        }
+    }
+
+    if unreachable > 0 {
+        println!(
+            "{}",
+            format!("Skipping {unreachable} repositories with unreachable remotes").yellow()
+        );
+    }
+
+    Ok(reachable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::git;
+
+    #[tokio::test]
+    async fn test_check_remotes() {
+        let dir =
+            std::env::temp_dir().join(format!("rrepos-preflight-test-{}", std::process::id()));
+        let origin = dir.join("origin.git");
+        std::fs::create_dir_all(&origin).unwrap();
+        git(&origin, &["init", "--quiet", "--bare"]);
+
+        let repositories = [
+            ("reachable", origin.clone()),
+            ("gone", dir.join("gone.git")),
+        ]
+        .map(|(name, url)| Repository::new(name.to_string(), url.to_string_lossy().to_string()))
+        .to_vec();
+        let reachable = check_remotes(&Config::new(), repositories).await.unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let names: Vec<&str> = reachable.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["reachable"]);
+    }
+}
@@ -0,0 +1,113 @@
+//! Pseudo-terminals for commands that only emit colors or progress, or only
+//! run at all, when attached to a terminal
+
+use anyhow::Result;
+use std::fs::File;
+use std::io::{self, Read};
+
+/// A pseudo-terminal: the command writes to `slave`, output is read from
+/// `master`
+pub struct Pty {
+    pub master: File,
+    pub slave: File,
+}
+
+impl Pty {
+    /// Open a pseudo-terminal the size of the current terminal, or 80x24
+    #[cfg(unix)]
+    pub fn open() -> Result<Self> {
+        use std::os::fd::FromRawFd;
+
+        let mut master = -1;
+        let mut slave = -1;
+        // SAFETY: all pointers are valid for the duration of the calls, and
+        // the descriptors are owned by the returned files
+        unsafe {
+            let mut size: libc::winsize = std::mem::zeroed();
+            if libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) != 0
+                || size.ws_col == 0
+            {
+                size.ws_col = 80;
+                size.ws_row = 24;
+            }
+            if libc::openpty(
+                &mut master,
+                &mut slave,
+                std::ptr::null_mut(),
+                std::ptr::null(),
+                &size,
+            ) != 0
+            {
+                anyhow::bail!(
+                    "Failed to allocate a pseudo-terminal: {}",
+                    io::Error::last_os_error()
+                );
+            }
+            let pty = Pty {
+                master: File::from_raw_fd(master),
+                slave: File::from_raw_fd(slave),
+            };
+            // Commands started in parallel must not inherit each other's
+            // terminals, or the terminals are never closed
+            libc::fcntl(master, libc::F_SETFD, libc::FD_CLOEXEC);
+            libc::fcntl(slave, libc::F_SETFD, libc::FD_CLOEXEC);
+
+            // Keep line endings as written instead of translating to \r\n
+            let mut termios: libc::termios = std::mem::zeroed();
+            if libc::tcgetattr(slave, &mut termios) == 0 {
+                termios.c_oflag &= !libc::ONLCR;
+                libc::tcsetattr(slave, libc::TCSANOW, &termios);
+            }
+            Ok(pty)
+        }
+    }
+
+    #[cfg(not(unix))]
+    pub fn open() -> Result<Self> {
+        anyhow::bail!("--pty is only supported on Unix")
+    }
+
+    /// Reader of the command's output, ending once every process writing to
+    /// the terminal has exited
+    pub fn reader(&self) -> Result<PtyReader> {
+        Ok(PtyReader(self.master.try_clone()?))
+    }
+}
+
+/// Reads a pseudo-terminal's output until it is closed
+pub struct PtyReader(File);
+
+impl Read for PtyReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.0.read(buf) {
+            // Linux reports a closed terminal as EIO rather than end of file
+            Err(e) if e.raw_os_error() == Some(EIO) => Ok(0),
+            result => result,
+        }
+    }
+}
+
+#[cfg(unix)]
+const EIO: i32 = libc::EIO;
+#[cfg(not(unix))]
+const EIO: i32 = 5;
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_reader_ends_when_terminal_closes() {
+        let mut pty = Pty::open().unwrap();
+        let mut reader = pty.reader().unwrap();
+        pty.slave.write_all(b"colored\nprogress\n").unwrap();
+        drop(pty);
+
+        // Reading past the closed terminal ends the output instead of
+        // failing with EIO, and line endings are kept as written
+        let mut output = String::new();
+        reader.read_to_string(&mut output).unwrap();
+        assert_eq!(output, "colored\nprogress\n");
+    }
+}
@@ -0,0 +1,86 @@
+//! Latest published package versions from public registries
+
+use crate::offline;
+use anyhow::{Context, Result};
+use reqwest::{Client, StatusCode};
+use serde_json::Value;
+
+/// Client for crates.io, the npm registry, Maven Central and the Go proxy
+pub struct RegistryClient {
+    client: Client,
+}
+
+impl RegistryClient {
+    pub fn new() -> Result<Self> {
+        offline::require_network("Looking up package registries")?;
+        // crates.io rejects requests without a user agent
+        let client = Client::builder()
+            .user_agent(concat!("rrepos/", env!("CARGO_PKG_VERSION")))
+            .build()?;
+        Ok(Self { client })
+    }
+
+    /// Latest stable version of a package, or `None` if the registry does
+    /// not know it
+    pub async fn latest_version(&self, ecosystem: &str, name: &str) -> Result<Option<String>> {
+        let (url, pointer) = match ecosystem {
+            "cargo" => (
+                format!("https://crates.io/api/v1/crates/{name}"),
+                "/crate/max_stable_version",
+            ),
+            "npm" => (
+                format!("https://registry.npmjs.org/{name}/latest"),
+                "/version",
+            ),
+            "maven" => {
+                let Some((group, artifact)) = name.split_once(':') else {
+                    return Ok(None);
+                };
+                (
+                    format!(
+                        "https://search.maven.org/solrsearch/select?q=g:{group}+AND+a:{artifact}&rows=1&wt=json"
+                    ),
+                    "/response/docs/0/latestVersion",
+                )
+            }
+            "go" => (
+                format!("https://proxy.golang.org/{}/@latest", go_proxy_path(name)),
+                "/Version",
+            ),
+            _ => return Ok(None),
+        };
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .with_context(|| format!("Failed to query {url}"))?;
+        if matches!(response.status(), StatusCode::NOT_FOUND | StatusCode::GONE) {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            anyhow::bail!("{} returned {}", url, response.status());
+        }
+
+        let body: Value = response.json().await?;
+        Ok(body
+            .pointer(pointer)
+            .and_then(Value::as_str)
+            .map(String::from))
+    }
+}
+
+/// Go proxy paths escape upper-case letters as `!` and the lower-case letter
+fn go_proxy_path(module: &str) -> String {
+    module
+        .chars()
+        .flat_map(|c| {
+            if c.is_ascii_uppercase() {
+                vec!['!', c.to_ascii_lowercase()]
+            } else {
+                vec![c]
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,215 @@
+//! Repository selection by metadata fetched from GitHub
+
+use crate::config::{Config, Repository};
+use crate::github::{GitHubClient, GitHubRepo};
+use crate::util;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Default location of the metadata cache, relative to the working directory
+pub const METADATA_CACHE: &str = ".rrepos/metadata.json";
+
+/// How long cached metadata is used before it is fetched again
+const METADATA_TTL_HOURS: i64 = 1;
+
+/// A condition on a repository's GitHub metadata, written as `key=value`
+/// (`language`, `topic`, `visibility`, `archived`) or `pushed>AGE`/`pushed<AGE`
+#[derive(Debug, Clone, PartialEq)]
+pub enum RemoteFilter {
+    Language(String),
+    Topic(String),
+    Visibility(String),
+    Archived(bool),
+    /// Pushed to after this time
+    PushedAfter(DateTime<Utc>),
+    /// Not pushed to since this time
+    PushedBefore(DateTime<Utc>),
+}
+
+impl FromStr for RemoteFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((key, value)) = s.split_once(['>', '<']) {
+            if key.trim() != "pushed" {
+                return Err(format!("Only 'pushed' can be compared, got '{key}'"));
+            }
+            let time = util::parse_time(value.trim(), Utc::now())?;
+            return Ok(if s.contains('>') {
+                RemoteFilter::PushedAfter(time)
+            } else {
+                RemoteFilter::PushedBefore(time)
+            });
+        }
+
+        let Some((key, value)) = s.split_once('=') else {
+            return Err(format!("Expected KEY=VALUE or pushed>AGE, got '{s}'"));
+        };
+        let value = value.trim().to_string();
+        match key.trim() {
+            "language" => Ok(RemoteFilter::Language(value)),
+            "topic" => Ok(RemoteFilter::Topic(value)),
+            "visibility" => Ok(RemoteFilter::Visibility(value.to_lowercase())),
+            "archived" => value
+                .parse()
+                .map(RemoteFilter::Archived)
+                .map_err(|_| format!("archived must be true or false, got '{value}'")),
+            other => Err(format!(
+                "Unknown filter '{other}' (expected language, topic, visibility, archived or pushed)"
+            )),
+        }
+    }
+}
+
+impl RemoteFilter {
+    pub fn matches(&self, repo: &GitHubRepo) -> bool {
+        let pushed_at = || {
+            repo.pushed_at
+                .as_deref()
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                .map(|t| t.with_timezone(&Utc))
+        };
+        match self {
+            RemoteFilter::Language(language) => repo
+                .language
+                .as_deref()
+                .is_some_and(|l| l.eq_ignore_ascii_case(language)),
+            RemoteFilter::Topic(topic) => repo.topics.iter().any(|t| t == topic),
+            RemoteFilter::Visibility(visibility) => {
+                let actual = repo
+                    .visibility
+                    .clone()
+                    .unwrap_or_else(|| if repo.private { "private" } else { "public" }.to_string());
+                actual == *visibility
+            }
+            RemoteFilter::Archived(archived) => repo.archived == *archived,
+            RemoteFilter::PushedAfter(time) => pushed_at().is_some_and(|t| t > *time),
+            RemoteFilter::PushedBefore(time) => pushed_at().is_some_and(|t| t < *time),
+        }
+    }
+}
+
+/// A cached metadata lookup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedMetadata {
+    fetched_at: DateTime<Utc>,
+    metadata: GitHubRepo,
+}
+
+/// Keep only the repositories selected by `tag` and `names` whose GitHub
+/// metadata matches every filter.
+///
+/// Metadata is cached in [`METADATA_CACHE`] for an hour so repeated
+/// invocations don't query the API for every repository. Repositories whose
+/// metadata cannot be fetched are left out.
+pub async fn apply(
+    config: &mut Config,
+    tag: Option<&str>,
+    names: Option<&[String]>,
+    filters: &[RemoteFilter],
+    token: Option<String>,
+) -> Result<()> {
+    if filters.is_empty() {
+        return Ok(());
+    }
+
+    let client = GitHubClient::with_settings(token, &config.github_settings())?;
+    let mut cache = load_cache(METADATA_CACHE);
+    let now = Utc::now();
+    let mut selected = Vec::new();
+
+    for repo in config.filter_repositories(tag, names) {
+        let cached = cache
+            .get(&repo.url)
+            .filter(|entry| now - entry.fetched_at < Duration::hours(METADATA_TTL_HOURS))
+            .map(|entry| entry.metadata.clone());
+        let metadata = match cached {
+            Some(metadata) => metadata,
+            None => match fetch_metadata(&client, &repo).await {
+                Ok(metadata) => {
+                    cache.insert(
+                        repo.url.clone(),
+                        CachedMetadata {
+                            fetched_at: now,
+                            metadata: metadata.clone(),
+                        },
+                    );
+                    metadata
+                }
+                Err(e) => {
+                    eprintln!(
+                        "{} | {}",
+                        repo.name.cyan().bold(),
+                        format!("Error: {e}").red()
+                    );
+                    continue;
+                }
+            },
+        };
+
+        if filters.iter().all(|filter| filter.matches(&metadata)) {
+            selected.push(repo);
+        }
+    }
+
+    config.repositories = selected;
+    save_cache(METADATA_CACHE, &cache)
+}
+
+async fn fetch_metadata(client: &GitHubClient, repo: &Repository) -> Result<GitHubRepo> {
+    let (owner, name) = client.parse_github_url(&repo.url)?;
+    client.get_repository(&owner, &name).await
+}
+
+fn load_cache(path: &str) -> BTreeMap<String, CachedMetadata> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_cache(path: &str, cache: &BTreeMap<String, CachedMetadata>) -> Result<()> {
+    if let Some(parent) = Path::new(path).parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(cache)?)
+        .with_context(|| format!("Failed to write metadata cache '{path}'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remote_filters() {
+        let repo: GitHubRepo = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "name": "api",
+            "full_name": "org/api",
+            "html_url": "https://github.com/org/api",
+            "clone_url": "https://github.com/org/api.git",
+            "default_branch": "main",
+            "language": "Go",
+            "visibility": "internal",
+            "topics": ["backend"],
+            "pushed_at": (Utc::now() - Duration::days(10)).to_rfc3339(),
+        }))
+        .unwrap();
+
+        let matches = |filter: &str| filter.parse::<RemoteFilter>().unwrap().matches(&repo);
+        assert!(matches("language=go"));
+        assert!(matches("topic=backend"));
+        assert!(matches("visibility=internal"));
+        assert!(matches("archived=false"));
+        assert!(matches("pushed>30d"));
+        assert!(!matches("pushed<2w"));
+        assert!(matches("pushed>2020-01-01"));
+        assert!("stars=5".parse::<RemoteFilter>().is_err());
+        assert!("pushed>30x".parse::<RemoteFilter>().is_err());
+    }
+}
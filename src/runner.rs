@@ -1,20 +1,414 @@
 //! Command execution runner for managing operations across multiple repositories
 
 use crate::config::Repository;
-use crate::git::Logger;
+use crate::environment::Environment;
+use crate::events::{Event, EventSink, Stream};
+use crate::git::{self, Logger};
+use crate::pty::Pty;
+use crate::runs;
+use crate::sandbox;
+use crate::template;
+use crate::util::{parse_size, shell_quote, split_command_line};
 use anyhow::Result;
 use chrono::Utc;
 use colored::*;
+use glob::{MatchOptions, Pattern};
+use serde::{Deserialize, Serialize};
 use std::fs::{File, create_dir_all};
-use std::io::{BufRead, BufReader, Write};
+use std::io::{BufRead, BufReader, Read, Write};
 use std::path::Path;
 use std::process::{Command, Stdio};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Instant;
 use tokio::sync::Mutex;
 
+/// Shell used to interpret commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Shell {
+    Sh,
+    Bash,
+    Zsh,
+    Pwsh,
+    Cmd,
+    /// Execute the program directly, splitting arguments without a shell
+    None,
+}
+
+impl Default for Shell {
+    fn default() -> Self {
+        if cfg!(windows) { Shell::Cmd } else { Shell::Sh }
+    }
+}
+
+impl Shell {
+    /// Build a process that runs `command` with this shell
+    pub fn command(&self, command: &str) -> Result<Command> {
+        let cmd = match self {
+            Shell::Sh | Shell::Bash | Shell::Zsh => {
+                let mut cmd = Command::new(self.program());
+                cmd.arg("-c").arg(command);
+                cmd
+            }
+            Shell::Pwsh => {
+                let mut cmd = Command::new("pwsh");
+                cmd.args(["-NoProfile", "-NonInteractive", "-Command", command]);
+                cmd
+            }
+            Shell::Cmd => {
+                let mut cmd = Command::new("cmd");
+                cmd.arg("/C").arg(command);
+                cmd
+            }
+            Shell::None => {
+                let words = split_command_line(command)?;
+                let (program, args) = words
+                    .split_first()
+                    .ok_or_else(|| anyhow::anyhow!("Command cannot be empty"))?;
+                let mut cmd = Command::new(program);
+                cmd.args(args);
+                cmd
+            }
+        };
+        Ok(cmd)
+    }
+
+    /// Command line that runs a script file with this shell
+    pub fn script_command(&self, script: &str) -> String {
+        match self {
+            Shell::Sh | Shell::Bash | Shell::Zsh => {
+                format!("{} {}", self.program(), self.quote(script))
+            }
+            Shell::Pwsh => format!("& {}", self.quote(script)),
+            Shell::Cmd | Shell::None => self.quote(script),
+        }
+    }
+
+    /// Quote a single argument for this shell
+    pub fn quote(&self, value: &str) -> String {
+        match self {
+            Shell::Pwsh => format!("'{}'", value.replace('\'', "''")),
+            Shell::Cmd => {
+                if value.is_empty() || value.contains([' ', '\t', '"', '&', '|', '<', '>', '^']) {
+                    format!("\"{}\"", value.replace('"', "\"\""))
+                } else {
+                    value.to_string()
+                }
+            }
+            _ => shell_quote(value),
+        }
+    }
+
+    /// Quote a single argument that may contain `{placeholder}`s, leaving
+    /// them unquoted since their values are quoted when they are expanded
+    pub fn quote_template(&self, value: &str) -> String {
+        let mut segments = Vec::new();
+        let mut rest = value;
+        while let Some(start) = rest.find('{') {
+            let placeholder = rest[start + 1..]
+                .find('}')
+                .map(|end| &rest[start..start + end + 2])
+                .filter(|p| template::PLACEHOLDERS.contains(&&p[1..p.len() - 1]));
+            match placeholder {
+                Some(placeholder) => {
+                    segments.push((&rest[..start], false));
+                    segments.push((placeholder, true));
+                    rest = &rest[start + placeholder.len()..];
+                }
+                None => {
+                    segments.push((&rest[..start + 1], false));
+                    rest = &rest[start + 1..];
+                }
+            }
+        }
+        segments.push((rest, false));
+
+        if !segments.iter().any(|(_, placeholder)| *placeholder) {
+            return self.quote(value);
+        }
+        // PowerShell splits adjacent quoted and unquoted parts into separate
+        // arguments, so only a lone placeholder can stay unquoted there
+        if *self == Shell::Pwsh && segments.iter().filter(|(s, _)| !s.is_empty()).count() > 1 {
+            return self.quote(value);
+        }
+
+        // Merge adjacent literal parts before quoting them
+        let mut quoted = String::new();
+        let mut literal = String::new();
+        for (segment, placeholder) in segments {
+            if placeholder {
+                if !literal.is_empty() {
+                    quoted.push_str(&self.quote(&literal));
+                    literal.clear();
+                }
+                quoted.push_str(segment);
+            } else {
+                literal.push_str(segment);
+            }
+        }
+        if !literal.is_empty() {
+            quoted.push_str(&self.quote(&literal));
+        }
+        quoted
+    }
+
+    fn program(&self) -> &'static str {
+        match self {
+            Shell::Sh => "sh",
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Pwsh => "pwsh",
+            Shell::Cmd => "cmd",
+            Shell::None => "",
+        }
+    }
+}
+
+/// What to do when the `--cwd` subdirectory does not exist in a repository
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum MissingSubdir {
+    /// Skip the repository
+    #[default]
+    Skip,
+    /// Run in the repository root instead
+    Root,
+}
+
+/// Predicates on the files of a repository, so commands only run where
+/// they apply
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct PathFilter {
+    /// Only run in repositories where this path exists (repeatable, all must exist)
+    #[arg(long = "if-exists", value_name = "PATH")]
+    pub if_exists: Vec<String>,
+    /// Only run in repositories with a tracked file matching this glob, e.g. 'src/**/*.kt' (repeatable, all must match)
+    #[arg(long = "if-glob", value_name = "GLOB")]
+    pub if_glob: Vec<String>,
+}
+
+impl PathFilter {
+    /// Check that the globs are valid
+    pub fn validate(&self) -> Result<()> {
+        for glob in &self.if_glob {
+            Pattern::new(glob).map_err(|e| anyhow::anyhow!("Invalid glob '{}': {}", glob, e))?;
+        }
+        Ok(())
+    }
+
+    /// The first predicate the repository doesn't meet, if any
+    pub fn unmet(&self, repo: &Repository) -> Option<String> {
+        let root = repo.get_target_dir();
+        let root = Path::new(&root);
+
+        if let Some(path) = self.if_exists.iter().find(|path| !root.join(path).exists()) {
+            return Some(format!("'{path}' does not exist"));
+        }
+
+        if self.if_glob.is_empty() {
+            return None;
+        }
+        // Globs match tracked files, so ignored build output and vendored
+        // dependencies neither match nor have to be walked
+        let files = match git::tracked_files(&root.to_string_lossy()) {
+            Ok(files) => files,
+            Err(e) => return Some(e.to_string()),
+        };
+        let options = MatchOptions {
+            require_literal_separator: true,
+            ..MatchOptions::new()
+        };
+        self.if_glob
+            .iter()
+            .filter_map(|glob| Pattern::new(glob).ok().map(|pattern| (glob, pattern)))
+            .find(|(_, pattern)| {
+                !files
+                    .iter()
+                    .any(|file| pattern.matches_path_with(Path::new(file), options))
+            })
+            .map(|(glob, _)| format!("no files match '{glob}'"))
+    }
+
+    /// Keep the repositories meeting every predicate, logging the others
+    pub fn apply(&self, repositories: Vec<Repository>) -> Vec<Repository> {
+        let logger = Logger;
+        repositories
+            .into_iter()
+            .filter(|repo| match self.unmet(repo) {
+                Some(reason) => {
+                    logger.info(repo, &format!("Skipping, {reason}"));
+                    false
+                }
+                None => true,
+            })
+            .collect()
+    }
+}
+
+/// Caps on the resources of each spawned command, so one runaway command
+/// can't starve the machine running many of them
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::Args)]
+pub struct ResourceLimits {
+    /// Run commands with this added niceness (lower scheduling priority)
+    #[arg(long, allow_negative_numbers = true)]
+    pub nice: Option<i32>,
+    /// Cap each command's memory (address space), e.g. 4G
+    #[arg(long = "memory-limit", value_name = "SIZE", value_parser = parse_size)]
+    pub memory: Option<u64>,
+    /// Cap each command's CPU time, in seconds
+    #[arg(long = "cpu-limit", value_name = "SECONDS")]
+    pub cpu_seconds: Option<u64>,
+    /// Kill a command once it prints more than this much output, e.g. 10M
+    #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+    pub max_output: Option<u64>,
+}
+
+impl ResourceLimits {
+    /// Set up a process to start with these limits, via `setrlimit`
+    #[cfg(unix)]
+    fn apply(&self, process: &mut Command) -> Result<()> {
+        use std::os::unix::process::CommandExt;
+
+        if self.max_output.is_some() {
+            // A group of its own, so the whole process tree can be killed
+            process.process_group(0);
+        }
+        if self.nice.is_none() && self.memory.is_none() && self.cpu_seconds.is_none() {
+            return Ok(());
+        }
+
+        let limits = *self;
+        // SAFETY: the closure only makes async-signal-safe system calls
+        unsafe {
+            process.pre_exec(move || {
+                if let Some(nice) = limits.nice {
+                    // nice(2) may legitimately return -1, so errors are
+                    // ignored; lowering the niceness needs privileges
+                    libc::nice(nice);
+                }
+                if let Some(bytes) = limits.memory {
+                    set_rlimit(libc::RLIMIT_AS, bytes)?;
+                }
+                if let Some(seconds) = limits.cpu_seconds {
+                    set_rlimit(libc::RLIMIT_CPU, seconds)?;
+                }
+                Ok(())
+            });
+        }
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    fn apply(&self, _process: &mut Command) -> Result<()> {
+        if self.nice.is_some() || self.memory.is_some() || self.cpu_seconds.is_some() {
+            anyhow::bail!("Niceness, memory and CPU limits are only supported on Unix");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(unix, target_os = "linux", target_env = "gnu"))]
+type RlimitResource = libc::__rlimit_resource_t;
+#[cfg(all(unix, not(all(target_os = "linux", target_env = "gnu"))))]
+type RlimitResource = libc::c_int;
+
+#[cfg(unix)]
+fn set_rlimit(resource: RlimitResource, value: u64) -> std::io::Result<()> {
+    let limit = libc::rlimit {
+        rlim_cur: value as libc::rlim_t,
+        rlim_max: value as libc::rlim_t,
+    };
+    // SAFETY: `limit` is a valid rlimit for the duration of the call
+    if unsafe { libc::setrlimit(resource, &limit) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Kill a command started in its own process group, with its children
+#[cfg(unix)]
+fn kill_process_group(pid: u32) {
+    // SAFETY: kill(2) has no memory-safety preconditions
+    unsafe {
+        libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+    }
+}
+
+#[cfg(not(unix))]
+fn kill_process_group(_pid: u32) {}
+
+/// Bytes a command may still print before it is killed, shared by its
+/// stdout and stderr readers
+struct OutputBudget {
+    limit: Option<u64>,
+    used: AtomicU64,
+    exceeded: AtomicBool,
+    pid: u32,
+}
+
+impl OutputBudget {
+    /// Count a line against the budget, returning false once it is spent.
+    /// The command is killed the first time.
+    fn take(&self, line: &str) -> bool {
+        let Some(limit) = self.limit else {
+            return true;
+        };
+        let size = line.len() as u64 + 1;
+        if self.used.fetch_add(size, Ordering::Relaxed) + size <= limit {
+            return true;
+        }
+        if !self.exceeded.swap(true, Ordering::Relaxed) {
+            kill_process_group(self.pid);
+        }
+        false
+    }
+}
+
+/// A line printed by a command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputLine {
+    pub stderr: bool,
+    pub text: String,
+}
+
+/// Output and exit status of a command run in a repository
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommandOutput {
+    pub lines: Vec<OutputLine>,
+    pub exit_code: i32,
+}
+
+impl CommandOutput {
+    /// Fail if the command exited with a non-zero status
+    pub fn check(&self) -> Result<()> {
+        if self.exit_code != 0 {
+            anyhow::bail!("Command failed with exit code: {}", self.exit_code);
+        }
+        Ok(())
+    }
+
+    /// Print the output again, as it appeared when the command ran
+    pub fn replay(&self, repo: &Repository) {
+        for line in &self.lines {
+            if line.stderr {
+                eprintln!("{} | {}", repo.name.red().bold(), line.text);
+            } else {
+                println!("{} | {}", repo.name.cyan(), line.text);
+            }
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct CommandRunner {
     logger: Logger,
+    shell: Shell,
+    environment: Environment,
+    stdin: Option<Arc<Vec<u8>>>,
+    subdir: Option<(String, MissingSubdir)>,
+    limits: ResourceLimits,
+    sandbox: bool,
+    pty: bool,
+    events: Option<Arc<EventSink>>,
 }
 
 impl CommandRunner {
@@ -22,19 +416,112 @@ impl CommandRunner {
         Self::default()
     }
 
+    /// Use a specific shell to interpret commands
+    pub fn with_shell(mut self, shell: Shell) -> Self {
+        self.shell = shell;
+        self
+    }
+
+    /// Control the environment variables passed to commands
+    pub fn with_environment(mut self, environment: Environment) -> Self {
+        self.environment = environment;
+        self
+    }
+
+    /// Run commands in a subdirectory of each repository
+    pub fn with_subdir(mut self, subdir: String, missing: MissingSubdir) -> Self {
+        self.subdir = Some((subdir, missing));
+        self
+    }
+
+    /// Feed the same input to every command's stdin
+    pub fn with_stdin(mut self, input: Vec<u8>) -> Self {
+        self.stdin = Some(Arc::new(input));
+        self
+    }
+
+    /// Cap the resources of every command
+    pub fn with_limits(mut self, limits: ResourceLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Run commands without network access, only allowed to write to
+    /// their repository and the temporary directory
+    pub fn with_sandbox(mut self, sandbox: bool) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+
+    /// Attach commands to a pseudo-terminal instead of pipes, so they keep
+    /// their colors and interactive behavior
+    pub fn with_pty(mut self, pty: bool) -> Self {
+        self.pty = pty;
+        self
+    }
+
+    /// Report each command's progress as events
+    pub fn with_events(mut self, events: Arc<EventSink>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
     pub async fn run_command(
         &self,
         repo: &Repository,
         command: &str,
         log_dir: Option<&str>,
     ) -> Result<()> {
+        self.run_command_captured(repo, command, log_dir)
+            .await?
+            .check()
+    }
+
+    /// Run a command and return its output instead of failing on a
+    /// non-zero exit status
+    pub async fn run_command_captured(
+        &self,
+        repo: &Repository,
+        command: &str,
+        log_dir: Option<&str>,
+    ) -> Result<CommandOutput> {
         let repo_dir = repo.get_target_dir();
 
+        // Expand {name}, {path}, {branch}, {url} and {tags} placeholders,
+        // quoted so values can't inject shell syntax
+        let command =
+            &template::render_command(command, &template::repository_variables(repo), |value| {
+                self.shell.quote(value)
+            });
+
         // Check if directory exists
         if !Path::new(&repo_dir).exists() {
             anyhow::bail!("Repository directory does not exist: {}", repo_dir);
         }
 
+        // Resolve the working directory inside the repository
+        let repo_dir = match &self.subdir {
+            Some((subdir, missing)) => {
+                let dir = Path::new(&repo_dir).join(subdir);
+                if dir.is_dir() {
+                    dir.to_string_lossy().to_string()
+                } else if *missing == MissingSubdir::Root {
+                    self.logger.warn(
+                        repo,
+                        &format!("Subdirectory '{subdir}' not found, running in repository root"),
+                    );
+                    repo_dir
+                } else {
+                    self.logger.warn(
+                        repo,
+                        &format!("Subdirectory '{subdir}' not found, skipping"),
+                    );
+                    return Ok(CommandOutput::default());
+                }
+            }
+            None => repo_dir,
+        };
+
         // Prepare log file if log directory is specified
         let log_file = if let Some(log_dir) = log_dir {
             Some(self.prepare_log_file(repo, log_dir, command, &repo_dir)?)
@@ -45,38 +532,104 @@ impl CommandRunner {
         self.logger.info(repo, &format!("Running '{command}'"));
 
         // Execute command
-        let mut cmd = Command::new("sh")
-            .arg("-c")
-            .arg(command)
-            .current_dir(&repo_dir)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()?;
+        let mut process = self.shell.command(command)?;
+        if self.sandbox {
+            process = sandbox::sandbox(process, Path::new(&repo.get_target_dir()))?;
+        }
+        self.environment.apply(&mut process);
+        process.envs(&repo.env);
+        self.limits.apply(&mut process)?;
+        if self.stdin.is_some() {
+            process.stdin(Stdio::piped());
+        }
+        // A pseudo-terminal receives both stdout and stderr
+        let pty = if self.pty { Some(Pty::open()?) } else { None };
+        match &pty {
+            Some(pty) => process
+                .stdout(pty.slave.try_clone()?)
+                .stderr(pty.slave.try_clone()?),
+            None => process.stdout(Stdio::piped()).stderr(Stdio::piped()),
+        };
+        let spawned = process.current_dir(&repo_dir).spawn();
+        // Only the command may keep the terminal open, so reading its output
+        // ends when it exits
+        drop(process);
+        let started = Instant::now();
+        let mut cmd = match spawned {
+            Ok(cmd) => cmd,
+            Err(e) if self.sandbox => anyhow::bail!("Failed to start the sandbox: {e}"),
+            Err(e) => return Err(e.into()),
+        };
 
-        let stdout = cmd.stdout.take().unwrap();
-        let stderr = cmd.stderr.take().unwrap();
+        // Replay buffered input on a separate thread so a command that does
+        // not read its stdin cannot block output processing
+        if let (Some(input), Some(mut child_stdin)) = (self.stdin.clone(), cmd.stdin.take()) {
+            std::thread::spawn(move || {
+                child_stdin.write_all(&input).ok();
+            });
+        }
 
+        let stdout: Box<dyn Read + Send> = match pty {
+            Some(pty) => Box::new(pty.reader()?),
+            None => Box::new(cmd.stdout.take().unwrap()),
+        };
+        let stderr = cmd.stderr.take();
+
+        if let Some(events) = &self.events {
+            events.emit(&Event::Started {
+                repo: &repo.name,
+                command,
+            });
+        }
+
+        let budget = Arc::new(OutputBudget {
+            limit: self.limits.max_output,
+            used: AtomicU64::new(0),
+            exceeded: AtomicBool::new(false),
+            pid: cmd.id(),
+        });
         let log_file = Arc::new(Mutex::new(log_file));
+        let lines = Arc::new(Mutex::new(Vec::new()));
         let repo_name = repo.name.clone();
 
         // Handle stdout
         let stdout_log_file = Arc::clone(&log_file);
         let stdout_repo_name = repo_name.clone();
-        let stdout_handle = tokio::spawn(async move {
+        let stdout_lines = Arc::clone(&lines);
+        let stdout_budget = Arc::clone(&budget);
+        let stdout_events = self.events.clone();
+        let stdout_handle = tokio::task::spawn_blocking(move || {
             let reader = BufReader::new(stdout);
             // Note: We explicitly handle Result instead of using .flatten()
             // to avoid infinite loops on repeated I/O errors
             #[allow(clippy::manual_flatten)]
             for line in reader.lines() {
                 if let Ok(line) = line {
+                    // Keep draining past the cap so the command can't block
+                    if !stdout_budget.take(&line) {
+                        continue;
+                    }
+
                     // Print to console with colored repo name
                     println!("{} | {line}", stdout_repo_name.cyan());
+                    if let Some(events) = &stdout_events {
+                        events.emit(&Event::Output {
+                            repo: &stdout_repo_name,
+                            stream: Stream::Stdout,
+                            line: &line,
+                        });
+                    }
 
                     // Write to log file if available
-                    if let Some(ref mut log_file) = *stdout_log_file.lock().await {
+                    if let Some(ref mut log_file) = *stdout_log_file.blocking_lock() {
                         writeln!(log_file, "{stdout_repo_name} | {line}").ok();
                         log_file.flush().ok();
                     }
+
+                    stdout_lines.blocking_lock().push(OutputLine {
+                        stderr: false,
+                        text: line,
+                    });
                 }
             }
         });
@@ -84,7 +637,13 @@ impl CommandRunner {
         // Handle stderr
         let stderr_log_file = Arc::clone(&log_file);
         let stderr_repo_name = repo_name.clone();
-        let stderr_handle = tokio::spawn(async move {
+        let stderr_lines = Arc::clone(&lines);
+        let stderr_budget = Arc::clone(&budget);
+        let stderr_events = self.events.clone();
+        let stderr_handle = tokio::task::spawn_blocking(move || {
+            let Some(stderr) = stderr else {
+                return;
+            };
             let reader = BufReader::new(stderr);
             let mut header_written = false;
 
@@ -93,11 +652,22 @@ impl CommandRunner {
             #[allow(clippy::manual_flatten)]
             for line in reader.lines() {
                 if let Ok(line) = line {
+                    if !stderr_budget.take(&line) {
+                        continue;
+                    }
+
                     // Print to console with colored repo name
                     eprintln!("{} | {line}", stderr_repo_name.red().bold());
+                    if let Some(events) = &stderr_events {
+                        events.emit(&Event::Output {
+                            repo: &stderr_repo_name,
+                            stream: Stream::Stderr,
+                            line: &line,
+                        });
+                    }
 
                     // Write to log file if available
-                    if let Some(ref mut log_file) = *stderr_log_file.lock().await {
+                    if let Some(ref mut log_file) = *stderr_log_file.blocking_lock() {
                         if !header_written {
                             writeln!(log_file, "\n=== STDERR ===").ok();
                             header_written = true;
@@ -105,6 +675,11 @@ impl CommandRunner {
                         writeln!(log_file, "{stderr_repo_name} | {line}").ok();
                         log_file.flush().ok();
                     }
+
+                    stderr_lines.blocking_lock().push(OutputLine {
+                        stderr: true,
+                        text: line,
+                    });
                 }
             }
         });
@@ -114,15 +689,23 @@ impl CommandRunner {
 
         // Wait for command to complete
         let status = cmd.wait()?;
+        let exit_code = status.code().unwrap_or(-1);
+        if let Some(events) = &self.events {
+            events.emit(&Event::Finished {
+                repo: &repo.name,
+                exit_code,
+                duration_ms: started.elapsed().as_millis(),
+            });
+        }
 
-        if !status.success() {
-            anyhow::bail!(
-                "Command failed with exit code: {}",
-                status.code().unwrap_or(-1)
-            );
+        if let Some(limit) = self.limits.max_output
+            && budget.exceeded.load(Ordering::Relaxed)
+        {
+            anyhow::bail!("Command killed after printing more than {} bytes", limit);
         }
 
-        Ok(())
+        let lines = std::mem::take(&mut *lines.lock().await);
+        Ok(CommandOutput { lines, exit_code })
     }
 
     fn prepare_log_file(
@@ -135,14 +718,16 @@ impl CommandRunner {
         // Create log directory if it doesn't exist
         create_dir_all(log_dir)?;
 
-        let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
-        let log_file_path = format!("{}/{}_{}.log", log_dir, repo.name, timestamp);
+        // Run IDs start with a timestamp, so logs still sort by time
+        let log_file_path = Path::new(log_dir).join(format!("{}_{}.log", repo.name, runs::id()));
 
         let mut log_file = File::create(&log_file_path)?;
+        runs::record_artifact(&log_file_path);
 
         // Write header information
         writeln!(log_file, "Repository: {}", repo.name)?;
         writeln!(log_file, "Command: {command}")?;
+        writeln!(log_file, "Run: {}", runs::id())?;
         writeln!(log_file, "Directory: {repo_dir}")?;
         writeln!(log_file, "Timestamp: {}", Utc::now().to_rfc3339())?;
         writeln!(log_file, "\n=== STDOUT ===")?;
@@ -150,3 +735,144 @@ impl CommandRunner {
         Ok(log_file)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::git;
+
+    #[test]
+    fn test_quote_template() {
+        assert_eq!(Shell::Sh.quote_template("{name}"), "{name}");
+        assert_eq!(Shell::Sh.quote_template("--repo={name}"), "--repo={name}");
+        assert_eq!(
+            Shell::Sh.quote_template("a b/{path}/{x}"),
+            "'a b/'{path}'/{x}'"
+        );
+        assert_eq!(Shell::Pwsh.quote_template("{name}"), "{name}");
+        assert_eq!(Shell::Pwsh.quote_template("a b {name}"), "'a b {name}'");
+    }
+
+    #[tokio::test]
+    async fn test_placeholders_are_quoted() {
+        let dir = std::env::temp_dir().join(format!(
+            "rrepos-runner-quote-test-{}/my repo; echo $(id)",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut repo = Repository::new(
+            "app".to_string(),
+            "git@github.com:owner/app.git".to_string(),
+        );
+        repo.path = Some(dir.to_string_lossy().to_string());
+
+        let output = CommandRunner::new()
+            .run_command_captured(&repo, "printf '%s|' {path} \"${{path}}\"", None)
+            .await
+            .unwrap();
+        std::fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+
+        let printed: Vec<&str> = output.lines.iter().map(|l| l.text.as_str()).collect();
+        assert_eq!(printed, vec![format!("{}||", dir.display())]);
+    }
+
+    #[tokio::test]
+    async fn test_subdir() {
+        let dir =
+            std::env::temp_dir().join(format!("rrepos-runner-cwd-test-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("backend")).unwrap();
+        let dir = dir.canonicalize().unwrap();
+        let mut repo = Repository::new(
+            "app".to_string(),
+            "git@github.com:owner/app.git".to_string(),
+        );
+        repo.path = Some(dir.to_string_lossy().to_string());
+
+        let pwd = |subdir: &str, missing| {
+            let runner = CommandRunner::new().with_subdir(subdir.to_string(), missing);
+            let repo = repo.clone();
+            async move {
+                let output = runner
+                    .run_command_captured(&repo, "pwd", None)
+                    .await
+                    .unwrap();
+                output.lines.into_iter().map(|l| l.text).collect::<Vec<_>>()
+            }
+        };
+        let backend = pwd("backend", MissingSubdir::Skip).await;
+        let skipped = pwd("web", MissingSubdir::Skip).await;
+        let root = pwd("web", MissingSubdir::Root).await;
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(backend, vec![dir.join("backend").display().to_string()]);
+        assert!(skipped.is_empty());
+        assert_eq!(root, vec![dir.display().to_string()]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_pty() {
+        let dir =
+            std::env::temp_dir().join(format!("rrepos-runner-pty-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut repo = Repository::new(
+            "app".to_string(),
+            "git@github.com:owner/app.git".to_string(),
+        );
+        repo.path = Some(dir.to_string_lossy().to_string());
+
+        let command = "test -t 1 && echo terminal || echo pipe";
+        let attached = CommandRunner::new()
+            .with_pty(true)
+            .run_command_captured(&repo, command, None)
+            .await
+            .unwrap();
+        let piped = CommandRunner::new()
+            .run_command_captured(&repo, command, None)
+            .await
+            .unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(attached.lines[0].text, "terminal");
+        assert_eq!(piped.lines[0].text, "pipe");
+    }
+
+    #[test]
+    fn test_path_filter() {
+        let dir = std::env::temp_dir().join(format!("rrepos-runner-test-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("src/main/kotlin")).unwrap();
+        std::fs::write(dir.join("Dockerfile"), "FROM scratch\n").unwrap();
+        std::fs::write(dir.join("src/main/kotlin/App.kt"), "fun main() {}\n").unwrap();
+        git(&dir, &["init", "--quiet"]);
+        git(&dir, &["add", "."]);
+        // Untracked build output never matches
+        std::fs::create_dir_all(dir.join("build/generated")).unwrap();
+        std::fs::write(dir.join("build/generated/Stub.java"), "class Stub {}\n").unwrap();
+
+        let mut repo = Repository::new(
+            "app".to_string(),
+            "git@github.com:owner/app.git".to_string(),
+        );
+        repo.path = Some(dir.to_string_lossy().to_string());
+
+        let filter = |if_exists: &[&str], if_glob: &[&str]| PathFilter {
+            if_exists: if_exists.iter().map(|s| s.to_string()).collect(),
+            if_glob: if_glob.iter().map(|s| s.to_string()).collect(),
+        };
+        assert_eq!(filter(&["Dockerfile"], &["src/**/*.kt"]).unmet(&repo), None);
+        assert_eq!(
+            filter(&["pom.xml"], &[]).unmet(&repo),
+            Some("'pom.xml' does not exist".to_string())
+        );
+        assert_eq!(
+            filter(&[], &["*.kt"]).unmet(&repo),
+            Some("no files match '*.kt'".to_string())
+        );
+        assert_eq!(
+            filter(&[], &["build/**/*.java"]).unmet(&repo),
+            Some("no files match 'build/**/*.java'".to_string())
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
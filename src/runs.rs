@@ -0,0 +1,274 @@
+//! Run IDs and the history of past invocations, used to correlate the logs,
+//! reports, notifications and state file of a run
+
+use crate::state::{RepoStatus, RunState};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// Location of the run history, relative to the config file's directory
+pub const RUNS_DIR: &str = ".rrepos/runs";
+
+/// Commands recorded in the run history: those that change repositories,
+/// clones or hosts, or run commands in them. Queries are not recorded.
+const RECORDED_COMMANDS: &[&str] = &[
+    "actions",
+    "admin",
+    "bench",
+    "bump",
+    "campaign",
+    "cherry-pick",
+    "ci",
+    "clone",
+    "codeowners",
+    "consolidate",
+    "coverage",
+    "dependabot",
+    "devfiles",
+    "docker",
+    "extract",
+    "git-config",
+    "hooks",
+    "maintenance",
+    "mirror",
+    "move",
+    "new",
+    "pr",
+    "rebase",
+    "relayout",
+    "rename",
+    "replace",
+    "rm",
+    "run",
+    "scan",
+    "settings",
+    "sync",
+    "sync-files",
+    "tag",
+    "task",
+    "test",
+];
+
+/// Whether invocations of `command` are recorded in the run history
+pub fn is_recorded(command: &str) -> bool {
+    RECORDED_COMMANDS.contains(&command)
+}
+
+/// Run history kept next to the config file in `config_dir`
+pub fn history_dir(config_dir: Option<&Path>) -> PathBuf {
+    config_dir.unwrap_or(Path::new("")).join(RUNS_DIR)
+}
+
+/// Number of runs kept in the history; older ones are removed
+const MAX_RUNS: usize = 200;
+
+static RUN_ID: OnceLock<String> = OnceLock::new();
+static ARTIFACTS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// ID of the current invocation, e.g. `20240501-093012-5f1c2a9b`. IDs sort
+/// by start time.
+pub fn id() -> &'static str {
+    RUN_ID.get_or_init(|| {
+        let suffix = uuid::Uuid::new_v4().simple().to_string();
+        format!("{}-{}", Utc::now().format("%Y%m%d-%H%M%S"), &suffix[..8])
+    })
+}
+
+/// Remember a file written by the current invocation, such as a log or report
+pub fn record_artifact(path: &Path) {
+    ARTIFACTS
+        .lock()
+        .unwrap()
+        .push(path.to_string_lossy().to_string());
+}
+
+/// Outcome of an invocation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunStatus {
+    Success,
+    Failure,
+}
+
+/// A past invocation in the run history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub id: String,
+    /// Subcommand, e.g. `run` or `clone`
+    pub command: String,
+    /// Full command line
+    pub args: Vec<String>,
+    pub started_at: String,
+    pub finished_at: String,
+    pub status: RunStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Logs and reports written by the run
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artifacts: Vec<String>,
+    /// Per-repository outcomes, for commands that keep a state file
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub repositories: BTreeMap<String, RepoStatus>,
+}
+
+impl RunRecord {
+    /// Record the current invocation as finished with `result`, with the
+    /// repository outcomes it left in `state_file`
+    pub fn finish(
+        command: &str,
+        args: Vec<String>,
+        started_at: String,
+        result: &Result<()>,
+        state_file: &Path,
+    ) -> Self {
+        let repositories = RunState::load(state_file)
+            .ok()
+            .filter(|state| state.run_id == id())
+            .map(|state| state.repositories)
+            .unwrap_or_default();
+        Self {
+            id: id().to_string(),
+            command: command.to_string(),
+            args,
+            started_at,
+            finished_at: Utc::now().to_rfc3339(),
+            status: if result.is_ok() {
+                RunStatus::Success
+            } else {
+                RunStatus::Failure
+            },
+            error: result.as_ref().err().map(|e| e.to_string()),
+            artifacts: std::mem::take(&mut *ARTIFACTS.lock().unwrap()),
+            repositories,
+        }
+    }
+
+    /// Write the record to the history in `dir`, removing the oldest runs
+    /// beyond the history limit
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(
+            dir.join(format!("{}.json", self.id)),
+            serde_json::to_string_pretty(self)?,
+        )?;
+
+        let mut ids = record_ids(dir)?;
+        if ids.len() > MAX_RUNS {
+            ids.sort();
+            for id in &ids[..ids.len() - MAX_RUNS] {
+                std::fs::remove_file(dir.join(format!("{id}.json")))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Load the run whose ID is or starts with `id`
+    pub fn load(dir: &Path, id: &str) -> Result<Self> {
+        let matches: Vec<String> = record_ids(dir)?
+            .into_iter()
+            .filter(|candidate| candidate.starts_with(id))
+            .collect();
+        let id = match matches.as_slice() {
+            [id] => id,
+            [] => anyhow::bail!("No run with ID '{}'", id),
+            _ => anyhow::bail!(
+                "Run ID '{}' is ambiguous, matching {} runs",
+                id,
+                matches.len()
+            ),
+        };
+        let path = dir.join(format!("{id}.json"));
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read run '{}'", path.display()))?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// All runs in the history, most recent first
+    pub fn list(dir: &Path) -> Result<Vec<Self>> {
+        let mut ids = record_ids(dir)?;
+        ids.sort();
+        ids.into_iter()
+            .rev()
+            .map(|id| Self::load(dir, &id))
+            .collect()
+    }
+}
+
+/// IDs of the runs in the history
+fn record_ids(dir: &Path) -> Result<Vec<String>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut ids = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "json")
+            && let Some(stem) = path.file_stem()
+        {
+            ids.push(stem.to_string_lossy().to_string());
+        }
+    }
+    Ok(ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history() {
+        let dir = std::env::temp_dir().join(format!("rrepos-runs-test-{}", std::process::id()));
+        let record = |id: &str| RunRecord {
+            id: id.to_string(),
+            command: "run".to_string(),
+            args: vec!["rrepos".to_string(), "run".to_string()],
+            started_at: String::new(),
+            finished_at: String::new(),
+            status: RunStatus::Success,
+            error: None,
+            artifacts: Vec::new(),
+            repositories: BTreeMap::new(),
+        };
+        record("20240501-090000-aaaa0000").save(&dir).unwrap();
+        record("20240502-090000-bbbb0000").save(&dir).unwrap();
+        record("20240502-100000-cccc0000").save(&dir).unwrap();
+
+        let ids: Vec<String> = RunRecord::list(&dir)
+            .unwrap()
+            .into_iter()
+            .map(|run| run.id)
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                "20240502-100000-cccc0000",
+                "20240502-090000-bbbb0000",
+                "20240501-090000-aaaa0000"
+            ]
+        );
+        assert_eq!(
+            RunRecord::load(&dir, "20240501").unwrap().id,
+            "20240501-090000-aaaa0000"
+        );
+        assert!(RunRecord::load(&dir, "20240502").is_err());
+        assert!(RunRecord::load(&dir, "2023").is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_recorded_commands() {
+        assert!(is_recorded("run"));
+        assert!(is_recorded("sync"));
+        assert!(!is_recorded("list"));
+        assert!(!is_recorded("runs"));
+        assert_eq!(
+            history_dir(Some(Path::new("/home/me/repos"))),
+            Path::new("/home/me/repos/.rrepos/runs")
+        );
+        assert_eq!(history_dir(None), Path::new(RUNS_DIR));
+    }
+}
@@ -0,0 +1,390 @@
+//! Sandboxed execution of untrusted repository commands: no network access,
+//! and writes limited to the repository and the temporary directory. The
+//! repository's `.git` stays read-only, so a command can't plant hooks or
+//! configuration that later git operations would run outside the sandbox.
+//!
+//! On Linux the command gets its own network and mount namespaces
+//! (`unshare`), with `.git` bind-mounted read-only, and a Landlock ruleset;
+//! on macOS it runs under `sandbox-exec`. Reads are not restricted, so
+//! toolchains installed elsewhere keep working.
+
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Directories a sandboxed command may write to
+fn writable_paths(dir: &Path) -> Vec<PathBuf> {
+    let dir = std::path::absolute(dir).unwrap_or_else(|_| dir.to_path_buf());
+    vec![dir, std::env::temp_dir(), PathBuf::from("/dev")]
+}
+
+/// The repository's `.git`, kept read-only in the sandbox
+fn git_dir(dir: &Path) -> Option<PathBuf> {
+    let git_dir = std::path::absolute(dir.join(".git")).ok()?;
+    git_dir.exists().then_some(git_dir)
+}
+
+/// Set up `process` to run sandboxed, allowed to write to `dir`
+#[cfg(target_os = "linux")]
+pub fn sandbox(mut process: Command, dir: &Path) -> Result<Command> {
+    use std::os::unix::process::CommandExt;
+
+    let access = landlock::handled_access()?;
+    let paths = landlock::c_paths(&writable_paths(dir))?;
+    let git_dir = landlock::c_paths(&git_dir(dir).into_iter().collect::<Vec<_>>())?;
+    let id_maps = namespace::IdMaps::current();
+
+    // SAFETY: the closure only makes system calls on data prepared before
+    // forking and does not allocate
+    unsafe {
+        process.pre_exec(move || {
+            namespace::isolate(&id_maps)?;
+            if let Some(git_dir) = git_dir.first() {
+                namespace::mount_read_only(git_dir)?;
+            }
+            landlock::restrict_writes(access, &paths)
+        });
+    }
+    Ok(process)
+}
+
+/// Set up `process` to run sandboxed, allowed to write to `dir`
+#[cfg(target_os = "macos")]
+pub fn sandbox(process: Command, dir: &Path) -> Result<Command> {
+    let subpath = |path: &Path| {
+        // The temporary directory usually sits behind the /var symlink
+        let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        format!(
+            " (subpath \"{}\")",
+            path.display().to_string().replace('"', "\\\"")
+        )
+    };
+    let rules: String = writable_paths(dir)
+        .iter()
+        .map(|path| subpath(path))
+        .collect();
+    // Later rules take precedence
+    let git_rule = git_dir(dir)
+        .map(|git_dir| format!(" (deny file-write*{})", subpath(&git_dir)))
+        .unwrap_or_default();
+    let profile = format!(
+        "(version 1) (allow default) (deny network*) (deny file-write*) (allow file-write*{rules}){git_rule}"
+    );
+
+    let mut sandboxed = Command::new("sandbox-exec");
+    sandboxed
+        .arg("-p")
+        .arg(profile)
+        .arg(process.get_program())
+        .args(process.get_args());
+    if let Some(dir) = process.get_current_dir() {
+        sandboxed.current_dir(dir);
+    }
+    for (key, value) in process.get_envs() {
+        match value {
+            Some(value) => sandboxed.env(key, value),
+            None => sandboxed.env_remove(key),
+        };
+    }
+    Ok(sandboxed)
+}
+
+/// Set up `process` to run sandboxed, allowed to write to `dir`
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn sandbox(_process: Command, _dir: &Path) -> Result<Command> {
+    anyhow::bail!("--sandbox is only supported on Linux and macOS")
+}
+
+#[cfg(target_os = "linux")]
+mod namespace {
+    use std::ffi::CString;
+    use std::io;
+
+    /// Identity mappings written when an unprivileged user needs a user
+    /// namespace to get a network namespace
+    pub struct IdMaps {
+        uid_map: CString,
+        gid_map: CString,
+    }
+
+    impl IdMaps {
+        pub fn current() -> Self {
+            // SAFETY: getuid and getgid always succeed
+            let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+            Self {
+                uid_map: CString::new(format!("{uid} {uid} 1")).unwrap_or_default(),
+                gid_map: CString::new(format!("{gid} {gid} 1")).unwrap_or_default(),
+            }
+        }
+    }
+
+    fn check(result: libc::c_int) -> io::Result<()> {
+        if result < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `path` must be NUL-terminated.
+    unsafe fn write_file(path: &[u8], content: &[u8]) -> io::Result<()> {
+        // SAFETY: upheld by the caller; `content` is a valid buffer
+        unsafe {
+            let fd = libc::open(path.as_ptr().cast(), libc::O_WRONLY | libc::O_CLOEXEC);
+            check(fd)?;
+            let written = libc::write(fd, content.as_ptr().cast(), content.len());
+            libc::close(fd);
+            if written < 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
+
+    /// Move the calling process into a network namespace of its own, which
+    /// only has a loopback interface that is down, and a mount namespace of
+    /// its own
+    pub fn isolate(id_maps: &IdMaps) -> io::Result<()> {
+        let namespaces = libc::CLONE_NEWNET | libc::CLONE_NEWNS;
+        // SAFETY: unshare only affects the calling (forked) process
+        if unsafe { libc::unshare(namespaces) } == 0 {
+            return Ok(());
+        }
+        // Without privileges, a user namespace grants them for the new
+        // namespaces
+        // SAFETY: as above; the paths are NUL-terminated literals
+        unsafe {
+            check(libc::unshare(libc::CLONE_NEWUSER | namespaces))?;
+            write_file(b"/proc/self/setgroups\0", b"deny")?;
+            write_file(b"/proc/self/uid_map\0", id_maps.uid_map.as_bytes())?;
+            write_file(b"/proc/self/gid_map\0", id_maps.gid_map.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Bind-mount `path` read-only over itself, visible only in the calling
+    /// process's mount namespace
+    pub fn mount_read_only(path: &CString) -> io::Result<()> {
+        let none = std::ptr::null::<libc::c_char>();
+        // SAFETY: the paths are NUL-terminated and outlive the calls, and
+        // `stat` is only read after statvfs filled it in
+        unsafe {
+            // Keep the mounts from propagating back to the parent namespace
+            check(libc::mount(
+                none,
+                c"/".as_ptr(),
+                none,
+                libc::MS_REC | libc::MS_PRIVATE,
+                std::ptr::null(),
+            ))?;
+            check(libc::mount(
+                path.as_ptr(),
+                path.as_ptr(),
+                none,
+                libc::MS_BIND | libc::MS_REC,
+                std::ptr::null(),
+            ))?;
+            // A remount in a user namespace must keep the locked flags of
+            // the original mount
+            let mut stat: libc::statvfs = std::mem::zeroed();
+            check(libc::statvfs(path.as_ptr(), &mut stat))?;
+            let mut flags = libc::MS_BIND | libc::MS_REMOUNT | libc::MS_RDONLY;
+            for (st, ms) in [
+                (libc::ST_NOSUID, libc::MS_NOSUID),
+                (libc::ST_NODEV, libc::MS_NODEV),
+                (libc::ST_NOEXEC, libc::MS_NOEXEC),
+                (libc::ST_NOATIME, libc::MS_NOATIME),
+                (libc::ST_NODIRATIME, libc::MS_NODIRATIME),
+                (libc::ST_RELATIME, libc::MS_RELATIME),
+            ] {
+                if stat.f_flag & st != 0 {
+                    flags |= ms;
+                }
+            }
+            check(libc::mount(
+                none,
+                path.as_ptr(),
+                none,
+                flags,
+                std::ptr::null(),
+            ))
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod landlock {
+    use anyhow::Result;
+    use std::ffi::CString;
+    use std::io;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::PathBuf;
+
+    const CREATE_RULESET_VERSION: libc::c_uint = 1;
+    const RULE_PATH_BENEATH: libc::c_int = 1;
+
+    const ACCESS_FS_WRITE_FILE: u64 = 1 << 1;
+    const ACCESS_FS_REMOVE_DIR: u64 = 1 << 4;
+    const ACCESS_FS_REMOVE_FILE: u64 = 1 << 5;
+    const ACCESS_FS_MAKE_CHAR: u64 = 1 << 6;
+    const ACCESS_FS_MAKE_DIR: u64 = 1 << 7;
+    const ACCESS_FS_MAKE_REG: u64 = 1 << 8;
+    const ACCESS_FS_MAKE_SOCK: u64 = 1 << 9;
+    const ACCESS_FS_MAKE_FIFO: u64 = 1 << 10;
+    const ACCESS_FS_MAKE_BLOCK: u64 = 1 << 11;
+    const ACCESS_FS_MAKE_SYM: u64 = 1 << 12;
+    const ACCESS_FS_REFER: u64 = 1 << 13;
+    const ACCESS_FS_TRUNCATE: u64 = 1 << 14;
+
+    #[repr(C)]
+    struct RulesetAttr {
+        handled_access_fs: u64,
+    }
+
+    #[repr(C, packed)]
+    struct PathBeneathAttr {
+        allowed_access: u64,
+        parent_fd: i32,
+    }
+
+    /// Write accesses to restrict, as far as the kernel's Landlock ABI
+    /// supports them
+    pub fn handled_access() -> Result<u64> {
+        // SAFETY: querying the ABI version takes no attribute
+        let abi = unsafe {
+            libc::syscall(
+                libc::SYS_landlock_create_ruleset,
+                std::ptr::null::<RulesetAttr>(),
+                0usize,
+                CREATE_RULESET_VERSION,
+            )
+        };
+        if abi < 1 {
+            anyhow::bail!(
+                "--sandbox needs Landlock (Linux 5.13 or later, enabled in the kernel): {}",
+                io::Error::last_os_error()
+            );
+        }
+
+        let mut access = ACCESS_FS_WRITE_FILE
+            | ACCESS_FS_REMOVE_DIR
+            | ACCESS_FS_REMOVE_FILE
+            | ACCESS_FS_MAKE_CHAR
+            | ACCESS_FS_MAKE_DIR
+            | ACCESS_FS_MAKE_REG
+            | ACCESS_FS_MAKE_SOCK
+            | ACCESS_FS_MAKE_FIFO
+            | ACCESS_FS_MAKE_BLOCK
+            | ACCESS_FS_MAKE_SYM;
+        // Without REFER, the first ABI denies every rename across directories
+        if abi >= 2 {
+            access |= ACCESS_FS_REFER;
+        }
+        if abi >= 3 {
+            access |= ACCESS_FS_TRUNCATE;
+        }
+        Ok(access)
+    }
+
+    pub fn c_paths(paths: &[PathBuf]) -> Result<Vec<CString>> {
+        Ok(paths
+            .iter()
+            .map(|path| CString::new(path.as_os_str().as_bytes()))
+            .collect::<std::result::Result<_, _>>()?)
+    }
+
+    /// Allow the handled `access` only beneath `paths` for the calling
+    /// process and its children. Paths that don't exist are skipped.
+    pub fn restrict_writes(access: u64, paths: &[CString]) -> io::Result<()> {
+        let attr = RulesetAttr {
+            handled_access_fs: access,
+        };
+        // SAFETY: the attribute and paths outlive the calls, and every file
+        // descriptor opened here is closed again
+        unsafe {
+            let ruleset = libc::syscall(
+                libc::SYS_landlock_create_ruleset,
+                &attr as *const RulesetAttr,
+                std::mem::size_of::<RulesetAttr>(),
+                0,
+            );
+            if ruleset < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let ruleset = ruleset as libc::c_int;
+
+            for path in paths {
+                let fd = libc::open(path.as_ptr(), libc::O_PATH | libc::O_CLOEXEC);
+                if fd < 0 {
+                    continue;
+                }
+                let rule = PathBeneathAttr {
+                    allowed_access: access,
+                    parent_fd: fd,
+                };
+                let result = libc::syscall(
+                    libc::SYS_landlock_add_rule,
+                    ruleset,
+                    RULE_PATH_BENEATH,
+                    &rule as *const PathBeneathAttr,
+                    0,
+                );
+                libc::close(fd);
+                if result < 0 {
+                    libc::close(ruleset);
+                    return Err(io::Error::last_os_error());
+                }
+            }
+
+            if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) < 0
+                || libc::syscall(libc::SYS_landlock_restrict_self, ruleset, 0) < 0
+            {
+                let error = io::Error::last_os_error();
+                libc::close(ruleset);
+                return Err(error);
+            }
+            libc::close(ruleset);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sandbox_limits_writes() {
+        let pid = std::process::id();
+        let repo = std::env::temp_dir().join(format!("rrepos-sandbox-test-{pid}"));
+        std::fs::create_dir_all(repo.join(".git").join("hooks")).unwrap();
+        // Outside both the repository and the temporary directory
+        let outside = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("target")
+            .join(format!("rrepos-sandbox-test-{pid}"));
+
+        let mut process = Command::new("sh");
+        process.current_dir(&repo).arg("-c").arg(format!(
+            "echo ok > inside; echo x > .git/hooks/pre-commit; echo x > '{}'; true",
+            outside.display()
+        ));
+        // Landlock isn't available on every kernel
+        let Ok(mut process) = sandbox(process, &repo) else {
+            std::fs::remove_dir_all(&repo).unwrap();
+            return;
+        };
+        let status = process.status().unwrap();
+
+        let written = (
+            repo.join("inside").exists(),
+            repo.join(".git/hooks/pre-commit").exists(),
+            outside.exists(),
+        );
+        std::fs::remove_dir_all(&repo).unwrap();
+        let _ = std::fs::remove_file(&outside);
+        assert!(status.success());
+        assert_eq!(written, (true, false, false));
+    }
+}
@@ -0,0 +1,197 @@
+//! Minimal SARIF 2.1.0 documents for code-scanning dashboards
+
+use crate::runner::CommandOutput;
+use crate::runs;
+use regex::Regex;
+use serde_json::{Value, json};
+use std::sync::OnceLock;
+
+pub const SARIF_VERSION: &str = "2.1.0";
+pub const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// A SARIF log containing `runs`, each tagged with the current run ID
+pub fn log(mut runs: Vec<Value>) -> Value {
+    for run in &mut runs {
+        if let Value::Object(run) = run {
+            run.entry("automationDetails")
+                .or_insert_with(|| json!({ "id": format!("rrepos/{}", runs::id()) }));
+        }
+    }
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": SARIF_VERSION,
+        "runs": runs,
+    })
+}
+
+/// A run of `tool`, with a rule for every distinct `ruleId` in `results`
+pub fn run(tool: &str, results: Vec<Value>) -> Value {
+    let mut rule_ids: Vec<&str> = results
+        .iter()
+        .filter_map(|result| result["ruleId"].as_str())
+        .collect();
+    rule_ids.sort();
+    rule_ids.dedup();
+    let rules: Vec<Value> = rule_ids.iter().map(|id| json!({ "id": id })).collect();
+
+    json!({
+        "tool": { "driver": { "name": tool, "rules": rules } },
+        "results": results,
+    })
+}
+
+/// A result reported against a file, `uri` being relative to the fleet root
+pub fn result(rule_id: &str, level: &str, message: &str, uri: &str) -> Value {
+    json!({
+        "ruleId": rule_id,
+        "level": level,
+        "message": { "text": message },
+        "locations": [{
+            "physicalLocation": { "artifactLocation": { "uri": uri } }
+        }],
+    })
+}
+
+/// Build SARIF runs from the output of a linter run in `repository`.
+///
+/// Output that is already a SARIF log is used as is; otherwise compiler-style
+/// `file:line[:column]: message` diagnostics are converted. File locations are
+/// prefixed with the repository name so runs from many repositories can share
+/// one log.
+pub fn runs_from_output(tool: &str, repository: &str, output: &CommandOutput) -> Vec<Value> {
+    let stdout: Vec<&str> = output
+        .lines
+        .iter()
+        .filter(|line| !line.stderr)
+        .map(|line| line.text.as_str())
+        .collect();
+
+    let mut runs = match serde_json::from_str::<Value>(&stdout.join("\n")) {
+        Ok(Value::Object(mut log)) if log.get("runs").is_some_and(Value::is_array) => {
+            match log.remove("runs") {
+                Some(Value::Array(runs)) => runs,
+                _ => Vec::new(),
+            }
+        }
+        _ => {
+            let results: Vec<Value> = output
+                .lines
+                .iter()
+                .filter_map(|line| diagnostic(&line.text))
+                .collect();
+            if results.is_empty() {
+                return Vec::new();
+            }
+            vec![run(tool, results)]
+        }
+    };
+
+    for run in &mut runs {
+        prefix_locations(run, repository);
+        run["properties"]["repository"] = json!(repository);
+    }
+    runs
+}
+
+/// Convert a `file:line[:column]: [level:] message [rule]` diagnostic
+fn diagnostic(line: &str) -> Option<Value> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let pattern = PATTERN.get_or_init(|| {
+        Regex::new(
+            r"^(?P<file>[^\s:][^:]*):(?P<line>\d+):(?:(?P<column>\d+):)?\s*(?:(?P<level>error|warning|note|info)[^:]*:\s*)?(?P<message>.+?)(?:\s+\[(?P<rule>[^\]]+)\])?$",
+        )
+        .unwrap()
+    });
+
+    let caps = pattern.captures(line.trim())?;
+    let level = match caps.name("level").map(|m| m.as_str()) {
+        Some("error") => "error",
+        Some("note") | Some("info") => "note",
+        _ => "warning",
+    };
+    let rule = caps.name("rule").map_or("diagnostic", |m| m.as_str());
+
+    let mut value = result(rule, level, &caps["message"], &caps["file"]);
+    let mut region = json!({ "startLine": caps["line"].parse::<u64>().ok()? });
+    if let Some(column) = caps.name("column") {
+        region["startColumn"] = json!(column.as_str().parse::<u64>().ok()?);
+    }
+    value["locations"][0]["physicalLocation"]["region"] = region;
+    Some(value)
+}
+
+/// Prefix every relative artifact location in a run with `prefix/`
+fn prefix_locations(run: &mut Value, prefix: &str) {
+    let Some(results) = run["results"].as_array_mut() else {
+        return;
+    };
+    for location in results
+        .iter_mut()
+        .filter_map(|result| result["locations"].as_array_mut())
+        .flatten()
+    {
+        let uri = &mut location["physicalLocation"]["artifactLocation"]["uri"];
+        if let Some(path) = uri.as_str()
+            && !path.contains("://")
+            && !path.starts_with('/')
+        {
+            *uri = json!(format!("{prefix}/{}", path.trim_start_matches("./")));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::OutputLine;
+
+    fn output(lines: &[&str]) -> CommandOutput {
+        CommandOutput {
+            lines: lines
+                .iter()
+                .map(|text| OutputLine {
+                    stderr: false,
+                    text: text.to_string(),
+                })
+                .collect(),
+            exit_code: 1,
+        }
+    }
+
+    #[test]
+    fn test_runs_from_diagnostics() {
+        let runs = runs_from_output(
+            "shellcheck",
+            "api",
+            &output(&[
+                "deploy.sh:3:7: warning: foo appears unused [SC2034]",
+                "Checked 1 file",
+            ]),
+        );
+        assert_eq!(runs.len(), 1);
+        let result = &runs[0]["results"][0];
+        assert_eq!(result["ruleId"], "SC2034");
+        assert_eq!(result["level"], "warning");
+        assert_eq!(result["message"]["text"], "foo appears unused");
+        let location = &result["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "api/deploy.sh");
+        assert_eq!(location["region"]["startLine"], 3);
+        assert_eq!(location["region"]["startColumn"], 7);
+    }
+
+    #[test]
+    fn test_runs_from_sarif_output() {
+        let sarif = log(vec![run(
+            "eslint",
+            vec![result("no-unused-vars", "error", "unused", "./src/a.js")],
+        )])
+        .to_string();
+        let runs = runs_from_output("npx", "web", &output(&[&sarif]));
+        assert_eq!(runs[0]["tool"]["driver"]["name"], "eslint");
+        assert_eq!(
+            runs[0]["results"][0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "web/src/a.js"
+        );
+        assert_eq!(runs[0]["properties"]["repository"], "web");
+    }
+}
@@ -0,0 +1,126 @@
+//! Scheduling of parallel work across repositories, capped per host and in
+//! total
+
+use crate::config::{Config, Repository};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, OnceLock};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinHandle;
+
+/// Cap on repositories processed at once by parallel commands, from the
+/// `jobs` user setting; unset means no cap
+static JOBS: OnceLock<Arc<Semaphore>> = OnceLock::new();
+
+/// Cap how many repositories parallel commands process at once
+pub fn set_max_jobs(jobs: usize) {
+    let _ = JOBS.set(Arc::new(Semaphore::new(jobs.max(1))));
+}
+
+/// Wait for a free job slot; `None` when jobs aren't capped
+pub async fn job_slot() -> Option<OwnedSemaphorePermit> {
+    let semaphore = JOBS.get()?.clone();
+    semaphore.acquire_owned().await.ok()
+}
+
+/// Run `task` once a job slot is free
+pub async fn limited<F: Future>(task: F) -> F::Output {
+    let _slot = job_slot().await;
+    task.await
+}
+
+/// Like `tokio::task::spawn_blocking`, starting `task` once a job slot is free
+pub fn spawn_blocking<F, T>(task: F) -> JoinHandle<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::spawn(async move {
+        let _slot = job_slot().await;
+        match tokio::task::spawn_blocking(task).await {
+            Ok(output) => output,
+            Err(e) => std::panic::resume_unwind(e.into_panic()),
+        }
+    })
+}
+
+/// Limits how many repositories of each host are processed at once, from
+/// the hosts' `max_concurrent` settings. A clone of a limiter holds the
+/// same semaphores, so tasks holding different clones still count against
+/// one limit per host.
+#[derive(Debug, Clone, Default)]
+pub struct HostLimiter {
+    hosts: HashMap<String, Arc<Semaphore>>,
+}
+
+impl HostLimiter {
+    /// Build the limits of the hosts the repositories point at
+    pub fn new(config: &Config, repos: &[Repository]) -> Self {
+        let mut hosts = HashMap::new();
+        for repo in repos {
+            let limit = repo.host_settings(config).max_concurrent;
+            if let (Some(host), Some(limit)) = (repo.host(), limit) {
+                hosts
+                    .entry(host.to_string())
+                    .or_insert_with(|| Arc::new(Semaphore::new(limit.max(1))));
+            }
+        }
+        Self { hosts }
+    }
+
+    /// Wait for a free slot on the repository's host; `None` when the host
+    /// has no limit
+    pub async fn acquire(&self, repo: &Repository) -> Option<OwnedSemaphorePermit> {
+        let semaphore = self.hosts.get(repo.host()?)?.clone();
+        semaphore.acquire_owned().await.ok()
+    }
+
+    /// Run `task` once a slot on the repository's host and a job slot are
+    /// free
+    pub async fn run<F: Future>(&self, repo: &Repository, task: F) -> F::Output {
+        let _permit = self.acquire(repo).await;
+        limited(task).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::HostSettings;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_host_limiter() {
+        let mut config = Config::new();
+        config.hosts.insert(
+            "github.com".to_string(),
+            HostSettings {
+                max_concurrent: Some(2),
+                ..Default::default()
+            },
+        );
+        let repos: Vec<Repository> = ["a", "b", "c", "d"]
+            .iter()
+            .map(|n| Repository::new(n.to_string(), format!("git@github.com:owner/{n}.git")))
+            .collect();
+        let limiter = HostLimiter::new(&config, &repos);
+
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let tasks = repos.iter().map(|repo| {
+            limiter.run(repo, async {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+            })
+        });
+        futures::future::join_all(tasks).await;
+
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+
+        let unlimited = Repository::new("e".to_string(), "git@gitlab.com:owner/e.git".to_string());
+        assert!(limiter.acquire(&unlimited).await.is_none());
+    }
+}
@@ -0,0 +1,267 @@
+//! Persisted per-repository outcomes of an invocation, used to resume runs
+
+use crate::config::Repository;
+use crate::runs;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Location of the state file, relative to the config file's directory
+pub const STATE_FILE: &str = ".rrepos/state.json";
+
+/// State file kept next to the config file in `config_dir`
+pub fn state_file(config_dir: Option<&Path>) -> PathBuf {
+    config_dir.unwrap_or(Path::new("")).join(STATE_FILE)
+}
+
+/// Which repositories to process when a previous state exists
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResumeMode {
+    /// Start over with all selected repositories
+    #[default]
+    Fresh,
+    /// Process repositories that failed or were never processed
+    Resume,
+    /// Process only repositories that failed
+    RerunFailed,
+}
+
+impl ResumeMode {
+    /// Build the mode from the `--resume` and `--rerun-failed` flags
+    pub fn from_flags(resume: bool, rerun_failed: bool) -> Self {
+        match (resume, rerun_failed) {
+            (_, true) => ResumeMode::RerunFailed,
+            (true, false) => ResumeMode::Resume,
+            (false, false) => ResumeMode::Fresh,
+        }
+    }
+}
+
+/// Outcome of a repository within a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RepoStatus {
+    Pending,
+    Succeeded,
+    Failed,
+}
+
+/// Contents of the state file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunState {
+    /// Operation that produced the state, e.g. `run` or `clone`
+    pub operation: String,
+    /// Identifies the invocation, e.g. the command being run
+    pub fingerprint: String,
+    /// ID of the run that last updated the state
+    #[serde(default)]
+    pub run_id: String,
+    pub started_at: String,
+    pub updated_at: String,
+    pub repositories: BTreeMap<String, RepoStatus>,
+    /// Commit each repository was at when the invocation started, recorded
+    /// to detect repositories that changed since
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub heads: BTreeMap<String, String>,
+}
+
+impl RunState {
+    /// Create a new state with all repositories pending
+    pub fn new(operation: &str, fingerprint: &str, repos: &[Repository]) -> Self {
+        let now = Utc::now().to_rfc3339();
+        Self {
+            operation: operation.to_string(),
+            fingerprint: fingerprint.to_string(),
+            run_id: runs::id().to_string(),
+            started_at: now.clone(),
+            updated_at: now,
+            repositories: repos
+                .iter()
+                .map(|r| (r.name.clone(), RepoStatus::Pending))
+                .collect(),
+            heads: BTreeMap::new(),
+        }
+    }
+
+    /// Whether the repository succeeded at the given commit
+    pub fn succeeded_at(&self, name: &str, head: &str) -> bool {
+        self.repositories.get(name) == Some(&RepoStatus::Succeeded)
+            && self
+                .heads
+                .get(name)
+                .is_some_and(|recorded| recorded == head)
+    }
+
+    /// Load a state file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read state file '{}'", path.display()))?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Write the state file, creating its directory if needed
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Select the repositories that still need processing
+    pub fn select(&self, repos: Vec<Repository>, mode: ResumeMode) -> Vec<Repository> {
+        repos
+            .into_iter()
+            .filter(|repo| match (mode, self.repositories.get(&repo.name)) {
+                (ResumeMode::Fresh, _) => true,
+                (ResumeMode::Resume, Some(RepoStatus::Succeeded)) => false,
+                (ResumeMode::Resume, _) => true,
+                (ResumeMode::RerunFailed, status) => status == Some(&RepoStatus::Failed),
+            })
+            .collect()
+    }
+}
+
+/// Records repository outcomes to the state file as they happen
+pub struct StateTracker {
+    path: PathBuf,
+    state: Mutex<RunState>,
+}
+
+impl StateTracker {
+    /// Start tracking an operation, returning the repositories to process.
+    ///
+    /// When resuming, the previous state must belong to the same operation
+    /// and fingerprint.
+    pub fn start(
+        path: PathBuf,
+        operation: &str,
+        fingerprint: &str,
+        repos: Vec<Repository>,
+        mode: ResumeMode,
+    ) -> Result<(Self, Vec<Repository>)> {
+        let (state, repos) = if mode == ResumeMode::Fresh {
+            (RunState::new(operation, fingerprint, &repos), repos)
+        } else {
+            let mut state = RunState::load(&path)?;
+            if state.operation != operation || state.fingerprint != fingerprint {
+                anyhow::bail!(
+                    "State file belongs to a different invocation ({} '{}'); run without --resume/--rerun-failed to start over",
+                    state.operation,
+                    state.fingerprint
+                );
+            }
+            state.run_id = runs::id().to_string();
+            for repo in &repos {
+                state
+                    .repositories
+                    .entry(repo.name.clone())
+                    .or_insert(RepoStatus::Pending);
+            }
+            let selected = state.select(repos, mode);
+            (state, selected)
+        };
+
+        state.save(&path)?;
+        Ok((
+            Self {
+                path,
+                state: Mutex::new(state),
+            },
+            repos,
+        ))
+    }
+
+    /// Record the outcome of a repository
+    pub fn record(&self, name: &str, succeeded: bool) {
+        let mut state = self.state.lock().unwrap();
+        let status = if succeeded {
+            RepoStatus::Succeeded
+        } else {
+            RepoStatus::Failed
+        };
+        state.repositories.insert(name.to_string(), status);
+        state.updated_at = Utc::now().to_rfc3339();
+
+        if let Err(e) = state.save(&self.path) {
+            eprintln!("{}", format!("Failed to update state file: {e}").yellow());
+        }
+    }
+
+    /// Record the commit a repository is at
+    pub fn record_head(&self, name: &str, head: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.heads.insert(name.to_string(), head.to_string());
+
+        if let Err(e) = state.save(&self.path) {
+            eprintln!("{}", format!("Failed to update state file: {e}").yellow());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repos(names: &[&str]) -> Vec<Repository> {
+        names
+            .iter()
+            .map(|n| Repository::new(n.to_string(), format!("git@github.com:owner/{n}.git")))
+            .collect()
+    }
+
+    fn names(repos: &[Repository]) -> Vec<&str> {
+        repos.iter().map(|r| r.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_select_by_mode() {
+        let all = repos(&["ok", "failed", "pending", "new"]);
+        let mut state = RunState::new("run", "make", &all[..3]);
+        state
+            .repositories
+            .insert("ok".to_string(), RepoStatus::Succeeded);
+        state
+            .repositories
+            .insert("failed".to_string(), RepoStatus::Failed);
+
+        assert_eq!(
+            names(&state.select(all.clone(), ResumeMode::Fresh)).len(),
+            4
+        );
+        assert_eq!(
+            names(&state.select(all.clone(), ResumeMode::Resume)),
+            vec!["failed", "pending", "new"]
+        );
+        assert_eq!(
+            names(&state.select(all, ResumeMode::RerunFailed)),
+            vec!["failed"]
+        );
+    }
+
+    #[test]
+    fn test_succeeded_at() {
+        let all = repos(&["ok", "failed"]);
+        let mut state = RunState::new("run", "make", &all);
+        state
+            .repositories
+            .insert("ok".to_string(), RepoStatus::Succeeded);
+        state.heads.insert("ok".to_string(), "abc".to_string());
+        state.heads.insert("failed".to_string(), "def".to_string());
+
+        assert!(state.succeeded_at("ok", "abc"));
+        assert!(!state.succeeded_at("ok", "123"));
+        assert!(!state.succeeded_at("failed", "def"));
+    }
+
+    #[test]
+    fn test_resume_mode_from_flags() {
+        assert_eq!(ResumeMode::from_flags(false, false), ResumeMode::Fresh);
+        assert_eq!(ResumeMode::from_flags(true, false), ResumeMode::Resume);
+        assert_eq!(ResumeMode::from_flags(false, true), ResumeMode::RerunFailed);
+    }
+}
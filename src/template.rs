@@ -0,0 +1,188 @@
+//! Simple `{placeholder}` rendering with per-repository variables, and
+//! Jinja templates for user-supplied pull request bodies and reports
+
+use crate::config::Repository;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Names of the standard per-repository placeholders
+pub const PLACEHOLDERS: &[&str] = &["name", "url", "path", "branch", "tags"];
+
+/// Build the standard set of template variables for a repository
+pub fn repository_variables(repo: &Repository) -> HashMap<String, String> {
+    let mut vars = HashMap::new();
+    vars.insert("name".to_string(), repo.name.clone());
+    vars.insert("url".to_string(), repo.url.clone());
+    vars.insert("path".to_string(), repo.get_target_dir());
+    vars.insert(
+        "branch".to_string(),
+        repo.branch.clone().unwrap_or_default(),
+    );
+    vars.insert("tags".to_string(), repo.tags.join(","));
+    vars
+}
+
+/// Replace `{key}` placeholders with values from `vars`.
+/// Unknown placeholders are left untouched.
+pub fn render(template: &str, vars: &HashMap<String, String>) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+
+        match after.find('}') {
+            Some(end) if vars.contains_key(&after[..end]) => {
+                result.push_str(&vars[&after[..end]]);
+                rest = &after[end + 1..];
+            }
+            _ => {
+                result.push('{');
+                rest = after;
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Replace `{key}` placeholders in a command line with values passed through
+/// `quote`, so a value with spaces or shell syntax stays a single argument.
+/// A doubled `{{key}}` is kept as a literal `{key}`, e.g. `${{name}}` for a
+/// shell variable called `name`.
+pub fn render_command(
+    command: &str,
+    vars: &HashMap<String, String>,
+    quote: impl Fn(&str) -> String,
+) -> String {
+    let mut result = String::with_capacity(command.len());
+    let mut rest = command;
+
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+
+        if let Some(inner) = after.strip_prefix('{')
+            && let Some(end) = inner.find("}}")
+            && vars.contains_key(&inner[..end])
+        {
+            result.push_str(&after[..end + 2]);
+            rest = &inner[end + 2..];
+            continue;
+        }
+        match after.find('}') {
+            Some(end) if vars.contains_key(&after[..end]) => {
+                result.push_str(&quote(&vars[&after[..end]]));
+                rest = &after[end + 1..];
+            }
+            _ => {
+                result.push('{');
+                rest = after;
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Render a Jinja template, e.g. `{{ repo.name }}` or
+/// `{% for name, status in repositories|items %}`, with `context`.
+/// Undefined values render as empty.
+pub fn render_jinja(template: &str, context: impl Serialize) -> Result<String> {
+    minijinja::Environment::new()
+        .render_str(template, context)
+        .map_err(|e| anyhow::anyhow!("Failed to render template: {e}"))
+}
+
+/// Render the Jinja template in a file with `context`
+pub fn render_file(path: &Path, context: impl Serialize) -> Result<String> {
+    let template = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read template '{}'", path.display()))?;
+    render_jinja(&template, context)
+        .with_context(|| format!("Invalid template '{}'", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_known_placeholders() {
+        let mut vars = HashMap::new();
+        vars.insert("name".to_string(), "api".to_string());
+        vars.insert("branch".to_string(), "main".to_string());
+
+        assert_eq!(
+            render("registry/{name}:{branch}", &vars),
+            "registry/api:main"
+        );
+    }
+
+    #[test]
+    fn test_render_keeps_unknown_placeholders() {
+        let mut vars = HashMap::new();
+        vars.insert("name".to_string(), "api".to_string());
+
+        assert_eq!(render("echo ${HOME} {name}", &vars), "echo ${HOME} api");
+        assert_eq!(render("{unclosed", &vars), "{unclosed");
+        assert_eq!(render("{{name}}", &vars), "{api}");
+    }
+
+    #[test]
+    fn test_render_command() {
+        let mut vars = HashMap::new();
+        vars.insert("name".to_string(), "api".to_string());
+        vars.insert("path".to_string(), "/work/my repo; rm -rf ~".to_string());
+        let quote = |value: &str| crate::util::shell_quote(value);
+
+        assert_eq!(
+            render_command("docker build -t registry/{name}:dev {path}", &vars, quote),
+            "docker build -t registry/api:dev '/work/my repo; rm -rf ~'"
+        );
+        assert_eq!(
+            render_command("name=x; echo ${{name}} {{path}} {HOME}", &vars, quote),
+            "name=x; echo ${name} {path} {HOME}"
+        );
+        assert_eq!(
+            render_command("awk '{print $1}'", &vars, quote),
+            "awk '{print $1}'"
+        );
+    }
+
+    #[test]
+    fn test_render_jinja() {
+        let context = serde_json::json!({
+            "title": "Bump serde",
+            "repositories": { "api": "succeeded", "web": "failed" },
+        });
+        assert_eq!(
+            render_jinja(
+                "{{ title }}:{% for name, status in repositories|items %} {{ name }}={{ status }}{% endfor %}{{ missing }}",
+                &context
+            )
+            .unwrap(),
+            "Bump serde: api=succeeded web=failed"
+        );
+        assert!(render_jinja("{% if %}", &context).is_err());
+    }
+
+    #[test]
+    fn test_repository_variables() {
+        let mut repo = Repository::new(
+            "api".to_string(),
+            "git@github.com:owner/api.git".to_string(),
+        );
+        repo.add_tag("backend".to_string());
+        repo.add_tag("rust".to_string());
+
+        let vars = repository_variables(&repo);
+        assert_eq!(vars["name"], "api");
+        assert_eq!(vars["branch"], "");
+        assert_eq!(vars["tags"], "backend,rust");
+    }
+}
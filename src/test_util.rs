@@ -0,0 +1,16 @@
+//! Helpers shared by unit tests
+
+use std::path::Path;
+
+/// Run git in `dir` with a fixed identity, panicking when it fails, and
+/// return its trimmed output
+pub fn git(dir: &Path, args: &[&str]) -> String {
+    let output = std::process::Command::new("git")
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {args:?} failed");
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
@@ -1,7 +1,9 @@
 //! Utility functions for repository discovery and file system operations
 
-use crate::config::Repository;
+use crate::config::{Policy, Repository};
 use anyhow::Result;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use colored::*;
 use std::path::Path;
 use walkdir::WalkDir;
 
@@ -17,10 +19,11 @@ pub fn find_git_repositories(start_path: &str) -> Result<Vec<Repository>> {
         let path = entry.path();
 
         // Check if this directory contains a .git folder
-        if path.is_dir() && path.join(".git").exists() {
-            if let Some(repo) = create_repository_from_path(path)? {
-                repositories.push(repo);
-            }
+        if path.is_dir()
+            && path.join(".git").exists()
+            && let Some(repo) = create_repository_from_path(path)?
+        {
+            repositories.push(repo);
         }
     }
 
@@ -47,7 +50,22 @@ fn create_repository_from_path(path: &Path) -> Result<Option<Repository>> {
                 tags,
                 path: Some(path.to_string_lossy().to_string()),
                 branch: None,
+                reference: None,
+                depends_on: Vec::new(),
+                archived: false,
+                default_branch: None,
+                ssh_key: None,
+                ssh_command: None,
                 config_dir: None, // Will be set when config is loaded
+                env: Default::default(),
+                provider: Default::default(),
+                branch_template: None,
+                commit_lint: None,
+                if_exists: None,
+                max_size: None,
+                visibility: None,
+                owner: None,
+                layout: Default::default(),
             };
 
             return Ok(Some(repository));
@@ -67,11 +85,11 @@ fn get_remote_url(repo_path: &Path) -> Result<Option<String>> {
         .current_dir(repo_path)
         .output();
 
-    if let Ok(output) = output {
-        if output.status.success() {
-            let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            return Ok(Some(url));
-        }
+    if let Ok(output) = output
+        && output.status.success()
+    {
+        let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        return Ok(Some(url));
     }
 
     Ok(None)
@@ -84,24 +102,11 @@ fn detect_tags_from_path(path: &Path) -> Vec<String> {
     let path_str = path.to_string_lossy().to_lowercase();
 
     // Language detection based on files
-    if path.join("go.mod").exists() || path.join("main.go").exists() {
-        tags.push("go".to_string());
-    }
-    if path.join("package.json").exists() {
-        tags.push("javascript".to_string());
-        tags.push("node".to_string());
-    }
-    if path.join("requirements.txt").exists()
-        || path.join("setup.py").exists()
-        || path.join("pyproject.toml").exists()
-    {
-        tags.push("python".to_string());
-    }
-    if path.join("pom.xml").exists() || path.join("build.gradle").exists() {
-        tags.push("java".to_string());
-    }
-    if path.join("Cargo.toml").exists() {
-        tags.push("rust".to_string());
+    for language in crate::detect::languages(path) {
+        tags.push(language.to_string());
+        if language == "javascript" {
+            tags.push("node".to_string());
+        }
     }
 
     // Type detection based on directory names
@@ -118,8 +123,328 @@ fn detect_tags_from_path(path: &Path) -> Vec<String> {
     tags
 }
 
+/// Quote a string for safe use as a single POSIX shell word
+pub fn shell_quote(value: &str) -> String {
+    if !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:@,+".contains(c))
+    {
+        return value.to_string();
+    }
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Split a command line into words, honoring single quotes, double quotes
+/// and backslash escapes the way a POSIX shell would
+pub fn split_command_line(command: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut chars = command.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => current.push(c),
+                        None => anyhow::bail!("Unterminated single quote in: {}", command),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => current.push(c),
+                            Some(c) => {
+                                current.push('\\');
+                                current.push(c);
+                            }
+                            None => anyhow::bail!("Unterminated double quote in: {}", command),
+                        },
+                        Some(c) => current.push(c),
+                        None => anyhow::bail!("Unterminated double quote in: {}", command),
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                if let Some(c) = chars.next() {
+                    current.push(c);
+                }
+            }
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            c => {
+                in_word = true;
+                current.push(c);
+            }
+        }
+    }
+
+    if in_word {
+        words.push(current);
+    }
+
+    Ok(words)
+}
+
 #[allow(dead_code)]
+/// Ask the user a yes/no question on the terminal, defaulting to no.
+///
+/// Fails when stdin is not a terminal so scripts don't block or proceed
+/// silently.
+pub fn confirm(prompt: &str) -> Result<bool> {
+    use std::io::{IsTerminal, Write};
+
+    if !std::io::stdin().is_terminal() {
+        anyhow::bail!("Confirmation required but stdin is not a terminal; use --yes to proceed");
+    }
+
+    print!("{prompt} [y/N] ");
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Ask before an operation on `count` repositories. When the policy requires
+/// approval, the user has to type the repository count; otherwise operations
+/// that normally ask (`ask`) get a y/N prompt and the rest go ahead.
+///
+/// Callers skip this entirely when `--yes` is given.
+pub fn approve(
+    policy: &Policy,
+    prompt: &str,
+    count: usize,
+    force: bool,
+    ask: bool,
+) -> Result<bool> {
+    use std::io::{IsTerminal, Write};
+
+    let Some(reason) = policy.approval_reason(count, force) else {
+        return if ask { confirm(prompt) } else { Ok(true) };
+    };
+
+    if !std::io::stdin().is_terminal() {
+        anyhow::bail!(
+            "Confirmation required ({reason}) but stdin is not a terminal; use --yes to proceed"
+        );
+    }
+
+    println!("{}", format!("Confirmation required: {reason}").yellow());
+    print!("{prompt} Type {count} to continue: ");
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(answer.trim() == count.to_string())
+}
+
+/// Move a directory, copying and deleting it when a rename is not possible
+/// (e.g. across filesystems)
+pub fn move_dir(source: &Path, destination: &Path) -> Result<()> {
+    if std::fs::rename(source, destination).is_ok() {
+        return Ok(());
+    }
+
+    for entry in WalkDir::new(source) {
+        let entry = entry?;
+        let target = destination.join(entry.path().strip_prefix(source)?);
+        if entry.file_type().is_dir() {
+            std::fs::create_dir_all(&target)?;
+        } else if entry.file_type().is_symlink() {
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(std::fs::read_link(entry.path())?, &target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    std::fs::remove_dir_all(source)?;
+    Ok(())
+}
+
+/// Parse an age such as `30d`, `2w`, `12h` or `1y` relative to `now`, or a date
+/// such as `2024-01-31`
+pub fn parse_time(value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap().and_utc());
+    }
+
+    // Split before the last character, which may take more than one byte
+    let split = value.char_indices().last().map_or(0, |(idx, _)| idx);
+    let (amount, unit) = value.split_at(split);
+    let amount: i64 = amount
+        .parse()
+        .map_err(|_| format!("Invalid age '{value}' (expected e.g. 30d, 2w, 12h, 1y or a date)"))?;
+    let age = match unit {
+        "h" => Duration::hours(amount),
+        "d" => Duration::days(amount),
+        "w" => Duration::weeks(amount),
+        "y" => Duration::days(amount * 365),
+        _ => {
+            return Err(format!(
+                "Invalid age unit in '{value}' (expected h, d, w or y)"
+            ));
+        }
+    };
+    Ok(now - age)
+}
+
+/// Parse a byte size such as `512`, `64K`, `2M` or `1G` (powers of 1024)
+pub fn parse_size(value: &str) -> Result<u64> {
+    let upper = value.trim().to_ascii_uppercase();
+    let digits = upper
+        .trim_end_matches('B')
+        .trim_end_matches('I')
+        .to_string();
+    let (number, multiplier) = match digits.chars().last() {
+        Some('K') => (&digits[..digits.len() - 1], 1u64 << 10),
+        Some('M') => (&digits[..digits.len() - 1], 1 << 20),
+        Some('G') => (&digits[..digits.len() - 1], 1 << 30),
+        Some('T') => (&digits[..digits.len() - 1], 1 << 40),
+        _ => (digits.as_str(), 1),
+    };
+    number
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(multiplier))
+        .ok_or_else(|| anyhow::anyhow!("Invalid size '{}', expected e.g. 512K, 2M or 1G", value))
+}
+
+/// Format a byte size for people, e.g. `1.5 GiB` (powers of 1024)
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64;
+    let mut unit = "B";
+    for next in UNITS {
+        if size < 1024.0 {
+            break;
+        }
+        size /= 1024.0;
+        unit = next;
+    }
+    format!("{size:.1} {unit}")
+}
+
+/// Total size of the files below a path, without following symlinks
+pub fn dir_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
 pub fn ensure_directory_exists(path: &str) -> Result<()> {
     std::fs::create_dir_all(path)?;
     Ok(())
 }
+
+/// Set the default value of every argument with the given id, recursing
+/// into subcommands
+pub fn with_arg_default(cli: clap::Command, id: &str, value: &str) -> clap::Command {
+    let subcommands: Vec<String> = cli
+        .get_subcommands()
+        .map(|sub| sub.get_name().to_string())
+        .collect();
+
+    let cli = cli.mut_args(|arg| {
+        if arg.get_id() == id {
+            arg.default_value(value.to_string())
+        } else {
+            arg
+        }
+    });
+
+    subcommands.iter().fold(cli, |cli, name| {
+        cli.mut_subcommand(name, |sub| with_arg_default(sub, id, value))
+    })
+}
+
+/// Value of the string argument `id` given to the innermost subcommand
+pub fn subcommand_arg(matches: &clap::ArgMatches, id: &str) -> Option<String> {
+    match matches.subcommand() {
+        Some((_, sub)) => subcommand_arg(sub, id),
+        None => matches.try_get_one::<String>(id).ok().flatten().cloned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("simple-arg"), "simple-arg");
+        assert_eq!(shell_quote("two words"), "'two words'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+        assert_eq!(shell_quote(""), "''");
+    }
+
+    #[test]
+    fn test_split_command_line() {
+        assert_eq!(
+            split_command_line("cargo test --  --nocapture").unwrap(),
+            vec!["cargo", "test", "--", "--nocapture"]
+        );
+        assert_eq!(
+            split_command_line(r#"echo 'a b' "c \"d\"" e\ f ''"#).unwrap(),
+            vec!["echo", "a b", "c \"d\"", "e f", ""]
+        );
+        assert!(split_command_line("echo 'open").is_err());
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("512").unwrap(), 512);
+        assert_eq!(parse_size("64k").unwrap(), 64 * 1024);
+        assert_eq!(parse_size("2MiB").unwrap(), 2 * 1024 * 1024);
+        assert_eq!(parse_size("1G").unwrap(), 1 << 30);
+        assert!(parse_size("lots").is_err());
+        assert!(parse_size("").is_err());
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(3 << 30), "3.0 GiB");
+    }
+
+    #[test]
+    fn test_parse_time() {
+        let now = NaiveDate::from_ymd_opt(2024, 6, 30)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+            .and_utc();
+        assert_eq!(parse_time("1y", now).unwrap(), now - Duration::days(365));
+        assert_eq!(parse_time("2w", now).unwrap(), now - Duration::days(14));
+        assert_eq!(
+            parse_time("2024-01-31", now).unwrap().date_naive(),
+            NaiveDate::from_ymd_opt(2024, 1, 31).unwrap()
+        );
+        assert!(parse_time("3x", now).is_err());
+        assert!(parse_time("30é", now).is_err());
+        assert!(parse_time("é", now).is_err());
+        assert!(parse_time("", now).is_err());
+    }
+}
@@ -0,0 +1,245 @@
+//! GitHub Actions workflow files: their jobs, runners and the actions they use
+
+use anyhow::Result;
+use serde_yaml::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Where GitHub reads workflows from, relative to the repository root
+pub const WORKFLOWS_DIR: &str = ".github/workflows";
+
+/// Workflow files of a repository, sorted by name
+pub fn workflow_files(repo_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(repo_dir.join(WORKFLOWS_DIR)) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext == "yml" || ext == "yaml")
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// An action or reusable workflow referenced by a `uses:` line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActionRef {
+    /// Action name, e.g. `actions/checkout` or `org/repo/path/to/action`
+    pub name: String,
+    /// Tag, branch or commit SHA after the `@`
+    pub reference: String,
+    /// 1-based line number in the workflow
+    pub line: usize,
+}
+
+impl ActionRef {
+    /// Owner and name of the repository hosting the action
+    pub fn repository(&self) -> Option<(&str, &str)> {
+        let mut segments = self.name.split('/');
+        Some((segments.next()?, segments.next()?))
+    }
+
+    /// Whether the reference is a full commit SHA, which can't be moved
+    pub fn is_pinned(&self) -> bool {
+        is_commit_sha(&self.reference)
+    }
+}
+
+fn is_commit_sha(reference: &str) -> bool {
+    reference.len() == 40 && reference.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Actions referenced by a workflow, skipping local actions and Docker images
+pub fn action_refs(content: &str) -> Vec<ActionRef> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let (name, reference) = parse_uses(line)?;
+            Some(ActionRef {
+                name: name.to_string(),
+                reference: reference.to_string(),
+                line: idx + 1,
+            })
+        })
+        .collect()
+}
+
+/// Action name and reference of a `uses: name@ref` line
+fn parse_uses(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim_start();
+    let line = line.strip_prefix("- ").unwrap_or(line).trim_start();
+    let value = line.strip_prefix("uses:")?;
+    let value = value.split(" #").next().unwrap_or_default().trim();
+    let value = value.trim_matches(|c| c == '"' || c == '\'');
+    if value.starts_with("./") || value.starts_with("docker://") {
+        return None;
+    }
+    value.split_once('@')
+}
+
+/// Replace unpinned references with the commit SHAs in `shas`, keyed by
+/// `name@reference`, keeping the original reference as a trailing comment
+pub fn pin(content: &str, shas: &HashMap<String, String>) -> String {
+    content
+        .split_inclusive('\n')
+        .map(|line| {
+            let Some((name, reference)) = parse_uses(line) else {
+                return line.to_string();
+            };
+            let original = format!("{name}@{reference}");
+            let (Some(sha), false) = (shas.get(&original), is_commit_sha(reference)) else {
+                return line.to_string();
+            };
+            let Some(idx) = line.find(&original) else {
+                return line.to_string();
+            };
+
+            let (rest, newline) = match line[idx + original.len()..].strip_suffix('\n') {
+                Some(rest) => (rest, "\n"),
+                None => (&line[idx + original.len()..], ""),
+            };
+            // A comment that already names the version is kept as it is
+            let comment = if rest.contains('#') {
+                String::new()
+            } else {
+                format!(" # {reference}")
+            };
+            format!("{}{name}@{sha}{rest}{comment}{newline}", &line[..idx])
+        })
+        .collect()
+}
+
+/// A job in a workflow
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Job {
+    pub id: String,
+    /// Display name, when set
+    pub name: Option<String>,
+    /// Runner labels from `runs-on`; empty for jobs calling reusable workflows
+    pub runs_on: Vec<String>,
+}
+
+/// Jobs defined by a workflow
+pub fn jobs(content: &str) -> Result<Vec<Job>> {
+    let workflow: Value = serde_yaml::from_str(content)?;
+    let Some(jobs) = workflow.get("jobs").and_then(Value::as_mapping) else {
+        return Ok(Vec::new());
+    };
+
+    Ok(jobs
+        .iter()
+        .filter_map(|(id, job)| {
+            let runs_on = match job.get("runs-on") {
+                // runs-on: { group: ..., labels: ... }
+                Some(Value::Mapping(runner)) => {
+                    runner.get("labels").map(labels).unwrap_or_default()
+                }
+                Some(value) => labels(value),
+                None => Vec::new(),
+            };
+            Some(Job {
+                id: id.as_str()?.to_string(),
+                name: job.get("name").and_then(Value::as_str).map(str::to_string),
+                runs_on,
+            })
+        })
+        .collect())
+}
+
+fn labels(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(label) => vec![label.clone()],
+        Value::Sequence(labels) => labels
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORKFLOW: &str = "\
+name: CI
+on: push
+jobs:
+  build:
+    name: Build
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v4
+      - uses: \"actions/setup-node@v4\" # node
+      - uses: ./local-action
+      - uses: docker://alpine:3
+      - uses: org/tools/lint@8f4b7f84864484a7bf31766abe9204da3cbe65b3
+  deploy:
+    runs-on: [self-hosted, linux]
+    uses: org/workflows/.github/workflows/deploy.yml@main
+";
+
+    #[test]
+    fn test_action_refs() {
+        let refs = action_refs(WORKFLOW);
+        let names: Vec<(&str, &str, bool)> = refs
+            .iter()
+            .map(|r| (r.name.as_str(), r.reference.as_str(), r.is_pinned()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("actions/checkout", "v4", false),
+                ("actions/setup-node", "v4", false),
+                (
+                    "org/tools/lint",
+                    "8f4b7f84864484a7bf31766abe9204da3cbe65b3",
+                    true
+                ),
+                ("org/workflows/.github/workflows/deploy.yml", "main", false),
+            ]
+        );
+        assert_eq!(refs[0].line, 8);
+        assert_eq!(refs[2].repository(), Some(("org", "tools")));
+    }
+
+    #[test]
+    fn test_pin() {
+        let sha = "b4ffde65f46336ab88eb53be808477a3936bae11".to_string();
+        let shas = HashMap::from([
+            ("actions/checkout@v4".to_string(), sha.clone()),
+            ("actions/setup-node@v4".to_string(), sha.clone()),
+        ]);
+        let pinned = pin(WORKFLOW, &shas);
+        assert!(pinned.contains(&format!("      - uses: actions/checkout@{sha} # v4\n")));
+        assert!(pinned.contains(&format!(
+            "      - uses: \"actions/setup-node@{sha}\" # node\n"
+        )));
+        assert!(pinned.contains("deploy.yml@main\n"));
+    }
+
+    #[test]
+    fn test_jobs() {
+        let jobs = jobs(WORKFLOW).unwrap();
+        assert_eq!(
+            jobs,
+            vec![
+                Job {
+                    id: "build".to_string(),
+                    name: Some("Build".to_string()),
+                    runs_on: vec!["ubuntu-latest".to_string()],
+                },
+                Job {
+                    id: "deploy".to_string(),
+                    name: None,
+                    runs_on: vec!["self-hosted".to_string(), "linux".to_string()],
+                },
+            ]
+        );
+    }
+}